@@ -0,0 +1,401 @@
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::UNIX_EPOCH;
+
+use chrono::{DateTime, Local};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use walkdir::WalkDir;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::project_info::{ProjectInfo, PROJECT_INFO_FILE};
+
+pub mod contents;
+
+/// The manifest of file checksums at the top of every archive, in the format of
+/// `sha256sum`, so `sha256sum -c MANIFEST.sha256` verifies an extracted project.
+pub const MANIFEST_FILE: &str = "MANIFEST.sha256";
+
+/// The zstd compression level of `.tar.zst` archives, favoring speed since most of a
+/// creative project is already-compressed media.
+const ZSTD_LEVEL: i32 = 9;
+
+/// An archive format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ArchiveFormat {
+    /// A zip file, readable everywhere.
+    #[serde(rename = "zip")]
+    Zip,
+    /// A zstd-compressed tarball, smaller and keeping Unix permissions.
+    #[serde(rename = "tar.zst")]
+    TarZst,
+}
+
+impl ArchiveFormat {
+    /// Every format.
+    pub const ALL: [ArchiveFormat; 2] = [ArchiveFormat::Zip, ArchiveFormat::TarZst];
+
+    /// Returns the format's file extension, which is also its name on the command line.
+    pub fn extension(self) -> &'static str {
+        match self {
+            ArchiveFormat::Zip => "zip",
+            ArchiveFormat::TarZst => "tar.zst",
+        }
+    }
+}
+
+impl fmt::Display for ArchiveFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.extension())
+    }
+}
+
+impl FromStr for ArchiveFormat {
+    type Err = String;
+
+    /// Parses a format name, ignoring case and a leading dot.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim().trim_start_matches('.').to_lowercase();
+        Self::ALL.into_iter().find(|format| format.extension() == value).ok_or_else(|| {
+            let names: Vec<&str> = Self::ALL.iter().map(|format| format.extension()).collect();
+            format!("unknown archive format '{}' (expected one of: {})", value, names.join(", "))
+        })
+    }
+}
+
+/// An archive made of a project, recorded in its information.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchiveRecord {
+    /// The absolute path the archive was written to.
+    pub path: PathBuf,
+    /// The archive's format.
+    pub format: ArchiveFormat,
+    /// The SHA-256 checksum of the archive file, in hex.
+    pub sha256: String,
+    /// The size of the archive file, in bytes.
+    pub size_bytes: u64,
+    /// The number of project files in the archive.
+    pub file_count: usize,
+    /// When the archive was made.
+    pub created: DateTime<Local>,
+}
+
+/// Packs a project directory into an archive.
+///
+/// Every file is stored under a top-level folder named after the directory, next to
+/// the project's information as `project_info.toml` and a [`MANIFEST_FILE`] with the
+/// checksum of every file. Symbolic links are skipped. The archive is written to a
+/// temporary file first, so an interrupted run does not leave a truncated archive
+/// behind.
+///
+/// # Errors
+///
+/// Returns an `AlreadyExists` error rather than overwrite an existing archive.
+pub fn create(directory: &Path, info: &ProjectInfo, output: &Path, format: ArchiveFormat) -> io::Result<ArchiveRecord> {
+    if output.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", output.display()),
+        ));
+    }
+    let directory = fs::canonicalize(directory)?;
+    let folder = directory
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "project".to_string());
+    let partial = partial_of(output);
+
+    let result = write_archive(&directory, &folder, info, &partial, output, format);
+    let file_count = match result {
+        Ok(file_count) => file_count,
+        Err(e) => {
+            let _ = fs::remove_file(&partial);
+            return Err(e);
+        }
+    };
+    fs::rename(&partial, output)?;
+
+    let (sha256, size_bytes) = hash_file(output)?;
+    let record = ArchiveRecord {
+        path: fs::canonicalize(output)?,
+        format,
+        sha256,
+        size_bytes,
+        file_count,
+        created: Local::now(),
+    };
+    info!(
+        "Archived {} files of {} to {} ({} bytes)",
+        file_count,
+        directory.display(),
+        record.path.display(),
+        size_bytes
+    );
+    Ok(record)
+}
+
+/// Writes every file of the project, its information, and the manifest, returning
+/// the number of project files.
+fn write_archive(
+    directory: &Path,
+    folder: &str,
+    info: &ProjectInfo,
+    partial: &Path,
+    output: &Path,
+    format: ArchiveFormat,
+) -> io::Result<usize> {
+    let mut writer = ArchiveWriter::create(partial, format)?;
+    let mut manifest = String::new();
+    let mut file_count = 0;
+
+    let output = output.parent().and_then(|parent| fs::canonicalize(parent).ok()).map(|parent| {
+        parent.join(output.file_name().unwrap_or_default())
+    });
+    for entry in WalkDir::new(directory).min_depth(1).sort_by_file_name() {
+        let entry = entry?;
+        let relative = entry.path().strip_prefix(directory).unwrap_or(entry.path());
+        if entry.file_type().is_symlink() {
+            warn!("Skipping symbolic link {} while archiving", entry.path().display());
+            continue;
+        }
+        // The information is written from the saved copy below, and an archive
+        // written inside the project must not contain itself.
+        let is_output = output.as_deref().is_some_and(|output| {
+            entry.path() == output || entry.path() == partial_of(output)
+        });
+        if !entry.file_type().is_file() || is_output || relative == Path::new(PROJECT_INFO_FILE) {
+            continue;
+        }
+
+        let metadata = entry.metadata().map_err(io::Error::from)?;
+        let name = archive_name(folder, relative);
+        let mut reader = HashingReader::new(File::open(entry.path())?);
+        writer.add(&name, metadata.len(), modified_secs(&metadata), mode(&metadata), &mut reader)?;
+        manifest.push_str(&format!("{}  {}\n", reader.finish(), slash_path(relative)));
+        file_count += 1;
+    }
+
+    let info_toml = toml::to_string(info).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let now = Local::now().timestamp().max(0) as u64;
+    let mut reader = HashingReader::new(info_toml.as_bytes());
+    writer.add(
+        &format!("{}/{}", folder, PROJECT_INFO_FILE),
+        info_toml.len() as u64,
+        now,
+        0o644,
+        &mut reader,
+    )?;
+    manifest.push_str(&format!("{}  {}\n", reader.finish(), PROJECT_INFO_FILE));
+
+    writer.add(
+        &format!("{}/{}", folder, MANIFEST_FILE),
+        manifest.len() as u64,
+        now,
+        0o644,
+        &mut manifest.as_bytes(),
+    )?;
+    writer.finish()?;
+    Ok(file_count)
+}
+
+fn partial_of(output: &Path) -> PathBuf {
+    let mut partial = output.as_os_str().to_owned();
+    partial.push(".partial");
+    PathBuf::from(partial)
+}
+
+fn archive_name(folder: &str, relative: &Path) -> String {
+    format!("{}/{}", folder, slash_path(relative))
+}
+
+/// Returns a relative path with `/` separators on every platform.
+fn slash_path(relative: &Path) -> String {
+    relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn modified_secs(metadata: &fs::Metadata) -> u64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(unix)]
+fn mode(metadata: &fs::Metadata) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode() & 0o7777
+}
+
+#[cfg(not(unix))]
+fn mode(_metadata: &fs::Metadata) -> u32 {
+    0o644
+}
+
+/// Returns the SHA-256 checksum, in hex, and the size of a file.
+pub fn hash_file(path: &Path) -> io::Result<(String, u64)> {
+    let mut reader = HashingReader::new(File::open(path)?);
+    let size = io::copy(&mut reader, &mut io::sink())?;
+    Ok((reader.finish(), size))
+}
+
+/// A reader that computes the SHA-256 checksum of what is read through it.
+struct HashingReader<R> {
+    inner: R,
+    hasher: Sha256,
+}
+
+impl<R: Read> HashingReader<R> {
+    fn new(inner: R) -> Self {
+        HashingReader {
+            inner,
+            hasher: Sha256::new(),
+        }
+    }
+
+    /// Returns the checksum of everything read, in hex.
+    fn finish(self) -> String {
+        format!("{:x}", self.hasher.finalize())
+    }
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.hasher.update(&buf[..read]);
+        Ok(read)
+    }
+}
+
+/// The writer of an archive being made, in either format.
+enum ArchiveWriter {
+    Zip(Box<ZipWriter<BufWriter<File>>>),
+    TarZst(tar::Builder<zstd::Encoder<'static, BufWriter<File>>>),
+}
+
+impl ArchiveWriter {
+    fn create(path: &Path, format: ArchiveFormat) -> io::Result<Self> {
+        let file = BufWriter::new(File::create(path)?);
+        Ok(match format {
+            ArchiveFormat::Zip => ArchiveWriter::Zip(Box::new(ZipWriter::new(file))),
+            ArchiveFormat::TarZst => {
+                ArchiveWriter::TarZst(tar::Builder::new(zstd::Encoder::new(file, ZSTD_LEVEL)?))
+            }
+        })
+    }
+
+    /// Adds a file with the given size, modification time (in seconds since the Unix
+    /// epoch), and permissions.
+    fn add(&mut self, name: &str, size: u64, modified: u64, mode: u32, reader: &mut dyn Read) -> io::Result<()> {
+        match self {
+            ArchiveWriter::Zip(zip) => {
+                let mut options = SimpleFileOptions::default()
+                    .compression_method(CompressionMethod::Deflated)
+                    .unix_permissions(mode)
+                    .large_file(size >= u32::MAX as u64);
+                if let Some(time) = DateTime::from_timestamp(modified as i64, 0)
+                    .and_then(|time| zip::DateTime::try_from(time.with_timezone(&Local).naive_local()).ok())
+                {
+                    options = options.last_modified_time(time);
+                }
+                zip.start_file(name, options).map_err(io::Error::other)?;
+                io::copy(reader, zip)?;
+            }
+            ArchiveWriter::TarZst(tar) => {
+                let mut header = tar::Header::new_gnu();
+                header.set_size(size);
+                header.set_mtime(modified);
+                header.set_mode(mode);
+                tar.append_data(&mut header, name, reader)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> io::Result<()> {
+        let mut file = match self {
+            ArchiveWriter::Zip(zip) => zip.finish().map_err(io::Error::other)?,
+            ArchiveWriter::TarZst(tar) => tar.into_inner()?.finish()?,
+        };
+        file.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use tempfile::tempdir;
+
+    fn project() -> tempfile::TempDir {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("Stems")).unwrap();
+        fs::write(dir.path().join("song.rpp"), "<REAPER_PROJECT").unwrap();
+        fs::write(dir.path().join("Stems/kick.wav"), vec![1; 2048]).unwrap();
+        fs::write(dir.path().join(PROJECT_INFO_FILE), "stale").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_tar_zst_archive_contents() {
+        let dir = project();
+        let out = tempdir().unwrap();
+        let output = out.path().join("song.tar.zst");
+        let info = ProjectInfo {
+            name: "song".to_string(),
+            ..Default::default()
+        };
+
+        let record = create(dir.path(), &info, &output, ArchiveFormat::TarZst).unwrap();
+        assert_eq!(record.file_count, 2);
+        assert_eq!(record.sha256, hash_file(&output).unwrap().0);
+        assert!(create(dir.path(), &info, &output, ArchiveFormat::TarZst).is_err());
+
+        let decoder = zstd::Decoder::new(File::open(&output).unwrap()).unwrap();
+        let mut files = BTreeMap::new();
+        for entry in tar::Archive::new(decoder).entries().unwrap() {
+            let mut entry = entry.unwrap();
+            let mut content = String::new();
+            let _ = entry.read_to_string(&mut content);
+            files.insert(entry.path().unwrap().to_string_lossy().to_string(), content);
+        }
+
+        let folder = dir.path().canonicalize().unwrap().file_name().unwrap().to_string_lossy().to_string();
+        let names: Vec<_> = files.keys().cloned().collect();
+        assert_eq!(
+            names,
+            vec![
+                format!("{}/{}", folder, MANIFEST_FILE),
+                format!("{}/Stems/kick.wav", folder),
+                format!("{}/project_info.toml", folder),
+                format!("{}/song.rpp", folder),
+            ]
+        );
+        assert!(files[&format!("{}/project_info.toml", folder)].contains("name = \"song\""));
+        let manifest = &files[&format!("{}/{}", folder, MANIFEST_FILE)];
+        assert_eq!(manifest.lines().count(), 3);
+        assert!(manifest.contains("  Stems/kick.wav\n"));
+        assert!(manifest.contains(&format!("{}  song.rpp\n", hash_file(&dir.path().join("song.rpp")).unwrap().0)));
+    }
+
+    #[test]
+    fn test_zip_archive_inside_the_project() {
+        let dir = project();
+        let output = dir.path().join("backup.zip");
+        let record = create(dir.path(), &ProjectInfo::default(), &output, ArchiveFormat::Zip).unwrap();
+        assert_eq!(record.file_count, 2);
+        assert_eq!(record.format, ArchiveFormat::Zip);
+        assert!(!dir.path().join("backup.zip.partial").exists());
+        assert_eq!("TAR.ZST".parse(), Ok(ArchiveFormat::TarZst));
+    }
+}
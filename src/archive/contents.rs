@@ -0,0 +1,290 @@
+//! Scanning projects that are still packed in an archive.
+//!
+//! An archive is never unpacked in full. Its entries are recreated in a temporary
+//! "skeleton" directory: small files, such as manifests and sessions, with their
+//! contents, and large ones, mostly media, as empty sparse files of the same size, so
+//! every detector and the disk usage work as on the real project.
+
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, Local, NaiveDateTime};
+use flate2::read::GzDecoder;
+use log::{debug, info, warn};
+use tempfile::TempDir;
+use walkdir::WalkDir;
+use zip::ZipArchive;
+
+use crate::project_info::SKIPPED_DIRECTORIES;
+
+/// Files up to this size are unpacked with their contents; larger ones are only
+/// recorded by size.
+const CONTENT_LIMIT: u64 = 1024 * 1024;
+
+/// The kinds of archive that can be looked into, by file name suffix.
+const ARCHIVE_SUFFIXES: &[(&str, ArchiveKind)] = &[
+    (".zip", ArchiveKind::Zip),
+    (".tar", ArchiveKind::Tar),
+    (".tar.gz", ArchiveKind::TarGz),
+    (".tgz", ArchiveKind::TarGz),
+    (".tar.zst", ArchiveKind::TarZst),
+    (".tzst", ArchiveKind::TarZst),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArchiveKind {
+    Zip,
+    Tar,
+    TarGz,
+    TarZst,
+}
+
+/// Returns the kind of archive and the file name without its archive suffix, e.g.
+/// `song` for `song.tar.gz`.
+fn archive_kind(path: &Path) -> Option<(ArchiveKind, String)> {
+    let file_name = path.file_name()?.to_string_lossy();
+    let lowercase = file_name.to_lowercase();
+    ARCHIVE_SUFFIXES
+        .iter()
+        .filter(|(suffix, _)| lowercase.ends_with(suffix) && lowercase.len() > suffix.len())
+        .max_by_key(|(suffix, _)| suffix.len())
+        .map(|(suffix, kind)| (*kind, file_name[..file_name.len() - suffix.len()].to_string()))
+}
+
+/// Returns `true` if the path is a file in an archive format that can be scanned.
+pub fn is_archive(path: &Path) -> bool {
+    path.is_file() && archive_kind(path).is_some()
+}
+
+/// Finds the archives below a directory that are not inside one of the given
+/// projects, skipping hidden and build directories.
+pub fn find_archives(root: &Path, projects: &[PathBuf]) -> Vec<PathBuf> {
+    let mut walker = WalkDir::new(root).min_depth(1).sort_by_file_name().into_iter();
+    let mut archives = Vec::new();
+
+    while let Some(entry) = walker.next() {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        let file_name = entry.file_name().to_string_lossy();
+        if entry.file_type().is_dir() {
+            if file_name.starts_with('.')
+                || SKIPPED_DIRECTORIES.contains(&file_name.as_ref())
+                || projects.iter().any(|project| project == entry.path())
+            {
+                walker.skip_current_dir();
+            }
+            continue;
+        }
+        if entry.file_type().is_file() && archive_kind(entry.path()).is_some() {
+            archives.push(entry.path().to_path_buf());
+        }
+    }
+
+    info!("Found {} archives under {}", archives.len(), root.display());
+    archives
+}
+
+/// The entries of an archive, recreated in a temporary directory that is removed
+/// when dropped.
+#[derive(Debug)]
+pub struct Skeleton {
+    _temp_dir: TempDir,
+    root: PathBuf,
+    oldest_modified: Option<SystemTime>,
+}
+
+impl Skeleton {
+    /// Recreates the entries of an archive.
+    ///
+    /// Entries with absolute paths or `..` components, and symbolic links, are
+    /// skipped. When every entry is inside one top-level folder, as in archives made
+    /// by `tagger archive`, that folder is the project; otherwise the project is named
+    /// after the archive.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `InvalidInput` error for files that are not a supported archive, and
+    /// any error met while reading the archive.
+    pub fn build(archive: &Path) -> io::Result<Self> {
+        let (kind, name) = archive_kind(archive).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} is not a zip or tar archive", archive.display()),
+            )
+        })?;
+
+        // A fresh directory with an unpredictable name, created only for this user.
+        let temp_dir = tempfile::Builder::new().prefix("tagger-archive-").tempdir()?;
+        let base = temp_dir.path().join(&name);
+        fs::create_dir(&base)?;
+        let mut skeleton = Skeleton {
+            root: base.clone(),
+            _temp_dir: temp_dir,
+            oldest_modified: None,
+        };
+
+        let file = File::open(archive)?;
+        match kind {
+            ArchiveKind::Zip => skeleton.add_zip_entries(file)?,
+            ArchiveKind::Tar => skeleton.add_tar_entries(file)?,
+            ArchiveKind::TarGz => skeleton.add_tar_entries(GzDecoder::new(file))?,
+            ArchiveKind::TarZst => skeleton.add_tar_entries(zstd::Decoder::new(file)?)?,
+        }
+
+        let children: Vec<_> = fs::read_dir(&base)?.flatten().collect();
+        if let [only] = children.as_slice() {
+            if only.file_type()?.is_dir() {
+                skeleton.root = only.path();
+            }
+        }
+        info!("Listed {} as {}", archive.display(), skeleton.root.display());
+        Ok(skeleton)
+    }
+
+    /// Returns the directory standing in for the archived project.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Returns the modification time of the archive's oldest file, if any was recorded.
+    pub fn oldest_modified(&self) -> Option<DateTime<Local>> {
+        self.oldest_modified.map(DateTime::<Local>::from)
+    }
+
+    fn add_zip_entries(&mut self, file: File) -> io::Result<()> {
+        let mut zip = ZipArchive::new(file).map_err(io::Error::other)?;
+        for index in 0..zip.len() {
+            let mut entry = zip.by_index(index).map_err(io::Error::other)?;
+            let Some(relative) = entry.enclosed_name() else {
+                warn!("Skipping archive entry with an unsafe path: {}", entry.name());
+                continue;
+            };
+            if entry.is_symlink() {
+                continue;
+            }
+            if entry.is_dir() {
+                fs::create_dir_all(self.root.join(relative))?;
+                continue;
+            }
+            let modified = entry
+                .last_modified()
+                .and_then(|time| NaiveDateTime::try_from(time).ok())
+                .and_then(|time| time.and_local_timezone(Local).earliest())
+                .map(SystemTime::from);
+            let size = entry.size();
+            self.add_file(&relative, size, modified, &mut entry)?;
+        }
+        Ok(())
+    }
+
+    fn add_tar_entries(&mut self, reader: impl Read) -> io::Result<()> {
+        let mut tar = tar::Archive::new(reader);
+        for entry in tar.entries()? {
+            let mut entry = entry?;
+            let relative = entry.path()?.into_owned();
+            if !relative.components().all(|component| matches!(component, Component::Normal(_) | Component::CurDir)) {
+                warn!("Skipping archive entry with an unsafe path: {}", relative.display());
+                continue;
+            }
+            let entry_type = entry.header().entry_type();
+            if entry_type.is_dir() {
+                fs::create_dir_all(self.root.join(&relative))?;
+                continue;
+            }
+            if !entry_type.is_file() {
+                continue;
+            }
+            let modified = entry.header().mtime().ok().map(|secs| UNIX_EPOCH + Duration::from_secs(secs));
+            let size = entry.size();
+            self.add_file(&relative, size, modified, &mut entry)?;
+        }
+        Ok(())
+    }
+
+    /// Writes one file of the skeleton: its contents when it is small, otherwise an
+    /// empty file of the same length.
+    fn add_file(&mut self, relative: &Path, size: u64, modified: Option<SystemTime>, contents: &mut dyn Read) -> io::Result<()> {
+        let path = self.root.join(relative);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut file = File::create(&path)?;
+        if size <= CONTENT_LIMIT {
+            io::copy(&mut contents.take(CONTENT_LIMIT), &mut file)?;
+        } else {
+            debug!("Recording {} ({} bytes) without its contents", relative.display(), size);
+            file.set_len(size)?;
+        }
+        if let Some(modified) = modified {
+            file.set_modified(modified)?;
+            self.oldest_modified = Some(self.oldest_modified.map_or(modified, |oldest| oldest.min(modified)));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::tempdir;
+    use zip::write::SimpleFileOptions;
+
+    #[test]
+    fn test_zip_skeleton_without_top_folder() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("Beat.Final.zip");
+        let mut zip = zip::ZipWriter::new(File::create(&path).unwrap());
+        zip.start_file("beat.rpp", SimpleFileOptions::default()).unwrap();
+        zip.write_all(b"<REAPER_PROJECT").unwrap();
+        zip.start_file("Stems/kick.wav", SimpleFileOptions::default()).unwrap();
+        zip.write_all(&vec![1; 2048]).unwrap();
+        zip.start_file("../escape.txt", SimpleFileOptions::default()).unwrap();
+        zip.finish().unwrap();
+
+        let skeleton = Skeleton::build(&path).unwrap();
+        let root = skeleton.root().to_path_buf();
+        assert_eq!(root.file_name().unwrap(), "Beat.Final");
+        assert_eq!(fs::read_to_string(root.join("beat.rpp")).unwrap(), "<REAPER_PROJECT");
+        assert_eq!(fs::metadata(root.join("Stems/kick.wav")).unwrap().len(), 2048);
+        assert!(!root.parent().unwrap().join("escape.txt").exists());
+
+        // Each skeleton gets a directory of its own.
+        let other = Skeleton::build(&path).unwrap();
+        assert_ne!(other.root(), root);
+        drop(other);
+        assert!(root.exists());
+
+        drop(skeleton);
+        assert!(!root.exists());
+    }
+
+    #[test]
+    fn test_tar_gz_skeleton_keeps_sizes_of_large_files() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("song.tar.gz");
+        let mut tar = tar::Builder::new(flate2::write::GzEncoder::new(
+            File::create(&path).unwrap(),
+            flate2::Compression::fast(),
+        ));
+        for (name, size) in [("song/song.rpp", 16), ("song/mix.wav", CONTENT_LIMIT + 1)] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(size);
+            header.set_mtime(1_600_000_000);
+            header.set_mode(0o644);
+            tar.append_data(&mut header, name, io::repeat(b'x').take(size)).unwrap();
+        }
+        tar.into_inner().unwrap().finish().unwrap();
+
+        assert!(is_archive(&path));
+        let skeleton = Skeleton::build(&path).unwrap();
+        assert_eq!(skeleton.root().file_name().unwrap(), "song");
+        assert_eq!(fs::metadata(skeleton.root().join("mix.wav")).unwrap().len(), CONTENT_LIMIT + 1);
+        assert_eq!(skeleton.oldest_modified().unwrap().timestamp(), 1_600_000_000);
+        assert_eq!(find_archives(dir.path(), &[]), vec![path]);
+    }
+}
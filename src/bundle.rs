@@ -0,0 +1,564 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use flate2::read::GzDecoder;
+use log::{info, warn};
+use regex::Regex;
+
+use crate::config::dirs;
+use crate::enrichment::Enrichment;
+use crate::project_info::ignore::{glob_match, slash_path};
+use crate::project_info::inventory::Inventory;
+
+/// The manifest every bundle carries at its root.
+pub const BUNDLE_MANIFEST: &str = "bundle.toml";
+
+/// The directory holding a bundle's scaffolding templates, one subdirectory each.
+pub const TEMPLATES_DIR: &str = "templates";
+
+/// The description of a shareable bundle of tagging conventions.
+///
+/// A bundle is a directory, or a `.tar`/`.tar.gz` archive of one, laid out as:
+///
+/// ```text
+/// bundle.toml           name, version, rules, and taxonomy
+/// templates/<name>/     scaffolding templates for new projects (optional)
+/// ```
+///
+/// with a manifest such as:
+///
+/// ```toml
+/// name = "gamedev"
+/// version = "1.0.0"
+/// description = "Tagging conventions for game projects"
+///
+/// [[rules]]
+/// extensions = ["uproject"]
+/// tags = ["unreal", "game"]
+///
+/// [[rules]]
+/// files = ["project.godot"]
+/// types = ["programming"]
+/// tags = ["godot", "game"]
+///
+/// [taxonomy]
+/// engine = ["unreal", "godot", "unity"]
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BundleManifest {
+    /// The bundle's name, which is also the directory it is installed in.
+    pub name: String,
+    /// The bundle's version.
+    pub version: String,
+    /// A one-line summary of the conventions the bundle holds.
+    #[serde(default)]
+    pub description: String,
+    /// Rules that add tags to projects containing matching files.
+    #[serde(default)]
+    pub rules: Vec<TagRule>,
+    /// The bundle's vocabulary of tags, grouped by category (e.g. `genre`, `engine`).
+    #[serde(default)]
+    pub taxonomy: BTreeMap<String, Vec<String>>,
+}
+
+/// Adds tags to projects that contain a file or directory with one of the given
+/// names or extensions, or whose path matches one of the given globs or regular
+/// expressions.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TagRule {
+    /// File names that trigger the rule, compared exactly.
+    #[serde(default)]
+    pub files: Vec<String>,
+    /// Extensions that trigger the rule, without the dot and compared case-insensitively.
+    #[serde(default)]
+    pub extensions: Vec<String>,
+    /// Globs that trigger the rule, matched against paths relative to the project
+    /// (`**/Dockerfile`); one without a `/` matches names at any depth (`*.alp`).
+    #[serde(default)]
+    pub globs: Vec<String>,
+    /// Regular expressions that trigger the rule, searched for in paths relative to
+    /// the project, with `/` separators (`\.ableton-live-pack$`).
+    #[serde(default)]
+    pub regexes: Vec<String>,
+    /// The project types the rule applies to; every type when empty.
+    #[serde(default)]
+    pub types: Vec<String>,
+    /// The tags added when the rule matches.
+    pub tags: Vec<String>,
+}
+
+impl TagRule {
+    /// Returns `true` if the rule applies to projects of the given type.
+    pub fn applies_to(&self, project_type: &str) -> bool {
+        self.types.is_empty() || self.types.iter().any(|t| t == project_type)
+    }
+
+    /// Checks that the rule has tags, something to match, and valid regular
+    /// expressions, describing the problem otherwise.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.tags.is_empty()
+            || (self.files.is_empty() && self.extensions.is_empty() && self.globs.is_empty() && self.regexes.is_empty())
+        {
+            return Err("needs tags and at least one file name, extension, glob, or regex".to_string());
+        }
+        for pattern in &self.regexes {
+            Regex::new(pattern).map_err(|e| format!("has an invalid regex: {}", e))?;
+        }
+        Ok(())
+    }
+}
+
+/// A rule with its regular expressions compiled, for matching one project's files.
+struct RuleMatcher<'a> {
+    rule: &'a TagRule,
+    regexes: Vec<Regex>,
+}
+
+impl<'a> RuleMatcher<'a> {
+    fn new(rule: &'a TagRule) -> Self {
+        let regexes = rule
+            .regexes
+            .iter()
+            .filter_map(|pattern| {
+                Regex::new(pattern)
+                    .inspect_err(|e| warn!("Ignoring invalid rule regex {:?}: {}", pattern, e))
+                    .ok()
+            })
+            .collect();
+        RuleMatcher { rule, regexes }
+    }
+
+    /// Returns `true` if the file triggers the rule.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file's path.
+    /// * `relative` - The file's path relative to the project, with `/` separators.
+    fn matches(&self, path: &Path, relative: &str) -> bool {
+        let file_name = path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+        let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or_default();
+
+        self.rule.files.iter().any(|file| *file == file_name)
+            || self.rule.extensions.iter().any(|ext| ext.eq_ignore_ascii_case(extension))
+            || self.rule.globs.iter().any(|glob| {
+                let target = if glob.contains('/') { relative } else { file_name.as_ref() };
+                glob_match(glob, target)
+            })
+            || self.regexes.iter().any(|regex| regex.is_match(relative))
+    }
+}
+
+/// An installed bundle.
+#[derive(Debug, Clone)]
+pub struct Bundle {
+    /// The directory the bundle is installed in.
+    pub path: PathBuf,
+    /// The bundle's manifest.
+    pub manifest: BundleManifest,
+}
+
+impl Bundle {
+    /// Returns the names of the bundle's scaffolding templates, sorted.
+    pub fn templates(&self) -> Vec<String> {
+        let Ok(entries) = fs::read_dir(self.path.join(TEMPLATES_DIR)) else {
+            return Vec::new();
+        };
+
+        let mut templates: Vec<String> = entries
+            .flatten()
+            .filter(|entry| entry.path().is_dir())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect();
+        templates.sort();
+        templates
+    }
+}
+
+/// The directory installed bundles are kept in, one subdirectory per bundle.
+pub struct BundleStore {
+    root: PathBuf,
+}
+
+impl BundleStore {
+    /// Opens the bundle store in the given directory, creating it if necessary.
+    pub fn open(root: &Path) -> io::Result<Self> {
+        fs::create_dir_all(root)?;
+        Ok(BundleStore {
+            root: root.to_path_buf(),
+        })
+    }
+
+    /// Opens the bundle store at its default location.
+    pub fn open_default() -> io::Result<Self> {
+        Self::open(&Self::default_location()?)
+    }
+
+    /// Returns the default bundle store location, `bundles` in the data directory.
+    pub fn default_location() -> io::Result<PathBuf> {
+        Ok(dirs::data_dir()?.join("bundles"))
+    }
+
+    /// Installs a bundle from a directory, a `.tar` or `.tar.gz` archive, or an
+    /// `http(s)` URL of an archive, replacing any installed bundle of the same name.
+    ///
+    /// URLs are downloaded through `enrichment`, so they fail when tagger is offline.
+    pub fn install(&self, source: &str, enrichment: &Enrichment) -> io::Result<Bundle> {
+        let staging = self.root.join(".staging");
+        if staging.exists() {
+            fs::remove_dir_all(&staging)?;
+        }
+        fs::create_dir_all(&staging)?;
+
+        let source_path = Path::new(source);
+        if source.starts_with("http://") || source.starts_with("https://") {
+            unpack_archive(&download(source, enrichment)?, &staging)?;
+        } else if source_path.is_dir() {
+            copy_dir(source_path, &staging)?;
+        } else {
+            unpack_archive(&fs::read(source_path)?, &staging)?;
+        }
+
+        let result = self.install_staged(&staging);
+        if staging.exists() {
+            let _ = fs::remove_dir_all(&staging);
+        }
+        result
+    }
+
+    /// Validates the bundle unpacked into `staging` and moves it into place.
+    fn install_staged(&self, staging: &Path) -> io::Result<Bundle> {
+        let content_dir = find_manifest_dir(staging).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("no {} found in the bundle", BUNDLE_MANIFEST),
+            )
+        })?;
+        let manifest = load_manifest(&content_dir)?;
+
+        let slug: String = manifest
+            .name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c.to_ascii_lowercase() } else { '-' })
+            .collect();
+        let slug = slug.trim_matches('-');
+        // An empty slug would make the store itself the destination.
+        if !slug.chars().any(|c| c.is_ascii_alphanumeric()) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("bundle name '{}' needs at least one letter or digit", manifest.name),
+            ));
+        }
+        let destination = self.root.join(slug);
+        if destination.exists() {
+            info!("Replacing installed bundle at {}", destination.display());
+            fs::remove_dir_all(&destination)?;
+        }
+        fs::rename(&content_dir, &destination)?;
+
+        info!("Installed bundle '{}' {} at {}", manifest.name, manifest.version, destination.display());
+        Ok(Bundle {
+            path: destination,
+            manifest,
+        })
+    }
+
+    /// Loads every installed bundle, sorted by name.
+    ///
+    /// Bundles whose manifest cannot be read are skipped with a warning.
+    pub fn bundles(&self) -> io::Result<Vec<Bundle>> {
+        let mut bundles = Vec::new();
+
+        for entry in fs::read_dir(&self.root)?.flatten() {
+            let path = entry.path();
+            if !path.is_dir() || entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            match load_manifest(&path) {
+                Ok(manifest) => bundles.push(Bundle { path, manifest }),
+                Err(e) => warn!("Skipping unreadable bundle {}: {}", path.display(), e),
+            }
+        }
+
+        bundles.sort_by(|a, b| a.manifest.name.cmp(&b.manifest.name));
+        Ok(bundles)
+    }
+}
+
+/// Returns the tag rules of every installed bundle.
+///
+/// A missing or unreadable bundle store yields no rules.
+pub fn installed_rules() -> Vec<TagRule> {
+    let store_path = match BundleStore::default_location() {
+        Ok(path) if path.exists() => path,
+        _ => return Vec::new(),
+    };
+
+    match BundleStore::open(&store_path).and_then(|store| store.bundles()) {
+        Ok(bundles) => bundles.into_iter().flat_map(|bundle| bundle.manifest.rules).collect(),
+        Err(e) => {
+            warn!("Unable to read installed bundles: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Generates the tags of the rules that match files in the project.
+///
+/// # Arguments
+///
+/// * `inventory` - The project's inventory.
+/// * `project_type` - The project's type, which decides the rules that apply.
+/// * `rules` - The rules to apply.
+///
+/// # Returns
+///
+/// The tags of every matching rule.
+pub fn rule_tags(inventory: &Inventory, project_type: &str, rules: &[TagRule]) -> Vec<String> {
+    let rules: Vec<RuleMatcher> = rules
+        .iter()
+        .filter(|rule| rule.applies_to(project_type))
+        .map(RuleMatcher::new)
+        .collect();
+    if rules.is_empty() {
+        return Vec::new();
+    }
+
+    let mut matched = vec![false; rules.len()];
+    for entry in inventory.entries() {
+        let relative = entry.path().strip_prefix(inventory.root()).unwrap_or(entry.path());
+        let relative = slash_path(relative);
+        for (index, rule) in rules.iter().enumerate() {
+            if !matched[index] && rule.matches(entry.path(), &relative) {
+                matched[index] = true;
+            }
+        }
+        if matched.iter().all(|matched| *matched) {
+            break;
+        }
+    }
+
+    rules
+        .iter()
+        .zip(matched)
+        .filter(|(_, matched)| *matched)
+        .flat_map(|(matcher, _)| matcher.rule.tags.iter().cloned())
+        .collect()
+}
+
+/// Reads and validates a bundle manifest.
+fn load_manifest(directory: &Path) -> io::Result<BundleManifest> {
+    let path = directory.join(BUNDLE_MANIFEST);
+    let content = fs::read_to_string(&path)?;
+    let invalid = |message: String| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid bundle manifest {}: {}", path.display(), message),
+        )
+    };
+
+    let manifest: BundleManifest = toml::from_str(&content).map_err(|e| invalid(e.to_string()))?;
+    if manifest.name.trim().is_empty() {
+        return Err(invalid("the bundle has no name".to_string()));
+    }
+    for (index, rule) in manifest.rules.iter().enumerate() {
+        rule.validate().map_err(|e| invalid(format!("rule {} {}", index + 1, e)))?;
+    }
+
+    Ok(manifest)
+}
+
+/// Returns the directory holding the manifest: the root, or the single top-level
+/// directory archives are often created with.
+fn find_manifest_dir(root: &Path) -> Option<PathBuf> {
+    if root.join(BUNDLE_MANIFEST).is_file() {
+        return Some(root.to_path_buf());
+    }
+
+    let entries: Vec<_> = fs::read_dir(root).ok()?.flatten().collect();
+    match entries.as_slice() {
+        [entry] if entry.path().join(BUNDLE_MANIFEST).is_file() => Some(entry.path()),
+        _ => None,
+    }
+}
+
+/// Unpacks a `.tar` or gzip-compressed `.tar.gz` archive.
+///
+/// Entries that would land outside `destination` are skipped by the `tar` crate.
+fn unpack_archive(bytes: &[u8], destination: &Path) -> io::Result<()> {
+    let is_gzip = bytes.starts_with(&[0x1f, 0x8b]);
+    if is_gzip {
+        tar::Archive::new(GzDecoder::new(bytes)).unpack(destination)
+    } else {
+        tar::Archive::new(bytes).unpack(destination)
+    }
+}
+
+/// Downloads a bundle archive.
+fn download(url: &str, enrichment: &Enrichment) -> io::Result<Vec<u8>> {
+    let agent = enrichment.client(&format!("downloading {}", url))?;
+    info!("Downloading bundle from {}", url);
+    let mut response = agent
+        .get(url)
+        .header("User-Agent", "tagger")
+        .call()
+        .map_err(|e| io::Error::other(format!("unable to download {}: {}", url, e)))?;
+    if !response.status().is_success() {
+        return Err(io::Error::other(format!("unable to download {}: HTTP {}", url, response.status())));
+    }
+
+    let mut bytes = Vec::new();
+    response.body_mut().as_reader().read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Copies a directory tree.
+fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)?.flatten() {
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enrichment::EnrichmentConfig;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use tempfile::tempdir;
+
+    fn enrichment() -> Enrichment {
+        Enrichment::new(EnrichmentConfig::default())
+    }
+
+    const MANIFEST: &str = r#"
+name = "Game Dev"
+version = "1.0.0"
+description = "Tagging conventions for game projects"
+
+[[rules]]
+extensions = ["uproject"]
+tags = ["unreal", "game"]
+
+[[rules]]
+files = ["project.godot"]
+types = ["music"]
+tags = ["godot"]
+
+[taxonomy]
+engine = ["unreal", "godot"]
+"#;
+
+    #[test]
+    fn test_install_archive_and_apply_rules() {
+        let source = tempdir().unwrap();
+        let store_dir = tempdir().unwrap();
+
+        // Archives usually wrap the bundle in a top-level directory.
+        fs::create_dir_all(source.path().join("gamedev/templates/unreal-game/Content")).unwrap();
+        fs::write(source.path().join("gamedev/bundle.toml"), MANIFEST).unwrap();
+        let archive_path = source.path().join("gamedev.tar.gz");
+        let mut builder = tar::Builder::new(GzEncoder::new(
+            fs::File::create(&archive_path).unwrap(),
+            Compression::default(),
+        ));
+        builder.append_dir_all("gamedev", source.path().join("gamedev")).unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+
+        let store = BundleStore::open(store_dir.path()).unwrap();
+        let bundle = store.install(archive_path.to_str().unwrap(), &enrichment()).unwrap();
+        assert_eq!(bundle.path, store_dir.path().join("game-dev"));
+        assert_eq!(bundle.templates(), vec!["unreal-game"]);
+        assert_eq!(bundle.manifest.taxonomy["engine"], vec!["unreal", "godot"]);
+
+        // Installing again replaces the bundle instead of adding a second one.
+        store.install(archive_path.to_str().unwrap(), &enrichment()).unwrap();
+        let bundles = store.bundles().unwrap();
+        assert_eq!(bundles.len(), 1);
+
+        let project = tempdir().unwrap();
+        fs::write(project.path().join("Shooter.uproject"), "{}").unwrap();
+        fs::write(project.path().join("project.godot"), "").unwrap();
+        let tags = rule_tags(&Inventory::scan(project.path()), "programming", &bundles[0].manifest.rules);
+        assert_eq!(tags, vec!["unreal", "game"]);
+    }
+
+    #[test]
+    fn test_install_rejects_invalid_rules() {
+        let source = tempdir().unwrap();
+        let store_dir = tempdir().unwrap();
+        fs::write(
+            source.path().join(BUNDLE_MANIFEST),
+            "name = \"broken\"\nversion = \"0.1.0\"\n\n[[rules]]\ntags = [\"x\"]\n",
+        )
+        .unwrap();
+
+        let store = BundleStore::open(store_dir.path()).unwrap();
+        let error = store.install(source.path().to_str().unwrap(), &enrichment()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(store.bundles().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_install_rejects_names_without_letters_or_digits() {
+        let source = tempdir().unwrap();
+        let store_dir = tempdir().unwrap();
+        fs::write(source.path().join(BUNDLE_MANIFEST), "name = \"***\"\nversion = \"0.1.0\"\n").unwrap();
+
+        let store = BundleStore::open(store_dir.path()).unwrap();
+        let installed = store_dir.path().join("keep");
+        fs::create_dir(&installed).unwrap();
+
+        let error = store.install(source.path().to_str().unwrap(), &enrichment()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(installed.is_dir());
+    }
+
+    #[test]
+    fn test_install_from_url_fails_offline() {
+        let store_dir = tempdir().unwrap();
+        let store = BundleStore::open(store_dir.path()).unwrap();
+        let offline = Enrichment::new(EnrichmentConfig {
+            offline: true,
+            ..EnrichmentConfig::default()
+        });
+
+        let error = store.install("https://example.invalid/game-dev.tar.gz", &offline).unwrap_err();
+        assert!(error.to_string().contains("offline"), "{}", error);
+    }
+
+    #[test]
+    fn test_glob_and_regex_rules() {
+        let project = tempdir().unwrap();
+        fs::create_dir_all(project.path().join("deploy/web")).unwrap();
+        fs::write(project.path().join("deploy/web/Dockerfile"), "FROM scratch").unwrap();
+        fs::write(project.path().join("Drums.ableton-live-pack"), "").unwrap();
+
+        let rule = |globs: &[&str], regexes: &[&str], tag: &str| TagRule {
+            globs: globs.iter().map(|glob| glob.to_string()).collect(),
+            regexes: regexes.iter().map(|regex| regex.to_string()).collect(),
+            tags: vec![tag.to_string()],
+            ..TagRule::default()
+        };
+        let rules = [
+            rule(&["**/Dockerfile"], &[], "docker"),
+            rule(&["Dockerfile"], &[], "container"),
+            rule(&["web/*"], &[], "web"),
+            rule(&[], &[r"\.ableton-live-pack$"], "sample-pack"),
+            rule(&[], &["^deploy/.*/Dockerfile$"], "deploy"),
+            rule(&[], &["(unclosed"], "broken"),
+        ];
+        assert!(rules[5].validate().is_err());
+
+        let tags = rule_tags(&Inventory::scan(project.path()), "programming", &rules);
+        assert_eq!(tags, vec!["docker", "container", "sample-pack", "deploy"]);
+    }
+}
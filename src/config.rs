@@ -0,0 +1,813 @@
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use log::info;
+
+use crate::bundle::{self, TagRule};
+use crate::plugins::{self, Plugin};
+use crate::project_info::{ignore, tags};
+use crate::project_info::taxonomy::Taxonomy;
+use crate::project_info::PROJECT_INFO_FILE;
+use crate::registry;
+
+pub mod dirs;
+
+/// User settings that shape how projects are tagged.
+///
+/// Stored as TOML, for example:
+///
+/// ```toml
+/// language = "es"
+///
+/// [tags]
+/// blacklist = ["software development", "cli"]
+/// min_files = 2
+///
+/// [types.music]
+/// blacklist = ["production"]
+/// min_percent = 10.0
+///
+/// [types.photography]
+/// default_tags = false
+///
+/// [git]
+/// remote = "upstream"
+/// commit_date_as_modified = true
+/// max_contributors = 10
+///
+/// [description]
+/// max_length = 120
+///
+/// [security]
+/// disable_external_commands = true
+/// plugin_timeout_secs = 10
+/// restrict_paths = true
+/// allowed_paths = ["/Volumes/Backup"]
+///
+/// [refresh]
+/// default = "on-change"
+/// music = "weekly"
+/// archive = "never"
+///
+/// [watch]
+/// debounce_secs = 5
+///
+/// [output]
+/// path = ".project_info.toml"
+///
+/// [fingerprint]
+/// contents = true
+///
+/// [network]
+/// providers = ["github"]
+///
+/// [suggest]
+/// endpoint = "http://localhost:11434/v1"
+/// model = "llama3.1"
+///
+/// [server]
+/// allowed_origins = ["http://localhost:3000"]
+/// token_env = "TAGGER_TOKEN"
+///
+/// [remote]
+/// url = "s3://studio-projects/tagger"
+/// region = "eu-west-1"
+/// projects = true
+///
+/// [synonyms]
+/// js = "javascript"
+/// "daw:live" = "daw:ableton live"
+///
+/// [[rules]]
+/// regexes = ['\.ableton-live-pack$']
+/// tags = ["ableton", "sample-pack"]
+///
+/// [[rules]]
+/// globs = ["**/Dockerfile"]
+/// tags = ["docker"]
+///
+/// [[roots]]
+/// path = "~/Code"
+/// type = "programming"
+///
+/// [[roots]]
+/// path = "/Volumes/Sessions"
+/// type = "music"
+/// schedule = "weekly"
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// The display language (e.g. `es`); the system locale is used when unset.
+    pub language: Option<String>,
+    /// Settings that apply to every project.
+    pub tags: TagConfig,
+    /// Settings that only apply to projects of one type, keyed by project type.
+    pub types: BTreeMap<String, TagConfig>,
+    /// Git settings.
+    pub git: GitConfig,
+    /// README description settings.
+    pub description: DescriptionConfig,
+    /// Settings for external commands such as hooks and plugins.
+    pub security: SecurityConfig,
+    /// Commands run around every save of a project's information.
+    pub hooks: HookConfig,
+    /// How often cached results are regenerated, keyed by project type. The
+    /// `default` entry applies to types without their own.
+    pub refresh: BTreeMap<String, RefreshPolicy>,
+    /// The program `tagger open` starts, keyed by project type, such as `code` or
+    /// `open -a Reaper`. The `default` entry applies to types without their own.
+    pub open: BTreeMap<String, String>,
+    /// Which files of each project are scanned.
+    pub scan: ScanConfig,
+    /// Watch mode settings.
+    pub watch: WatchConfig,
+    /// Where project information is written.
+    pub output: OutputConfig,
+    /// How project fingerprints are taken.
+    pub fingerprint: FingerprintConfig,
+    /// Which network enrichment providers run.
+    pub network: NetworkConfig,
+    /// Tag suggestions from a language model, requested with `--suggest-tags`.
+    pub suggest: SuggestConfig,
+    /// Who may call the `tagger serve` API.
+    pub server: ServerConfig,
+    /// Where `tagger sync push` and `pull` share the registry.
+    pub remote: RemoteConfig,
+    /// Tag synonyms, e.g. `js = "javascript"`, applied before the built-in ones.
+    pub synonyms: BTreeMap<String, String>,
+    /// Tag rules, from the `[[rules]]` tables followed by those of installed bundles.
+    pub rules: Vec<TagRule>,
+    /// The directories projects are kept under, rescanned by `tagger sync`.
+    pub roots: Vec<RootConfig>,
+    /// Genre and mood keywords, loaded from [`Taxonomy::default_location`].
+    #[serde(skip)]
+    pub taxonomy: Taxonomy,
+    /// Detector plugins, found in [`plugins::default_location`].
+    #[serde(skip)]
+    pub plugins: Vec<Plugin>,
+}
+
+/// When a project's cached information is regenerated.
+///
+/// Written in the configuration as `"on-change"`, `"always"`, `"never"`, `"daily"`,
+/// `"weekly"`, or an interval such as `"12h"`, `"3d"`, or `"2w"`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum RefreshPolicy {
+    /// Regenerate when the git HEAD, the project's files, or the configuration
+    /// changes.
+    #[default]
+    OnChange,
+    /// Regenerate on every scan.
+    Always,
+    /// Keep the first result until the configuration changes.
+    Never,
+    /// Regenerate once the result is older than the interval.
+    Every(Duration),
+}
+
+impl TryFrom<String> for RefreshPolicy {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        const HOUR: u64 = 60 * 60;
+
+        let policy = match value.trim().to_ascii_lowercase().as_str() {
+            "on-change" => RefreshPolicy::OnChange,
+            "always" => RefreshPolicy::Always,
+            "never" => RefreshPolicy::Never,
+            "hourly" => RefreshPolicy::Every(Duration::from_secs(HOUR)),
+            "daily" => RefreshPolicy::Every(Duration::from_secs(24 * HOUR)),
+            "weekly" => RefreshPolicy::Every(Duration::from_secs(7 * 24 * HOUR)),
+            interval => {
+                let unknown = || format!("unknown refresh policy '{}'", value);
+                let (count, unit_secs) = [("h", HOUR), ("d", 24 * HOUR), ("w", 7 * 24 * HOUR)]
+                    .into_iter()
+                    .find_map(|(suffix, secs)| interval.strip_suffix(suffix).map(|count| (count, secs)))
+                    .ok_or_else(unknown)?;
+                let count: u64 = count.parse().map_err(|_| unknown())?;
+                RefreshPolicy::Every(Duration::from_secs(count * unit_secs))
+            }
+        };
+        Ok(policy)
+    }
+}
+
+/// A directory projects are kept under, such as `~/Code` or a drive of sessions.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RootConfig {
+    /// The directory; a leading `~/` stands for the home directory.
+    pub path: PathBuf,
+    /// The type of the projects below the root whose type their files do not settle,
+    /// such as loose audio files on a drive of music sessions.
+    #[serde(rename = "type")]
+    pub project_type: Option<String>,
+    /// How often `tagger sync` rescans the root, written like a refresh policy.
+    /// `on-change`, the default, rescans it on every sync but only regenerates the
+    /// projects that changed; `never` only scans it the first time or with `--force`.
+    #[serde(default)]
+    pub schedule: RefreshPolicy,
+}
+
+impl RootConfig {
+    /// Returns the root's directory, with a leading `~` expanded.
+    pub fn directory(&self) -> PathBuf {
+        match self.path.strip_prefix("~") {
+            Ok(rest) => dirs::home_dir().map_or_else(|| self.path.clone(), |home| home.join(rest)),
+            Err(_) => self.path.clone(),
+        }
+    }
+}
+
+/// Watch mode settings.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct WatchConfig {
+    /// How long a project must be quiet after a change before it is refreshed, in
+    /// seconds, so a build or a sync that touches many files causes one refresh.
+    pub debounce_secs: u64,
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        WatchConfig { debounce_secs: 2 }
+    }
+}
+
+/// Who may call the `tagger serve` API.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ServerConfig {
+    /// The browser origins allowed to call the API, such as `http://localhost:3000`.
+    /// Pages from any other origin are refused.
+    pub allowed_origins: Vec<String>,
+    /// Host names accepted in requests besides `localhost` and loopback addresses,
+    /// for serving other machines.
+    pub allowed_hosts: Vec<String>,
+    /// The environment variable holding the token that `POST` requests must send as
+    /// `Authorization: Bearer TOKEN`. Without one, `tagger serve` generates a token
+    /// and prints it.
+    pub token_env: Option<String>,
+}
+
+/// Where project information is written.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct OutputConfig {
+    /// The file to write instead of `project_info.toml` in the project directory.
+    ///
+    /// A relative path is resolved against the project directory, so
+    /// `.project_info.toml` keeps the file hidden. A directory (an existing one, or a
+    /// path ending in `/`) collects every project's file as `<directory name>-<hash>.toml`,
+    /// the hash telling apart projects in directories of the same name.
+    pub path: Option<PathBuf>,
+    /// On macOS, also read the project folder's Finder tags as project tags and write
+    /// the project's tags back as Finder tags. Needs the `finder-tags` feature.
+    pub finder_tags: bool,
+    /// Also write the project's tags to the project folder's `user.xdg.tags` extended
+    /// attribute (an alternate data stream on Windows). Needs the `xattr-tags` feature.
+    pub xattr_tags: bool,
+}
+
+impl OutputConfig {
+    /// Returns the file the project's information is written to and read from.
+    ///
+    /// A project scanned inside an archive cannot hold its information, which is
+    /// written next to the archive as `<archive name>.toml` instead, unless a folder
+    /// collects every project's file.
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - A reference to the project's directory path.
+    pub fn file_for(&self, directory: &Path) -> PathBuf {
+        let is_archive = directory.is_file();
+        let archive_file = || {
+            let name = directory.file_name().unwrap_or_default().to_string_lossy();
+            directory.with_file_name(format!("{}.toml", name))
+        };
+        let Some(path) = &self.path else {
+            return if is_archive { archive_file() } else { directory.join(PROJECT_INFO_FILE) };
+        };
+
+        let is_folder = path.to_string_lossy().ends_with(['/', std::path::MAIN_SEPARATOR]);
+        let base = if is_archive { directory.parent().unwrap_or(directory) } else { directory };
+        let path = base.join(path);
+        if is_folder || path.is_dir() {
+            // Named like registry entries, so `a/app` and `b/app` get files of their own.
+            let absolute = fs::canonicalize(directory).unwrap_or_else(|_| directory.to_path_buf());
+            path.join(format!("{}.toml", registry::file_stem(&absolute)))
+        } else if is_archive {
+            archive_file()
+        } else {
+            path
+        }
+    }
+}
+
+/// How project fingerprints, checked by `tagger verify`, are taken.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct FingerprintConfig {
+    /// Hash the contents of every file rather than only listing names and sizes, which
+    /// also catches bit rot but reads the whole project on every scan.
+    pub contents: bool,
+}
+
+/// Settings for external commands such as hooks and plugins.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SecurityConfig {
+    /// Never run external commands, whatever hooks or plugins are configured.
+    pub disable_external_commands: bool,
+    /// Environment variables passed to external commands in addition to the
+    /// defaults (`PATH`, locale, terminal, and temporary directory settings).
+    pub env_allowlist: Vec<String>,
+    /// How long a detector plugin may run before it is killed, in seconds; 30 when
+    /// unset.
+    pub plugin_timeout_secs: Option<u64>,
+    /// How long a hook may run before it is killed, in seconds; 60 when unset.
+    pub hook_timeout_secs: Option<u64>,
+    /// Only let external commands write to the project and `allowed_paths`, and read
+    /// nothing else of the user's. Commands refuse to run where the operating system
+    /// cannot enforce this.
+    pub restrict_paths: bool,
+    /// Directories external commands may also read and write when `restrict_paths`
+    /// is set, such as a backup drive.
+    pub allowed_paths: Vec<PathBuf>,
+}
+
+impl SecurityConfig {
+    /// Returns how long a detector plugin may run.
+    pub fn plugin_timeout(&self) -> Duration {
+        Duration::from_secs(self.plugin_timeout_secs.unwrap_or(30))
+    }
+
+    /// Returns how long a hook may run.
+    pub fn hook_timeout(&self) -> Duration {
+        Duration::from_secs(self.hook_timeout_secs.unwrap_or(60))
+    }
+}
+
+/// Files left out of every scan, as if they were not there: they add no tags and do
+/// not count towards the size. Patterns are globs relative to the project directory
+/// (`Bounces/**`); one without a `/` matches names at any depth (`*.wav`).
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ScanConfig {
+    /// Files and directories to leave out.
+    pub exclude: Vec<String>,
+    /// The only files to scan, unless empty. Directories are always searched.
+    pub include: Vec<String>,
+    /// Scan vendored directories (`vendor`, `third_party`) and git submodules as part
+    /// of the project. Off by default, so bundled third-party code adds no tags and
+    /// only counts towards the size.
+    pub vendored: bool,
+    /// Walk into symbolically linked files and directories instead of skipping them.
+    /// A directory reached through several links is read once.
+    pub follow_symlinks: bool,
+    /// Date `date_modified` by the project's contents rather than its directory,
+    /// whose modification time only changes when direct children are added or
+    /// removed. On unless set to `false`.
+    pub modified_from_contents: Option<bool>,
+}
+
+impl ScanConfig {
+    /// Returns `true` if the path, relative to the project directory, is left out.
+    pub fn excludes(&self, relative: &Path, is_dir: bool) -> bool {
+        let path = ignore::slash_path(relative);
+        let name = relative.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+        let matches = |pattern: &String| {
+            if !pattern.contains('/') {
+                return ignore::glob_match(pattern, &name);
+            }
+            // `Bounces/**` also covers the `Bounces` directory itself.
+            ignore::glob_match(pattern, &path) || (is_dir && ignore::glob_match(pattern, &format!("{}/", path)))
+        };
+
+        if self.exclude.iter().any(matches) {
+            return true;
+        }
+        !is_dir && !self.include.is_empty() && !self.include.iter().any(matches)
+    }
+
+    /// Returns whether `date_modified` comes from the project's contents.
+    pub fn modified_from_contents(&self) -> bool {
+        self.modified_from_contents.unwrap_or(true)
+    }
+}
+
+/// Shell commands run when a project's information is saved, in the project directory
+/// and with the project, as JSON, on standard input.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct HookConfig {
+    /// Runs before the information is written; a failure cancels the save.
+    pub pre_save: Option<String>,
+    /// Runs after the information is written; a failure is only reported.
+    pub post_save: Option<String>,
+}
+
+/// Which network enrichment providers run.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct NetworkConfig {
+    /// The providers to run, such as `github`. None run unless listed here or passed
+    /// with `--enable-provider`, so scans make no network requests by default.
+    pub providers: Vec<String>,
+}
+
+/// Settings for tag suggestions from a language model.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SuggestConfig {
+    /// The base URL of an OpenAI-compatible API, such as `https://api.openai.com/v1`
+    /// or `http://localhost:11434/v1` for a local model.
+    pub endpoint: Option<String>,
+    /// The model to ask; `gpt-4o-mini` when unset.
+    pub model: Option<String>,
+    /// The environment variable holding the API key; `OPENAI_API_KEY` when unset.
+    /// No key is sent when the variable is not set.
+    pub api_key_env: Option<String>,
+    /// The most tags suggested for a project; 8 when unset.
+    pub max_tags: Option<usize>,
+}
+
+/// Where the registry is shared between machines by `tagger sync push` and `pull`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct RemoteConfig {
+    /// The remote: `s3://bucket/prefix`, the `https://` URL of a WebDAV folder, or a
+    /// git repository (`git@host:user/repo.git`, or any URL after `git+`).
+    pub url: Option<String>,
+    /// The S3 region; `AWS_REGION`, or else `us-east-1`, when unset.
+    pub region: Option<String>,
+    /// The endpoint of an S3-compatible service, such as MinIO or Cloudflare R2,
+    /// instead of AWS.
+    pub endpoint: Option<String>,
+    /// The environment variable holding the S3 access key id; `AWS_ACCESS_KEY_ID`
+    /// when unset.
+    pub access_key_env: Option<String>,
+    /// The environment variable holding the S3 secret key; `AWS_SECRET_ACCESS_KEY`
+    /// when unset.
+    pub secret_key_env: Option<String>,
+    /// The WebDAV user name.
+    pub username: Option<String>,
+    /// The environment variable holding the WebDAV password; `TAGGER_WEBDAV_PASSWORD`
+    /// when unset.
+    pub password_env: Option<String>,
+    /// Also share each project's information file, not only the registry.
+    pub projects: bool,
+}
+
+/// README description settings.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct DescriptionConfig {
+    /// The maximum length of the description, in characters; 200 when unset.
+    pub max_length: Option<usize>,
+}
+
+/// Git settings.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct GitConfig {
+    /// The remote whose URL is recorded as `git_url`; `origin` is used when unset.
+    pub remote: Option<String>,
+    /// Use the date of the last commit as `date_modified` instead of the directory's
+    /// modification time, which changes on every build.
+    pub commit_date_as_modified: bool,
+    /// The number of contributors recorded, most active first; 5 when unset.
+    pub max_contributors: Option<usize>,
+    /// Merge the names and emails an author committed under as the repository's
+    /// `.mailmap` (or git's `mailmap.file` setting) says. On unless set to `false`.
+    pub mailmap: Option<bool>,
+}
+
+/// Tag generation settings.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct TagConfig {
+    /// Tags that are never generated automatically, compared case-insensitively. A
+    /// namespace such as `format:` blocks every tag in it.
+    pub blacklist: Vec<String>,
+    /// Whether to add the general tags every project of a type gets, such as `cli`
+    /// and `software development` for programming projects; on when unset.
+    pub default_tags: Option<bool>,
+    /// The number of files that must share an extension before it yields a language
+    /// or audio-format tag.
+    pub min_files: Option<usize>,
+    /// The percentage of the project's files that must share an extension before it
+    /// yields a language or audio-format tag.
+    pub min_percent: Option<f64>,
+}
+
+impl Config {
+    /// The contents of a new configuration file created by `tagger config edit`:
+    /// every setting is left at its default, with a few commented-out examples.
+    pub const TEMPLATE: &'static str = "\
+# tagger configuration. Every setting is optional; see the README for all of them.
+
+# language = \"es\"
+
+# [tags]
+# blacklist = [\"software development\"]
+
+# [scan]
+# exclude = [\"Bounces/**\"]
+";
+
+    /// Loads the configuration from a TOML file.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let content = fs::read_to_string(path)?;
+        let invalid = |message: String| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid configuration in {}: {}", path.display(), message),
+            )
+        };
+
+        let config: Config = toml::from_str(&content).map_err(|e| invalid(e.to_string()))?;
+        for (index, rule) in config.rules.iter().enumerate() {
+            rule.validate().map_err(|e| invalid(format!("rule {} {}", index + 1, e)))?;
+        }
+        Ok(config)
+    }
+
+    /// Loads the configuration from its default location, or returns the defaults
+    /// when no configuration file exists.
+    ///
+    /// The location can be overridden with the `TAGGER_CONFIG` environment variable
+    /// and otherwise defaults to `config.toml` in the configuration directory (see
+    /// [`dirs`]), e.g. `~/.config/tagger/config.toml` on Linux.
+    ///
+    /// The tag rules of installed bundles, the user's taxonomy, and the installed
+    /// plugins are loaded along with it.
+    pub fn load_default() -> io::Result<Self> {
+        let path = Self::default_location()?;
+        let mut config = if path.exists() {
+            info!("Loading configuration from {}", path.display());
+            Self::load(&path)?
+        } else {
+            Config::default()
+        };
+
+        config.rules.extend(bundle::installed_rules());
+        config.plugins = plugins::installed_plugins();
+        let taxonomy_path = Taxonomy::default_location()?;
+        if taxonomy_path.exists() {
+            info!("Loading taxonomy from {}", taxonomy_path.display());
+            config.taxonomy = Taxonomy::load(&taxonomy_path)?;
+        }
+        Ok(config)
+    }
+
+    /// Returns the default configuration file location.
+    pub fn default_location() -> io::Result<PathBuf> {
+        if let Some(path) = env::var_os("TAGGER_CONFIG") {
+            return Ok(PathBuf::from(path));
+        }
+
+        Ok(dirs::config_dir()?.join("config.toml"))
+    }
+
+    /// Returns `true` if the tag must not be generated for projects of the given type,
+    /// either through the global blacklist or the type's own blacklist.
+    ///
+    /// Blacklist entries are tag patterns (see [`tags::matches`]): `rust` blocks
+    /// `lang:rust`, and `format:` blocks every format tag.
+    pub fn is_blacklisted(&self, project_type: &str, tag: &str) -> bool {
+        let type_blacklist = self
+            .types
+            .get(project_type)
+            .map(|settings| settings.blacklist.as_slice())
+            .unwrap_or_default();
+
+        self.tags
+            .blacklist
+            .iter()
+            .chain(type_blacklist)
+            .any(|blocked| tags::matches(tag, blocked))
+    }
+
+    /// Returns `true` if projects of the given type get their type's general tags:
+    /// the type's own setting wins over the global one, and both default to on.
+    pub fn default_tags(&self, project_type: &str) -> bool {
+        self.types
+            .get(project_type)
+            .and_then(|settings| settings.default_tags)
+            .or(self.tags.default_tags)
+            .unwrap_or(true)
+    }
+
+    /// Returns the refresh policy for projects of the given type: the type's own, the
+    /// `default` entry, or [`RefreshPolicy::OnChange`].
+    pub fn refresh_policy(&self, project_type: &str) -> RefreshPolicy {
+        self.refresh
+            .get(project_type)
+            .or_else(|| self.refresh.get("default"))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Returns the configured root the directory is in, the innermost one when roots
+    /// are nested.
+    pub fn root_for(&self, directory: &Path) -> Option<&RootConfig> {
+        let directory = fs::canonicalize(directory).unwrap_or_else(|_| directory.to_path_buf());
+        self.roots
+            .iter()
+            .filter_map(|root| {
+                let path = root.directory();
+                let path = fs::canonicalize(&path).unwrap_or(path);
+                directory.starts_with(&path).then(|| (path.components().count(), root))
+            })
+            .max_by_key(|(depth, _)| *depth)
+            .map(|(_, root)| root)
+    }
+
+    /// Returns the program, with any arguments, that opens projects of the given type,
+    /// if one is configured.
+    pub fn open_command(&self, project_type: &str) -> Option<&str> {
+        self.open
+            .get(project_type)
+            .or_else(|| self.open.get("default"))
+            .map(String::as_str)
+            .filter(|command| !command.trim().is_empty())
+    }
+
+    /// Returns `true` if `count` files out of `total` are enough to emit a tag inferred
+    /// from their extension in a project of the given type.
+    ///
+    /// The type's own thresholds take precedence over the global ones. Without any
+    /// configuration a single file is enough.
+    pub fn meets_extension_threshold(&self, project_type: &str, count: usize, total: usize) -> bool {
+        let type_settings = self.types.get(project_type);
+        let min_files = type_settings
+            .and_then(|settings| settings.min_files)
+            .or(self.tags.min_files)
+            .unwrap_or(1);
+        let min_percent = type_settings
+            .and_then(|settings| settings.min_percent)
+            .or(self.tags.min_percent)
+            .unwrap_or(0.0);
+
+        count >= min_files.max(1) && count as f64 * 100.0 >= min_percent * total as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_load_blacklists() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(
+            &path,
+            "[tags]\nblacklist = [\"Software Development\"]\n\n[types.music]\nblacklist = [\"production\"]\n",
+        )
+        .unwrap();
+
+        let config = Config::load(&path).unwrap();
+        assert!(config.is_blacklisted("programming", "software development"));
+        assert!(config.is_blacklisted("music", "production"));
+        assert!(!config.is_blacklisted("video", "production"));
+        assert!(config.default_tags("music"));
+    }
+
+    #[test]
+    fn test_load_rules() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "[[rules]]\nglobs = [\"**/Dockerfile\"]\ntags = [\"docker\"]\n").unwrap();
+        assert_eq!(Config::load(&path).unwrap().rules[0].globs, ["**/Dockerfile"]);
+
+        fs::write(&path, "[[rules]]\nregexes = [\"(\"]\ntags = [\"broken\"]\n").unwrap();
+        let error = Config::load(&path).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(error.to_string().contains("rule 1 has an invalid regex"), "{}", error);
+    }
+
+    #[test]
+    fn test_roots() {
+        let dir = tempdir().unwrap();
+        let sessions = dir.path().join("sessions");
+        fs::create_dir_all(sessions.join("live/set")).unwrap();
+        let config: Config = toml::from_str(&format!(
+            "[[roots]]\npath = {:?}\ntype = \"music\"\nschedule = \"weekly\"\n\n[[roots]]\npath = {:?}\n",
+            sessions,
+            sessions.join("live")
+        ))
+        .unwrap();
+
+        assert_eq!(config.roots[0].project_type.as_deref(), Some("music"));
+        assert_eq!(config.roots[0].schedule, RefreshPolicy::Every(Duration::from_secs(7 * 24 * 60 * 60)));
+        assert_eq!(config.roots[1].schedule, RefreshPolicy::OnChange);
+        assert_eq!(config.root_for(&sessions.join("live/set")).unwrap().path, sessions.join("live"));
+        assert_eq!(config.root_for(&sessions).unwrap().path, sessions);
+        assert!(config.root_for(dir.path()).is_none());
+
+        let home: RootConfig = toml::from_str("path = \"~/Code\"").unwrap();
+        assert_eq!(home.directory(), dirs::home_dir().unwrap().join("Code"));
+    }
+
+    #[test]
+    fn test_default_tags_setting() {
+        let mut config: Config =
+            toml::from_str("[tags]\ndefault_tags = false\n\n[types.music]\ndefault_tags = true\n").unwrap();
+        assert!(!config.default_tags("programming"));
+        assert!(config.default_tags("music"));
+
+        config.tags.default_tags = None;
+        assert!(config.default_tags("programming"));
+    }
+
+    #[test]
+    fn test_load_refresh_policies() {
+        let config: Config =
+            toml::from_str("[refresh]\ndefault = \"never\"\nmusic = \"weekly\"\nvideo = \"3d\"\n").unwrap();
+        assert_eq!(
+            config.refresh_policy("music"),
+            RefreshPolicy::Every(Duration::from_secs(7 * 24 * 60 * 60))
+        );
+        assert_eq!(
+            config.refresh_policy("video"),
+            RefreshPolicy::Every(Duration::from_secs(3 * 24 * 60 * 60))
+        );
+        assert_eq!(config.refresh_policy("programming"), RefreshPolicy::Never);
+        assert_eq!(Config::default().refresh_policy("music"), RefreshPolicy::OnChange);
+
+        assert!(toml::from_str::<Config>("[refresh]\nmusic = \"soon\"\n").is_err());
+    }
+
+    #[test]
+    fn test_extension_thresholds() {
+        let mut config = Config::default();
+        assert!(config.meets_extension_threshold("programming", 1, 500));
+
+        config.tags.min_files = Some(3);
+        config.types.entry("music".to_string()).or_default().min_percent = Some(10.0);
+        assert!(!config.meets_extension_threshold("programming", 2, 10));
+        assert!(config.meets_extension_threshold("programming", 3, 100));
+        assert!(!config.meets_extension_threshold("music", 3, 100));
+        assert!(config.meets_extension_threshold("music", 10, 100));
+    }
+
+    #[test]
+    fn test_scan_excludes() {
+        let scan = ScanConfig {
+            exclude: vec!["Bounces/**".to_string(), "*.tmp".to_string()],
+            include: vec!["*.rpp".to_string(), "Stems/*.wav".to_string()],
+            ..ScanConfig::default()
+        };
+        assert!(scan.excludes(Path::new("Bounces"), true));
+        assert!(scan.excludes(Path::new("Bounces/mix.wav"), false));
+        assert!(scan.excludes(Path::new("Stems/old.tmp"), false));
+        assert!(!scan.excludes(Path::new("Stems"), true));
+        assert!(!scan.excludes(Path::new("Stems/kick.wav"), false));
+        assert!(!scan.excludes(Path::new("sessions/song.rpp"), false));
+        assert!(scan.excludes(Path::new("notes.txt"), false));
+        assert!(!ScanConfig::default().excludes(Path::new("notes.txt"), false));
+    }
+
+    #[test]
+    fn test_output_file_for() {
+        let dir = tempdir().unwrap();
+        let project = dir.path().join("song");
+        fs::create_dir_all(dir.path().join("meta")).unwrap();
+        fs::create_dir_all(&project).unwrap();
+
+        let output = |path: Option<PathBuf>| OutputConfig { path, ..Default::default() }.file_for(&project);
+        assert_eq!(output(None), project.join("project_info.toml"));
+        assert_eq!(output(Some(".project_info.toml".into())), project.join(".project_info.toml"));
+        assert_eq!(output(Some(dir.path().join("all.toml"))), dir.path().join("all.toml"));
+
+        // Folders collect one file per project, named after its directory and path.
+        let name = format!("{}.toml", registry::file_stem(&fs::canonicalize(&project).unwrap()));
+        assert!(name.starts_with("song-"));
+        assert_eq!(output(Some(dir.path().join("meta"))), dir.path().join("meta").join(&name));
+        assert_eq!(output(Some("../new/".into())), project.join("../new/").join(&name));
+
+        // Projects in directories of the same name do not share a file.
+        let other = dir.path().join("b/song");
+        fs::create_dir_all(&other).unwrap();
+        let meta = OutputConfig { path: Some(dir.path().join("meta")), ..Default::default() };
+        assert_ne!(meta.file_for(&project), meta.file_for(&other));
+
+        // Archives keep their information next to them.
+        let archive = dir.path().join("old.zip");
+        fs::write(&archive, "").unwrap();
+        assert_eq!(OutputConfig::default().file_for(&archive), dir.path().join("old.zip.toml"));
+
+        // Archives keep their information next to them.
+        let archive = dir.path().join("old.zip");
+        fs::write(&archive, "").unwrap();
+        assert_eq!(OutputConfig::default().file_for(&archive), dir.path().join("old.zip.toml"));
+    }
+}
@@ -0,0 +1,109 @@
+//! Where tagger keeps its own files.
+//!
+//! Each kind of file lives where the platform expects it, as the `directories` crate
+//! works it out:
+//!
+//! | Kind   | Linux and other Unix                               | macOS                                   | Windows                         |
+//! |--------|----------------------------------------------------|-----------------------------------------|---------------------------------|
+//! | config | `$XDG_CONFIG_HOME/tagger` or `~/.config/tagger`    | `~/Library/Application Support/tagger`  | `%APPDATA%\tagger\config`       |
+//! | data   | `$XDG_DATA_HOME/tagger` or `~/.local/share/tagger` | `~/Library/Application Support/tagger`  | `%APPDATA%\tagger\data`         |
+//! | cache  | `$XDG_CACHE_HOME/tagger` or `~/.cache/tagger`      | `~/Library/Caches/tagger`               | `%LOCALAPPDATA%\tagger\cache`   |
+//!
+//! XDG variables that are not absolute paths are ignored.
+
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use directories::{BaseDirs, ProjectDirs};
+
+/// The name of tagger's folder in each location.
+const APP_NAME: &str = "tagger";
+
+/// A kind of file tagger keeps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DirKind {
+    /// Settings the user writes: `config.toml`, `taxonomy.toml`, and templates.
+    Config,
+    /// Files tagger manages: the registry, installed bundles, and sync times.
+    Data,
+    /// Files that can be deleted at any time: scan results and network responses.
+    Cache,
+}
+
+impl DirKind {
+    /// Every kind.
+    pub const ALL: [DirKind; 3] = [DirKind::Config, DirKind::Data, DirKind::Cache];
+
+    /// Returns the kind's name on the command line.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DirKind::Config => "config",
+            DirKind::Data => "data",
+            DirKind::Cache => "cache",
+        }
+    }
+
+    /// Returns the directory for this kind of file.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `NotFound` error if there is no home directory, or it is not an
+    /// absolute path, so the directory would end up wherever tagger was started.
+    pub fn dir(self) -> io::Result<PathBuf> {
+        ProjectDirs::from_path(PathBuf::from(APP_NAME))
+            .map(|dirs| match self {
+                DirKind::Config => dirs.config_dir().to_path_buf(),
+                DirKind::Data => dirs.data_dir().to_path_buf(),
+                DirKind::Cache => dirs.cache_dir().to_path_buf(),
+            })
+            .filter(|dir| dir.is_absolute())
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("no home directory to keep tagger's {} files in", self),
+                )
+            })
+    }
+}
+
+impl fmt::Display for DirKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for DirKind {
+    type Err = String;
+
+    /// Parses a kind name, ignoring case.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim().to_lowercase();
+        Self::ALL.into_iter().find(|kind| kind.as_str() == value).ok_or_else(|| {
+            let names: Vec<&str> = Self::ALL.iter().map(|kind| kind.as_str()).collect();
+            format!("unknown directory '{}' (expected one of: {})", value, names.join(", "))
+        })
+    }
+}
+
+/// Returns the user's home directory, or `None` when it is unknown or not an
+/// absolute path.
+pub fn home_dir() -> Option<PathBuf> {
+    BaseDirs::new().map(|dirs| dirs.home_dir().to_path_buf()).filter(|home| home.is_absolute())
+}
+
+/// Returns the directory for the user's settings.
+pub fn config_dir() -> io::Result<PathBuf> {
+    DirKind::Config.dir()
+}
+
+/// Returns the directory for files tagger manages.
+pub fn data_dir() -> io::Result<PathBuf> {
+    DirKind::Data.dir()
+}
+
+/// Returns the directory for cached files.
+pub fn cache_dir() -> io::Result<PathBuf> {
+    DirKind::Cache.dir()
+}
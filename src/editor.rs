@@ -0,0 +1,366 @@
+use std::io;
+
+use log::info;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+
+use crate::messages::{tr, Message};
+use crate::project_info::ProjectInfo;
+
+/// The part of the form that has the focus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Tags,
+    Alias,
+    Notes,
+}
+
+/// What the text being typed will become.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Input {
+    Tag,
+    Alias,
+    /// A note: a new one, or the one at the index.
+    Note(Option<usize>),
+}
+
+/// How the user left the editor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Save,
+    Quit,
+}
+
+/// A form for reviewing generated project information before it is saved.
+#[derive(Debug)]
+struct Editor {
+    project: ProjectInfo,
+    /// Every suggested or custom tag, and whether it will be saved.
+    tags: Vec<(String, bool)>,
+    section: Section,
+    tag_cursor: usize,
+    note_cursor: usize,
+    input: Option<(Input, String)>,
+}
+
+impl Editor {
+    /// Creates the form with the given tags checked, followed by the project's
+    /// unconfirmed suggestions, unchecked.
+    fn new(project: ProjectInfo, tags: Vec<String>) -> Self {
+        let mut tags: Vec<(String, bool)> = tags.into_iter().map(|tag| (tag, true)).collect();
+        for suggested in &project.suggested_tags {
+            if !tags.iter().any(|(tag, _)| tag == suggested) {
+                tags.push((suggested.clone(), false));
+            }
+        }
+        Editor {
+            project,
+            tags,
+            section: Section::Tags,
+            tag_cursor: 0,
+            note_cursor: 0,
+            input: None,
+        }
+    }
+
+    /// Applies a key press, returning the action that ends the editor, if any.
+    fn handle_key(&mut self, key: KeyCode) -> Option<Action> {
+        if let Some((target, text)) = &mut self.input {
+            match key {
+                KeyCode::Char(c) => text.push(c),
+                KeyCode::Backspace => {
+                    text.pop();
+                }
+                KeyCode::Enter => {
+                    let target = *target;
+                    let text = text.trim().to_string();
+                    self.input = None;
+                    self.commit(target, text);
+                }
+                KeyCode::Esc => self.input = None,
+                _ => {}
+            }
+            return None;
+        }
+
+        match key {
+            KeyCode::Char('s') => return Some(Action::Save),
+            KeyCode::Char('q') | KeyCode::Esc => return Some(Action::Quit),
+            KeyCode::Tab => {
+                self.section = match self.section {
+                    Section::Tags => Section::Alias,
+                    Section::Alias => Section::Notes,
+                    Section::Notes => Section::Tags,
+                }
+            }
+            KeyCode::BackTab => {
+                self.section = match self.section {
+                    Section::Tags => Section::Notes,
+                    Section::Alias => Section::Tags,
+                    Section::Notes => Section::Alias,
+                }
+            }
+            KeyCode::Up | KeyCode::Char('k') => match self.section {
+                Section::Tags => self.tag_cursor = self.tag_cursor.saturating_sub(1),
+                Section::Notes => self.note_cursor = self.note_cursor.saturating_sub(1),
+                Section::Alias => {}
+            },
+            KeyCode::Down | KeyCode::Char('j') => match self.section {
+                Section::Tags if self.tag_cursor + 1 < self.tags.len() => self.tag_cursor += 1,
+                Section::Notes if self.note_cursor + 1 < self.project.notes.len() => self.note_cursor += 1,
+                _ => {}
+            },
+            KeyCode::Char(' ') if self.section == Section::Tags => {
+                if let Some((_, checked)) = self.tags.get_mut(self.tag_cursor) {
+                    *checked = !*checked;
+                }
+            }
+            KeyCode::Char('a') => {
+                self.input = Some(match self.section {
+                    Section::Tags => (Input::Tag, String::new()),
+                    Section::Alias => (Input::Alias, self.project.alias.clone()),
+                    Section::Notes => (Input::Note(None), String::new()),
+                })
+            }
+            KeyCode::Enter | KeyCode::Char('e') => match self.section {
+                Section::Alias => self.input = Some((Input::Alias, self.project.alias.clone())),
+                Section::Notes => {
+                    if let Some(note) = self.project.notes.get(self.note_cursor) {
+                        self.input = Some((Input::Note(Some(self.note_cursor)), note.clone()));
+                    }
+                }
+                Section::Tags => {}
+            },
+            KeyCode::Char('d') if self.section == Section::Notes && self.note_cursor < self.project.notes.len() => {
+                self.project.notes.remove(self.note_cursor);
+                self.note_cursor = self.note_cursor.min(self.project.notes.len().saturating_sub(1));
+            }
+            _ => {}
+        }
+        None
+    }
+
+    /// Stores typed text. Empty text clears the alias and deletes an edited note.
+    fn commit(&mut self, target: Input, text: String) {
+        match target {
+            Input::Tag => {
+                let tag = text.to_lowercase();
+                if tag.is_empty() {
+                    return;
+                }
+                match self.tags.iter().position(|(existing, _)| *existing == tag) {
+                    Some(index) => {
+                        self.tags[index].1 = true;
+                        self.tag_cursor = index;
+                    }
+                    None => {
+                        self.tags.push((tag, true));
+                        self.tag_cursor = self.tags.len() - 1;
+                    }
+                }
+            }
+            Input::Alias => self.project.set_alias(text),
+            Input::Note(None) => {
+                if !text.is_empty() {
+                    self.project.add_note(text);
+                    self.note_cursor = self.project.notes.len() - 1;
+                }
+            }
+            Input::Note(Some(index)) => {
+                if text.is_empty() {
+                    self.project.notes.remove(index);
+                    self.note_cursor = self.note_cursor.min(self.project.notes.len().saturating_sub(1));
+                } else {
+                    self.project.notes[index] = text;
+                }
+            }
+        }
+    }
+
+    /// Returns the project with the checked tags; suggestions left unchecked are
+    /// dropped.
+    fn into_project(self) -> ProjectInfo {
+        let mut project = self.project;
+        project.suggested_tags.clear();
+        project.tags = self.tags.into_iter().filter(|(_, checked)| *checked).map(|(tag, _)| tag).collect();
+        project.tags.sort();
+        project
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [header, body, footer] =
+            Layout::vertical([Constraint::Length(1), Constraint::Min(3), Constraint::Length(1)]).areas(frame.area());
+        let [tags_area, side] = Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)]).areas(body);
+        let [alias_area, notes_area] = Layout::vertical([Constraint::Length(3), Constraint::Min(3)]).areas(side);
+
+        let title = format!(
+            "{} — {}",
+            tr(Message::ProjectName, &[&self.project.name]),
+            tr(Message::ProjectType, &[&self.project.project_type])
+        );
+        frame.render_widget(Paragraph::new(title).style(Style::new().add_modifier(Modifier::BOLD)), header);
+
+        let block = |label: Message, section: Section| {
+            let block = Block::bordered().title(tr(label, &[]));
+            if self.section == section {
+                block.border_style(Style::new().add_modifier(Modifier::BOLD | Modifier::REVERSED))
+            } else {
+                block
+            }
+        };
+        let highlight = Style::new().add_modifier(Modifier::REVERSED);
+
+        let tags: Vec<ListItem> = self
+            .tags
+            .iter()
+            .map(|(tag, checked)| {
+                let tag = if self.project.suggested_tags.contains(tag) {
+                    tr(Message::SuggestedTag, &[tag])
+                } else {
+                    tag.clone()
+                };
+                ListItem::new(format!("[{}] {}", if *checked { "x" } else { " " }, tag))
+            })
+            .collect();
+        let mut tag_state = ListState::default().with_selected(Some(self.tag_cursor).filter(|_| !self.tags.is_empty()));
+        let tags = List::new(tags).block(block(Message::EditorTags, Section::Tags));
+        let tags = if self.section == Section::Tags { tags.highlight_style(highlight) } else { tags };
+        frame.render_stateful_widget(tags, tags_area, &mut tag_state);
+
+        frame.render_widget(
+            Paragraph::new(self.project.alias.as_str()).block(block(Message::EditorAlias, Section::Alias)),
+            alias_area,
+        );
+
+        let notes: Vec<ListItem> = self.project.notes.iter().map(|note| ListItem::new(note.as_str())).collect();
+        let mut note_state =
+            ListState::default().with_selected(Some(self.note_cursor).filter(|_| !self.project.notes.is_empty()));
+        let notes = List::new(notes).block(block(Message::EditorNotes, Section::Notes));
+        let notes = if self.section == Section::Notes { notes.highlight_style(highlight) } else { notes };
+        frame.render_stateful_widget(notes, notes_area, &mut note_state);
+
+        let status = match &self.input {
+            Some((target, text)) => {
+                let label = match target {
+                    Input::Tag => Message::EditorNewTag,
+                    Input::Alias => Message::EditorEditAlias,
+                    Input::Note(_) => Message::EditorEditNote,
+                };
+                Line::from(format!("{} {}▏", tr(label, &[]), text))
+            }
+            None => Line::from(tr(Message::EditorHelp, &[])),
+        };
+        frame.render_widget(Paragraph::new(status), footer);
+    }
+
+    fn run(mut self, terminal: &mut DefaultTerminal) -> io::Result<Option<ProjectInfo>> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            // Only presses count; some terminals also report releases.
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            match self.handle_key(key.code) {
+                Some(Action::Save) => return Ok(Some(self.into_project())),
+                Some(Action::Quit) => return Ok(None),
+                None => {}
+            }
+        }
+    }
+}
+
+/// Opens a full-screen form for reviewing a project's information before it is saved.
+///
+/// The user can toggle the suggested tags, add custom tags, and edit the alias and
+/// notes. Every tag starts checked.
+///
+/// Returns the edited project when the user saves, or `None` when they quit.
+///
+/// # Arguments
+///
+/// * `project` - The generated project information.
+/// * `tags` - The tags to offer, such as the generated tags plus custom tags recorded earlier.
+pub fn run(project: ProjectInfo, tags: Vec<String>) -> io::Result<Option<ProjectInfo>> {
+    let mut terminal = ratatui::try_init()?;
+    let result = Editor::new(project, tags).run(&mut terminal);
+    ratatui::try_restore()?;
+
+    if result.as_ref().is_ok_and(Option::is_none) {
+        info!("Editor closed without saving.");
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(editor: &mut Editor, keys: &str) {
+        for c in keys.chars() {
+            editor.handle_key(KeyCode::Char(c));
+        }
+    }
+
+    #[test]
+    fn test_suggested_tags_start_unchecked() {
+        let project = ProjectInfo {
+            suggested_tags: vec!["genre:synth-pop".to_string(), "podcast".to_string()],
+            ..Default::default()
+        };
+        let mut editor = Editor::new(project, vec!["music".to_string()]);
+
+        // Confirm the first suggestion only.
+        editor.handle_key(KeyCode::Down);
+        editor.handle_key(KeyCode::Char(' '));
+
+        let project = editor.into_project();
+        assert_eq!(project.tags, vec!["genre:synth-pop", "music"]);
+        assert!(project.suggested_tags.is_empty());
+    }
+
+    #[test]
+    fn test_edit_tags_alias_and_notes() {
+        let project = ProjectInfo {
+            notes: vec!["Old note".to_string()],
+            ..Default::default()
+        };
+        let mut editor = Editor::new(project, vec!["rust".to_string(), "cli".to_string()]);
+
+        // Uncheck "cli" and add a custom tag.
+        editor.handle_key(KeyCode::Down);
+        editor.handle_key(KeyCode::Char(' '));
+        press(&mut editor, "aGame");
+        editor.handle_key(KeyCode::Enter);
+
+        // Set the alias; typed letters do not trigger commands while editing.
+        editor.handle_key(KeyCode::Tab);
+        press(&mut editor, "aspaceship");
+        editor.handle_key(KeyCode::Enter);
+
+        // Replace the note, then add one and cancel it.
+        editor.handle_key(KeyCode::Tab);
+        editor.handle_key(KeyCode::Enter);
+        for _ in 0.."Old note".len() {
+            editor.handle_key(KeyCode::Backspace);
+        }
+        press(&mut editor, "New note");
+        editor.handle_key(KeyCode::Enter);
+        press(&mut editor, "adiscarded");
+        editor.handle_key(KeyCode::Esc);
+
+        assert_eq!(editor.handle_key(KeyCode::Char('s')), Some(Action::Save));
+        let project = editor.into_project();
+        assert_eq!(project.tags, vec!["game", "rust"]);
+        assert_eq!(project.alias, "spaceship");
+        assert_eq!(project.notes, vec!["New note"]);
+    }
+}
@@ -0,0 +1,418 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use chrono::{DateTime, Local};
+use log::{debug, info, warn};
+
+use crate::config::{dirs, ScanConfig, SuggestConfig};
+use crate::project_info::ProjectInfo;
+use crate::registry::fnv1a;
+
+pub mod github;
+pub mod suggest;
+
+use github::GitHubProvider;
+use suggest::SuggestProvider;
+
+/// A source of network metadata (e.g. the GitHub API) that can enrich a project.
+pub trait Provider {
+    /// The provider's name, used for enable switches and the cache directory.
+    fn name(&self) -> &'static str;
+
+    /// The minimum time between two requests to this provider.
+    fn min_interval(&self) -> Duration {
+        Duration::from_millis(500)
+    }
+
+    /// Adds the provider's metadata to the project in `directory`, fetching through
+    /// `enrichment`.
+    fn enrich(&self, enrichment: &Enrichment, directory: &Path, info: &mut ProjectInfo);
+}
+
+/// Settings for the enrichment layer.
+#[derive(Debug, Clone)]
+pub struct EnrichmentConfig {
+    /// Never touch the network; only previously cached responses are used.
+    pub offline: bool,
+    /// Names of the providers that may run, such as `github`. None run by default,
+    /// apart from `suggest` when it is configured.
+    pub enabled_providers: BTreeSet<String>,
+    /// Names of providers that must not run, even when enabled.
+    pub disabled_providers: BTreeSet<String>,
+    /// Directory in which responses are cached; `None` caches nothing.
+    pub cache_dir: Option<PathBuf>,
+    /// How long a cached response is considered fresh.
+    pub cache_ttl: Duration,
+    /// How many times a failed request is retried.
+    pub max_retries: u32,
+    /// The delay before the first retry, doubled for every further attempt.
+    pub initial_backoff: Duration,
+    /// Settings for asking a language model for tag suggestions; `None` leaves the
+    /// `suggest` provider out.
+    pub suggest: Option<SuggestConfig>,
+    /// The `[scan]` settings; files they leave out are not described to a language model.
+    pub scan: ScanConfig,
+}
+
+impl Default for EnrichmentConfig {
+    fn default() -> Self {
+        EnrichmentConfig {
+            offline: false,
+            enabled_providers: BTreeSet::new(),
+            disabled_providers: BTreeSet::new(),
+            cache_dir: default_cache_dir(),
+            cache_ttl: Duration::from_secs(24 * 60 * 60),
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            suggest: None,
+            scan: ScanConfig::default(),
+        }
+    }
+}
+
+/// Returns the default response cache location, `enrichment` in the cache directory.
+fn default_cache_dir() -> Option<PathBuf> {
+    dirs::cache_dir().ok().map(|dir| dir.join("enrichment"))
+}
+
+/// A cached HTTP response body.
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedResponse {
+    url: String,
+    fetched_at: DateTime<Local>,
+    body: String,
+}
+
+/// The single gateway for every network call made by tagger.
+///
+/// Provider requests are cached on disk, throttled per provider, and retried with
+/// exponential backoff. In offline mode only cached responses are returned. Other
+/// transfers, such as bundle downloads and S3 or WebDAV remotes, go through
+/// [`Enrichment::client`], which refuses them in offline mode.
+pub struct Enrichment {
+    config: EnrichmentConfig,
+    agent: ureq::Agent,
+    transfer_agent: ureq::Agent,
+    last_request: Mutex<HashMap<&'static str, Instant>>,
+}
+
+impl Enrichment {
+    /// Creates the enrichment layer with the given settings.
+    pub fn new(config: EnrichmentConfig) -> Self {
+        let agent = ureq::Agent::config_builder()
+            .http_status_as_error(false)
+            .timeout_global(Some(Duration::from_secs(10)))
+            .build()
+            .into();
+
+        let transfer_agent = ureq::Agent::config_builder()
+            .http_status_as_error(false)
+            .allow_non_standard_methods(true)
+            .timeout_global(Some(Duration::from_secs(60)))
+            .build()
+            .into();
+
+        Enrichment {
+            config,
+            agent,
+            transfer_agent,
+            last_request: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns every built-in provider, with the opt-in ones that were configured.
+    fn providers(&self) -> Vec<Box<dyn Provider>> {
+        let mut providers: Vec<Box<dyn Provider>> = vec![Box::new(GitHubProvider)];
+        if let Some(suggest) = &self.config.suggest {
+            providers.push(Box::new(SuggestProvider::new(suggest.clone(), self.config.scan.clone())));
+        }
+        providers
+    }
+
+    /// Returns `true` if the named provider is allowed to run: it was enabled (or, for
+    /// `suggest`, configured) and not disabled.
+    pub fn is_enabled(&self, provider: &str) -> bool {
+        let opted_in = match provider {
+            "suggest" => self.config.suggest.is_some(),
+            name => self.config.enabled_providers.contains(name),
+        };
+        opted_in && !self.config.disabled_providers.contains(provider)
+    }
+
+    /// Returns the HTTP client for transfers that are not provider requests, such as
+    /// bundle downloads and S3 or WebDAV remotes. Its responses are not cached, and
+    /// HTTP error statuses are returned rather than treated as failures.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error saying that `what` needs the network when tagger is offline.
+    pub fn client(&self, what: &str) -> io::Result<ureq::Agent> {
+        self.ensure_online(what)?;
+        Ok(self.transfer_agent.clone())
+    }
+
+    /// Fails, saying that `what` needs the network, when tagger is offline.
+    pub fn ensure_online(&self, what: &str) -> io::Result<()> {
+        if self.config.offline {
+            info!("Offline: not {}", what);
+            return Err(io::Error::other(format!("{} needs the network, but tagger is offline", what)));
+        }
+        Ok(())
+    }
+
+    /// Runs every enabled provider against the project in `directory`.
+    pub fn enrich(&self, directory: &Path, info: &mut ProjectInfo) {
+        for provider in self.providers() {
+            if !self.is_enabled(provider.name()) {
+                debug!("Provider '{}' is not enabled; skipping.", provider.name());
+                continue;
+            }
+            provider.enrich(self, directory, info);
+        }
+    }
+
+    /// Fetches a URL with GET on behalf of a provider.
+    ///
+    /// Fresh cached responses are returned without a request. When offline, or when
+    /// every attempt fails, a stale cached response is used if one exists.
+    ///
+    /// # Returns
+    ///
+    /// The response body, or `None` if nothing could be obtained.
+    pub fn get(&self, provider: &dyn Provider, url: &str, headers: &[(&str, String)]) -> Option<String> {
+        self.fetch(provider, url, headers, None)
+    }
+
+    /// Sends a body to a URL with POST on behalf of a provider, for APIs that answer
+    /// queries rather than serve resources.
+    ///
+    /// Responses are cached by URL and body, and otherwise handled like those of
+    /// [`Enrichment::get`].
+    pub fn post(&self, provider: &dyn Provider, url: &str, headers: &[(&str, String)], body: &str) -> Option<String> {
+        self.fetch(provider, url, headers, Some(body))
+    }
+
+    fn fetch(&self, provider: &dyn Provider, url: &str, headers: &[(&str, String)], body: Option<&str>) -> Option<String> {
+        let cache_key = match body {
+            Some(body) => format!("{}\n{}", url, body),
+            None => url.to_string(),
+        };
+        let cache_path = self.cache_path(provider.name(), &cache_key);
+        let cached = cache_path.as_deref().and_then(Self::read_cache);
+
+        if let Some(cached) = &cached {
+            let age = Local::now().signed_duration_since(cached.fetched_at);
+            let fresh = age.to_std().is_ok_and(|age| age < self.config.cache_ttl);
+            if fresh || self.config.offline {
+                info!("Using cached response for {}", url);
+                return Some(cached.body.clone());
+            }
+        }
+
+        if self.config.offline {
+            info!("Offline: no cached response for {}", url);
+            return None;
+        }
+
+        match self.fetch_with_retries(provider, url, headers, body) {
+            Some(body) => {
+                if let Some(cache_path) = &cache_path {
+                    self.write_cache(cache_path, url, &body);
+                }
+                Some(body)
+            }
+            None => cached.map(|cached| {
+                warn!("Falling back to stale cached response for {}", url);
+                cached.body
+            }),
+        }
+    }
+
+    /// Performs the request, retrying rate-limited, server, and transport errors
+    /// with exponential backoff.
+    fn fetch_with_retries(
+        &self,
+        provider: &dyn Provider,
+        url: &str,
+        headers: &[(&str, String)],
+        body: Option<&str>,
+    ) -> Option<String> {
+        let mut backoff = self.config.initial_backoff;
+
+        for attempt in 0..=self.config.max_retries {
+            if attempt > 0 {
+                info!("Retrying {} in {:?} (attempt {})", url, backoff, attempt + 1);
+                thread::sleep(backoff);
+                backoff *= 2;
+            }
+
+            self.throttle(provider);
+
+            let result = match body {
+                None => {
+                    let mut request = self.agent.get(url).header("User-Agent", "tagger");
+                    for (name, value) in headers {
+                        request = request.header(*name, value);
+                    }
+                    request.call()
+                }
+                Some(body) => {
+                    let mut request = self.agent.post(url).header("User-Agent", "tagger");
+                    for (name, value) in headers {
+                        request = request.header(*name, value);
+                    }
+                    request.send(body)
+                }
+            };
+
+            let mut response = match result {
+                Ok(response) => response,
+                Err(e) => {
+                    warn!("Request to {} failed: {}", url, e);
+                    continue;
+                }
+            };
+
+            let status = response.status().as_u16();
+            match status {
+                200..=299 => return response.body_mut().read_to_string().ok(),
+                429 | 500..=599 => {
+                    // Honour Retry-After when the server provides one.
+                    if let Some(seconds) = response
+                        .headers()
+                        .get("retry-after")
+                        .and_then(|value| value.to_str().ok())
+                        .and_then(|value| value.parse::<u64>().ok())
+                    {
+                        backoff = backoff.max(Duration::from_secs(seconds));
+                    }
+                    warn!("Request to {} returned HTTP {}", url, status);
+                }
+                _ => {
+                    warn!("Request to {} returned HTTP {}; not retrying.", url, status);
+                    return None;
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Waits until the provider's minimum request interval has elapsed.
+    fn throttle(&self, provider: &dyn Provider) {
+        let mut last_request = self.last_request.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(last) = last_request.get(provider.name()) {
+            let elapsed = last.elapsed();
+            if elapsed < provider.min_interval() {
+                thread::sleep(provider.min_interval() - elapsed);
+            }
+        }
+        last_request.insert(provider.name(), Instant::now());
+    }
+
+    /// Returns the cache file for a provider's request, identified by its URL (and
+    /// body, for POST requests), or `None` when responses are not cached.
+    fn cache_path(&self, provider: &str, key: &str) -> Option<PathBuf> {
+        let cache_dir = self.config.cache_dir.as_ref()?;
+        Some(cache_dir.join(provider).join(format!("{:016x}.json", fnv1a(key.as_bytes()))))
+    }
+
+    fn read_cache(path: &Path) -> Option<CachedResponse> {
+        let content = fs::read_to_string(path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    fn write_cache(&self, path: &Path, url: &str, body: &str) {
+        let cached = CachedResponse {
+            url: url.to_string(),
+            fetched_at: Local::now(),
+            body: body.to_string(),
+        };
+
+        let result = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| {
+                let json = serde_json::to_string(&cached)?;
+                fs::write(path, json)
+            });
+        if let Err(e) = result {
+            warn!("Unable to cache response for {}: {}", url, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    struct TestProvider;
+
+    impl Provider for TestProvider {
+        fn name(&self) -> &'static str {
+            "test"
+        }
+
+        fn enrich(&self, _enrichment: &Enrichment, _directory: &Path, _info: &mut ProjectInfo) {}
+    }
+
+    fn offline_config(cache_dir: &Path) -> EnrichmentConfig {
+        EnrichmentConfig {
+            offline: true,
+            cache_dir: Some(cache_dir.to_path_buf()),
+            ..EnrichmentConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_offline_refuses_transfers() {
+        let cache_dir = tempdir().unwrap();
+        let enrichment = Enrichment::new(offline_config(cache_dir.path()));
+
+        assert!(enrichment.client("downloading a bundle").is_err());
+        assert!(Enrichment::new(EnrichmentConfig::default()).client("downloading a bundle").is_ok());
+    }
+
+    #[test]
+    fn test_offline_without_cache_returns_none() {
+        let cache_dir = tempdir().unwrap();
+        let enrichment = Enrichment::new(offline_config(cache_dir.path()));
+
+        assert!(enrichment.get(&TestProvider, "https://example.invalid/", &[]).is_none());
+    }
+
+    #[test]
+    fn test_offline_uses_stale_cache() {
+        let cache_dir = tempdir().unwrap();
+        let enrichment = Enrichment::new(offline_config(cache_dir.path()));
+
+        let url = "https://example.invalid/data";
+        let cache_path = enrichment.cache_path("test", url).unwrap();
+        enrichment.write_cache(&cache_path, url, "{\"cached\": true}");
+
+        let body = enrichment.get(&TestProvider, url, &[]);
+        assert_eq!(body.as_deref(), Some("{\"cached\": true}"));
+    }
+
+    #[test]
+    fn test_providers_are_opt_in() {
+        assert!(!Enrichment::new(EnrichmentConfig::default()).is_enabled("github"));
+
+        let config = EnrichmentConfig {
+            enabled_providers: ["github".to_string(), "test".to_string()].into_iter().collect(),
+            disabled_providers: ["github".to_string()].into_iter().collect(),
+            ..EnrichmentConfig::default()
+        };
+        let enrichment = Enrichment::new(config);
+
+        assert!(!enrichment.is_enabled("github"));
+        assert!(enrichment.is_enabled("test"));
+        assert!(!enrichment.is_enabled("suggest"));
+    }
+}
@@ -0,0 +1,89 @@
+use std::env;
+use std::path::Path;
+use std::time::Duration;
+
+use log::{info, warn};
+
+use super::{Enrichment, Provider};
+use crate::project_info::ProjectInfo;
+
+/// Adds repository topics from the GitHub API as tags.
+///
+/// Set `GITHUB_TOKEN` to authenticate and raise the API rate limit.
+pub struct GitHubProvider;
+
+impl Provider for GitHubProvider {
+    fn name(&self) -> &'static str {
+        "github"
+    }
+
+    fn min_interval(&self) -> Duration {
+        Duration::from_secs(1)
+    }
+
+    fn enrich(&self, enrichment: &Enrichment, _directory: &Path, info: &mut ProjectInfo) {
+        let Some((owner, repo)) = info.git_url.as_deref().and_then(parse_github_repo) else {
+            return;
+        };
+
+        let url = format!("https://api.github.com/repos/{}/{}", owner, repo);
+        let mut headers = vec![("Accept", "application/vnd.github+json".to_string())];
+        if let Ok(token) = env::var("GITHUB_TOKEN") {
+            headers.push(("Authorization", format!("Bearer {}", token)));
+        }
+
+        let Some(body) = enrichment.get(self, &url, &headers) else {
+            return;
+        };
+
+        let json: serde_json::Value = match serde_json::from_str(&body) {
+            Ok(json) => json,
+            Err(e) => {
+                warn!("Unexpected GitHub API response for {}/{}: {}", owner, repo, e);
+                return;
+            }
+        };
+
+        // Add repository topics as tags.
+        if let Some(topics) = json.get("topics").and_then(|topics| topics.as_array()) {
+            for topic in topics.iter().filter_map(|topic| topic.as_str()) {
+                if !info.tags.iter().any(|tag| tag == topic) {
+                    info.tags.push(topic.to_string());
+                }
+            }
+            info.tags.sort();
+            info!("GitHub topics added for {}/{}: {:?}", owner, repo, topics);
+        }
+    }
+}
+
+/// Parses the owner and repository name from a GitHub remote URL.
+///
+/// Both `git@github.com:owner/repo.git` and `https://github.com/owner/repo` forms
+/// are accepted.
+fn parse_github_repo(url: &str) -> Option<(String, String)> {
+    let path = url
+        .strip_prefix("git@github.com:")
+        .or_else(|| url.strip_prefix("ssh://git@github.com/"))
+        .or_else(|| url.strip_prefix("https://github.com/"))
+        .or_else(|| url.strip_prefix("http://github.com/"))?;
+
+    let mut parts = path.trim_end_matches('/').trim_end_matches(".git").splitn(2, '/');
+    let owner = parts.next().filter(|owner| !owner.is_empty())?;
+    let repo = parts.next().filter(|repo| !repo.is_empty() && !repo.contains('/'))?;
+    Some((owner.to_string(), repo.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_github_repo() {
+        let expected = Some(("user".to_string(), "repo".to_string()));
+        assert_eq!(parse_github_repo("git@github.com:user/repo.git"), expected);
+        assert_eq!(parse_github_repo("https://github.com/user/repo"), expected);
+        assert_eq!(parse_github_repo("https://github.com/user/repo.git/"), expected);
+        assert_eq!(parse_github_repo("https://gitlab.com/user/repo"), None);
+    }
+}
@@ -0,0 +1,260 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use log::{info, warn};
+use serde_json::json;
+
+use super::{Enrichment, Provider};
+use crate::config::{ScanConfig, SuggestConfig};
+use crate::project_info::inventory::Inventory;
+use crate::project_info::overrides::Overrides;
+use crate::project_info::readme::readme_file;
+use crate::project_info::{relative_display, ProjectInfo};
+
+/// The model asked when the configuration names none.
+pub const DEFAULT_MODEL: &str = "gpt-4o-mini";
+
+/// The environment variable read for the API key when the configuration names none.
+pub const DEFAULT_API_KEY_ENV: &str = "OPENAI_API_KEY";
+
+/// The number of tags suggested when the configuration sets no limit.
+pub const DEFAULT_MAX_TAGS: usize = 8;
+
+/// The most file paths described to the model.
+const MAX_FILES: usize = 200;
+
+/// The most characters of the README described to the model.
+const README_CHARS: usize = 2000;
+
+const INSTRUCTIONS: &str = "You label project folders on a personal computer: software, music \
+sessions, video edits, designs, writing, and so on. Given a project's name, detected type, \
+current tags, files, and README, reply with a JSON array of short lowercase tags that describe \
+what the project is (its genre, subject, medium, or purpose) and that are not already among its \
+tags. Use a `namespace:value` form such as `genre:synth-pop` where one fits. Reply with the \
+array only.";
+
+/// Asks a language model behind an OpenAI-compatible chat completions API for tags
+/// that heuristics cannot find, such as telling a synth-pop EP from a podcast edit.
+///
+/// The project's name, type, tags, the files the `[scan]` settings and the project's
+/// `.tagger.toml` keep, and the start of its README are sent to the configured endpoint. The answer is recorded in
+/// [`ProjectInfo::suggested_tags`], apart from the project's tags, until the user
+/// confirms it.
+pub struct SuggestProvider {
+    config: SuggestConfig,
+    scan: ScanConfig,
+}
+
+impl SuggestProvider {
+    /// Creates the provider with the `[suggest]` and `[scan]` settings.
+    pub fn new(config: SuggestConfig, scan: ScanConfig) -> Self {
+        SuggestProvider { config, scan }
+    }
+}
+
+impl Provider for SuggestProvider {
+    fn name(&self) -> &'static str {
+        "suggest"
+    }
+
+    fn min_interval(&self) -> Duration {
+        Duration::from_secs(1)
+    }
+
+    fn enrich(&self, enrichment: &Enrichment, directory: &Path, info: &mut ProjectInfo) {
+        let Some(endpoint) = &self.config.endpoint else {
+            return;
+        };
+
+        let url = format!("{}/chat/completions", endpoint.trim_end_matches('/'));
+        let body = json!({
+            "model": self.config.model.as_deref().unwrap_or(DEFAULT_MODEL),
+            "temperature": 0,
+            "messages": [
+                {"role": "system", "content": INSTRUCTIONS},
+                {"role": "user", "content": describe_project(directory, info, &self.scan)},
+            ],
+        });
+        let mut headers = vec![("Content-Type", "application/json".to_string())];
+        let key_env = self.config.api_key_env.as_deref().unwrap_or(DEFAULT_API_KEY_ENV);
+        if let Ok(key) = env::var(key_env) {
+            headers.push(("Authorization", format!("Bearer {}", key)));
+        }
+
+        let Some(response) = enrichment.post(self, &url, &headers, &body.to_string()) else {
+            return;
+        };
+        let answer = match serde_json::from_str::<serde_json::Value>(&response) {
+            Ok(json) => json["choices"][0]["message"]["content"].as_str().map(str::to_string),
+            Err(e) => {
+                warn!("Unexpected response from {}: {}", url, e);
+                return;
+            }
+        };
+        let Some(answer) = answer else {
+            warn!("Response from {} holds no answer", url);
+            return;
+        };
+
+        let max_tags = self.config.max_tags.unwrap_or(DEFAULT_MAX_TAGS);
+        info.suggested_tags = parse_suggestions(&answer)
+            .into_iter()
+            .filter(|tag| !info.tags.contains(tag))
+            .take(max_tags)
+            .collect();
+        info!("Tags suggested for {}: {:?}", info.name, info.suggested_tags);
+    }
+}
+
+/// Describes the project to the model.
+fn describe_project(directory: &Path, info: &ProjectInfo, scan: &ScanConfig) -> String {
+    let mut description = format!(
+        "Name: {}\nType: {}\nTags: {}\n\nFiles:\n",
+        info.name,
+        info.project_type,
+        info.tags.join(", ")
+    );
+
+    let inventory = Inventory::scan_with(directory, &Overrides::load(directory).scan_config(scan));
+    let mut files: Vec<String> = inventory.files().map(|entry| relative_display(directory, entry.path())).collect();
+    files.sort();
+    for file in files.iter().take(MAX_FILES) {
+        description.push_str(file);
+        description.push('\n');
+    }
+    if files.len() > MAX_FILES {
+        description.push_str(&format!("... and {} more\n", files.len() - MAX_FILES));
+    }
+
+    if let Some(readme) = readme_file(directory).and_then(|path| fs::read_to_string(path).ok()) {
+        description.push_str("\nREADME:\n");
+        description.extend(readme.chars().take(README_CHARS));
+    }
+    description
+}
+
+/// Reads the tags out of the model's answer: a JSON array, possibly in a code block
+/// or after some prose, or else a list separated by commas or lines.
+///
+/// Tags are trimmed and lowercased, list markers and quotes are dropped, and
+/// duplicates are removed.
+fn parse_suggestions(answer: &str) -> Vec<String> {
+    let array = answer
+        .find('[')
+        .zip(answer.rfind(']'))
+        .and_then(|(start, end)| serde_json::from_str::<Vec<String>>(&answer[start..=end]).ok());
+    let candidates = array.unwrap_or_else(|| answer.split([',', '\n']).map(str::to_string).collect());
+
+    let mut tags: Vec<String> = Vec::new();
+    for candidate in candidates {
+        let tag = candidate
+            .trim()
+            .trim_start_matches(['-', '*', '•'])
+            .trim()
+            .trim_matches(['"', '\'', '`'])
+            .to_lowercase();
+        if !tag.is_empty() && !tag.starts_with("```") && tag.len() <= 50 && !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enrichment::EnrichmentConfig;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_parse_suggestions() {
+        assert_eq!(parse_suggestions(r#"["genre:synth-pop", "EP"]"#), ["genre:synth-pop", "ep"]);
+        assert_eq!(
+            parse_suggestions("Sure!\n```json\n[\"podcast\", \"interview\"]\n```"),
+            ["podcast", "interview"]
+        );
+        assert_eq!(parse_suggestions("- podcast\n- Interview\n- podcast"), ["podcast", "interview"]);
+        assert_eq!(parse_suggestions("ambient, field-recording"), ["ambient", "field-recording"]);
+    }
+
+    #[test]
+    fn test_suggestions_from_endpoint() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}/v1", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut length = 0;
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                if header.trim().is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = header.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        length = value.trim().parse().unwrap();
+                    }
+                }
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+
+            let answer = r#"{"choices": [{"message": {"content": "[\"genre:synth-pop\", \"music\"]"}}]}"#;
+            write!(
+                reader.get_mut(),
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                answer.len(),
+                answer
+            )
+            .unwrap();
+            (request_line, String::from_utf8(body).unwrap())
+        });
+
+        let project = tempdir().unwrap();
+        fs::write(project.path().join("Neon Nights.rpp"), "").unwrap();
+        fs::write(project.path().join("README.md"), "Four-track synth-pop EP.").unwrap();
+        fs::write(project.path().join("Private Demo.wav"), "").unwrap();
+        fs::write(project.path().join(".tagger.toml"), "[scan]\nexclude = [\"bounces\"]\n").unwrap();
+        fs::create_dir(project.path().join("bounces")).unwrap();
+        fs::write(project.path().join("bounces/Client Mix.wav"), "").unwrap();
+        let scan = ScanConfig {
+            exclude: vec!["*.wav".to_string()],
+            ..ScanConfig::default()
+        };
+        let cache_dir = tempdir().unwrap();
+        let config = SuggestConfig {
+            endpoint: Some(endpoint),
+            api_key_env: Some("TAGGER_TEST_UNSET_KEY".to_string()),
+            ..SuggestConfig::default()
+        };
+        let enrichment = Enrichment::new(EnrichmentConfig {
+            cache_dir: Some(cache_dir.path().to_path_buf()),
+            max_retries: 0,
+            suggest: Some(config.clone()),
+            ..EnrichmentConfig::default()
+        });
+        let mut info = ProjectInfo {
+            name: "Neon Nights".to_string(),
+            project_type: "music".to_string(),
+            tags: vec!["music".to_string()],
+            ..ProjectInfo::default()
+        };
+
+        SuggestProvider::new(config, scan).enrich(&enrichment, project.path(), &mut info);
+        assert_eq!(info.suggested_tags, ["genre:synth-pop"]);
+        assert_eq!(info.tags, ["music"]);
+
+        let (request_line, body) = server.join().unwrap();
+        assert!(request_line.starts_with("POST /v1/chat/completions "), "{}", request_line);
+        assert!(body.contains("Neon Nights.rpp") && body.contains("Four-track synth-pop EP."), "{}", body);
+        assert!(!body.contains("Private Demo") && !body.contains("Client Mix"), "{}", body);
+    }
+}
@@ -0,0 +1,7 @@
+//! Exports of the registry for use in other tools, such as SQL databases,
+//! spreadsheets, and note-taking apps, and a standalone HTML report.
+
+pub mod csv;
+pub mod html;
+pub mod markdown;
+pub mod sqlite;
@@ -0,0 +1,107 @@
+use std::io::{self, Write};
+
+use crate::registry::RegistryEntry;
+
+/// The columns of a CSV or TSV export, one row per project.
+pub const COLUMNS: &[&str] = &[
+    "name",
+    "alias",
+    "type",
+    "status",
+    "tags",
+    "date_created",
+    "date_modified",
+    "total_size_bytes",
+    "file_count",
+    "git_url",
+    "web_url",
+    "path",
+];
+
+/// Writes projects as delimited text with a header row.
+///
+/// Tags are joined with `; `. With a comma delimiter, fields are quoted as RFC 4180
+/// describes; with a tab, tabs and line breaks inside fields become spaces, which is
+/// what spreadsheets expect from TSV.
+///
+/// # Arguments
+///
+/// * `entries` - The projects to export.
+/// * `delimiter` - `,` for CSV or `\t` for TSV.
+/// * `out` - Where to write.
+pub fn export(entries: &[RegistryEntry], delimiter: char, out: &mut impl Write) -> io::Result<()> {
+    write_row(out, delimiter, COLUMNS.iter().map(|column| column.to_string()))?;
+
+    for entry in entries {
+        let info = &entry.info;
+        write_row(
+            out,
+            delimiter,
+            [
+                info.name.clone(),
+                info.alias.clone(),
+                info.project_type.clone(),
+                info.status.map(|status| status.to_string()).unwrap_or_default(),
+                info.tags.join("; "),
+                info.date_created.to_rfc3339(),
+                info.date_modified.to_rfc3339(),
+                info.total_size_bytes.to_string(),
+                info.file_count.to_string(),
+                info.git_url.clone().unwrap_or_default(),
+                info.web_url.clone().unwrap_or_default(),
+                entry.path.display().to_string(),
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+fn write_row(out: &mut impl Write, delimiter: char, fields: impl IntoIterator<Item = String>) -> io::Result<()> {
+    let fields: Vec<String> = fields.into_iter().map(|field| escape(&field, delimiter)).collect();
+    writeln!(out, "{}", fields.join(&delimiter.to_string()))
+}
+
+fn escape(field: &str, delimiter: char) -> String {
+    if delimiter == '\t' {
+        return field.replace(['\t', '\r', '\n'], " ");
+    }
+    if field.contains([delimiter, '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project_info::ProjectInfo;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_export_csv_and_tsv() {
+        let entries = vec![RegistryEntry {
+            path: PathBuf::from("/music/album"),
+            info: ProjectInfo {
+                name: "Album, \"Deluxe\"".to_string(),
+                project_type: "music".to_string(),
+                tags: vec!["audio".to_string(), "daw:reaper".to_string()],
+                total_size_bytes: 2048,
+                ..Default::default()
+            },
+        }];
+
+        let mut csv = Vec::new();
+        export(&entries, ',', &mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], COLUMNS.join(","));
+        assert!(lines[1].starts_with("\"Album, \"\"Deluxe\"\"\",,music,,audio; daw:reaper,"));
+        assert!(lines[1].ends_with(",2048,0,,,/music/album"));
+
+        let mut tsv = Vec::new();
+        export(&entries, '\t', &mut tsv).unwrap();
+        let tsv = String::from_utf8(tsv).unwrap();
+        assert!(tsv.lines().nth(1).unwrap().starts_with("Album, \"Deluxe\"\t\tmusic\t\taudio; daw:reaper\t"));
+    }
+}
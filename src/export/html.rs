@@ -0,0 +1,267 @@
+use std::collections::{BTreeMap, HashMap};
+use std::f64::consts::PI;
+use std::fmt::Write as _;
+
+use crate::registry::RegistryEntry;
+
+/// How many projects the recently-modified list shows.
+pub const RECENT_PROJECTS: usize = 10;
+
+/// Slice colors of the type breakdown chart, reused in order.
+const PALETTE: &[&str] = &[
+    "#4e79a7", "#f28e2b", "#e15759", "#76b7b2", "#59a14f", "#edc948", "#b07aa1", "#ff9da7", "#9c755f", "#bab0ac",
+];
+
+/// Renders a self-contained HTML page summarizing the projects: a sortable table, a
+/// tag cloud, a pie chart of project types, and the most recently modified projects.
+///
+/// The page needs no network access; its styles, chart, and table-sorting script are
+/// inline.
+///
+/// # Arguments
+///
+/// * `title` - The page title, such as the scanned root directory.
+/// * `entries` - The projects to summarize.
+pub fn render(title: &str, entries: &[RegistryEntry]) -> String {
+    let mut html = String::new();
+    let total_size: u64 = entries.iter().map(|entry| entry.info.total_size_bytes).sum();
+
+    let _ = writeln!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>",
+        escape(title),
+        STYLE
+    );
+    let _ = writeln!(html, "<h1>{}</h1>", escape(title));
+    let _ = writeln!(
+        html,
+        "<p class=\"summary\">{} projects, {} in total</p>",
+        entries.len(),
+        format_size(total_size)
+    );
+
+    html.push_str("<div class=\"panels\">\n");
+    render_type_chart(&mut html, entries);
+    render_recent(&mut html, entries);
+    html.push_str("</div>\n");
+    render_tag_cloud(&mut html, entries);
+    render_table(&mut html, entries);
+
+    let _ = writeln!(html, "<script>{}</script>\n</body>\n</html>", SORT_SCRIPT);
+    html
+}
+
+fn render_table(html: &mut String, entries: &[RegistryEntry]) {
+    html.push_str("<h2>Projects</h2>\n<table id=\"projects\">\n<thead><tr>");
+    for (column, kind) in [
+        ("Name", "text"),
+        ("Type", "text"),
+        ("Status", "text"),
+        ("Tags", "text"),
+        ("Modified", "text"),
+        ("Size", "number"),
+    ] {
+        let _ = write!(html, "<th data-kind=\"{}\">{}</th>", kind, column);
+    }
+    html.push_str("</tr></thead>\n<tbody>\n");
+
+    for entry in entries {
+        let info = &entry.info;
+        let name = if info.alias.is_empty() {
+            escape(&info.name)
+        } else {
+            format!("{} <span class=\"alias\">({})</span>", escape(&info.name), escape(&info.alias))
+        };
+        let _ = writeln!(
+            html,
+            "<tr><td title=\"{}\">{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td data-value=\"{}\">{}</td></tr>",
+            escape(&entry.path.display().to_string()),
+            name,
+            escape(&info.project_type),
+            info.status.map(|status| status.as_str()).unwrap_or_default(),
+            escape(&info.tags.join(", ")),
+            info.date_modified.format("%Y-%m-%d"),
+            info.total_size_bytes,
+            format_size(info.total_size_bytes)
+        );
+    }
+    html.push_str("</tbody>\n</table>\n");
+}
+
+/// Lists every tag, sized by how many projects carry it.
+fn render_tag_cloud(html: &mut String, entries: &[RegistryEntry]) {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for tag in entries.iter().flat_map(|entry| &entry.info.tags) {
+        *counts.entry(tag).or_default() += 1;
+    }
+    let max = counts.values().copied().max().unwrap_or(1) as f64;
+
+    html.push_str("<h2>Tags</h2>\n<p class=\"cloud\">\n");
+    for (tag, count) in counts {
+        let size = 0.8 + 1.4 * (count as f64 / max);
+        let _ = writeln!(
+            html,
+            "<span style=\"font-size: {:.2}em\" title=\"{} projects\">{}</span>",
+            size,
+            count,
+            escape(tag)
+        );
+    }
+    html.push_str("</p>\n");
+}
+
+/// Draws a pie chart of the project types as inline SVG, with a legend.
+fn render_type_chart(html: &mut String, entries: &[RegistryEntry]) {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for entry in entries {
+        *counts.entry(entry.info.project_type.as_str()).or_default() += 1;
+    }
+    let mut counts: Vec<(&str, usize)> = counts.into_iter().collect();
+    counts.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then_with(|| a.cmp(b)));
+
+    html.push_str("<section>\n<h2>Types</h2>\n<svg viewBox=\"-1 -1 2 2\" width=\"200\" height=\"200\">\n");
+    let total = entries.len() as f64;
+    let mut angle = -PI / 2.0;
+    for (index, (project_type, count)) in counts.iter().enumerate() {
+        let color = PALETTE[index % PALETTE.len()];
+        let title = format!("{}: {}", escape(project_type), count);
+        if *count as f64 == total {
+            let _ = writeln!(html, "<circle r=\"1\" fill=\"{}\"><title>{}</title></circle>", color, title);
+            break;
+        }
+
+        let sweep = 2.0 * PI * *count as f64 / total;
+        let (x1, y1) = (angle.cos(), angle.sin());
+        angle += sweep;
+        let (x2, y2) = (angle.cos(), angle.sin());
+        let large_arc = u8::from(sweep > PI);
+        let _ = writeln!(
+            html,
+            "<path d=\"M 0 0 L {:.4} {:.4} A 1 1 0 {} 1 {:.4} {:.4} Z\" fill=\"{}\"><title>{}</title></path>",
+            x1, y1, large_arc, x2, y2, color, title
+        );
+    }
+    html.push_str("</svg>\n<ul class=\"legend\">\n");
+    for (index, (project_type, count)) in counts.iter().enumerate() {
+        let _ = writeln!(
+            html,
+            "<li><span class=\"swatch\" style=\"background: {}\"></span>{} ({})</li>",
+            PALETTE[index % PALETTE.len()],
+            escape(project_type),
+            count
+        );
+    }
+    html.push_str("</ul>\n</section>\n");
+}
+
+fn render_recent(html: &mut String, entries: &[RegistryEntry]) {
+    let mut recent: Vec<&RegistryEntry> = entries.iter().collect();
+    recent.sort_by_key(|entry| std::cmp::Reverse(entry.info.date_modified));
+
+    html.push_str("<section>\n<h2>Recently modified</h2>\n<ol>\n");
+    for entry in recent.into_iter().take(RECENT_PROJECTS) {
+        let _ = writeln!(
+            html,
+            "<li>{} <span class=\"date\">{}</span></li>",
+            escape(&entry.info.name),
+            entry.info.date_modified.format("%Y-%m-%d %H:%M")
+        );
+    }
+    html.push_str("</ol>\n</section>\n");
+}
+
+/// Formats a byte count with a binary unit, e.g. `1.5 MiB`.
+fn format_size(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+const STYLE: &str = "
+body { font-family: system-ui, sans-serif; margin: 2em; color: #222; }
+.summary, .date, .alias { color: #666; }
+.panels { display: flex; flex-wrap: wrap; gap: 3em; }
+.legend { list-style: none; padding: 0; }
+.swatch { display: inline-block; width: 0.8em; height: 0.8em; margin-right: 0.4em; }
+.cloud span { margin-right: 0.6em; white-space: nowrap; }
+table { border-collapse: collapse; width: 100%; }
+th, td { text-align: left; padding: 0.3em 0.6em; border-bottom: 1px solid #ddd; }
+th { cursor: pointer; user-select: none; background: #f4f4f4; }
+th[data-order=asc]::after { content: ' ▲'; }
+th[data-order=desc]::after { content: ' ▼'; }
+";
+
+/// Sorts the project table by the clicked column, toggling the direction.
+const SORT_SCRIPT: &str = "
+document.querySelectorAll('#projects th').forEach((th, column) => th.addEventListener('click', () => {
+  const body = th.closest('table').tBodies[0];
+  const ascending = th.dataset.order !== 'asc';
+  th.parentNode.querySelectorAll('th').forEach(other => delete other.dataset.order);
+  th.dataset.order = ascending ? 'asc' : 'desc';
+  const value = row => {
+    const cell = row.cells[column];
+    return th.dataset.kind === 'number' ? Number(cell.dataset.value) : cell.textContent.toLowerCase();
+  };
+  const rows = Array.from(body.rows).sort((a, b) => {
+    const [x, y] = [value(a), value(b)];
+    return (x < y ? -1 : x > y ? 1 : 0) * (ascending ? 1 : -1);
+  });
+  rows.forEach(row => body.appendChild(row));
+}));
+";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project_info::ProjectInfo;
+    use std::path::PathBuf;
+
+    fn entry(name: &str, project_type: &str, tags: &[&str]) -> RegistryEntry {
+        RegistryEntry {
+            path: PathBuf::from("/projects").join(name),
+            info: ProjectInfo {
+                name: name.to_string(),
+                project_type: project_type.to_string(),
+                tags: tags.iter().map(|tag| tag.to_string()).collect(),
+                total_size_bytes: 3 * 1024 * 1024 / 2,
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_render_report() {
+        let entries = vec![
+            entry("beat <1>", "music", &["audio", "daw:reaper"]),
+            entry("song", "music", &["audio"]),
+            entry("app", "programming", &["lang:rust"]),
+        ];
+        let html = render("/projects", &entries);
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<p class=\"summary\">3 projects, 4.5 MiB in total</p>"));
+        assert!(html.contains("beat &lt;1&gt;"));
+        assert!(html.contains("<td data-value=\"1572864\">1.5 MiB</td>"));
+        // Music holds two thirds of the chart, so its slice takes the large arc.
+        assert!(html.contains("A 1 1 0 1 1"));
+        assert!(html.contains("music (2)</li>"));
+        assert!(html.contains("<span style=\"font-size: 2.20em\" title=\"2 projects\">audio</span>"));
+        assert!(html.trim_end().ends_with("</html>"));
+    }
+}
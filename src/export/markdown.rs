@@ -0,0 +1,253 @@
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use log::info;
+
+use crate::project_info::readme::{readme_file, split_frontmatter};
+use crate::project_info::slugify;
+use crate::registry::RegistryEntry;
+
+/// The name of the page that links every exported note.
+pub const INDEX_NOTE: &str = "index.md";
+
+/// Writes one Markdown note per project, plus an index page linking them all, into a
+/// directory such as an Obsidian vault folder.
+///
+/// Notes are named after the project's slug, with a number added when two projects
+/// share one. Links between related projects and from the index use `[[wikilinks]]`.
+///
+/// # Arguments
+///
+/// * `entries` - The projects to export.
+/// * `directory` - The folder to write the notes to, created if necessary.
+///
+/// # Returns
+///
+/// The number of notes written, not counting the index.
+pub fn export(entries: &[RegistryEntry], directory: &Path) -> io::Result<usize> {
+    fs::create_dir_all(directory)?;
+
+    let names = note_names(entries);
+    let by_id: HashMap<&str, &str> = entries
+        .iter()
+        .zip(&names)
+        .filter(|(entry, _)| !entry.info.id.is_empty())
+        .map(|(entry, name)| (entry.info.id.as_str(), name.as_str()))
+        .collect();
+
+    let mut index = String::from("# Projects\n\n");
+    for (entry, name) in entries.iter().zip(&names) {
+        fs::write(directory.join(format!("{}.md", name)), note(entry, &by_id))?;
+        let _ = writeln!(index, "- [[{}|{}]] ({})", name, entry.info.name, entry.info.project_type);
+    }
+    fs::write(directory.join(INDEX_NOTE), index)?;
+
+    info!("Wrote {} notes to {}", entries.len(), directory.display());
+    Ok(entries.len())
+}
+
+/// Adds frontmatter to the top of each project's Markdown README, replacing the
+/// frontmatter it already has. Projects without a Markdown README are skipped.
+///
+/// # Returns
+///
+/// The number of READMEs updated.
+pub fn prepend_frontmatter(entries: &[RegistryEntry]) -> io::Result<usize> {
+    let mut updated = 0;
+    for entry in entries {
+        let Some(path) = readme_file(&entry.path).filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown"))
+        }) else {
+            continue;
+        };
+
+        let content = fs::read_to_string(&path)?;
+        let (_, body) = split_frontmatter(&content);
+        fs::write(&path, format!("{}{}", frontmatter(entry), body))?;
+        info!("Added frontmatter to {}", path.display());
+        updated += 1;
+    }
+    Ok(updated)
+}
+
+/// Renders a project's YAML frontmatter, delimiters included.
+///
+/// Tags are converted to Obsidian's tag syntax, which allows neither spaces nor
+/// colons: `lang:rust` becomes the nested tag `lang/rust`, and `software development`
+/// becomes `software-development`.
+pub fn frontmatter(entry: &RegistryEntry) -> String {
+    let info = &entry.info;
+    let mut yaml = String::from("---\n");
+    let mut field = |key: &str, value: &str| {
+        let _ = writeln!(yaml, "{}: {}", key, quote(value));
+    };
+
+    field("title", &info.name);
+    if !info.id.is_empty() {
+        field("id", &info.id);
+    }
+    field("type", &info.project_type);
+    if let Some(status) = info.status {
+        field("status", status.as_str());
+    }
+    field("created", &info.date_created.to_rfc3339());
+    field("modified", &info.date_modified.to_rfc3339());
+    if let Some(git_url) = &info.git_url {
+        field("git_url", git_url);
+    }
+    if let Some(web_url) = &info.web_url {
+        field("web_url", web_url);
+    }
+    field("path", &entry.path.display().to_string());
+
+    if !info.alias.is_empty() {
+        let _ = writeln!(yaml, "aliases:\n  - {}", quote(&info.alias));
+    }
+    if !info.tags.is_empty() {
+        yaml.push_str("tags:\n");
+        for tag in &info.tags {
+            let _ = writeln!(yaml, "  - {}", quote(&obsidian_tag(tag)));
+        }
+    }
+    yaml.push_str("---\n");
+    yaml
+}
+
+/// Renders a project's note: its frontmatter, description, notes, and related projects.
+fn note(entry: &RegistryEntry, notes_by_id: &HashMap<&str, &str>) -> String {
+    let info = &entry.info;
+    let mut note = frontmatter(entry);
+    let _ = writeln!(note, "\n# {}\n", info.name);
+
+    if let Some(description) = &info.description {
+        let _ = writeln!(note, "{}\n", description);
+    }
+    if !info.notes.is_empty() {
+        note.push_str("## Notes\n\n");
+        for text in &info.notes {
+            let _ = writeln!(note, "- {}", text);
+        }
+        note.push('\n');
+    }
+    if !info.related.is_empty() {
+        note.push_str("## Related\n\n");
+        for related in &info.related {
+            match notes_by_id.get(related.id.as_str()) {
+                Some(name) => {
+                    let _ = writeln!(note, "- [[{}|{}]]", name, related.name);
+                }
+                None => {
+                    let _ = writeln!(note, "- {}", related.label());
+                }
+            }
+        }
+        note.push('\n');
+    }
+    note
+}
+
+/// Picks a unique file name, without extension, for each project's note.
+fn note_names(entries: &[RegistryEntry]) -> Vec<String> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    entries
+        .iter()
+        .map(|entry| {
+            let slug = if entry.info.slug.is_empty() {
+                slugify(&entry.info.name)
+            } else {
+                entry.info.slug.clone()
+            };
+            // Keep the index page's name free.
+            let slug = if slug == "index" { "index-project".to_string() } else { slug };
+
+            let count = counts.entry(slug.clone()).or_default();
+            *count += 1;
+            if *count == 1 {
+                slug
+            } else {
+                format!("{}-{}", slug, count)
+            }
+        })
+        .collect()
+}
+
+/// Converts a tag to Obsidian's syntax (see [`frontmatter`]).
+fn obsidian_tag(tag: &str) -> String {
+    tag.replace(':', "/").split_whitespace().collect::<Vec<_>>().join("-")
+}
+
+/// Quotes a YAML string.
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project_info::readme::extract_description;
+    use crate::project_info::related::RelatedProject;
+    use crate::project_info::ProjectInfo;
+    use tempfile::tempdir;
+
+    fn entry(directory: &Path, name: &str, id: &str) -> RegistryEntry {
+        RegistryEntry {
+            path: directory.join(name),
+            info: ProjectInfo {
+                name: name.to_string(),
+                id: id.to_string(),
+                slug: slugify(name),
+                project_type: "music".to_string(),
+                tags: vec!["daw:reaper".to_string(), "software development".to_string()],
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_export_markdown_notes_and_index() {
+        let dir = tempdir().unwrap();
+        let mut album = entry(dir.path(), "Album \"One\"", "album-id");
+        album.info.alias = "lp".to_string();
+        album.info.notes = vec!["Mix the bridge".to_string()];
+        album.info.related = vec![RelatedProject {
+            id: "pack-id".to_string(),
+            name: "pack".to_string(),
+            ..Default::default()
+        }];
+        let entries = vec![album, entry(dir.path(), "pack", "pack-id"), entry(dir.path(), "Pack", "")];
+
+        let vault = dir.path().join("vault");
+        assert_eq!(export(&entries, &vault).unwrap(), 3);
+
+        let album = fs::read_to_string(vault.join("album-one.md")).unwrap();
+        assert!(album.starts_with("---\ntitle: \"Album \\\"One\\\"\"\nid: \"album-id\"\ntype: \"music\"\n"));
+        assert!(album.contains("aliases:\n  - \"lp\"\ntags:\n  - \"daw/reaper\"\n  - \"software-development\"\n---\n"));
+        assert!(album.contains("## Notes\n\n- Mix the bridge\n"));
+        assert!(album.contains("## Related\n\n- [[pack|pack]]\n"));
+        assert!(vault.join("pack-2.md").exists());
+
+        let index = fs::read_to_string(vault.join(INDEX_NOTE)).unwrap();
+        assert!(index.contains("- [[album-one|Album \"One\"]] (music)\n"));
+    }
+
+    #[test]
+    fn test_prepend_frontmatter_replaces_existing() {
+        let dir = tempdir().unwrap();
+        let projects = [entry(dir.path(), "beat", "")];
+        let project = &projects[0];
+        fs::create_dir_all(&project.path).unwrap();
+        fs::write(project.path.join("README.md"), "# Beat\n\nA drum loop.\n").unwrap();
+
+        assert_eq!(prepend_frontmatter(&projects).unwrap(), 1);
+        assert_eq!(prepend_frontmatter(&projects).unwrap(), 1);
+
+        let readme = fs::read_to_string(project.path.join("README.md")).unwrap();
+        assert_eq!(readme.matches("---\n").count(), 2);
+        assert!(readme.ends_with("---\n# Beat\n\nA drum loop.\n"));
+        assert_eq!(extract_description(&project.path, 200).as_deref(), Some("Beat — A drum loop."));
+    }
+}
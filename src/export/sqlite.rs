@@ -0,0 +1,182 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use log::info;
+use rusqlite::{params, Connection};
+
+use crate::project_info::tags;
+use crate::registry::RegistryEntry;
+
+/// The tables of an export. Tags are shared between projects through `project_tags`,
+/// and notes keep their order in `position`.
+pub const SCHEMA: &str = "
+CREATE TABLE projects (
+    id INTEGER PRIMARY KEY,
+    uuid TEXT,
+    name TEXT NOT NULL,
+    alias TEXT,
+    slug TEXT,
+    project_type TEXT NOT NULL,
+    status TEXT,
+    description TEXT,
+    version TEXT,
+    license TEXT,
+    path TEXT NOT NULL UNIQUE,
+    git_url TEXT,
+    web_url TEXT,
+    date_created TEXT NOT NULL,
+    date_modified TEXT NOT NULL,
+    total_size_bytes INTEGER NOT NULL,
+    file_count INTEGER NOT NULL
+);
+CREATE TABLE tags (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    namespace TEXT,
+    value TEXT NOT NULL
+);
+CREATE TABLE project_tags (
+    project_id INTEGER NOT NULL REFERENCES projects(id),
+    tag_id INTEGER NOT NULL REFERENCES tags(id),
+    PRIMARY KEY (project_id, tag_id)
+);
+CREATE TABLE notes (
+    id INTEGER PRIMARY KEY,
+    project_id INTEGER NOT NULL REFERENCES projects(id),
+    position INTEGER NOT NULL,
+    text TEXT NOT NULL
+);
+";
+
+/// Writes projects to a new SQLite database, replacing the file if it exists.
+///
+/// Dates are stored as RFC 3339 text, which sorts and compares correctly in SQL.
+///
+/// # Arguments
+///
+/// * `entries` - The projects to export, usually every registry entry.
+/// * `path` - The database file to write.
+pub fn export(entries: &[RegistryEntry], path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    match fs::remove_file(path) {
+        Ok(()) => info!("Replacing {}", path.display()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
+
+    let mut connection = Connection::open(path)?;
+    let transaction = connection.transaction()?;
+    transaction.execute_batch(SCHEMA)?;
+
+    {
+        let mut insert_project = transaction.prepare(
+            "INSERT INTO projects (uuid, name, alias, slug, project_type, status, description, version,
+                license, path, git_url, web_url, date_created, date_modified, total_size_bytes, file_count)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)",
+        )?;
+        let mut insert_tag =
+            transaction.prepare("INSERT OR IGNORE INTO tags (name, namespace, value) VALUES (?1, ?2, ?3)")?;
+        let mut link_tag = transaction.prepare(
+            "INSERT OR IGNORE INTO project_tags (project_id, tag_id) SELECT ?1, id FROM tags WHERE name = ?2",
+        )?;
+        let mut insert_note = transaction.prepare("INSERT INTO notes (project_id, position, text) VALUES (?1, ?2, ?3)")?;
+
+        for entry in entries {
+            let info = &entry.info;
+            let empty_as_null = |text: &str| Some(text.to_string()).filter(|text| !text.is_empty());
+
+            insert_project.execute(params![
+                empty_as_null(&info.id),
+                info.name,
+                empty_as_null(&info.alias),
+                empty_as_null(&info.slug),
+                info.project_type,
+                info.status.map(|status| status.as_str()),
+                info.description,
+                info.version,
+                info.license,
+                entry.path.to_string_lossy(),
+                info.git_url,
+                info.web_url,
+                info.date_created.to_rfc3339(),
+                info.date_modified.to_rfc3339(),
+                info.total_size_bytes as i64,
+                info.file_count as i64,
+            ])?;
+            let project_id = transaction.last_insert_rowid();
+
+            for tag in &info.tags {
+                let (namespace, value) = tags::split(tag);
+                insert_tag.execute(params![tag, namespace, value])?;
+                link_tag.execute(params![project_id, tag])?;
+            }
+            for (position, note) in info.notes.iter().enumerate() {
+                insert_note.execute(params![project_id, position as i64, note])?;
+            }
+        }
+    }
+
+    transaction.commit()?;
+    info!("Exported {} projects to {}", entries.len(), path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project_info::ProjectInfo;
+    use std::path::PathBuf;
+    use tempfile::tempdir;
+
+    fn entry(name: &str, tags: &[&str], notes: &[&str]) -> RegistryEntry {
+        RegistryEntry {
+            path: PathBuf::from("/projects").join(name),
+            info: ProjectInfo {
+                name: name.to_string(),
+                project_type: "music".to_string(),
+                tags: tags.iter().map(|tag| tag.to_string()).collect(),
+                notes: notes.iter().map(|note| note.to_string()).collect(),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_export_sqlite() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("projects.db");
+        let entries = vec![
+            entry("album", &["audio", "daw:reaper"], &["Mix the bridge", "Send to mastering"]),
+            entry("single", &["audio"], &[]),
+        ];
+
+        // Exporting twice replaces the database instead of appending to it.
+        export(&entries, &path).unwrap();
+        export(&entries, &path).unwrap();
+
+        let connection = Connection::open(&path).unwrap();
+        let count = |sql: &str| connection.query_row(sql, [], |row| row.get::<_, i64>(0)).unwrap();
+        assert_eq!(count("SELECT COUNT(*) FROM projects"), 2);
+        assert_eq!(count("SELECT COUNT(*) FROM tags"), 2);
+        assert_eq!(count("SELECT COUNT(*) FROM project_tags"), 3);
+        assert_eq!(
+            count("SELECT COUNT(*) FROM project_tags JOIN tags ON tags.id = tag_id WHERE tags.name = 'audio'"),
+            2
+        );
+
+        let namespace: String = connection
+            .query_row("SELECT namespace FROM tags WHERE value = 'reaper'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(namespace, "daw");
+
+        let last_note: String = connection
+            .query_row(
+                "SELECT text FROM notes JOIN projects ON projects.id = project_id
+                 WHERE projects.name = 'album' ORDER BY position DESC LIMIT 1",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(last_note, "Send to mastering");
+    }
+}
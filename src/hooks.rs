@@ -0,0 +1,184 @@
+use std::fmt;
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::Stdio;
+use std::thread;
+
+use log::{debug, info, warn};
+
+use crate::config::SecurityConfig;
+use crate::project_info::ProjectInfo;
+use crate::registry::RegistryEntry;
+use crate::sandbox::{self, Sandbox};
+
+/// The environment variable that tells a hook which event it runs for.
+pub const HOOK_VAR: &str = "TAGGER_HOOK";
+
+/// When a hook runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hook {
+    /// Before a project's information is written.
+    PreSave,
+    /// After a project's information is written.
+    PostSave,
+}
+
+impl Hook {
+    /// Returns the hook's name in the configuration, e.g. `post_save`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Hook::PreSave => "pre_save",
+            Hook::PostSave => "post_save",
+        }
+    }
+}
+
+impl fmt::Display for Hook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Runs a hook's shell command for a project and waits for it to finish.
+///
+/// The command runs through the system shell (`sh -c`, or `cmd /C` on Windows) in the
+/// [`Sandbox`], with [`HOOK_VAR`] set to the hook's name and the project, as the JSON
+/// of its registry entry, on standard input. Its output is logged rather than printed,
+/// so it cannot mix with tagger's own output. Nothing runs when external commands are
+/// disabled, and a command still running after `hook_timeout_secs` is killed.
+///
+/// # Errors
+///
+/// Returns an error if the command cannot be started, exits unsuccessfully, or is
+/// killed for running too long; the error includes what the command wrote to
+/// standard error.
+pub fn run(
+    hook: Hook,
+    command_line: &str,
+    directory: &Path,
+    project: &ProjectInfo,
+    security: &SecurityConfig,
+) -> io::Result<()> {
+    let sandbox = Sandbox::new(security);
+    if !sandbox.allows_external_commands() {
+        warn!("Skipping the {} hook: external commands are disabled.", hook);
+        return Ok(());
+    }
+
+    let entry = RegistryEntry {
+        path: directory.canonicalize().unwrap_or_else(|_| directory.to_path_buf()),
+        info: project.clone(),
+    };
+    let input = serde_json::to_vec(&entry).map_err(io::Error::other)?;
+
+    let mut command = if cfg!(windows) {
+        let mut command = sandbox.command("cmd", directory)?;
+        command.arg("/C").arg(command_line);
+        command
+    } else {
+        let mut command = sandbox.command("sh", directory)?;
+        command.arg("-c").arg(command_line);
+        command
+    };
+    info!("Running the {} hook: {}", hook, command_line);
+    let mut child = command
+        .env(HOOK_VAR, hook.as_str())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // Feed standard input from another thread, so a hook that writes a lot before
+    // reading cannot block on a full pipe. A hook that ignores its input is fine.
+    let mut stdin = child.stdin.take().expect("standard input is piped");
+    let writer = thread::spawn(move || match stdin.write_all(&input) {
+        Err(e) if e.kind() != io::ErrorKind::BrokenPipe => Err(e),
+        _ => Ok(()),
+    });
+    let output = sandbox::wait_with_timeout(child, security.hook_timeout())
+        .map_err(|e| io::Error::new(e.kind(), format!("the {} hook: {}", hook, e)))?;
+    writer.join().expect("the hook input thread panicked")?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !stdout.trim().is_empty() {
+        debug!("{} hook output: {}", hook, stdout.trim_end());
+    }
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "the {} hook failed ({}): {}",
+            hook,
+            output.status,
+            stderr.trim()
+        )));
+    }
+    if !stderr.trim().is_empty() {
+        debug!("{} hook errors: {}", hook, stderr.trim_end());
+    }
+    Ok(())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_hook_receives_project_json() {
+        let dir = tempdir().unwrap();
+        let project = ProjectInfo {
+            name: "beat42".to_string(),
+            tags: vec!["audio".to_string()],
+            ..Default::default()
+        };
+
+        run(
+            Hook::PostSave,
+            "cat > received.json; echo \"$TAGGER_HOOK\" > hook.txt",
+            dir.path(),
+            &project,
+            &SecurityConfig::default(),
+        )
+        .unwrap();
+
+        let received: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(dir.path().join("received.json")).unwrap()).unwrap();
+        assert_eq!(received["name"], "beat42");
+        assert_eq!(received["tags"][0], "audio");
+        assert_eq!(received["path"], dir.path().canonicalize().unwrap().to_str().unwrap());
+        assert_eq!(fs::read_to_string(dir.path().join("hook.txt")).unwrap(), "post_save\n");
+    }
+
+    #[test]
+    fn test_failing_hook_reports_its_errors() {
+        let dir = tempdir().unwrap();
+        let error = run(
+            Hook::PreSave,
+            "echo 'backup drive not mounted' >&2; exit 3",
+            dir.path(),
+            &ProjectInfo::default(),
+            &SecurityConfig::default(),
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("backup drive not mounted"));
+
+        let disabled = SecurityConfig {
+            disable_external_commands: true,
+            ..SecurityConfig::default()
+        };
+        assert!(run(Hook::PreSave, "exit 3", dir.path(), &ProjectInfo::default(), &disabled).is_ok());
+    }
+
+    #[test]
+    fn test_slow_hook_is_killed() {
+        let dir = tempdir().unwrap();
+        let security = SecurityConfig {
+            hook_timeout_secs: Some(1),
+            ..SecurityConfig::default()
+        };
+        // The shell's child keeps the output pipes open after the shell is killed.
+        let error = run(Hook::PreSave, "sleep 10", dir.path(), &ProjectInfo::default(), &security).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::TimedOut);
+    }
+}
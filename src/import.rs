@@ -0,0 +1,410 @@
+//! Imports of metadata kept in other tools, such as GitHub repository listings,
+//! `onefetch` and `tokei` output, and CSV inventories, into saved project information.
+
+pub mod csv;
+pub mod github;
+pub mod onefetch;
+pub mod tokei;
+
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::config::Config;
+use crate::project_info::overrides;
+use crate::project_info::status::ProjectStatus;
+use crate::project_info::tags;
+use crate::project_info::ProjectInfo;
+use crate::registry::{Registry, RegistryEntry};
+
+/// The smallest share of a project's code, in percent, that gets its language a tag
+/// from `onefetch` or `tokei` output, so a stray script does not.
+pub const MIN_LANGUAGE_PERCENT: f64 = 1.0;
+
+/// The tools whose output can be imported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// Repositories from the GitHub API or `gh repo list --json`.
+    GitHub,
+    /// The JSON `onefetch --output json` prints for a repository.
+    Onefetch,
+    /// The JSON `tokei --output json` prints for a directory.
+    Tokei,
+    /// A spreadsheet of projects, one row each, such as a `tagger export --csv`.
+    Csv,
+}
+
+impl ImportFormat {
+    /// Every format.
+    pub const ALL: [ImportFormat; 4] =
+        [ImportFormat::GitHub, ImportFormat::Onefetch, ImportFormat::Tokei, ImportFormat::Csv];
+
+    /// Returns the format's name on the command line.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ImportFormat::GitHub => "github",
+            ImportFormat::Onefetch => "onefetch",
+            ImportFormat::Tokei => "tokei",
+            ImportFormat::Csv => "csv",
+        }
+    }
+
+    /// Tells the format from a file's extension or, for JSON, its shape.
+    pub fn detect(path: &Path, content: &str) -> Option<Self> {
+        let extension = path.extension().map(|extension| extension.to_string_lossy().to_lowercase());
+        if matches!(extension.as_deref(), Some("csv" | "tsv")) {
+            return Some(ImportFormat::Csv);
+        }
+
+        let json: serde_json::Value = serde_json::from_str(content).ok()?;
+        match &json {
+            serde_json::Value::Array(_) => Some(ImportFormat::GitHub),
+            serde_json::Value::Object(object) if object.contains_key("Total") => Some(ImportFormat::Tokei),
+            serde_json::Value::Object(object) if object.contains_key("infoFields") => Some(ImportFormat::Onefetch),
+            serde_json::Value::Object(object)
+                if ["full_name", "html_url", "topics", "nameWithOwner"].iter().any(|key| object.contains_key(*key)) =>
+            {
+                Some(ImportFormat::GitHub)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ImportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ImportFormat {
+    type Err = String;
+
+    /// Parses a format name, ignoring case.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim().to_lowercase();
+        Self::ALL.into_iter().find(|format| format.as_str() == value).ok_or_else(|| {
+            let names: Vec<&str> = Self::ALL.iter().map(|format| format.as_str()).collect();
+            format!("unknown import format '{}' (expected one of: {})", value, names.join(", "))
+        })
+    }
+}
+
+/// What another tool records about one project.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Seed {
+    /// The project's directory.
+    pub path: Option<PathBuf>,
+    /// The URLs of the project's repository.
+    pub urls: Vec<String>,
+    /// The project's name, alias, or id.
+    pub name: Option<String>,
+    /// Tags to add.
+    pub tags: Vec<String>,
+    /// A one-line summary.
+    pub description: Option<String>,
+    /// The project type.
+    pub project_type: Option<String>,
+    /// An alias.
+    pub alias: Option<String>,
+    /// The lifecycle status.
+    pub status: Option<ProjectStatus>,
+    /// Notes to add.
+    pub notes: Vec<String>,
+}
+
+impl Seed {
+    /// Returns `true` if the seed does not say which project it is about, as with
+    /// `tokei` output.
+    pub fn is_anonymous(&self) -> bool {
+        self.path.is_none() && self.urls.is_empty() && self.name.is_none()
+    }
+
+    /// Names the project the seed is about, for messages.
+    pub fn label(&self) -> String {
+        self.name
+            .clone()
+            .or_else(|| self.path.as_ref().map(|path| path.display().to_string()))
+            .or_else(|| self.urls.first().cloned())
+            .unwrap_or_default()
+    }
+}
+
+/// Reads the seeds from a tool's output.
+///
+/// # Errors
+///
+/// Returns an `InvalidData` error when the content is not in the given format.
+pub fn parse(format: ImportFormat, content: &str) -> io::Result<Vec<Seed>> {
+    match format {
+        ImportFormat::GitHub => github::parse(content),
+        ImportFormat::Onefetch => onefetch::parse(content).map(|seed| vec![seed]),
+        ImportFormat::Tokei => tokei::parse(content).map(|seed| vec![seed]),
+        ImportFormat::Csv => csv::parse(content),
+    }
+}
+
+/// Tags the languages that make up at least [`MIN_LANGUAGE_PERCENT`] of a project,
+/// given each language's share in percent.
+fn language_tags<'a>(languages: impl IntoIterator<Item = (&'a str, f64)>) -> Vec<String> {
+    languages
+        .into_iter()
+        .filter(|(_, percent)| *percent >= MIN_LANGUAGE_PERCENT)
+        .map(|(language, _)| tags::namespaced("lang", language))
+        .collect()
+}
+
+/// The registered project a seed is about.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+    /// The project in this directory.
+    Found(PathBuf),
+    /// Several projects match; none is picked.
+    Ambiguous(Vec<PathBuf>),
+    /// No project matches.
+    NotFound,
+}
+
+/// Finds the registered project a seed is about: by its directory, else by its
+/// repository's URL, else by its name as [`Registry::lookup`] resolves names.
+pub fn find(seed: &Seed, registry: &Registry, entries: &[RegistryEntry]) -> io::Result<Target> {
+    if let Some(path) = &seed.path {
+        let path = path.canonicalize().unwrap_or_else(|_| path.clone());
+        if path.is_dir() || entries.iter().any(|entry| entry.path == path) {
+            return Ok(Target::Found(path));
+        }
+    }
+
+    let urls: Vec<String> = seed.urls.iter().map(|url| repository_key(url)).collect();
+    let mut found: Vec<PathBuf> = entries
+        .iter()
+        .filter(|entry| {
+            entry
+                .info
+                .git_url
+                .iter()
+                .chain(entry.info.remotes.values())
+                .any(|url| urls.contains(&repository_key(url)))
+        })
+        .map(|entry| entry.path.clone())
+        .collect();
+    if found.is_empty() {
+        if let Some(name) = &seed.name {
+            found = registry.lookup(name)?.into_iter().map(|entry| entry.path).collect();
+        }
+    }
+
+    found.dedup();
+    Ok(match found.len() {
+        0 => Target::NotFound,
+        1 => Target::Found(found.remove(0)),
+        _ => Target::Ambiguous(found),
+    })
+}
+
+/// Reduces a repository URL to its host and path, so the SSH and HTTPS URLs of a
+/// repository compare equal: `git@github.com:me/app.git` and
+/// `https://github.com/me/app` both become `github.com/me/app`.
+fn repository_key(url: &str) -> String {
+    let url = url.trim().to_lowercase();
+    let rest = url.split_once("://").map_or(url.as_str(), |(_, rest)| rest);
+    let rest = match rest.split_once('@') {
+        Some((user, host)) if !user.contains('/') => host,
+        _ => rest,
+    };
+    let rest = match rest.split_once(':') {
+        // `host:path` in the scp-like syntax, but not `host:port/path`.
+        Some((host, path)) if !path.starts_with(|c: char| c.is_ascii_digit()) => format!("{}/{}", host, path),
+        _ => rest.to_string(),
+    };
+    rest.trim_end_matches('/').trim_end_matches(".git").to_string()
+}
+
+/// Returns a value with its surrounding whitespace removed, unless that leaves nothing.
+fn trimmed(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|value| !value.is_empty())
+}
+
+/// What importing a seed changes in a project.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Changes {
+    /// Tags the project does not have yet.
+    pub tags: Vec<String>,
+    /// A new description.
+    pub description: Option<String>,
+    /// A new project type.
+    pub project_type: Option<String>,
+    /// A new alias.
+    pub alias: Option<String>,
+    /// A new status.
+    pub status: Option<ProjectStatus>,
+    /// Notes the project does not have yet.
+    pub notes: Vec<String>,
+}
+
+impl Changes {
+    /// Works out what a seed changes in a project's saved information, leaving out
+    /// what the project already has. Tags are normalized like generated ones.
+    pub fn new(seed: &Seed, info: &ProjectInfo, config: &Config) -> Self {
+        let mut tags: Vec<String> = seed
+            .tags
+            .iter()
+            .map(|tag| tags::normalize(tag, &config.synonyms))
+            .filter(|tag| !tag.is_empty() && !info.tags.contains(tag))
+            .collect();
+        tags.sort();
+        tags.dedup();
+
+        Changes {
+            tags,
+            description: trimmed(&seed.description)
+                .filter(|description| info.description.as_deref() != Some(*description))
+                .map(str::to_string),
+            project_type: trimmed(&seed.project_type)
+                .map(str::to_lowercase)
+                .filter(|project_type| *project_type != info.project_type),
+            alias: trimmed(&seed.alias).filter(|alias| *alias != info.alias).map(str::to_string),
+            status: seed.status.filter(|status| info.status != Some(*status)),
+            notes: seed
+                .notes
+                .iter()
+                .map(|note| note.trim())
+                .filter(|note| !note.is_empty() && !info.notes.iter().any(|existing| existing == note))
+                .map(str::to_string)
+                .collect(),
+        }
+    }
+
+    /// Returns `true` if nothing changes.
+    pub fn is_empty(&self) -> bool {
+        *self == Changes::default()
+    }
+
+    /// Describes the changes on one line, such as `+lang:rust +cli, description`.
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.tags.is_empty() {
+            parts.push(self.tags.iter().map(|tag| format!("+{}", tag)).collect::<Vec<_>>().join(" "));
+        }
+        if let Some(project_type) = &self.project_type {
+            parts.push(format!("type {}", project_type));
+        }
+        if self.description.is_some() {
+            parts.push("description".to_string());
+        }
+        if let Some(alias) = &self.alias {
+            parts.push(format!("alias {}", alias));
+        }
+        if let Some(status) = self.status {
+            parts.push(format!("status {}", status));
+        }
+        if !self.notes.is_empty() {
+            parts.push(format!("{} notes", self.notes.len()));
+        }
+        parts.join(", ")
+    }
+
+    /// Applies the changes to a project's information, which the caller saves.
+    ///
+    /// The tags, description, and type are also recorded in the project's
+    /// `.tagger.toml`, so rescans keep them; the alias, status, and notes are kept
+    /// by rescans anyway.
+    pub fn apply(&self, directory: &Path, info: &mut ProjectInfo, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+        if !self.tags.is_empty() {
+            overrides::save_added_tags(directory, &self.tags)?;
+            info.tags.extend(self.tags.iter().cloned());
+            info.tags.sort();
+            info.tags.dedup();
+        }
+        if let Some(description) = &self.description {
+            overrides::save_description(directory, description)?;
+            info.description = Some(description.clone());
+        }
+        if let Some(project_type) = &self.project_type {
+            overrides::save_project_type(directory, project_type)?;
+            info.set_project_type(directory, project_type, config)?;
+        }
+        if let Some(alias) = &self.alias {
+            info.set_alias(alias.clone());
+        }
+        if let Some(status) = self.status {
+            info.status = Some(status);
+        }
+        for note in &self.notes {
+            info.add_note(note.clone());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_detect_format() {
+        let detect = |name: &str, content: &str| ImportFormat::detect(Path::new(name), content);
+        assert_eq!(detect("inventory.csv", "name,tags"), Some(ImportFormat::Csv));
+        assert_eq!(detect("repos.json", "[]"), Some(ImportFormat::GitHub));
+        assert_eq!(detect("repo.json", r#"{"full_name": "me/app"}"#), Some(ImportFormat::GitHub));
+        assert_eq!(detect("lines.json", r#"{"Total": {"code": 0}}"#), Some(ImportFormat::Tokei));
+        assert_eq!(detect("info.json", r#"{"infoFields": []}"#), Some(ImportFormat::Onefetch));
+        assert_eq!(detect("notes.txt", "hello"), None);
+        assert_eq!("GitHub".parse(), Ok(ImportFormat::GitHub));
+    }
+
+    #[test]
+    fn test_find_and_apply() {
+        let dir = tempdir().unwrap();
+        let registry = Registry::open(&dir.path().join("registry")).unwrap();
+        let app = dir.path().join("app");
+        std::fs::create_dir(&app).unwrap();
+        let info = ProjectInfo {
+            name: "app".to_string(),
+            project_type: "programming".to_string(),
+            tags: vec!["lang:rust".to_string()],
+            git_url: Some("git@github.com:me/App.git".to_string()),
+            ..Default::default()
+        };
+        registry.register(&app, &info).unwrap();
+        let entries = registry.entries().unwrap();
+        let app = app.canonicalize().unwrap();
+
+        let seed = Seed {
+            urls: vec!["https://github.com/me/app".to_string()],
+            tags: vec!["CLI".to_string(), "lang:rust".to_string(), "js".to_string()],
+            description: Some("A tiny app".to_string()),
+            status: Some(ProjectStatus::Archived),
+            ..Default::default()
+        };
+        assert_eq!(find(&seed, &registry, &entries).unwrap(), Target::Found(app.clone()));
+        let by_name = Seed { name: Some("APP".to_string()), ..Default::default() };
+        assert_eq!(find(&by_name, &registry, &entries).unwrap(), Target::Found(app.clone()));
+        let unknown = Seed { name: Some("other".to_string()), ..Default::default() };
+        assert_eq!(find(&unknown, &registry, &entries).unwrap(), Target::NotFound);
+
+        let changes = Changes::new(&seed, &info, &Config::default());
+        assert_eq!(changes.tags, ["cli", "javascript"]);
+        assert_eq!(changes.summary(), "+cli +javascript, description, status archived");
+
+        let mut updated = info.clone();
+        changes.apply(&app, &mut updated, &Config::default()).unwrap();
+        assert_eq!(updated.tags, ["cli", "javascript", "lang:rust"]);
+        assert!(Changes::new(&seed, &updated, &Config::default()).is_empty());
+
+        // Rescans keep the imported tags and description.
+        let recorded = overrides::Overrides::load(&app);
+        assert_eq!(recorded.tags.add, ["cli", "javascript"]);
+        assert_eq!(recorded.description.as_deref(), Some("A tiny app"));
+    }
+
+    #[test]
+    fn test_repository_key() {
+        for url in ["git@github.com:me/app.git", "https://github.com/me/app/", "ssh://git@github.com/me/app"] {
+            assert_eq!(repository_key(url), "github.com/me/app");
+        }
+        assert_eq!(repository_key("https://git.example.com:8443/me/app.git"), "git.example.com:8443/me/app");
+    }
+}
@@ -0,0 +1,132 @@
+use std::io;
+use std::path::PathBuf;
+
+use log::warn;
+
+use super::Seed;
+
+/// Reads a spreadsheet of projects with a header row, such as `tagger export --csv`
+/// writes, as CSV or, when the header has tabs and no commas, TSV.
+///
+/// Each row needs a `path`, `name`, or `git_url` column to find its project by; the
+/// others are optional: `alias`, `type`, `status`, `tags` (separated by `;` or `,`),
+/// `description`, and `notes` (one per line). Column names ignore case, and other
+/// columns are ignored.
+pub fn parse(content: &str) -> io::Result<Vec<Seed>> {
+    let header = content.lines().next().unwrap_or_default();
+    let delimiter = if header.contains('\t') && !header.contains(',') { '\t' } else { ',' };
+    let mut rows = records(content, delimiter).into_iter();
+    let Some(columns) = rows.next() else {
+        return Ok(Vec::new());
+    };
+    let columns: Vec<String> = columns.iter().map(|column| column.trim().to_lowercase()).collect();
+
+    let mut seeds = Vec::new();
+    for (index, row) in rows.enumerate() {
+        let field = |names: &[&str]| {
+            columns
+                .iter()
+                .position(|column| names.contains(&column.as_str()))
+                .and_then(|position| row.get(position))
+                .map(|value| value.trim())
+                .filter(|value| !value.is_empty())
+        };
+        // The header is row 1.
+        let number = index + 2;
+
+        let status = match field(&["status"]) {
+            Some(status) => Some(
+                status
+                    .parse()
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("row {}: {}", number, e)))?,
+            ),
+            None => None,
+        };
+        let seed = Seed {
+            path: field(&["path", "directory"]).map(PathBuf::from),
+            urls: field(&["git_url", "url", "repository"]).map(str::to_string).into_iter().collect(),
+            name: field(&["name"]).map(str::to_string),
+            tags: field(&["tags"])
+                .map(|tags| tags.split([';', ',']).map(str::trim).filter(|tag| !tag.is_empty()).map(str::to_string).collect())
+                .unwrap_or_default(),
+            description: field(&["description"]).map(str::to_string),
+            project_type: field(&["type", "project_type"]).map(str::to_string),
+            alias: field(&["alias"]).map(str::to_string),
+            status,
+            notes: field(&["notes"])
+                .map(|notes| notes.lines().map(str::trim).filter(|note| !note.is_empty()).map(str::to_string).collect())
+                .unwrap_or_default(),
+        };
+
+        if seed.is_anonymous() {
+            if row.iter().any(|value| !value.trim().is_empty()) {
+                warn!("Skipping row {}: it has no path, name, or git_url", number);
+            }
+            continue;
+        }
+        seeds.push(seed);
+    }
+    Ok(seeds)
+}
+
+/// Splits delimited text into records of fields, unquoting fields as RFC 4180
+/// describes: quoted fields may hold delimiters, line breaks, and doubled quotes.
+fn records(content: &str, delimiter: char) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.is_empty() => quoted = true,
+            c if quoted => field.push(c),
+            c if c == delimiter => record.push(std::mem::take(&mut field)),
+            '\r' => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            c => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project_info::status::ProjectStatus;
+
+    #[test]
+    fn test_parse() {
+        let inventory = "Name,Path,Tags,Status,Notes,Client\n\
+            \"Album, \"\"Deluxe\"\"\",/music/album,\"audio; daw:reaper\",released,\"mixed by Sam\nmastered\",Acme\n\
+            ,,,,,\n\
+            sketch,,loop,,,\n";
+        let seeds = parse(inventory).unwrap();
+        assert_eq!(seeds.len(), 2);
+        assert_eq!(seeds[0].name.as_deref(), Some("Album, \"Deluxe\""));
+        assert_eq!(seeds[0].path, Some(PathBuf::from("/music/album")));
+        assert_eq!(seeds[0].tags, ["audio", "daw:reaper"]);
+        assert_eq!(seeds[0].status, Some(ProjectStatus::Released));
+        assert_eq!(seeds[0].notes, ["mixed by Sam", "mastered"]);
+        assert_eq!(seeds[1].tags, ["loop"]);
+
+        let tsv = parse("name\ttags\nsketch\tloop, ambient\n").unwrap();
+        assert_eq!(tsv[0].tags, ["loop", "ambient"]);
+
+        let error = parse("name,status\nsketch,finished\n").unwrap_err();
+        assert!(error.to_string().starts_with("row 2: unknown status 'finished'"));
+    }
+}
@@ -0,0 +1,87 @@
+use std::io;
+
+use serde_json::Value;
+
+use super::Seed;
+use crate::project_info::status::ProjectStatus;
+use crate::project_info::tags;
+
+/// Reads repositories as the GitHub API returns them (`/repos/{owner}/{repo}`, or a
+/// list from `/user/repos`) or as `gh repo list --json` prints them.
+///
+/// Topics become tags, the primary language a `lang:` tag, and archived repositories
+/// get the `archived` status. Repositories are matched to projects by their URLs,
+/// then by name.
+pub fn parse(content: &str) -> io::Result<Vec<Seed>> {
+    let json: Value = serde_json::from_str(content).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let repositories = match json {
+        Value::Array(repositories) => repositories,
+        repository => vec![repository],
+    };
+    Ok(repositories.iter().filter(|repository| repository.is_object()).map(seed).collect())
+}
+
+fn seed(repository: &Value) -> Seed {
+    let text = |key: &str| repository.get(key).and_then(Value::as_str).map(str::to_string);
+
+    // The API lists topics as strings, `gh` as objects with a name.
+    let topics = repository
+        .get("topics")
+        .or_else(|| repository.get("repositoryTopics"))
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|topic| topic.as_str().or_else(|| topic.get("name").and_then(Value::as_str)));
+    let language = text("language")
+        .or_else(|| repository.pointer("/primaryLanguage/name").and_then(Value::as_str).map(str::to_string))
+        .map(|language| tags::namespaced("lang", language));
+
+    let archived = ["archived", "isArchived"]
+        .iter()
+        .any(|key| repository.get(*key).and_then(Value::as_bool) == Some(true));
+
+    Seed {
+        urls: ["html_url", "clone_url", "ssh_url", "url"].iter().filter_map(|key| text(key)).collect(),
+        name: text("name"),
+        tags: topics.map(str::to_string).chain(language).collect(),
+        description: text("description"),
+        status: archived.then_some(ProjectStatus::Archived),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_api_and_gh_output() {
+        let api = r#"{
+            "name": "tagger",
+            "full_name": "me/tagger",
+            "html_url": "https://github.com/me/tagger",
+            "ssh_url": "git@github.com:me/tagger.git",
+            "description": "Tags project folders",
+            "language": "Rust",
+            "topics": ["cli", "metadata"],
+            "archived": false
+        }"#;
+        let seeds = parse(api).unwrap();
+        assert_eq!(seeds[0].name.as_deref(), Some("tagger"));
+        assert_eq!(seeds[0].urls, ["https://github.com/me/tagger", "git@github.com:me/tagger.git"]);
+        assert_eq!(seeds[0].tags, ["cli", "metadata", "lang:Rust"]);
+        assert_eq!(seeds[0].status, None);
+
+        let gh = r#"[{
+            "name": "old-site",
+            "url": "https://github.com/me/old-site",
+            "description": "",
+            "primaryLanguage": {"name": "TypeScript"},
+            "repositoryTopics": [{"name": "website"}],
+            "isArchived": true
+        }]"#;
+        let seeds = parse(gh).unwrap();
+        assert_eq!(seeds[0].tags, ["website", "lang:TypeScript"]);
+        assert_eq!(seeds[0].status, Some(ProjectStatus::Archived));
+    }
+}
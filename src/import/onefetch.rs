@@ -0,0 +1,75 @@
+use std::io;
+
+use serde_json::Value;
+
+use super::{language_tags, Seed};
+
+/// Reads a repository's name, URL, description, and languages from
+/// `onefetch --output json`.
+///
+/// onefetch nests each field in an object named after it, such as
+/// `{"DescriptionInfo": {"description": "…"}}`, and the set of fields varies between
+/// versions, so they are looked up by key wherever they are.
+pub fn parse(content: &str) -> io::Result<Seed> {
+    let json: Value = serde_json::from_str(content).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    if !json.is_object() {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "expected onefetch's JSON object"));
+    }
+    let text = |key: &str| find(&json, key).and_then(Value::as_str).map(str::to_string);
+
+    let languages = find(&json, "languagesWithPercentage")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|language| {
+            let name = language.get("language")?.as_str()?;
+            Some((name, language.get("percentage")?.as_f64()?))
+        });
+
+    Ok(Seed {
+        urls: text("repoUrl").into_iter().collect(),
+        name: text("repoName"),
+        tags: language_tags(languages),
+        description: text("description"),
+        ..Default::default()
+    })
+}
+
+/// Finds the first value under a key, searching depth first.
+fn find<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    match value {
+        Value::Object(object) => object
+            .get(key)
+            .filter(|found| !found.is_null())
+            .or_else(|| object.values().find_map(|value| find(value, key))),
+        Value::Array(values) => values.iter().find_map(|value| find(value, key)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let output = r#"{
+            "title": {"gitUsername": "me", "gitVersion": "git version 2.43.0"},
+            "infoFields": [
+                {"ProjectInfo": {"repoName": "tagger", "numberOfTags": 3, "numberOfBranches": 2}},
+                {"DescriptionInfo": {"description": "Tags project folders"}},
+                {"LanguageInfo": {"languagesWithPercentage": [
+                    {"language": "Rust", "percentage": 97.2},
+                    {"language": "Shell", "percentage": 2.1},
+                    {"language": "Makefile", "percentage": 0.7}
+                ]}},
+                {"UrlInfo": {"repoUrl": "https://github.com/me/tagger"}}
+            ]
+        }"#;
+        let seed = parse(output).unwrap();
+        assert_eq!(seed.name.as_deref(), Some("tagger"));
+        assert_eq!(seed.urls, ["https://github.com/me/tagger"]);
+        assert_eq!(seed.description.as_deref(), Some("Tags project folders"));
+        assert_eq!(seed.tags, ["lang:Rust", "lang:Shell"]);
+    }
+}
@@ -0,0 +1,49 @@
+use std::io;
+
+use serde_json::Value;
+
+use super::{language_tags, Seed};
+
+/// Reads the languages of a directory from `tokei --output json`, as `lang:` tags
+/// weighted by lines of code.
+///
+/// The output does not say which project it is about, so the seed has no name.
+pub fn parse(content: &str) -> io::Result<Seed> {
+    let json: Value = serde_json::from_str(content).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let Some(languages) = json.as_object() else {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "expected an object of languages"));
+    };
+
+    let code: Vec<(&str, f64)> = languages
+        .iter()
+        .filter(|(language, _)| *language != "Total")
+        .filter_map(|(language, stats)| Some((language.as_str(), stats.get("code")?.as_f64()?)))
+        .collect();
+    let total: f64 = code.iter().map(|(_, lines)| lines).sum();
+    if total == 0.0 {
+        return Ok(Seed::default());
+    }
+
+    Ok(Seed {
+        tags: language_tags(code.into_iter().map(|(language, lines)| (language, lines * 100.0 / total))),
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let output = r#"{
+            "Rust": {"blanks": 120, "code": 4200, "comments": 300, "reports": [], "children": {}, "inaccurate": false},
+            "Shell": {"blanks": 1, "code": 12, "comments": 0, "reports": [], "children": {}, "inaccurate": false},
+            "TOML": {"blanks": 2, "code": 80, "comments": 1, "reports": [], "children": {}, "inaccurate": false},
+            "Total": {"blanks": 123, "code": 4292, "comments": 301, "reports": [], "children": {}, "inaccurate": false}
+        }"#;
+        let seed = parse(output).unwrap();
+        assert!(seed.is_anonymous());
+        assert_eq!(seed.tags, ["lang:Rust", "lang:TOML"]);
+    }
+}
@@ -0,0 +1,25 @@
+//! Generates and saves metadata (type, tags, notes) for project directories.
+
+pub mod archive;
+pub mod bundle;
+pub mod config;
+pub mod editor;
+pub mod enrichment;
+pub mod export;
+pub mod hooks;
+pub mod import;
+pub mod mcp;
+pub mod messages;
+pub mod plugins;
+pub mod project_info;
+pub mod registry;
+pub mod sandbox;
+pub mod scan;
+pub mod server;
+pub mod template;
+pub mod terminal;
+pub mod validate;
+pub mod watch;
+
+#[cfg(feature = "test-support")]
+pub mod test_support;
@@ -1,27 +1,1615 @@
-mod project_info;
-
-use project_info::ProjectInfo;
+use clap::{ArgAction, ArgGroup, Parser, Subcommand};
+use tagger::archive::{self, contents, ArchiveFormat};
+use tagger::bundle::BundleStore;
+use tagger::config::dirs::DirKind;
+use tagger::config::Config;
+use tagger::editor;
+use tagger::enrichment::{Enrichment, EnrichmentConfig};
+use tagger::export;
+use tagger::import::{self, Changes, ImportFormat, Target};
+use tagger::mcp::McpServer;
+use tagger::messages::{self, tr, Locale, Message};
+use tagger::project_info::fingerprint::Fingerprint;
+use tagger::project_info::overrides::Overrides;
+use tagger::project_info::related;
+use tagger::project_info::stats;
+use tagger::project_info::status::ProjectStatus;
+use tagger::project_info::tags;
+use tagger::project_info::volume;
+use tagger::project_info::{find_enclosing_project, overrides, ProjectInfo, KNOWN_PROJECT_TYPES};
+use tagger::registry::duplicates;
+use tagger::registry::health;
+use tagger::registry::relocation::{self, Evidence, Mismatch, Search};
+use tagger::registry::remote;
+use tagger::registry::similar;
+use tagger::registry::stats::RegistryStats;
+use tagger::registry::timeline::{Period, Timeline};
+use tagger::registry::{Registry, RegistryEntry};
+use tagger::scan::cache::ScanCache;
+use tagger::scan::retag::RetagPlan;
+use tagger::scan::sync::{self, RootSync, SyncState};
+use tagger::scan::{self, save_project, ScanOptions};
+use tagger::server;
+use tagger::template;
+use tagger::terminal::{self, ColorChoice, Style};
+use tagger::validate::Validator;
+use tagger::watch;
+use chrono::{Local, NaiveDate};
+use dialoguer::{Input, MultiSelect};
 use std::env;
-use std::io::{self, Write};
-use std::path::Path;
-use log::{info, warn, error};
-use env_logger;
+use std::fs::{self, File};
+use std::io::{self, IsTerminal, Write};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use log::{info, warn, error, LevelFilter};
+
+/// Generates and saves metadata (type, tags, notes) for a project directory.
+#[derive(Parser, Debug)]
+#[command(version, about, subcommand_negates_reqs = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// The project directory to tag interactively.
+    #[arg(required = true)]
+    directory_path: Option<PathBuf>,
+
+    /// Do not make network requests; only previously cached responses are used.
+    #[arg(long, global = true)]
+    offline: bool,
+
+    /// Record the URL of this git remote as the project's git_url (default: origin).
+    #[arg(long, value_name = "NAME", global = true)]
+    remote: Option<String>,
+
+    /// Record at most this many of the git history's contributors, most active first
+    /// (default: 5).
+    #[arg(long, value_name = "N", global = true)]
+    max_contributors: Option<usize>,
+
+    /// Write project information to this file instead of <dir>/project_info.toml. A
+    /// relative path is resolved against the project directory; a directory collects
+    /// one <project>.toml per project.
+    #[arg(long, value_name = "PATH", global = true)]
+    output: Option<PathBuf>,
+
+    /// On macOS, read the project folder's Finder tags as project tags and write the
+    /// project's tags back as Finder tags.
+    #[cfg(feature = "finder-tags")]
+    #[arg(long, global = true)]
+    finder_tags: bool,
+
+    /// Also write the project's tags to the folder's user.xdg.tags extended attribute
+    /// (an alternate data stream on Windows), where file managers can see them.
+    #[cfg(feature = "xattr-tags")]
+    #[arg(long, global = true)]
+    xattr_tags: bool,
+
+    /// Leave out the general tags every project of a type gets (e.g. "cli" and
+    /// "software development"), keeping only tags found in the project's files.
+    #[arg(long, global = true)]
+    no_default_tags: bool,
+
+    /// Leave files matching this glob out of every project, e.g. 'Bounces/**' or '*.wav'.
+    /// May be repeated.
+    #[arg(long = "exclude", value_name = "GLOB", global = true)]
+    excludes: Vec<String>,
+
+    /// Only scan files matching this glob, e.g. '*.rpp'. May be repeated.
+    #[arg(long = "include", value_name = "GLOB", global = true)]
+    includes: Vec<String>,
+
+    /// Scan the targets of symbolic links, such as sample libraries linked into a
+    /// session, instead of skipping them.
+    #[arg(long, global = true)]
+    follow_symlinks: bool,
+
+    /// Work on the given directory even when it is inside a project, instead of on the
+    /// root of that project (e.g. tag `song/Samples` rather than `song`).
+    #[arg(long, global = true)]
+    no_ascend: bool,
+
+    /// When to color output: auto (when printing to a terminal and NO_COLOR is not
+    /// set), always, or never.
+    #[arg(long, value_name = "WHEN", default_value_t = ColorChoice::Auto, global = true)]
+    color: ColorChoice,
+
+    /// Print what tagger is doing on standard error: -v for each step, -vv for what
+    /// every detector found, -vvv for everything.
+    #[arg(short, long, action = ArgAction::Count, global = true)]
+    verbose: u8,
+
+    /// Only print errors on standard error, not warnings.
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// Ask the language model configured under [suggest] for more tags. The project's
+    /// file listing and README excerpt are sent to it, and its suggestions are kept
+    /// apart from the tags until confirmed.
+    #[arg(long, global = true)]
+    suggest_tags: bool,
+
+    /// Enable a network enrichment provider (e.g. "github") in addition to those under
+    /// [network] in the configuration. May be repeated.
+    #[arg(long = "enable-provider", value_name = "PROVIDER", global = true)]
+    enabled_providers: Vec<String>,
+
+    /// Disable a network enrichment provider (e.g. "github"). May be repeated.
+    #[arg(long = "disable-provider", value_name = "PROVIDER", global = true)]
+    disabled_providers: Vec<String>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Scan one or more projects without prompting.
+    Scan {
+        /// The directory, or zip or tar archive, to scan. With `-`, the directories to
+        /// scan are read from standard input, one per line, and the results are printed
+        /// as with --ndjson.
+        root: PathBuf,
+
+        /// Discover and scan every project below the directory.
+        #[arg(short, long)]
+        recursive: bool,
+
+        /// Save project_info.toml for every scanned project.
+        #[arg(short = 'y', long = "yes")]
+        save: bool,
+
+        /// Also scan every member of a monorepo or workspace as its own project.
+        #[arg(long)]
+        per_subproject: bool,
+
+        /// Number of projects to scan concurrently (default: one per CPU).
+        #[arg(short, long, value_name = "N", default_value_t = 0, hide_default_value = true)]
+        jobs: usize,
+
+        /// Write a scan report to this file (Markdown for .md, JSON otherwise).
+        #[arg(long, value_name = "FILE")]
+        report: Option<PathBuf>,
+
+        /// Rescan every project instead of reusing cached results.
+        #[arg(long)]
+        no_cache: bool,
+
+        /// Also scan the .zip, .tar, .tar.gz, and .tar.zst archives found outside of
+        /// projects, without unpacking them. Their information is saved next to them.
+        #[arg(long)]
+        archives: bool,
+
+        /// Print each project as a line of JSON as soon as it is scanned, in the order
+        /// the scans finish, instead of a summary at the end.
+        #[arg(long)]
+        ndjson: bool,
+    },
+
+    /// Keep a project's project_info.toml up to date as its files change.
+    Watch {
+        /// The project directory to watch.
+        directory: PathBuf,
+    },
+
+    /// Review and edit a project's information before saving it.
+    Edit {
+        /// The project directory to edit.
+        directory: PathBuf,
+
+        /// Edit in a full-screen form instead of answering prompts.
+        #[arg(long)]
+        tui: bool,
+    },
+
+    /// Change a recorded field of a tagged project.
+    Set {
+        #[command(subcommand)]
+        command: SetCommand,
+    },
+
+    /// Link two tagged projects as related to each other.
+    Link {
+        /// The first project directory.
+        first: PathBuf,
+
+        /// The second project directory.
+        second: PathBuf,
+    },
+
+    /// Export every project in the registry for use in other tools.
+    #[command(group(ArgGroup::new("format").required(true)))]
+    Export {
+        /// Write a SQLite database with projects, tags, project_tags, and notes tables.
+        #[arg(long, value_name = "FILE", group = "format")]
+        sqlite: Option<PathBuf>,
+
+        /// Write one CSV row per project, to standard output when no file is given.
+        #[arg(long, value_name = "FILE", group = "format", num_args = 0..=1, default_missing_value = "-")]
+        csv: Option<PathBuf>,
+
+        /// Like --csv, but tab-separated.
+        #[arg(long, value_name = "FILE", group = "format", num_args = 0..=1, default_missing_value = "-")]
+        tsv: Option<PathBuf>,
+
+        /// Write one Markdown note per project, with YAML frontmatter, and an index page
+        /// into this folder (e.g. an Obsidian vault).
+        #[arg(long, value_name = "DIR", group = "format")]
+        markdown: Option<PathBuf>,
+
+        /// Instead of writing notes, add the same frontmatter to each project's README.md.
+        #[arg(long, group = "format")]
+        frontmatter_only: bool,
+    },
+
+    /// Seed saved project information from metadata kept in other tools: GitHub
+    /// repository JSON, `onefetch` or `tokei` JSON output, or a CSV inventory. Imported
+    /// tags, descriptions, and types are also recorded in each project's .tagger.toml,
+    /// so rescans keep them.
+    Import {
+        /// The file to import, or - for standard input.
+        file: PathBuf,
+
+        /// The file's format: github, onefetch, tokei, or csv (default: detected).
+        #[arg(long)]
+        format: Option<ImportFormat>,
+
+        /// The project to import into, instead of the one the file names by path,
+        /// repository URL, or name (default for tokei output: the current project).
+        #[arg(long, value_name = "DIR")]
+        project: Option<PathBuf>,
+
+        /// Show what would change without saving anything.
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Write a standalone HTML page summarizing every project below a directory.
+    Report {
+        /// The directory to scan.
+        root: PathBuf,
+
+        /// The HTML file to write.
+        #[arg(long, value_name = "FILE", default_value = "tagger-report.html")]
+        html: PathBuf,
+
+        /// Rescan every project instead of reusing cached results.
+        #[arg(long)]
+        no_cache: bool,
+    },
+
+    /// Serve a local JSON API over the registry for dashboards and other tools.
+    Serve {
+        /// The address to listen on.
+        #[arg(long, value_name = "HOST:PORT", default_value = server::DEFAULT_ADDRESS)]
+        address: String,
+    },
+
+    /// Serve the registry to LLM assistants over the Model Context Protocol, on
+    /// standard input and output.
+    Mcp,
+
+    /// Print the directory of a registered project, e.g. `cd "$(tagger path beat42)"`.
+    #[command(visible_alias = "cd")]
+    Path {
+        /// The project's alias, name, slug, or id.
+        project: String,
+    },
+
+    /// Open a registered project in its configured editor, DAW, or file manager.
+    Open {
+        /// The project's alias, name, slug, or id.
+        project: String,
+
+        /// Open it with this program instead of the configured one.
+        #[arg(long, value_name = "PROGRAM")]
+        with: Option<String>,
+    },
+
+    /// List the tagged projects in the registry.
+    List {
+        /// Only list projects with this status.
+        #[arg(long)]
+        status: Option<ProjectStatus>,
+
+        /// Only list projects with a matching tag: `rust`, `lang:rust`, or a whole
+        /// namespace such as `daw:`. May be repeated; every pattern must match.
+        #[arg(long = "tag", value_name = "PATTERN")]
+        tags: Vec<String>,
+
+        /// Print each project's registry entry as a line of JSON.
+        #[arg(long)]
+        ndjson: bool,
+
+        /// Only list the projects on drives that are not mounted.
+        #[arg(long)]
+        unmounted: bool,
+
+        /// First look for projects whose directories no longer exist, and register
+        /// them where they were moved to.
+        #[arg(long)]
+        repair: bool,
+
+        /// Also look in this directory, such as another drive, when repairing. May be
+        /// repeated.
+        #[arg(long, value_name = "DIR", requires = "repair")]
+        search: Vec<PathBuf>,
+
+        /// First rescan and save the registered projects that changed since their last
+        /// scan, so the list is current.
+        #[arg(long)]
+        refresh: bool,
+
+        /// Rescan every project when refreshing instead of reusing cached results.
+        #[arg(long, requires = "refresh")]
+        no_cache: bool,
+    },
+
+    /// Create a project directory, optionally from a template, and tag it.
+    Init {
+        /// The new project's directory, created if necessary.
+        directory: PathBuf,
+
+        /// Copy this template's files and directories and pre-fill its type, tags, and
+        /// notes. Templates live in the `templates` folder of the configuration
+        /// directory (see `tagger config path config`) or in installed bundles.
+        #[arg(long, value_name = "NAME")]
+        template: Option<String>,
+    },
+
+    /// Regenerate every tagged project below a directory with the current detectors,
+    /// showing what would change.
+    Retag {
+        /// The directory to search for tagged projects.
+        root: PathBuf,
+
+        /// Save the regenerated information instead of only showing the changes.
+        #[arg(long)]
+        apply: bool,
+    },
+
+    /// Flag registered projects below a directory that are probably copies of each
+    /// other: same git remote, same name and size, or identical key files.
+    Dedupe {
+        /// The directory whose registered projects are compared.
+        root: PathBuf,
+    },
+
+    /// Rank registered projects by how many tags, and whether the type, they share
+    /// with a project.
+    Similar {
+        /// The project directory to compare against (tagged or not).
+        directory: PathBuf,
+
+        /// The most projects listed.
+        #[arg(short = 'n', long, value_name = "N", default_value_t = 10)]
+        limit: usize,
+    },
+
+    /// Summarize the registry: how often each tag is used, projects and disk usage per
+    /// type, projects created per month, and tags only one project uses.
+    Stats {
+        /// The number of most used tags listed.
+        #[arg(long, value_name = "N", default_value_t = 20)]
+        top: usize,
+
+        /// Print every figure as JSON instead.
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Draw the registered projects on a timeline, from their creation to their last
+    /// modification, to see what was worked on when.
+    Timeline {
+        /// The time each column covers: month, quarter, or year.
+        #[arg(long, value_name = "PERIOD", default_value_t = Period::Quarter)]
+        period: Period,
+
+        /// Leave out projects last modified before this date (YYYY-MM-DD).
+        #[arg(long, value_name = "DATE")]
+        since: Option<NaiveDate>,
+
+        /// Group the projects by type.
+        #[arg(long)]
+        by_type: bool,
+
+        /// Print the timeline as JSON, for plotting, instead of drawing it.
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// List the registered projects that need attention, most urgent first: missing
+    /// directories, uncommitted or unpushed work, no remote or backup, no license, or
+    /// untouched for a year.
+    Health {
+        /// Print the report as JSON instead.
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Rescan every root configured under [[roots]] that its schedule says is due,
+    /// saving the projects found below it. Projects that did not change since they
+    /// were last scanned are not regenerated.
+    ///
+    /// With `push` or `pull`, share the registry with other machines through the
+    /// remote configured under [remote] instead.
+    #[command(args_conflicts_with_subcommands = true)]
+    Sync {
+        #[command(subcommand)]
+        remote: Option<SyncCommand>,
+
+        /// Rescan every root, even those that are not due.
+        #[arg(long)]
+        force: bool,
+
+        /// Number of projects to scan concurrently (default: one per CPU).
+        #[arg(short, long, value_name = "N", default_value_t = 0, hide_default_value = true)]
+        jobs: usize,
+
+        /// Regenerate every project instead of reusing cached results.
+        #[arg(long)]
+        no_cache: bool,
+    },
+
+    /// Check saved project information for schema problems, unknown fields, missing
+    /// tags, broken links, duplicate aliases, and future dates.
+    Validate {
+        /// The project directories to check (default: every registered project).
+        directories: Vec<PathBuf>,
+
+        /// Repair what can be repaired: drop unknown fields, regenerate missing tags,
+        /// remove broken links, and reset future dates.
+        #[arg(long)]
+        fix: bool,
+    },
+
+    /// Pack a project into a compressed archive with its information and a manifest
+    /// of file checksums, and record the archive in its information.
+    Archive {
+        /// The project directory.
+        directory: PathBuf,
+
+        /// The archive format: zip or tar.zst.
+        #[arg(long, default_value = "tar.zst")]
+        format: ArchiveFormat,
+
+        /// Where to write the archive (default: next to the project, named after it).
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+
+    /// Check whether a project's files changed since it was tagged, using the
+    /// fingerprint saved in its information.
+    Verify {
+        /// The project directory.
+        directory: PathBuf,
+    },
+
+    /// Install and list bundles of shared tagging rules and templates.
+    Bundle {
+        #[command(subcommand)]
+        command: BundleCommand,
+    },
+
+    /// Show where tagger keeps its files, or edit the configuration.
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum SetCommand {
+    /// Set the project's lifecycle status: active, paused, archived, or released.
+    Status {
+        /// The new status.
+        status: ProjectStatus,
+
+        /// The project directory.
+        #[arg(default_value = ".")]
+        directory: PathBuf,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum SyncCommand {
+    /// Upload the registry entries that changed to the remote.
+    Push {
+        /// Also upload each project's information file.
+        #[arg(long)]
+        projects: bool,
+    },
+
+    /// Download the registry entries that changed from the remote. Local entries the
+    /// remote does not have are kept.
+    Pull {
+        /// Also download the information files of the projects on this machine.
+        #[arg(long)]
+        projects: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum BundleCommand {
+    /// Install a bundle from a directory, a .tar/.tar.gz archive, or a URL.
+    Install {
+        /// The bundle's path or URL.
+        source: String,
+    },
+
+    /// List the installed bundles.
+    List,
+}
+
+#[derive(Subcommand, Debug)]
+enum ConfigCommand {
+    /// Print the path of the configuration file, or of the config, data, or cache
+    /// directory.
+    Path {
+        /// The directory to print: config, data, or cache.
+        kind: Option<DirKind>,
+    },
+
+    /// Open the configuration file in $VISUAL or $EDITOR, creating it if needed.
+    Edit,
+}
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Parse command-line arguments.
+    let cli = Cli::parse();
+
     // Initialize the logger.
-    env_logger::init();
+    init_logger(cli.verbose, cli.quiet);
 
-    // Collect command-line arguments.
-    let args: Vec<String> = env::args().collect();
+    // Without a home directory, tagger's files would end up wherever it was started.
+    if let Some(kind) = DirKind::ALL.into_iter().find(|kind| kind.dir().is_err()) {
+        error!("{}", tr(Message::NoHomeDirectory, &[&kind]));
+        std::process::exit(2);
+    }
 
-    // Expect exactly one argument: the directory path.
-    if args.len() != 2 {
-        eprintln!("Usage: {} <directory_path>", args[0]);
-        std::process::exit(1);
+    // User settings (tag blacklists, etc.) shared by every command. A broken
+    // configuration can still be fixed with `config edit`.
+    let mut config = match Config::load_default() {
+        Err(e) if matches!(cli.command, Some(Command::Config { .. })) => {
+            warn!("{}", e);
+            Config::default()
+        }
+        loaded => loaded?,
+    };
+    messages::set_locale(Locale::detect(config.language.as_deref()));
+    terminal::set_color(cli.color);
+    if let Some(remote) = cli.remote {
+        config.git.remote = Some(remote);
+    }
+    if let Some(max_contributors) = cli.max_contributors {
+        config.git.max_contributors = Some(max_contributors);
+    }
+    if let Some(output) = cli.output {
+        config.output.path = Some(output);
+    }
+    #[cfg(feature = "finder-tags")]
+    if cli.finder_tags {
+        config.output.finder_tags = true;
+    }
+    #[cfg(feature = "xattr-tags")]
+    if cli.xattr_tags {
+        config.output.xattr_tags = true;
+    }
+    config.scan.exclude.extend(cli.excludes);
+    config.scan.include.extend(cli.includes);
+    if cli.follow_symlinks {
+        config.scan.follow_symlinks = true;
+    }
+    if cli.no_default_tags {
+        config.tags.default_tags = Some(false);
+        for settings in config.types.values_mut() {
+            settings.default_tags = None;
+        }
+    }
+
+    if cli.suggest_tags && config.suggest.endpoint.is_none() {
+        error!("{}", tr(Message::SuggestNoEndpoint, &[]));
+        std::process::exit(2);
     }
 
-    let dir_path = Path::new(&args[1]);
+    // Enrichment layer shared by every command (cached, rate-limited).
+    let enrichment = Enrichment::new(EnrichmentConfig {
+        offline: cli.offline,
+        enabled_providers: config.network.providers.iter().cloned().chain(cli.enabled_providers).collect(),
+        disabled_providers: cli.disabled_providers.into_iter().collect(),
+        suggest: cli.suggest_tags.then(|| config.suggest.clone()),
+        scan: config.scan.clone(),
+        ..EnrichmentConfig::default()
+    });
 
+    let no_ascend = cli.no_ascend;
+    match cli.command {
+        Some(Command::Scan { root, recursive, save, per_subproject, jobs, report, no_cache, archives, ndjson }) => {
+            let from_stdin = root == Path::new("-");
+            let ndjson = ndjson || from_stdin;
+            let root = if recursive || from_stdin { root } else { project_root(root, no_ascend) };
+            if !from_stdin && !contents::is_archive(&root) {
+                validate_directory(&root);
+            }
+
+            let options = ScanOptions {
+                recursive,
+                save,
+                per_subproject,
+                cache_dir: (!no_cache).then(ScanCache::default_location).transpose()?,
+                jobs,
+                archives,
+                quiet: ndjson,
+            };
+            let roots = if from_stdin { read_path_list(io::stdin().lock())? } else { vec![root.clone()] };
+            if ndjson {
+                let scan_report = scan::scan_streaming(&root, &roots, &options, &config, &enrichment, |project| {
+                    if let Err(e) = print_json_line(project) {
+                        warn!("Could not print the result for {}: {}", project.path.display(), e);
+                    }
+                });
+                if let Some(report_path) = report {
+                    scan_report.write(&report_path)?;
+                }
+                return Ok(());
+            }
+            let scan_report = scan::scan(&root, &options, &config, &enrichment);
+
+            for project in &scan_report.projects {
+                match (&project.info, &project.error) {
+                    (_, Some(e)) => println!("❌ {}: {}", project.path.display(), e),
+                    (Some(info), None) => println!(
+                        "{} [{}] {:?}",
+                        project.path.display(),
+                        info.project_type,
+                        info.tags
+                    ),
+                    (None, None) => {}
+                }
+                for warning in &project.warnings {
+                    println!("  ⚠️  {}", warning);
+                }
+            }
+
+            if let Some(report_path) = report {
+                scan_report.write(&report_path)?;
+                println!("{}", tr(Message::ScanReportWritten, &[&report_path.display()]));
+            }
+            Ok(())
+        }
+        Some(Command::Watch { directory }) => {
+            let directory = project_root(directory, no_ascend);
+            validate_directory(&directory);
+            watch::watch(&directory, &config, &enrichment)
+        }
+        Some(Command::Edit { directory, tui: true }) => {
+            edit_in_terminal(&project_root(directory, no_ascend), &config, &enrichment)
+        }
+        Some(Command::Edit { directory, tui: false }) => {
+            tag_interactively(&project_root(directory, no_ascend), &config, &enrichment)
+        }
+        Some(Command::Set { command }) => run_set_command(command, no_ascend, &config),
+        Some(Command::Link { first, second }) => link_projects(&first, &second, &config),
+        Some(Command::Export { sqlite, csv, tsv, markdown, frontmatter_only }) => {
+            export_projects(sqlite, csv, tsv, markdown, frontmatter_only)
+        }
+        Some(Command::Import { file, format, project, dry_run }) => {
+            import_metadata(&file, format, project, dry_run, no_ascend, &config)
+        }
+        Some(Command::Report { root, html, no_cache }) => {
+            validate_directory(&root);
+
+            let options = ScanOptions {
+                recursive: true,
+                cache_dir: (!no_cache).then(ScanCache::default_location).transpose()?,
+                ..ScanOptions::default()
+            };
+            let entries: Vec<RegistryEntry> = scan::scan(&root, &options, &config, &enrichment)
+                .projects
+                .into_iter()
+                .filter_map(|project| Some(RegistryEntry { info: project.info?, path: project.path }))
+                .collect();
+
+            fs::write(&html, export::html::render(&root.display().to_string(), &entries))?;
+            println!("{}", tr(Message::ScanReportWritten, &[&html.display()]));
+            Ok(())
+        }
+        Some(Command::Serve { address }) => {
+            let listener = TcpListener::bind(&address)?;
+            println!("{}", tr(Message::Serving, &[&listener.local_addr()?]));
+            let api = server::Api::new(Registry::open_default()?, &config, &enrichment);
+            if let Some(token) = api.generated_token() {
+                println!("{}", tr(Message::ServingToken, &[&token]));
+            }
+            server::serve(listener, &api)?;
+            Ok(())
+        }
+        Some(Command::Mcp) => {
+            let server = McpServer::new(Registry::open_default()?, &config);
+            server.run(io::stdin().lock(), &mut io::stdout().lock())?;
+            Ok(())
+        }
+        Some(Command::Path { project }) => {
+            println!("{}", find_registered_project(&project)?.path.display());
+            Ok(())
+        }
+        Some(Command::Open { project, with }) => open_project(&project, with.as_deref(), &config),
+        Some(Command::List { status, tags, ndjson, unmounted, repair, search, refresh, no_cache }) => {
+            if repair {
+                repair_registry(&search, &config)?;
+            }
+            if refresh {
+                let options = ScanOptions {
+                    save: true,
+                    quiet: true,
+                    cache_dir: (!no_cache).then(ScanCache::default_location).transpose()?,
+                    ..ScanOptions::default()
+                };
+                refresh_registry(&options, !ndjson, &config, &enrichment)?;
+            }
+            list_projects(status, &tags, ndjson, unmounted)
+        }
+        Some(Command::Init { directory, template }) => init_project(&directory, template.as_deref(), &config),
+        Some(Command::Retag { root, apply }) => {
+            validate_directory(&root);
+            retag_projects(&root, apply, &config, &enrichment)
+        }
+        Some(Command::Dedupe { root }) => {
+            validate_directory(&root);
+            find_duplicate_projects(&root)
+        }
+        Some(Command::Similar { directory, limit }) => {
+            let directory = project_root(directory, no_ascend);
+            validate_directory(&directory);
+            find_similar_projects(&directory, limit, &config)
+        }
+        Some(Command::Stats { top, json }) => print_registry_stats(top, json),
+        Some(Command::Timeline { period, since, by_type, json }) => {
+            let timeline = Timeline::new(&Registry::open_default()?.entries()?, period, since);
+            if json {
+                println!("{}", serde_json::to_string_pretty(&timeline)?);
+            } else if timeline.projects.is_empty() {
+                println!("{}", tr(Message::NoProjects, &[]));
+            } else {
+                print!("{}", timeline.render(by_type));
+            }
+            Ok(())
+        }
+        Some(Command::Health { json }) => print_health_report(json),
+        Some(Command::Sync { remote: Some(command), .. }) => sync_remote(command, &config, &enrichment),
+        Some(Command::Sync { remote: None, force, jobs, no_cache }) => {
+            let options = ScanOptions {
+                cache_dir: (!no_cache).then(ScanCache::default_location).transpose()?,
+                jobs,
+                ..ScanOptions::default()
+            };
+            sync_roots(&options, force, &config, &enrichment)
+        }
+        Some(Command::Validate { directories, fix }) => validate_projects(directories, fix, &config),
+        Some(Command::Archive { directory, format, output }) => {
+            let directory = project_root(directory, no_ascend);
+            validate_directory(&directory);
+            archive_project(&directory, format, output, &config)
+        }
+        Some(Command::Verify { directory }) => {
+            let directory = project_root(directory, no_ascend);
+            validate_directory(&directory);
+            verify_project(&directory, &config)
+        }
+        Some(Command::Bundle { command }) => run_bundle_command(command, &enrichment),
+        Some(Command::Config { command }) => run_config_command(command),
+        None => {
+            let dir_path = cli.directory_path.expect("clap requires a directory without a subcommand");
+            tag_interactively(&project_root(dir_path, no_ascend), &config, &enrichment)
+        }
+    }
+}
+
+/// Loads a project's saved information, explaining that it must be tagged first if
+/// there is none.
+fn load_saved_project(directory: &Path, config: &Config) -> Result<ProjectInfo, String> {
+    let output = config.output.file_for(directory);
+    ProjectInfo::load_from_file(&output)
+        .map_err(|e| format!("Unable to read {} ({}); tag the project first.", output.display(), e))
+}
+
+/// Sets up logging to standard error. Warnings and errors are shown by default;
+/// `RUST_LOG` is honoured unless `-v` or `-q` is given.
+fn init_logger(verbose: u8, quiet: bool) {
+    let level = match (quiet, verbose) {
+        (true, _) => LevelFilter::Error,
+        (false, 0) => LevelFilter::Warn,
+        (false, 1) => LevelFilter::Info,
+        (false, 2) => LevelFilter::Debug,
+        (false, _) => LevelFilter::Trace,
+    };
+    let mut builder = env_logger::Builder::new();
+    builder.filter_level(LevelFilter::Warn).parse_default_env();
+    if quiet || verbose > 0 {
+        builder.filter_level(level);
+    }
+    builder.init();
+}
+
+/// Changes a field recorded in a project's saved information.
+fn run_set_command(command: SetCommand, no_ascend: bool, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+    match command {
+        SetCommand::Status { status, directory } => {
+            let directory = project_root(directory, no_ascend);
+            validate_directory(&directory);
+
+            let mut project = load_saved_project(&directory, config)?;
+            project.status = Some(status);
+            save_project(&directory, &project, config)?;
+            Ok(())
+        }
+    }
+}
+
+/// Records two projects in each other's `related` list and saves both.
+fn link_projects(first: &Path, second: &Path, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+    validate_directory(first);
+    validate_directory(second);
+    if std::fs::canonicalize(first)? == std::fs::canonicalize(second)? {
+        return Err("A project cannot be linked to itself.".into());
+    }
+
+    let mut first_project = load_saved_project(first, config)?;
+    let mut second_project = load_saved_project(second, config)?;
+
+    related::link(&mut first_project, first, &mut second_project, second);
+    save_project(first, &first_project, config)?;
+    save_project(second, &second_project, config)?;
+    println!("{}", tr(Message::Linked, &[&first_project.name, &second_project.name]));
+    Ok(())
+}
+
+/// Resolves a project alias, name, slug, or id to its registry entry, listing the
+/// candidates when several projects match.
+fn find_registered_project(key: &str) -> Result<RegistryEntry, Box<dyn std::error::Error>> {
+    let mut found = Registry::open_default()?.lookup(key)?;
+    match found.len() {
+        0 => Err(format!("No registered project is called '{}'.", key).into()),
+        1 => Ok(found.remove(0)),
+        _ => {
+            let candidates: Vec<String> = found
+                .iter()
+                .map(|entry| format!("  {} ({}) {}", entry.info.name, entry.info.id, entry.path.display()))
+                .collect();
+            Err(format!(
+                "Several projects are called '{}'; use an id or path instead:\n{}",
+                key,
+                candidates.join("\n")
+            )
+            .into())
+        }
+    }
+}
+
+/// Opens a registered project with the given program, the one configured under
+/// `[open]` for its type, or the system's file manager.
+fn open_project(key: &str, with: Option<&str>, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+    let entry = find_registered_project(key)?;
+    let command_line = with
+        .or_else(|| config.open_command(&entry.info.project_type))
+        .unwrap_or(if cfg!(target_os = "macos") {
+            "open"
+        } else if cfg!(windows) {
+            "explorer"
+        } else {
+            "xdg-open"
+        });
+
+    let mut words = command_line.split_whitespace();
+    let program = words.next().ok_or("The open command is empty.")?;
+    info!("Opening {} with {}", entry.path.display(), command_line);
+    std::process::Command::new(program)
+        .args(words)
+        .arg(&entry.path)
+        .spawn()
+        .map_err(|e| format!("Could not run '{}': {}", program, e))?;
+    println!("{}", tr(Message::Opened, &[&entry.info.name, &program]));
+    Ok(())
+}
+
+/// Prints the registered projects, optionally only those with one status and tags
+/// matching every pattern, as text or as one line of JSON each.
+fn list_projects(
+    status: Option<ProjectStatus>,
+    patterns: &[String],
+    ndjson: bool,
+    unmounted: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mounted = volume::mounted();
+    let entries: Vec<_> = Registry::open_default()?
+        .entries()?
+        .into_iter()
+        .filter(|entry| !unmounted || entry.offline_volume(&mounted).is_some())
+        .filter(|entry| status.is_none() || entry.info.status == status)
+        .filter(|entry| {
+            patterns
+                .iter()
+                .all(|pattern| entry.info.tags.iter().any(|tag| tags::matches(tag, pattern)))
+        })
+        .collect();
+
+    if ndjson {
+        for entry in &entries {
+            print_json_line(entry)?;
+        }
+        return Ok(());
+    }
+    if entries.is_empty() {
+        println!("{}", tr(Message::NoProjects, &[]));
+    }
+    for entry in &entries {
+        let status = entry.info.status.map(|status| format!(" ({})", status)).unwrap_or_default();
+        let offline = entry
+            .offline_volume(&mounted)
+            .map(|volume| {
+                let note = tr(Message::OfflineProject, &[&volume.name()]);
+                format!(" {}", terminal::paint(&note, Style::Dim))
+            })
+            .unwrap_or_default();
+        println!(
+            "{} [{}]{} {}{}",
+            entry.info.name,
+            entry.info.project_type,
+            status,
+            entry.path.display(),
+            offline
+        );
+    }
+    Ok(())
+}
+
+/// Rescans every registered project whose directory is reachable and saves the
+/// results. Unchanged projects reuse their cached scans. Failures go to standard
+/// error, and the summary is printed when `announce` is set.
+fn refresh_registry(
+    options: &ScanOptions,
+    announce: bool,
+    config: &Config,
+    enrichment: &Enrichment,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let paths: Vec<PathBuf> = Registry::open_default()?
+        .entries()?
+        .into_iter()
+        .map(|entry| entry.path)
+        .filter(|path| path.exists())
+        .collect();
+    let report = scan::scan_list(Path::new("registry"), &paths, options, config, enrichment);
+    for project in &report.projects {
+        if let Some(e) = &project.error {
+            eprintln!("❌ {}: {}", project.path.display(), e);
+        }
+    }
+    if announce {
+        let rescanned = report.projects.iter().filter(|project| !project.cached).count();
+        println!("{}", tr(Message::ListRefreshed, &[&report.projects.len(), &rescanned]));
+    }
+    Ok(())
+}
+
+/// Registers the projects whose directories no longer exist where they were moved
+/// to, and reports the ones that could not be found. Projects found by name alone
+/// are only moved once the user confirms.
+fn repair_registry(search_dirs: &[PathBuf], config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+    let registry = Registry::open_default()?;
+    let interactive = io::stdin().is_terminal() && io::stderr().is_terminal();
+    for repair in relocation::repair(&registry, search_dirs, config)? {
+        let name = &repair.entry.info.name;
+        let old_path = repair.entry.path.display();
+        let line = match &repair.search {
+            Search::Found { path, .. } if repair.needs_confirmation() => {
+                if !interactive || !prompt_repair(name, &repair.entry.path, path)? {
+                    eprintln!("{}", tr(Message::RepairUnconfirmed, &[name, &old_path, &path.display()]));
+                    continue;
+                }
+                repair.apply(&registry)?;
+                tr(Message::Repaired, &[name, &old_path, &path.display(), &Evidence::Name.describe()])
+            }
+            Search::Found { path, evidence } => {
+                tr(Message::Repaired, &[name, &old_path, &path.display(), &evidence.describe()])
+            }
+            Search::Ambiguous { candidates } => {
+                let candidates: Vec<String> = candidates.iter().map(|path| path.display().to_string()).collect();
+                tr(Message::RepairAmbiguous, &[name, &old_path, &candidates.join(", ")])
+            }
+            Search::NotFound => tr(Message::RepairNotFound, &[name, &old_path]),
+        };
+        // Keep standard output for the listing, which may be NDJSON.
+        eprintln!("{}", line);
+    }
+    Ok(())
+}
+
+/// Imports another tool's metadata into the projects it is about and saves them.
+fn import_metadata(
+    file: &Path,
+    format: Option<ImportFormat>,
+    project: Option<PathBuf>,
+    dry_run: bool,
+    no_ascend: bool,
+    config: &Config,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let content = if file == Path::new("-") { io::read_to_string(io::stdin())? } else { fs::read_to_string(file)? };
+    let Some(format) = format.or_else(|| ImportFormat::detect(file, &content)) else {
+        return Err(tr(Message::ImportUnknownFormat, &[&file.display()]).into());
+    };
+    let seeds = import::parse(format, &content)?;
+    if project.is_some() && seeds.len() > 1 {
+        return Err(format!("{} describes {} projects; --project takes only one.", file.display(), seeds.len()).into());
+    }
+
+    let registry = Registry::open_default()?;
+    let entries = registry.entries()?;
+    for seed in seeds {
+        let target = match &project {
+            Some(directory) => Target::Found(project_root(directory.clone(), no_ascend)),
+            None if seed.is_anonymous() => Target::Found(project_root(PathBuf::from("."), no_ascend)),
+            None => import::find(&seed, &registry, &entries)?,
+        };
+        let directory = match target {
+            Target::Found(directory) => directory,
+            Target::Ambiguous(candidates) => {
+                let candidates: Vec<String> = candidates.iter().map(|path| path.display().to_string()).collect();
+                println!("{}", tr(Message::ImportAmbiguous, &[&seed.label(), &candidates.join(", ")]));
+                continue;
+            }
+            Target::NotFound => {
+                println!("{}", tr(Message::ImportNotFound, &[&seed.label()]));
+                continue;
+            }
+        };
+
+        let mut info = match load_saved_project(&directory, config) {
+            Ok(info) => info,
+            Err(e) => {
+                println!("❌ {}: {}", directory.display(), e);
+                continue;
+            }
+        };
+        let changes = Changes::new(&seed, &info, config);
+        if changes.is_empty() {
+            println!("{}", tr(Message::ImportUnchanged, &[&info.name]));
+            continue;
+        }
+        println!("{}", tr(Message::Imported, &[&info.name, &changes.summary()]));
+        if !dry_run {
+            changes.apply(&directory, &mut info, config)?;
+            save_project(&directory, &info, config)?;
+        }
+    }
+    if dry_run {
+        println!("{}", tr(Message::ImportDryRun, &[]));
+    }
+    Ok(())
+}
+
+/// Exports every registered project in the requested format.
+fn export_projects(
+    sqlite: Option<PathBuf>,
+    csv: Option<PathBuf>,
+    tsv: Option<PathBuf>,
+    markdown: Option<PathBuf>,
+    frontmatter_only: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let entries = Registry::open_default()?.entries()?;
+
+    if let Some(path) = sqlite {
+        export::sqlite::export(&entries, &path)?;
+        println!("{}", tr(Message::Exported, &[&entries.len(), &path.display()]));
+    }
+
+    let delimited = csv.map(|path| (path, ',')).or_else(|| tsv.map(|path| (path, '\t')));
+    if let Some((path, delimiter)) = delimited {
+        if path == Path::new("-") {
+            export::csv::export(&entries, delimiter, &mut io::stdout().lock())?;
+        } else {
+            let mut file = io::BufWriter::new(File::create(&path)?);
+            export::csv::export(&entries, delimiter, &mut file)?;
+            file.flush()?;
+            println!("{}", tr(Message::Exported, &[&entries.len(), &path.display()]));
+        }
+    }
+
+    if let Some(directory) = markdown {
+        let count = export::markdown::export(&entries, &directory)?;
+        println!("{}", tr(Message::Exported, &[&count, &directory.display()]));
+    }
+    if frontmatter_only {
+        let count = export::markdown::prepend_frontmatter(&entries)?;
+        println!("{}", tr(Message::FrontmatterAdded, &[&count]));
+    }
+    Ok(())
+}
+
+/// Creates a project directory from an optional template and saves its information.
+fn init_project(directory: &Path, template: Option<&str>, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+    let output = config.output.file_for(directory);
+    if output.exists() {
+        return Err(format!("{} already exists; use `tagger edit` to change it.", output.display()).into());
+    }
+    let template = template.map(template::find).transpose()?;
+
+    fs::create_dir_all(directory)?;
+    if let Some(template) = &template {
+        template.scaffold(directory)?;
+        // Pin the type, since a new project has few files to detect it from.
+        if let Some(project_type) = &template.manifest.project_type {
+            overrides::save_project_type(directory, project_type)?;
+        }
+    }
+
+    let mut project = ProjectInfo::generate_project_info(directory, config)?;
+    if let Some(template) = &template {
+        template.apply(&mut project);
+    }
+    save_project(directory, &project, config)?;
+    println!("{}", tr(Message::Initialized, &[&project.name, &project.project_type]));
+    Ok(())
+}
+
+/// Regenerates every tagged project below a directory, printing a summary of the
+/// changes and saving them when `apply` is set.
+fn retag_projects(
+    root: &Path,
+    apply: bool,
+    config: &Config,
+    enrichment: &Enrichment,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let plan = RetagPlan::new(root, config, enrichment);
+    for (directory, e) in &plan.errors {
+        println!("❌ {}: {}", directory.display(), e);
+    }
+
+    let changes: Vec<_> = plan.changes().collect();
+    if changes.is_empty() {
+        println!("{}", tr(Message::RetagUpToDate, &[&plan.projects.len()]));
+    } else {
+        println!("{}", tr(Message::RetagPlanned, &[&changes.len(), &plan.projects.len()]));
+        print!("{}", plan.summary());
+    }
+
+    if !apply {
+        if !changes.is_empty() {
+            println!("{}", tr(Message::RetagApplyHint, &[]));
+        }
+        return Ok(());
+    }
+    // Unchanged projects are saved too, so their sizes and dates are refreshed.
+    for project in &plan.projects {
+        save_project(&project.directory, &project.info, config)?;
+    }
+    println!("{}", tr(Message::Retagged, &[&plan.projects.len()]));
+    Ok(())
+}
+
+/// Prints the groups of registered projects below a directory that look like copies
+/// of one another.
+fn find_duplicate_projects(root: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let root = fs::canonicalize(root)?;
+    let entries: Vec<RegistryEntry> = Registry::open_default()?
+        .entries()?
+        .into_iter()
+        .filter(|entry| entry.path.starts_with(&root))
+        .collect();
+
+    let groups = duplicates::find_duplicates(&entries);
+    if groups.is_empty() {
+        println!("{}", tr(Message::NoDuplicates, &[&root.display()]));
+    }
+    for group in groups {
+        let reasons: Vec<String> = group.reasons.iter().map(ToString::to_string).collect();
+        println!("{}", tr(Message::Duplicates, &[&reasons.join("; ")]));
+        for entry in group.projects {
+            println!(
+                "  {} [{}, {} files, modified {}]",
+                entry.path.display(),
+                entry.info.name,
+                entry.info.file_count,
+                entry.info.date_modified.format("%Y-%m-%d")
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Lists the registered projects most similar to a project, with their score and the
+/// tags they share. An untagged project is compared by its generated information.
+fn find_similar_projects(directory: &Path, limit: usize, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+    let output = config.output.file_for(directory);
+    let info = match ProjectInfo::load_from_file(&output) {
+        Ok(info) => info,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => ProjectInfo::generate_project_info(directory, config)?,
+        Err(e) => return Err(format!("Unable to read {}: {}", output.display(), e).into()),
+    };
+    let directory = fs::canonicalize(directory)?;
+    let entries = Registry::open_default()?.entries()?;
+
+    let similar = similar::find_similar(&directory, &info, &entries, limit);
+    if similar.is_empty() {
+        println!("{}", tr(Message::NoSimilarProjects, &[&info.name]));
+        return Ok(());
+    }
+    println!("{}", tr(Message::SimilarProjects, &[&info.name]));
+    for project in similar {
+        println!(
+            "  {:>3.0}%  {} [{}] {}",
+            project.score * 100.0,
+            project.entry.info.name,
+            project.entry.info.project_type,
+            project.entry.path.display()
+        );
+        if !project.shared_tags.is_empty() {
+            println!("        {}", terminal::paint(&project.shared_tags.join(", "), Style::Dim));
+        }
+    }
+    Ok(())
+}
+
+/// Prints figures about every registered project, as tables or as JSON.
+fn print_registry_stats(top: usize, json: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let stats = RegistryStats::compute(&Registry::open_default()?.entries()?);
+    if json {
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+        return Ok(());
+    }
+
+    let heading = |text: String| terminal::paint(&text, Style::Label);
+    println!("{}", heading(tr(Message::StatsProjects, &[&stats.projects])));
+    if stats.projects == 0 {
+        return Ok(());
+    }
+
+    let shown = top.min(stats.tags.len());
+    println!("\n{}", heading(tr(Message::StatsTags, &[&shown, &stats.tags.len()])));
+    let width = stats.tags.iter().take(shown).map(|count| count.tag.chars().count()).max().unwrap_or_default();
+    for count in stats.tags.iter().take(shown) {
+        println!("  {:<width$}  {:>5}", count.tag, count.projects, width = width);
+    }
+
+    println!("\n{}", heading(tr(Message::StatsTypes, &[])));
+    let width = stats.types.iter().map(|types| types.project_type.chars().count()).max().unwrap_or_default();
+    for types in &stats.types {
+        println!(
+            "  {:<width$}  {:>5}  {:>9}",
+            types.project_type,
+            types.projects,
+            stats::format_size(types.total_size_bytes),
+            width = width
+        );
+    }
+
+    println!("\n{}", heading(tr(Message::StatsMonths, &[])));
+    let busiest = stats.created_per_month.values().copied().max().unwrap_or(1);
+    for (month, count) in &stats.created_per_month {
+        let bar = "█".repeat((count * 40).div_ceil(busiest));
+        println!("  {}  {:>5}  {}", month, count, terminal::paint(&bar, Style::Dim));
+    }
+
+    if !stats.orphaned_tags.is_empty() {
+        println!("\n{}", heading(tr(Message::StatsOrphanedTags, &[&stats.orphaned_tags.len()])));
+        println!("  {}", stats.orphaned_tags.join(", "));
+    }
+    Ok(())
+}
+
+/// Prints the registered projects that need attention as a numbered to-do list, or
+/// as JSON.
+fn print_health_report(json: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let report = health::assess(&Registry::open_default()?.entries()?, Local::now());
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+    if report.is_empty() {
+        println!("{}", tr(Message::AllHealthy, &[]));
+        return Ok(());
+    }
+
+    println!("{}", tr(Message::NeedsAttention, &[&report.len()]));
+    for (index, project) in report.iter().enumerate() {
+        println!(
+            "{:>3}. {} [{}] {}",
+            index + 1,
+            terminal::paint(&project.entry.info.name, Style::Title),
+            project.entry.info.project_type,
+            project.entry.path.display()
+        );
+        for issue in &project.issues {
+            let text = issue.to_string();
+            let text = if issue.weight() >= 3 { terminal::paint(&text, Style::Warning) } else { text };
+            println!("       - {}", text);
+        }
+    }
+    Ok(())
+}
+
+/// Rescans the configured roots that are due and reports each one.
+fn sync_roots(
+    options: &ScanOptions,
+    force: bool,
+    config: &Config,
+    enrichment: &Enrichment,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if config.roots.is_empty() {
+        println!("{}", tr(Message::SyncNoRoots, &[&Config::default_location()?.display()]));
+        return Ok(());
+    }
+
+    let state_path = SyncState::default_location()?;
+    let mut state = SyncState::load(&state_path);
+    for (root, outcome) in sync::sync(config, options, enrichment, &mut state, force) {
+        match outcome {
+            RootSync::Scanned { report, missing } => {
+                let rescanned = report.projects.iter().filter(|project| !project.cached).count();
+                println!("{}", tr(Message::SyncedRoot, &[&root.display(), &report.projects.len(), &rescanned]));
+                for project in &report.projects {
+                    if let Some(e) = &project.error {
+                        println!("  ❌ {}: {}", project.path.display(), e);
+                    }
+                }
+                if missing > 0 {
+                    println!("{}", tr(Message::SyncMissing, &[&missing]));
+                }
+            }
+            RootSync::NotDue { last_synced } => println!(
+                "{}",
+                tr(Message::SyncNotDue, &[&root.display(), &last_synced.format("%Y-%m-%d %H:%M")])
+            ),
+            RootSync::Unavailable => println!("{}", tr(Message::SyncUnavailable, &[&root.display()])),
+        }
+    }
+    state.save(&state_path)?;
+    Ok(())
+}
+
+/// Pushes the registry to, or pulls it from, the configured remote.
+fn sync_remote(command: SyncCommand, config: &Config, enrichment: &Enrichment) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(url) = &config.remote.url else {
+        println!("{}", tr(Message::SyncNoRemote, &[&Config::default_location()?.display()]));
+        return Ok(());
+    };
+
+    let registry = Registry::open_default()?;
+    let mut backend = remote::open(&config.remote, enrichment)?;
+    let transfer = match command {
+        SyncCommand::Push { projects } => {
+            let transfer = remote::push(backend.as_mut(), &registry, config, projects || config.remote.projects)?;
+            println!("{}", tr(Message::SyncPushed, &[&transfer.copied.len(), url, &transfer.unchanged]));
+            transfer
+        }
+        SyncCommand::Pull { projects } => {
+            let transfer = remote::pull(backend.as_mut(), &registry, config, projects || config.remote.projects)?;
+            println!("{}", tr(Message::SyncPulled, &[&transfer.copied.len(), url, &transfer.unchanged]));
+            transfer
+        }
+    };
+    for name in &transfer.conflicts {
+        println!("{}", tr(Message::SyncConflict, &[name]));
+    }
+    Ok(())
+}
+
+/// Archives a project and records the archive's location and checksum in its
+/// information.
+fn archive_project(
+    directory: &Path,
+    format: ArchiveFormat,
+    output: Option<PathBuf>,
+    config: &Config,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut project = load_saved_project(directory, config)?;
+    let directory = fs::canonicalize(directory)?;
+    let output = output.unwrap_or_else(|| {
+        let name = directory.file_name().unwrap_or_default().to_string_lossy();
+        directory.with_file_name(format!("{}.{}", name, format.extension()))
+    });
+
+    let record = archive::create(&directory, &project, &output, format)?;
+    println!(
+        "{}",
+        tr(Message::Archived, &[&record.file_count, &record.path.display(), &record.sha256])
+    );
+    project.archives.push(record);
+    save_project(&directory, &project, config)?;
+    Ok(())
+}
+
+/// Compares a project's files with the fingerprint saved in its information, and
+/// exits with an error if they changed.
+fn verify_project(directory: &Path, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+    let project = load_saved_project(directory, config)?;
+    let saved = project.fingerprint.ok_or_else(|| {
+        format!("{} has no fingerprint yet; scan and save it first.", directory.display())
+    })?;
+
+    let scan = Overrides::load(directory).scan_config(&config.scan);
+    let current = Fingerprint::compute(directory, saved.contents, &scan, &config.output.file_for(directory))?;
+    let date = saved.date.format("%Y-%m-%d %H:%M");
+    if current.matches(&saved) {
+        println!("{}", tr(Message::VerifyUnchanged, &[&project.name, &date, &current.file_count]));
+        return Ok(());
+    }
+    println!(
+        "{}",
+        tr(
+            Message::VerifyChanged,
+            &[
+                &project.name,
+                &date,
+                &saved.file_count,
+                &current.file_count,
+                &stats::format_size(saved.total_size_bytes),
+                &stats::format_size(current.total_size_bytes),
+            ]
+        )
+    );
+    std::process::exit(1);
+}
+
+/// Validates saved project information, optionally repairing it, and exits with an
+/// error if problems remain.
+fn validate_projects(directories: Vec<PathBuf>, fix: bool, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+    let entries = Registry::open_default()?.entries()?;
+    let directories = if directories.is_empty() {
+        entries.iter().map(|entry| entry.path.clone()).collect()
+    } else {
+        directories
+    };
+
+    let validator = Validator::new(&entries, config);
+    let mut remaining = 0;
+    let mut fixable = 0;
+    let mut affected = 0;
+    for directory in &directories {
+        let validation = validator.validate(directory);
+        if validation.problems.is_empty() {
+            continue;
+        }
+
+        if fix {
+            if let Some(repaired) = validator.fix(&validation)? {
+                save_project(directory, &repaired, config)?;
+                println!("{}", tr(Message::ValidationFixed, &[&validation.fixable(), &validation.file.display()]));
+            }
+        }
+        let unfixed: Vec<_> = validation
+            .problems
+            .iter()
+            .filter(|problem| !(fix && problem.is_fixable()))
+            .collect();
+        for problem in &unfixed {
+            println!("{}: {}", validation.file.display(), problem);
+        }
+        if !unfixed.is_empty() {
+            affected += 1;
+            remaining += unfixed.len();
+            fixable += unfixed.iter().filter(|problem| problem.is_fixable()).count();
+        }
+    }
+
+    if remaining == 0 {
+        println!("{}", tr(Message::ValidationPassed, &[&directories.len()]));
+        return Ok(());
+    }
+    println!("{}", tr(Message::ValidationFailed, &[&remaining, &affected, &fixable]));
+    std::process::exit(1);
+}
+
+/// Installs or lists bundles.
+/// Prints where tagger keeps its files, or edits the configuration file.
+fn run_config_command(command: ConfigCommand) -> Result<(), Box<dyn std::error::Error>> {
+    let path = Config::default_location()?;
+    match command {
+        ConfigCommand::Path { kind: None } => println!("{}", path.display()),
+        ConfigCommand::Path { kind: Some(kind) } => println!("{}", kind.dir()?.display()),
+        ConfigCommand::Edit => {
+            if !path.exists() {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(&path, Config::TEMPLATE)?;
+            }
+
+            let editor = env::var("VISUAL")
+                .or_else(|_| env::var("EDITOR"))
+                .ok()
+                .filter(|editor| !editor.trim().is_empty())
+                .unwrap_or_else(|| if cfg!(windows) { "notepad" } else { "vi" }.to_string());
+            let mut words = editor.split_whitespace();
+            let program = words.next().ok_or("The editor command is empty.")?;
+            info!("Editing {} with {}", path.display(), editor);
+            let status = std::process::Command::new(program)
+                .args(words)
+                .arg(&path)
+                .status()
+                .map_err(|e| format!("Could not run '{}': {}", program, e))?;
+            if !status.success() {
+                return Err(format!("'{}' exited with {}", program, status).into());
+            }
+            Config::load(&path)?;
+        }
+    }
+    Ok(())
+}
+
+fn run_bundle_command(command: BundleCommand, enrichment: &Enrichment) -> Result<(), Box<dyn std::error::Error>> {
+    let store = BundleStore::open_default()?;
+
+    match command {
+        BundleCommand::Install { source } => {
+            let bundle = store.install(&source, enrichment)?;
+            println!(
+                "{}",
+                tr(
+                    Message::BundleInstalled,
+                    &[
+                        &bundle.manifest.name,
+                        &bundle.manifest.version,
+                        &bundle.manifest.rules.len(),
+                        &bundle.templates().len(),
+                    ]
+                )
+            );
+        }
+        BundleCommand::List => {
+            let bundles = store.bundles()?;
+            if bundles.is_empty() {
+                println!("{}", tr(Message::NoBundles, &[]));
+            }
+            for bundle in bundles {
+                println!(
+                    "{} {} — {}",
+                    bundle.manifest.name, bundle.manifest.version, bundle.manifest.description
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the root of the project a directory is inside of, so that tagging
+/// `song/Samples` tags `song`, unless `no_ascend` is set or no enclosing project is
+/// found.
+fn project_root(directory: PathBuf, no_ascend: bool) -> PathBuf {
+    if no_ascend || !directory.is_dir() {
+        return directory;
+    }
+    match find_enclosing_project(&directory) {
+        Some(root) if fs::canonicalize(&directory).ok() != Some(root.clone()) => {
+            println!("{}", tr(Message::UsingProjectRoot, &[&root.display()]));
+            root
+        }
+        _ => directory,
+    }
+}
+
+/// Prints a value as one line of JSON, in one write so lines printed from several
+/// threads do not interleave.
+fn print_json_line(value: &impl serde::Serialize) -> io::Result<()> {
+    let mut line = serde_json::to_string(value)?;
+    line.push('\n');
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(line.as_bytes())?;
+    stdout.flush()
+}
+
+/// Reads a list of paths, one per line, skipping blank lines.
+fn read_path_list(input: impl io::BufRead) -> io::Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+    for line in input.lines() {
+        let line = line?;
+        let path = line.trim_end_matches('\r');
+        if !path.trim().is_empty() {
+            paths.push(PathBuf::from(path));
+        }
+    }
+    Ok(paths)
+}
+
+/// Exits with an error if the path is not an existing directory.
+fn validate_directory(dir_path: &Path) {
     // Validate that the path exists and is a directory.
     if !dir_path.exists() {
         error!("The path '{}' does not exist.", dir_path.display());
@@ -32,17 +1620,74 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         error!("The path '{}' is not a directory.", dir_path.display());
         std::process::exit(1);
     }
+}
+
+/// Generates project information and walks the user through editing and saving it.
+fn tag_interactively(
+    dir_path: &Path,
+    config: &Config,
+    enrichment: &Enrichment,
+) -> Result<(), Box<dyn std::error::Error>> {
+    validate_directory(dir_path);
 
     // Automatically generate ProjectInfo using the generate_project_info function.
-    let mut project = ProjectInfo::generate_project_info(dir_path)?; // Make project mutable.
+    let mut project = ProjectInfo::generate_project_info(dir_path, config)?; // Make project mutable.
+
+    // Enrich the project with network metadata.
+    enrichment.enrich(dir_path, &mut project);
 
     info!("Project information generated successfully.");
 
+    // Reconcile a project_info.toml that was written for another directory, so two
+    // directories do not silently share one identity.
+    let mut relocated = false;
+    if let Ok(registry) = Registry::open_default() {
+        if let Some(mismatch) = relocation::detect(dir_path, &registry) {
+            relocated = true;
+            if prompt_relink(&mismatch)? {
+                match mismatch.relink(&mut project, &registry) {
+                    Ok(()) => info!("Project relinked to {}.", dir_path.display()),
+                    Err(e) => warn!("Could not update the project registry: {}", e),
+                }
+            } else {
+                info!("Treating {} as a new project.", dir_path.display());
+            }
+            println!();
+        }
+    }
+
+    // Keep what was recorded for a project tagged before (its id, status, etc.).
+    if !relocated {
+        if let Ok(recorded) = ProjectInfo::load_from_file(&config.output.file_for(dir_path)) {
+            project.keep_user_fields(recorded);
+        }
+    }
+
     // Print project information.
     project.print_info();
 
+    // Let the user correct a type that could not be detected with confidence.
+    if project.type_uncertain {
+        if let Some(project_type) = prompt_project_type(&project.project_type)? {
+            project.set_project_type(dir_path, &project_type, config)?;
+            match overrides::save_project_type(dir_path, &project_type) {
+                Ok(()) => info!("Project type '{}' recorded in {}.", project_type, overrides::OVERRIDES_FILE),
+                Err(e) => warn!("Could not record the project type: {}", e),
+            }
+            println!();
+            project.print_info();
+        }
+    }
+
+    // Let the user drop noisy tags and add their own.
+    if io::stdin().is_terminal() && io::stderr().is_terminal() {
+        project.tags = prompt_tags(&project.tags, &project.suggested_tags)?;
+        project.suggested_tags.clear();
+        info!("Tags selected: {:?}", project.tags);
+    }
+
     // Prompt for alias.
-    println!("Enter an alias for the project (or press Enter to skip):");
+    println!("{}", tr(Message::PromptAlias, &[]));
     io::stdout().flush()?;
     let mut alias_input = String::new();
     io::stdin().read_line(&mut alias_input)?;
@@ -53,7 +1698,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     // Prompt for notes.
-    println!("Enter a note for the project (or press Enter to skip):");
+    println!("{}", tr(Message::PromptNote, &[]));
     io::stdout().flush()?;
     let mut note_input = String::new();
     io::stdin().read_line(&mut note_input)?;
@@ -65,33 +1710,36 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Prompt the user to decide whether to save the project information.
     loop {
-        println!("\nDo you want to save this project information to 'project_info.toml'? (y/n):");
+        println!("\n{}", tr(Message::PromptSave, &[]));
 
         // Flush stdout to ensure the prompt is displayed.
         io::stdout().flush()?;
 
-        // Read user input.
+        // Read user input; a closed stdin means the answer will never come.
         let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
+        if io::stdin().read_line(&mut input)? == 0 {
+            println!("{}", tr(Message::NotSaved, &[]));
+            warn!("Input ended before the save prompt was answered.");
+            break;
+        }
 
         let input = input.trim().to_lowercase();
         match input.as_str() {
-            "y" | "yes" => {
-                // Attempt to save the project info.
-                if let Err(e) = project.save_to_toml_file(dir_path) {
+            answer if messages::is_yes(answer) => {
+                // Attempt to save the project info and register it.
+                if let Err(e) = save_project(dir_path, &project, config) {
                     error!("Error saving project_info.toml: {}", e);
                     std::process::exit(1);
                 }
-                info!("Project information saved successfully.");
                 break;
             }
-            "n" | "no" => {
-                println!("❌ Project information not saved.");
+            answer if messages::is_no(answer) => {
+                println!("{}", tr(Message::NotSaved, &[]));
                 info!("User chose not to save the project information.");
                 break;
             }
             _ => {
-                println!("Invalid input. Please enter 'y' or 'n'.");
+                println!("{}", tr(Message::InvalidYesNo, &[]));
                 warn!("User provided invalid input: {}", input);
                 continue;
             }
@@ -100,3 +1748,167 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+/// Generates project information and lets the user review it in a full-screen form.
+///
+/// The alias, notes, and custom tags recorded in the existing output file are
+/// offered alongside the generated tags.
+fn edit_in_terminal(
+    dir_path: &Path,
+    config: &Config,
+    enrichment: &Enrichment,
+) -> Result<(), Box<dyn std::error::Error>> {
+    validate_directory(dir_path);
+
+    let mut project = ProjectInfo::generate_project_info(dir_path, config)?;
+    enrichment.enrich(dir_path, &mut project);
+
+    let mut tags = project.tags.clone();
+    let output = config.output.file_for(dir_path);
+    match ProjectInfo::load_from_file(&output) {
+        Ok(recorded) => {
+            for tag in &recorded.tags {
+                if !tags.contains(tag) {
+                    tags.push(tag.clone());
+                }
+            }
+            project.keep_user_fields(recorded);
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => warn!("Unable to read the existing {}: {}", output.display(), e),
+    }
+
+    // Save only once the terminal is back to normal, so messages are readable.
+    match editor::run(project, tags)? {
+        Some(project) => save_project(dir_path, &project, config)?,
+        None => println!("{}", tr(Message::NotSaved, &[])),
+    }
+    Ok(())
+}
+
+/// Shows the generated tags as a checklist, then asks for additional tags.
+///
+/// Every generated tag starts checked; suggested tags follow, marked and unchecked,
+/// so they are only kept when confirmed. Cancelling the checklist with Esc keeps the
+/// generated tags and drops the suggestions.
+fn prompt_tags(tags: &[String], suggested: &[String]) -> io::Result<Vec<String>> {
+    let mut selected: Vec<String> = if tags.is_empty() && suggested.is_empty() {
+        Vec::new()
+    } else {
+        let items: Vec<String> = tags
+            .iter()
+            .cloned()
+            .chain(suggested.iter().map(|tag| tr(Message::SuggestedTag, &[tag])))
+            .collect();
+        let defaults: Vec<bool> = (0..items.len()).map(|index| index < tags.len()).collect();
+        let checked = MultiSelect::new()
+            .with_prompt(tr(Message::PromptTags, &[]))
+            .items(&items)
+            .defaults(&defaults)
+            .interact_opt()
+            .map_err(|dialoguer::Error::IO(e)| e)?;
+        let all: Vec<&String> = tags.iter().chain(suggested).collect();
+        match checked {
+            Some(indices) => indices.into_iter().map(|index| all[index].clone()).collect(),
+            None => tags.to_vec(),
+        }
+    };
+
+    let extra: String = Input::new()
+        .with_prompt(tr(Message::PromptExtraTags, &[]))
+        .allow_empty(true)
+        .interact_text()
+        .map_err(|dialoguer::Error::IO(e)| e)?;
+    for tag in extra.split(',').map(|tag| tag.trim().to_lowercase()) {
+        if !tag.is_empty() && !selected.contains(&tag) {
+            selected.push(tag);
+        }
+    }
+
+    selected.sort();
+    Ok(selected)
+}
+
+/// Asks the user whether a missing project moved to a directory that only shares
+/// its name.
+///
+/// Returns `true` to register it there; a closed stdin leaves it as is.
+fn prompt_repair(name: &str, old_path: &Path, path: &Path) -> io::Result<bool> {
+    loop {
+        eprintln!("{}", tr(Message::PromptRepair, &[&name, &old_path.display(), &path.display()]));
+
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input)? == 0 {
+            return Ok(false);
+        }
+
+        match input.trim().to_lowercase().as_str() {
+            answer if messages::is_yes(answer) => return Ok(true),
+            answer if messages::is_no(answer) => return Ok(false),
+            other => {
+                eprintln!("{}", tr(Message::InvalidYesNo, &[]));
+                warn!("User provided invalid input: {}", other);
+            }
+        }
+    }
+}
+
+/// Asks the user whether a relocated project keeps its recorded identity.
+///
+/// Returns `true` to relink; a closed stdin starts a new project.
+fn prompt_relink(mismatch: &Mismatch) -> io::Result<bool> {
+    println!("⚠️  {}", mismatch.describe());
+
+    loop {
+        println!("{}", tr(Message::PromptRelink, &[]));
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input)? == 0 {
+            return Ok(false);
+        }
+
+        match input.trim().to_lowercase().as_str() {
+            "r" | "relink" => return Ok(true),
+            "n" | "new" => return Ok(false),
+            other => {
+                println!("{}", tr(Message::InvalidRelink, &[]));
+                warn!("User provided invalid input: {}", other);
+            }
+        }
+    }
+}
+
+/// Asks the user to pick a project type from the known list or enter a custom one.
+///
+/// Returns `None` when the user keeps the detected type.
+fn prompt_project_type(detected: &str) -> io::Result<Option<String>> {
+    println!("\n{}", tr(Message::TypeGuess, &[&detected]));
+    for (index, project_type) in KNOWN_PROJECT_TYPES.iter().enumerate() {
+        println!("  {}) {}", index + 1, project_type);
+    }
+
+    loop {
+        println!("{}", tr(Message::PromptType, &[&detected]));
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        let input = input.trim();
+
+        if input.is_empty() {
+            return Ok(None);
+        }
+
+        match input.parse::<usize>() {
+            Ok(number) if (1..=KNOWN_PROJECT_TYPES.len()).contains(&number) => {
+                return Ok(Some(KNOWN_PROJECT_TYPES[number - 1].to_string()));
+            }
+            Ok(_) => {
+                println!("{}", tr(Message::InvalidTypeChoice, &[]));
+                warn!("User provided an out-of-range type number: {}", input);
+            }
+            Err(_) => return Ok(Some(input.to_lowercase())),
+        }
+    }
+}
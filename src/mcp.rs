@@ -0,0 +1,373 @@
+//! A Model Context Protocol server over standard input and output, so LLM assistants
+//! can look up and annotate the projects in the registry.
+//!
+//! Messages are JSON-RPC 2.0, one per line. The server offers three tools:
+//! `search_projects`, `get_project_info`, and `add_note`.
+
+use std::io::{self, BufRead, Write};
+
+use chrono::{DateTime, Local, NaiveDate, TimeZone};
+use log::{info, warn};
+use serde_json::{json, Value};
+
+use crate::config::Config;
+use crate::project_info::status::ProjectStatus;
+use crate::project_info::{tags, ProjectInfo};
+use crate::registry::{Registry, RegistryEntry};
+use crate::scan;
+
+/// The protocol revision this server implements.
+pub const PROTOCOL_VERSION: &str = "2024-11-05";
+
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// Answers MCP requests from a registry.
+pub struct McpServer<'a> {
+    registry: Registry,
+    config: &'a Config,
+}
+
+impl<'a> McpServer<'a> {
+    /// Creates the server. Notes are saved where the configuration's `[output]` section
+    /// says, like every other change to a project.
+    pub fn new(registry: Registry, config: &'a Config) -> Self {
+        McpServer { registry, config }
+    }
+
+    /// Reads messages from `input` and writes responses to `output` until the input
+    /// ends.
+    pub fn run(&self, input: impl BufRead, output: &mut impl Write) -> io::Result<()> {
+        info!("MCP server started");
+        for line in input.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let response = match serde_json::from_str::<Value>(&line) {
+                Ok(message) => self.handle_message(&message),
+                Err(e) => Some(error_response(Value::Null, PARSE_ERROR, &e.to_string())),
+            };
+            if let Some(response) = response {
+                writeln!(output, "{}", response)?;
+                output.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Answers one JSON-RPC message. Notifications, which have no `id`, get no answer.
+    pub fn handle_message(&self, message: &Value) -> Option<Value> {
+        let id = message.get("id")?.clone();
+        let method = message["method"].as_str().unwrap_or_default();
+        let params = &message["params"];
+
+        let result = match method {
+            "initialize" => Ok(json!({
+                "protocolVersion": PROTOCOL_VERSION,
+                "capabilities": { "tools": {} },
+                "serverInfo": { "name": "tagger", "version": env!("CARGO_PKG_VERSION") },
+            })),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+            "tools/call" => match params["name"].as_str() {
+                Some(name) => Ok(self.call_tool(name, &params["arguments"])),
+                None => Err((INVALID_PARAMS, "tools/call needs a tool name".to_string())),
+            },
+            _ => Err((METHOD_NOT_FOUND, format!("unknown method '{}'", method))),
+        };
+
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => error_response(id, code, &message),
+        })
+    }
+
+    /// Runs a tool. Failures are reported in the result, as the protocol asks, so the
+    /// assistant can see them.
+    fn call_tool(&self, name: &str, arguments: &Value) -> Value {
+        let result = match name {
+            "search_projects" => self.search_projects(arguments),
+            "get_project_info" => self.find(arguments).map(|entry| json!(entry)),
+            "add_note" => self.add_note(arguments),
+            _ => Err(format!("unknown tool '{}'", name)),
+        };
+
+        match result {
+            Ok(value) => json!({
+                "content": [{ "type": "text", "text": serde_json::to_string_pretty(&value).unwrap_or_default() }],
+            }),
+            Err(message) => {
+                warn!("MCP tool {} failed: {}", name, message);
+                json!({ "content": [{ "type": "text", "text": message }], "isError": true })
+            }
+        }
+    }
+
+    fn entries(&self) -> Result<Vec<RegistryEntry>, String> {
+        self.registry.entries().map_err(|e| format!("could not read the registry: {}", e))
+    }
+
+    fn search_projects(&self, arguments: &Value) -> Result<Value, String> {
+        let patterns: Vec<&str> = arguments["tags"]
+            .as_array()
+            .map(|tags| tags.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        let project_type = arguments["type"].as_str();
+        let status = match arguments["status"].as_str() {
+            Some(status) => Some(status.parse::<ProjectStatus>()?),
+            None => None,
+        };
+        let since = match arguments["modified_since"].as_str() {
+            Some(date) => Some(parse_date(date)?),
+            None => None,
+        };
+        let text = arguments["text"].as_str().map(str::to_lowercase);
+
+        let matches: Vec<Value> = self
+            .entries()?
+            .into_iter()
+            .filter(|entry| {
+                let info = &entry.info;
+                patterns.iter().all(|pattern| info.tags.iter().any(|tag| tags::matches(tag, pattern)))
+                    && project_type.is_none_or(|project_type| info.project_type.eq_ignore_ascii_case(project_type))
+                    && (status.is_none() || info.status == status)
+                    && since.is_none_or(|since| info.date_modified >= since)
+                    && text.as_deref().is_none_or(|text| mentions(info, text))
+            })
+            .map(|entry| summary(&entry))
+            .collect();
+        Ok(json!(matches))
+    }
+
+    /// Finds the project named by the `project` argument (see [`Registry::lookup`]).
+    fn find(&self, arguments: &Value) -> Result<RegistryEntry, String> {
+        let key = arguments["project"]
+            .as_str()
+            .ok_or("the 'project' argument is required")?;
+        let mut found = self
+            .registry
+            .lookup(key)
+            .map_err(|e| format!("could not read the registry: {}", e))?;
+        match found.len() {
+            0 => Err(format!("no project matches '{}'", key)),
+            1 => Ok(found.remove(0)),
+            _ => Err(format!("several projects are called '{}'; use an id or path", key)),
+        }
+    }
+
+    fn add_note(&self, arguments: &Value) -> Result<Value, String> {
+        let note = arguments["note"]
+            .as_str()
+            .map(str::trim)
+            .filter(|note| !note.is_empty())
+            .ok_or("the 'note' argument is required")?;
+        let entry = self.find(arguments)?;
+
+        // Start from the saved file, which may be newer than the registry entry, but
+        // never replace one that cannot be read.
+        let file = self.config.output.file_for(&entry.path);
+        let mut info = match ProjectInfo::load_from_file(&file) {
+            Ok(info) => info,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => entry.info,
+            Err(e) => return Err(format!("could not read {}: {}", file.display(), e)),
+        };
+        info.notes.push(note.to_string());
+
+        // Save it as `tagger` itself would, running the save hooks.
+        scan::save_project_in(&entry.path, &info, self.config, &self.registry)
+            .map_err(|e| format!("could not save {}: {}", file.display(), e))?;
+        info!("Added a note to {}", entry.path.display());
+        Ok(json!({ "project": info.name, "notes": info.notes }))
+    }
+}
+
+/// Describes the tools and their arguments as JSON Schema.
+fn tool_definitions() -> Value {
+    let project = json!({
+        "type": "string",
+        "description": "The project's id, slug, alias, name, or directory path.",
+    });
+    json!([
+        {
+            "name": "search_projects",
+            "description": "Search the tagged projects on this machine. Every given filter must match. \
+                Returns each project's name, id, alias, type, status, tags, description, dates, and path.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "tags": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Tag patterns: 'rust' matches 'lang:rust', 'lang:rust' matches exactly, \
+                            and 'daw:' matches any tag in the namespace.",
+                    },
+                    "type": { "type": "string", "description": "A project type, such as 'programming' or 'music'." },
+                    "status": { "type": "string", "enum": ProjectStatus::ALL.map(|status| status.as_str()) },
+                    "modified_since": {
+                        "type": "string",
+                        "description": "Only projects modified on or after this date (YYYY-MM-DD or RFC 3339).",
+                    },
+                    "text": { "type": "string", "description": "Text to look for in the name, alias, description, and notes." },
+                },
+            },
+        },
+        {
+            "name": "get_project_info",
+            "description": "Get everything recorded about one project.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "project": project },
+                "required": ["project"],
+            },
+        },
+        {
+            "name": "add_note",
+            "description": "Add a note to a project's notes.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "project": project, "note": { "type": "string" } },
+                "required": ["project", "note"],
+            },
+        },
+    ])
+}
+
+/// The fields of a project a search returns, leaving out the bulky ones.
+fn summary(entry: &RegistryEntry) -> Value {
+    let info = &entry.info;
+    json!({
+        "name": info.name,
+        "id": info.id,
+        "alias": info.alias,
+        "type": info.project_type,
+        "status": info.status,
+        "tags": info.tags,
+        "description": info.description,
+        "date_created": info.date_created,
+        "date_modified": info.date_modified,
+        "path": entry.path,
+    })
+}
+
+/// Returns `true` if the lowercase text appears in the project's name, alias,
+/// description, or notes.
+fn mentions(info: &ProjectInfo, text: &str) -> bool {
+    [&info.name, &info.alias]
+        .into_iter()
+        .chain(&info.description)
+        .chain(&info.notes)
+        .any(|field| field.to_lowercase().contains(text))
+}
+
+/// Parses an RFC 3339 timestamp or a plain date, taken as local midnight.
+fn parse_date(text: &str) -> Result<DateTime<Local>, String> {
+    if let Ok(date) = DateTime::parse_from_rfc3339(text) {
+        return Ok(date.with_timezone(&Local));
+    }
+    NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| Local.from_local_datetime(&date.and_hms_opt(0, 0, 0)?).earliest())
+        .ok_or_else(|| format!("'{}' is not a date (expected YYYY-MM-DD)", text))
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_mcp_session() {
+        let dir = tempdir().unwrap();
+        let registry = Registry::open(&dir.path().join("registry")).unwrap();
+        for (name, tags, age_days) in [("api", vec!["lang:rust", "lib:tokio"], 3), ("old", vec!["lib:tokio"], 90)] {
+            let project = dir.path().join(name);
+            std::fs::create_dir_all(&project).unwrap();
+            let info = ProjectInfo {
+                name: name.to_string(),
+                id: format!("{}-id", name),
+                tags: tags.into_iter().map(String::from).collect(),
+                date_modified: Local::now() - Duration::days(age_days),
+                ..Default::default()
+            };
+            info.save_to_toml_file(&project).unwrap();
+            registry.register(&project, &info).unwrap();
+        }
+
+        let config = Config::default();
+        let server = McpServer::new(Registry::open(&dir.path().join("registry")).unwrap(), &config);
+        let since = (Local::now() - Duration::days(30)).format("%Y-%m-%d").to_string();
+        let input = [
+            json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} }),
+            json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+            json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" }),
+            json!({ "jsonrpc": "2.0", "id": 3, "method": "tools/call", "params": {
+                "name": "search_projects", "arguments": { "tags": ["tokio"], "modified_since": since },
+            }}),
+            json!({ "jsonrpc": "2.0", "id": 4, "method": "tools/call", "params": {
+                "name": "add_note", "arguments": { "project": "API", "note": "Bump tokio" },
+            }}),
+            json!({ "jsonrpc": "2.0", "id": 5, "method": "tools/call", "params": {
+                "name": "get_project_info", "arguments": { "project": "missing" },
+            }}),
+            json!({ "jsonrpc": "2.0", "id": 6, "method": "resources/list" }),
+        ]
+        .map(|message| message.to_string())
+        .join("\n");
+
+        let mut output = Vec::new();
+        server.run(input.as_bytes(), &mut output).unwrap();
+        let responses: Vec<Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        // The notification gets no response.
+        assert_eq!(responses.len(), 6);
+        assert_eq!(responses[0]["result"]["protocolVersion"], PROTOCOL_VERSION);
+        assert_eq!(responses[1]["result"]["tools"].as_array().unwrap().len(), 3);
+
+        let found: Value = serde_json::from_str(responses[2]["result"]["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(found.as_array().unwrap().len(), 1);
+        assert_eq!(found[0]["name"], "api");
+
+        assert!(responses[3]["result"]["isError"].is_null());
+        let saved = ProjectInfo::load_from_toml_file(&dir.path().join("api")).unwrap();
+        assert_eq!(saved.notes, vec!["Bump tokio"]);
+        let registered = Registry::open(&dir.path().join("registry")).unwrap().entries().unwrap();
+        assert_eq!(registered[0].info.notes, vec!["Bump tokio"]);
+
+        assert_eq!(responses[4]["result"]["isError"], true);
+        assert_eq!(responses[5]["error"]["code"], METHOD_NOT_FOUND);
+    }
+
+    #[test]
+    fn test_add_note_keeps_unreadable_files() {
+        let dir = tempdir().unwrap();
+        let registry = Registry::open(&dir.path().join("registry")).unwrap();
+        let project = dir.path().join("api");
+        std::fs::create_dir_all(&project).unwrap();
+        let info = ProjectInfo {
+            name: "api".to_string(),
+            id: "api-id".to_string(),
+            ..Default::default()
+        };
+        registry.register(&project, &info).unwrap();
+        let file = project.join("project_info.toml");
+        std::fs::write(&file, "name = ").unwrap();
+
+        let config = Config::default();
+        let server = McpServer::new(registry, &config);
+        let error = server.add_note(&json!({ "project": "api", "note": "Bump tokio" })).unwrap_err();
+        assert!(error.contains("could not read"), "{}", error);
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "name = ");
+    }
+}
@@ -0,0 +1,554 @@
+use std::env;
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+/// A language the command-line interface can be displayed in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Locale {
+    #[default]
+    English,
+    Spanish,
+}
+
+impl Locale {
+    /// Parses a language tag or POSIX locale such as `es`, `en-US`, or `es_ES.UTF-8`.
+    ///
+    /// Returns `None` for languages without a translation.
+    pub fn parse(tag: &str) -> Option<Self> {
+        let language = tag.split(['_', '-', '.', '@']).next()?.to_ascii_lowercase();
+        match language.as_str() {
+            "en" | "c" | "posix" => Some(Locale::English),
+            "es" => Some(Locale::Spanish),
+            _ => None,
+        }
+    }
+
+    /// Picks the display language.
+    ///
+    /// The `TAGGER_LANG` environment variable wins, then the `language` set in the
+    /// configuration, then the system locale (`LC_ALL`, `LC_MESSAGES`, `LANG`).
+    /// Unsupported languages fall back to English.
+    pub fn detect(configured: Option<&str>) -> Self {
+        let from_env = |name: &str| env::var(name).ok().filter(|value| !value.is_empty());
+
+        from_env("TAGGER_LANG")
+            .or_else(|| configured.map(str::to_string))
+            .or_else(|| ["LC_ALL", "LC_MESSAGES", "LANG"].into_iter().find_map(from_env))
+            .and_then(|tag| Self::parse(&tag))
+            .unwrap_or_default()
+    }
+}
+
+static LOCALE: OnceLock<Locale> = OnceLock::new();
+
+/// Sets the display language for the rest of the process.
+///
+/// Only the first call has an effect.
+pub fn set_locale(locale: Locale) {
+    let _ = LOCALE.set(locale);
+}
+
+/// Returns the display language, English unless [`set_locale`] chose another.
+pub fn locale() -> Locale {
+    LOCALE.get().copied().unwrap_or_default()
+}
+
+/// A user-facing message. Each `{}` in a message's text is filled in by [`tr`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Message {
+    ProjectName,
+    Directory,
+    Alias,
+    Status,
+    Description,
+    ProjectType,
+    Version,
+    Tags,
+    SuggestedTags,
+    DateCreated,
+    DateModified,
+    DateAdded,
+    LastOpened,
+    License,
+    GitUrl,
+    VersionControl,
+    GitStatus,
+    GitStatusDirty,
+    GitStatusClean,
+    Remote,
+    Submodule,
+    GitBranch,
+    LastCommit,
+    Commits,
+    Contributors,
+    Notes,
+    Related,
+    SubProjects,
+    Size,
+    LargestSubdirs,
+    None,
+    Saved,
+    NotSaved,
+    ScanReportWritten,
+    TypeUnknown,
+    PromptAlias,
+    PromptNote,
+    PromptTags,
+    SuggestedTag,
+    SuggestNoEndpoint,
+    PromptExtraTags,
+    PromptSave,
+    InvalidYesNo,
+    TypeGuess,
+    PromptType,
+    InvalidTypeChoice,
+    RelocationMoved,
+    RelocationRenamed,
+    RelocationCopied,
+    PromptRelink,
+    InvalidRelink,
+    RelinkInteractively,
+    BundleInstalled,
+    NoBundles,
+    NoProjects,
+    OfflineProject,
+    Repaired,
+    RepairAmbiguous,
+    RepairNotFound,
+    PromptRepair,
+    RepairUnconfirmed,
+    Imported,
+    ImportUnchanged,
+    ImportNotFound,
+    ImportAmbiguous,
+    ImportUnknownFormat,
+    ImportDryRun,
+    Linked,
+    Exported,
+    FrontmatterAdded,
+    Serving,
+    ServingToken,
+    Opened,
+    Initialized,
+    ValidationPassed,
+    ValidationFailed,
+    ValidationFixed,
+    RetagPlanned,
+    RetagUpToDate,
+    RetagApplyHint,
+    Retagged,
+    Duplicates,
+    NoDuplicates,
+    SimilarProjects,
+    NoSimilarProjects,
+    StatsProjects,
+    StatsTags,
+    StatsTypes,
+    StatsMonths,
+    StatsOrphanedTags,
+    NeedsAttention,
+    AllHealthy,
+    SyncNoRoots,
+    SyncedRoot,
+    ListRefreshed,
+    SyncNotDue,
+    SyncUnavailable,
+    SyncMissing,
+    SyncNoRemote,
+    SyncPushed,
+    SyncPulled,
+    SyncConflict,
+    NoHomeDirectory,
+    Archived,
+    UsingProjectRoot,
+    VerifyUnchanged,
+    VerifyChanged,
+    WatchStarted,
+    EditorTags,
+    EditorAlias,
+    EditorNotes,
+    EditorHelp,
+    EditorNewTag,
+    EditorEditAlias,
+    EditorEditNote,
+    JustNow,
+    Ago,
+    Minute,
+    Minutes,
+    Hour,
+    Hours,
+    Day,
+    Days,
+    Month,
+    Months,
+    Year,
+    Years,
+}
+
+impl Message {
+    /// Returns the message's text in the given language.
+    pub fn text(self, locale: Locale) -> &'static str {
+        let (english, spanish) = match self {
+            Message::ProjectName => ("Project Name: {}", "Nombre del proyecto: {}"),
+            Message::Directory => ("Directory: {}", "Directorio: {}"),
+            Message::Alias => ("Alias: {}", "Alias: {}"),
+            Message::Status => ("Status: {}", "Estado: {}"),
+            Message::Description => ("Description: {}", "Descripción: {}"),
+            Message::ProjectType => ("Project Type: {}", "Tipo de proyecto: {}"),
+            Message::Version => ("Version: {}", "Versión: {}"),
+            Message::Tags => ("Tags: {}", "Etiquetas: {}"),
+            Message::SuggestedTags => ("Suggested Tags: {}", "Etiquetas sugeridas: {}"),
+            Message::DateCreated => ("Date Created: {}", "Fecha de creación: {}"),
+            Message::DateModified => ("Date Modified: {}", "Fecha de modificación: {}"),
+            Message::DateAdded => ("Date Added: {}", "Fecha de incorporación: {}"),
+            Message::LastOpened => ("Last Opened: {}", "Última apertura: {}"),
+            Message::License => ("License: {}", "Licencia: {}"),
+            Message::GitUrl => ("Repository: {}", "Repositorio: {}"),
+            Message::VersionControl => ("Version control: {}", "Control de versiones: {}"),
+            Message::GitStatus => (
+                "Git Status: {}, {} unpushed commit(s)",
+                "Estado de git: {}, {} commit(s) sin enviar",
+            ),
+            Message::GitStatusDirty => ("uncommitted changes", "cambios sin confirmar"),
+            Message::GitStatusClean => ("clean", "limpio"),
+            Message::Remote => ("Remote {}: {}", "Remoto {}: {}"),
+            Message::Submodule => ("Submodule {}: {}", "Submódulo {}: {}"),
+            Message::GitBranch => ("Git Branch: {}", "Rama de git: {}"),
+            Message::LastCommit => ("Last Commit: {} ({})", "Último commit: {} ({})"),
+            Message::Commits => ("Commits: {}", "Commits: {}"),
+            Message::Contributors => ("Contributors: {}", "Colaboradores: {}"),
+            Message::Notes => ("Notes: {}", "Notas: {}"),
+            Message::Related => ("Related Projects: {}", "Proyectos relacionados: {}"),
+            Message::SubProjects => ("Sub-projects: {}", "Subproyectos: {}"),
+            Message::Size => ("Size: {} in {} files", "Tamaño: {} en {} archivos"),
+            Message::LargestSubdirs => ("Largest Directories: {}", "Directorios más grandes: {}"),
+            Message::None => ("None", "Ninguno"),
+            Message::Saved => ("✅ Saved to {}", "✅ Guardado en {}"),
+            Message::NotSaved => (
+                "❌ Project information not saved.",
+                "❌ La información del proyecto no se guardó.",
+            ),
+            Message::ScanReportWritten => (
+                "✅ Scan report written to {}",
+                "✅ Informe del escaneo guardado en {}",
+            ),
+            Message::TypeUnknown => (
+                "Project type could not be determined.",
+                "No se pudo determinar el tipo de proyecto.",
+            ),
+            Message::PromptAlias => (
+                "Enter an alias for the project (or press Enter to skip):",
+                "Introduce un alias para el proyecto (o pulsa Intro para omitirlo):",
+            ),
+            Message::PromptNote => (
+                "Enter a note for the project (or press Enter to skip):",
+                "Introduce una nota para el proyecto (o pulsa Intro para omitirla):",
+            ),
+            Message::PromptTags => (
+                "Select the tags to keep (Space toggles, Enter confirms)",
+                "Elige las etiquetas que quieres conservar (Espacio marca, Intro confirma)",
+            ),
+            Message::SuggestedTag => ("{} (suggested)", "{} (sugerida)"),
+            Message::SuggestNoEndpoint => (
+                "--suggest-tags needs an endpoint under [suggest] in the configuration.",
+                "--suggest-tags necesita un endpoint en [suggest] en la configuración.",
+            ),
+            Message::PromptExtraTags => (
+                "Additional tags, separated by commas (or press Enter to skip)",
+                "Etiquetas adicionales, separadas por comas (o pulsa Intro para omitirlas)",
+            ),
+            Message::PromptSave => (
+                "Do you want to save this project information to 'project_info.toml'? (y/n):",
+                "¿Quieres guardar esta información del proyecto en 'project_info.toml'? (s/n):",
+            ),
+            Message::InvalidYesNo => (
+                "Invalid input. Please enter 'y' or 'n'.",
+                "Respuesta no válida. Escribe 's' o 'n'.",
+            ),
+            Message::TypeGuess => (
+                "The project type '{}' is a guess. Pick the correct type:",
+                "El tipo de proyecto '{}' es una suposición. Elige el tipo correcto:",
+            ),
+            Message::PromptType => (
+                "Enter a number, a custom type, or press Enter to keep '{}':",
+                "Introduce un número, un tipo propio, o pulsa Intro para mantener '{}':",
+            ),
+            Message::InvalidTypeChoice => (
+                "Invalid choice. Please enter a number from the list.",
+                "Opción no válida. Introduce un número de la lista.",
+            ),
+            Message::RelocationMoved => (
+                "project_info.toml belongs to '{}', registered at {}, which no longer exists.",
+                "project_info.toml pertenece a '{}', registrado en {}, que ya no existe.",
+            ),
+            Message::RelocationRenamed => (
+                "project_info.toml was written for the directory '{}'.",
+                "project_info.toml se escribió para el directorio '{}'.",
+            ),
+            Message::RelocationCopied => (
+                "project_info.toml is a copy of the one in {}.",
+                "project_info.toml es una copia del que está en {}.",
+            ),
+            Message::PromptRelink => (
+                "Keep its alias, notes, and creation date (r = relink) or start a new project (n = new)? (r/n):",
+                "¿Conservar su alias, notas y fecha de creación (r = reenlazar) o empezar un proyecto nuevo (n = nuevo)? (r/n):",
+            ),
+            Message::InvalidRelink => (
+                "Invalid input. Please enter 'r' or 'n'.",
+                "Respuesta no válida. Escribe 'r' o 'n'.",
+            ),
+            Message::RelinkInteractively => (
+                "Tag it interactively to relink it or start a new project.",
+                "Etiquétalo de forma interactiva para reenlazarlo o empezar un proyecto nuevo.",
+            ),
+            Message::BundleInstalled => (
+                "✅ Installed bundle {} {} ({} rules, {} templates)",
+                "✅ Paquete {} {} instalado ({} reglas, {} plantillas)",
+            ),
+            Message::NoBundles => ("No bundles installed.", "No hay paquetes instalados."),
+            Message::NoProjects => ("No projects found.", "No se encontraron proyectos."),
+            Message::OfflineProject => ("(offline: {} is not mounted)", "(sin conexión: {} no está montado)"),
+            Message::Repaired => (
+                "🔗 {} moved from {} to {} (matched by {})",
+                "🔗 {} se movió de {} a {} (coincidencia por {})",
+            ),
+            Message::RepairAmbiguous => (
+                "⚠️  {} is gone from {}; it could be any of: {}",
+                "⚠️  {} ya no está en {}; podría ser cualquiera de: {}",
+            ),
+            Message::RepairNotFound => (
+                "⚠️  {} is gone from {} and was not found; pass --search with the directory it moved to",
+                "⚠️  {} ya no está en {} y no se encontró; indica con --search el directorio al que se movió",
+            ),
+            Message::PromptRepair => (
+                "❓ {} is gone from {}; only the name matches {}. Register it there? (y/n):",
+                "❓ {} ya no está en {}; solo el nombre coincide con {}. ¿Registrarlo ahí? (s/n):",
+            ),
+            Message::RepairUnconfirmed => (
+                "⚠️  {} is gone from {}; only the name matches {}, so it was left as is (run in a terminal to confirm)",
+                "⚠️  {} ya no está en {}; solo el nombre coincide con {}, así que se dejó como estaba (ejecútalo en una terminal para confirmarlo)",
+            ),
+            Message::Imported => ("📥 {}: {}", "📥 {}: {}"),
+            Message::ImportUnchanged => ("✅ {}: nothing new", "✅ {}: nada nuevo"),
+            Message::ImportNotFound => (
+                "⚠️  {}: no registered project matches; tag it first or pass --project",
+                "⚠️  {}: ningún proyecto registrado coincide; etiquétalo primero o usa --project",
+            ),
+            Message::ImportAmbiguous => (
+                "⚠️  {}: it could be any of: {}",
+                "⚠️  {}: podría ser cualquiera de: {}",
+            ),
+            Message::ImportUnknownFormat => (
+                "Unable to tell the format of {}; pass --format (github, onefetch, tokei, or csv).",
+                "No se reconoce el formato de {}; usa --format (github, onefetch, tokei o csv).",
+            ),
+            Message::ImportDryRun => (
+                "Nothing was saved (--dry-run).",
+                "No se guardó nada (--dry-run).",
+            ),
+            Message::Linked => ("🔗 Linked {} and {}", "🔗 {} y {} enlazados"),
+            Message::Exported => ("✅ Exported {} projects to {}", "✅ {} proyectos exportados a {}"),
+            Message::FrontmatterAdded => (
+                "✅ Added frontmatter to {} READMEs",
+                "✅ Frontmatter añadido a {} README",
+            ),
+            Message::Initialized => ("🆕 Created {} ({})", "🆕 {} creado ({})"),
+            Message::ValidationPassed => (
+                "✅ No problems found in {} projects",
+                "✅ No se encontraron problemas en {} proyectos",
+            ),
+            Message::ValidationFailed => (
+                "⚠️  {} problems found in {} projects ({} fixable with --fix)",
+                "⚠️  {} problemas encontrados en {} proyectos ({} reparables con --fix)",
+            ),
+            Message::ValidationFixed => ("🔧 Fixed {} problems in {}", "🔧 {} problemas reparados en {}"),
+            Message::RetagPlanned => (
+                "🔁 {} of {} tagged projects would change:",
+                "🔁 Cambiarían {} de {} proyectos etiquetados:",
+            ),
+            Message::RetagUpToDate => (
+                "✅ All {} tagged projects are up to date",
+                "✅ Los {} proyectos etiquetados están al día",
+            ),
+            Message::RetagApplyHint => (
+                "Run again with --apply to save these changes.",
+                "Vuelve a ejecutarlo con --apply para guardar estos cambios.",
+            ),
+            Message::Retagged => ("✅ Retagged {} projects", "✅ {} proyectos reetiquetados"),
+            Message::Duplicates => ("🔎 Probable duplicates ({}):", "🔎 Probables duplicados ({}):"),
+            Message::NoDuplicates => (
+                "✅ No duplicate projects found under {}",
+                "✅ No se encontraron proyectos duplicados en {}",
+            ),
+            Message::SimilarProjects => ("Projects similar to {}:", "Proyectos parecidos a {}:"),
+            Message::NoSimilarProjects => (
+                "No registered project shares tags with {}.",
+                "Ningún proyecto registrado comparte etiquetas con {}.",
+            ),
+            Message::StatsProjects => ("Projects: {}", "Proyectos: {}"),
+            Message::StatsTags => ("Most used tags ({} of {}):", "Etiquetas más usadas ({} de {}):"),
+            Message::StatsTypes => ("Projects and disk usage per type:", "Proyectos y espacio en disco por tipo:"),
+            Message::StatsMonths => ("Projects created per month:", "Proyectos creados por mes:"),
+            Message::StatsOrphanedTags => (
+                "Tags used by a single project ({}):",
+                "Etiquetas que usa un solo proyecto ({}):",
+            ),
+            Message::NeedsAttention => (
+                "🩺 {} projects need attention, most urgent first:",
+                "🩺 {} proyectos necesitan atención, los más urgentes primero:",
+            ),
+            Message::AllHealthy => (
+                "✅ Every registered project looks healthy.",
+                "✅ Todos los proyectos registrados parecen estar en orden.",
+            ),
+            Message::SyncNoRoots => (
+                "No roots to sync; add [[roots]] tables to {}.",
+                "No hay raíces que sincronizar; añade tablas [[roots]] a {}.",
+            ),
+            Message::SyncedRoot => (
+                "✅ {}: {} projects, {} rescanned",
+                "✅ {}: {} proyectos, {} reescaneados",
+            ),
+            Message::ListRefreshed => (
+                "🔄 Refreshed {} projects, {} rescanned",
+                "🔄 {} proyectos actualizados, {} reescaneados",
+            ),
+            Message::SyncNotDue => (
+                "⏭️  {}: not due, last synced {}",
+                "⏭️  {}: no toca aún, última sincronización {}",
+            ),
+            Message::SyncUnavailable => (
+                "⚠️  {} is not available; is the drive mounted?",
+                "⚠️  {} no está disponible; ¿está montada la unidad?",
+            ),
+            Message::SyncMissing => (
+                "  ⚠️  {} registered projects below it are missing; see `tagger list --repair`",
+                "  ⚠️  faltan {} proyectos registrados debajo; consulta `tagger list --repair`",
+            ),
+            Message::SyncNoRemote => (
+                "No remote to sync with; set url under [remote] in {}",
+                "No hay un remoto con el que sincronizar; define url en [remote] en {}",
+            ),
+            Message::SyncPushed => (
+                "⬆️  Pushed {} files to {} ({} unchanged)",
+                "⬆️  {} archivos enviados a {} ({} sin cambios)",
+            ),
+            Message::SyncPulled => (
+                "⬇️  Pulled {} files from {} ({} unchanged)",
+                "⬇️  {} archivos recibidos de {} ({} sin cambios)",
+            ),
+            Message::SyncConflict => (
+                "⚠️  {} changed here and on the remote since the last sync; left both copies as they are",
+                "⚠️  {} cambió aquí y en el remoto desde la última sincronización; se dejaron ambas copias como estaban",
+            ),
+            Message::NoHomeDirectory => (
+                "No home directory to keep tagger's {} files in; set HOME to an absolute path",
+                "No hay un directorio personal donde guardar los archivos {} de tagger; define HOME con una ruta absoluta",
+            ),
+            Message::Archived => (
+                "📦 Archived {} files to {} (sha256 {})",
+                "📦 {} archivos archivados en {} (sha256 {})",
+            ),
+            Message::UsingProjectRoot => ("📁 Using the project root {}", "📁 Usando la raíz del proyecto {}"),
+            Message::VerifyUnchanged => (
+                "✅ {} is unchanged since {} ({} files)",
+                "✅ {} no ha cambiado desde {} ({} archivos)",
+            ),
+            Message::VerifyChanged => (
+                "❌ {} changed since {}: {} files → {}, {} → {}",
+                "❌ {} cambió desde {}: {} archivos → {}, {} → {}",
+            ),
+            Message::Opened => ("📂 Opened {} with {}", "📂 {} abierto con {}"),
+            Message::Serving => (
+                "🌐 Serving the project API on http://{}. Press Ctrl+C to stop.",
+                "🌐 Sirviendo la API de proyectos en http://{}. Pulsa Ctrl+C para terminar.",
+            ),
+            Message::ServingToken => (
+                "🔑 POST requests must send: Authorization: Bearer {}",
+                "🔑 Las peticiones POST deben enviar: Authorization: Bearer {}",
+            ),
+            Message::WatchStarted => (
+                "👀 Watching {} for changes. Press Ctrl+C to stop.",
+                "👀 Vigilando los cambios en {}. Pulsa Ctrl+C para terminar.",
+            ),
+            Message::EditorTags => ("Tags", "Etiquetas"),
+            Message::EditorAlias => ("Alias", "Alias"),
+            Message::EditorNotes => ("Notes", "Notas"),
+            Message::EditorHelp => (
+                "Tab: next section  ↑/↓: move  Space: toggle tag  a: add  e: edit  d: delete note  s: save  q: quit",
+                "Tab: siguiente sección  ↑/↓: mover  Espacio: marcar etiqueta  a: añadir  e: editar  d: borrar nota  s: guardar  q: salir",
+            ),
+            Message::EditorNewTag => ("New tag:", "Nueva etiqueta:"),
+            Message::EditorEditAlias => ("Alias:", "Alias:"),
+            Message::EditorEditNote => ("Note:", "Nota:"),
+            Message::JustNow => ("just now", "ahora mismo"),
+            Message::Ago => ("{} ago", "hace {}"),
+            Message::Minute => ("{} minute", "{} minuto"),
+            Message::Minutes => ("{} minutes", "{} minutos"),
+            Message::Hour => ("{} hour", "{} hora"),
+            Message::Hours => ("{} hours", "{} horas"),
+            Message::Day => ("{} day", "{} día"),
+            Message::Days => ("{} days", "{} días"),
+            Message::Month => ("{} month", "{} mes"),
+            Message::Months => ("{} months", "{} meses"),
+            Message::Year => ("{} year", "{} año"),
+            Message::Years => ("{} years", "{} años"),
+        };
+
+        match locale {
+            Locale::English => english,
+            Locale::Spanish => spanish,
+        }
+    }
+}
+
+/// Returns the message in the current language, with each `{}` replaced by the
+/// next argument.
+pub fn tr(message: Message, args: &[&dyn Display]) -> String {
+    let mut parts = message.text(locale()).split("{}");
+    let mut text = parts.next().unwrap_or_default().to_string();
+    for (index, part) in parts.enumerate() {
+        if let Some(arg) = args.get(index) {
+            text.push_str(&arg.to_string());
+        }
+        text.push_str(part);
+    }
+    text
+}
+
+/// Returns `true` if the answer to a yes/no prompt means yes in English or the
+/// current language.
+pub fn is_yes(answer: &str) -> bool {
+    let localized: &[&str] = match locale() {
+        Locale::English => &[],
+        Locale::Spanish => &["s", "si", "sí"],
+    };
+    matches!(answer, "y" | "yes") || localized.contains(&answer)
+}
+
+/// Returns `true` if the answer to a yes/no prompt means no.
+pub fn is_no(answer: &str) -> bool {
+    matches!(answer, "n" | "no")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_locale() {
+        assert_eq!(Locale::parse("es_ES.UTF-8"), Some(Locale::Spanish));
+        assert_eq!(Locale::parse("en-US"), Some(Locale::English));
+        assert_eq!(Locale::parse("C"), Some(Locale::English));
+        assert_eq!(Locale::parse("de_DE"), None);
+    }
+
+    #[test]
+    fn test_tr_fills_placeholders() {
+        assert_eq!(tr(Message::LastCommit, &[&"abc1234", &"today"]), "Last Commit: abc1234 (today)");
+        assert_eq!(
+            Message::LastCommit.text(Locale::Spanish).matches("{}").count(),
+            Message::LastCommit.text(Locale::English).matches("{}").count()
+        );
+    }
+}
@@ -0,0 +1,276 @@
+//! Detectors provided by plugins: external executables, or WebAssembly modules.
+//!
+//! Every executable file in the `plugins` folder of the configuration directory (e.g.
+//! `~/.config/tagger/plugins/` on Linux) is a plugin. It runs in the [`Sandbox`] with
+//! the project's path as its only argument and prints its findings as JSON:
+//!
+//! ```json
+//! {"type": "max-msp", "tags": ["format:maxpat"], "fields": {"patchers": 3}}
+//! ```
+//!
+//! Every key is optional, and printing nothing means the plugin has nothing to say
+//! about the project. Tags are added to the generated ones, fields are recorded under
+//! the plugin's name, and the first plugin to name a type decides it, unless the
+//! project's `.tagger.toml` pins one. A plugin still running after
+//! `plugin_timeout_secs` under `[security]`, 30 seconds by default, is killed.
+//!
+//! With the `wasm-plugins` feature, `.wasm` (and `.wat`) modules in the same folder
+//! are plugins too. They run in WebAssembly instead of as native code; see [`wasm`]
+//! for the interface they implement.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+use log::{debug, warn};
+use serde::Deserialize;
+
+use crate::config::{dirs, SecurityConfig};
+use crate::project_info::detector::DetectorFields;
+use crate::project_info::inventory::Inventory;
+use crate::sandbox::{self, Sandbox};
+
+#[cfg(feature = "wasm-plugins")]
+pub mod wasm;
+
+/// File extensions of WebAssembly plugins: binary modules and the text format.
+const WASM_EXTENSIONS: &[&str] = &["wasm", "wat"];
+
+/// What a plugin found in a project.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct PluginOutput {
+    /// The project type, if the plugin recognizes the project.
+    #[serde(rename = "type")]
+    pub project_type: Option<String>,
+    /// Tags to add to the project.
+    pub tags: Vec<String>,
+    /// Extra fields, recorded under the plugin's name.
+    pub fields: DetectorFields,
+}
+
+/// A detector plugin.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plugin {
+    /// The plugin's name: its file name without extension.
+    pub name: String,
+    /// The plugin's executable or WebAssembly module.
+    pub path: PathBuf,
+    /// The WebAssembly module, compiled when the plugin first runs.
+    #[cfg(feature = "wasm-plugins")]
+    compiled: wasm::CompiledModule,
+}
+
+impl Plugin {
+    /// Creates the plugin with the given name, run from `path`.
+    pub fn new(name: impl Into<String>, path: impl Into<PathBuf>) -> Self {
+        Plugin {
+            name: name.into(),
+            path: path.into(),
+            #[cfg(feature = "wasm-plugins")]
+            compiled: wasm::CompiledModule::default(),
+        }
+    }
+
+    /// Returns `true` if the plugin is a WebAssembly module rather than an executable.
+    pub fn is_wasm(&self) -> bool {
+        is_wasm(&self.path)
+    }
+
+    /// Runs the plugin for a project and parses what it returns.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the plugin cannot be started, fails, or returns something
+    /// other than a JSON object of the expected shape. WebAssembly plugins fail with
+    /// an `Unsupported` error without the `wasm-plugins` feature.
+    pub fn run(&self, inventory: &Inventory, security: &SecurityConfig) -> io::Result<PluginOutput> {
+        if self.is_wasm() {
+            #[cfg(feature = "wasm-plugins")]
+            return wasm::run(self, inventory);
+            #[cfg(not(feature = "wasm-plugins"))]
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("plugin '{}' needs tagger built with the wasm-plugins feature", self.name),
+            ));
+        }
+        self.run_executable(inventory.root(), security)
+    }
+
+    fn run_executable(&self, directory: &Path, security: &SecurityConfig) -> io::Result<PluginOutput> {
+        let child = Sandbox::new(security)
+            .command(&self.path, directory)?
+            .arg(directory)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        let output = sandbox::wait_with_timeout(child, security.plugin_timeout())
+            .map_err(|e| io::Error::new(e.kind(), format!("plugin '{}': {}", self.name, e)))?;
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if !output.status.success() {
+            return Err(io::Error::other(format!(
+                "plugin '{}' failed ({}): {}",
+                self.name,
+                output.status,
+                stderr.trim()
+            )));
+        }
+        if !stderr.trim().is_empty() {
+            debug!("Plugin '{}' errors: {}", self.name, stderr.trim_end());
+        }
+
+        self.parse_output(&output.stdout)
+    }
+
+    /// Parses what the plugin returned; nothing at all means it found nothing.
+    fn parse_output(&self, output: &[u8]) -> io::Result<PluginOutput> {
+        let output = String::from_utf8_lossy(output);
+        if output.trim().is_empty() {
+            return Ok(PluginOutput::default());
+        }
+        serde_json::from_str(&output).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("plugin '{}' returned invalid JSON: {}", self.name, e),
+            )
+        })
+    }
+}
+
+/// Returns the default plugin folder, `plugins` in the configuration directory.
+pub fn default_location() -> io::Result<PathBuf> {
+    Ok(dirs::config_dir()?.join("plugins"))
+}
+
+/// Returns the plugins in a folder, by name: WebAssembly modules, and executables.
+/// Hidden files, directories, and (outside Windows) other files without the
+/// executable bit are skipped.
+///
+/// A missing folder holds no plugins.
+pub fn find_plugins(folder: &Path) -> Vec<Plugin> {
+    let Ok(entries) = fs::read_dir(folder) else {
+        return Vec::new();
+    };
+
+    let mut plugins: Vec<Plugin> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && (is_wasm(path) || is_executable(path)))
+        .filter_map(|path| {
+            let name = path.file_stem()?.to_string_lossy().into_owned();
+            (!name.starts_with('.')).then(|| Plugin::new(name, path))
+        })
+        .collect();
+    plugins.sort_by(|a, b| a.name.cmp(&b.name));
+    debug!("Found {} plugins in {}", plugins.len(), folder.display());
+    plugins
+}
+
+/// Returns the installed plugins, from [`default_location`].
+pub fn installed_plugins() -> Vec<Plugin> {
+    default_location().map(|folder| find_plugins(&folder)).unwrap_or_default()
+}
+
+/// Runs every plugin for a project, returning the output of each by plugin name.
+///
+/// A plugin that fails is logged and left out, so one broken plugin does not stop the
+/// scan. Executable plugins do not run when external commands are disabled;
+/// WebAssembly plugins, which cannot reach beyond the project's files, still do.
+pub fn run_plugins(plugins: &[Plugin], inventory: &Inventory, security: &SecurityConfig) -> Vec<(String, PluginOutput)> {
+    let directory = inventory.root();
+    let allows_executables = Sandbox::new(security).allows_external_commands();
+
+    plugins
+        .iter()
+        .filter(|plugin| {
+            let runs = allows_executables || plugin.is_wasm();
+            if !runs {
+                warn!("Skipping plugin '{}': external commands are disabled.", plugin.name);
+            }
+            runs
+        })
+        .filter_map(|plugin| match plugin.run(inventory, security) {
+            Ok(output) => {
+                debug!("Plugin '{}' found {:?}", plugin.name, output);
+                Some((plugin.name.clone(), output))
+            }
+            Err(e) => {
+                warn!("Skipping plugin for {}: {}", directory.display(), e);
+                None
+            }
+        })
+        .collect()
+}
+
+fn is_wasm(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| WASM_EXTENSIONS.iter().any(|wasm| extension.eq_ignore_ascii_case(wasm)))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path).is_ok_and(|metadata| metadata.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(_path: &Path) -> bool {
+    true
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+    use tempfile::tempdir;
+
+    fn write_plugin(folder: &Path, name: &str, script: &str) {
+        let path = folder.join(name);
+        fs::write(&path, format!("#!/bin/sh\n{}\n", script)).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[test]
+    fn test_run_plugins() {
+        let folder = tempdir().unwrap();
+        let project = tempdir().unwrap();
+        write_plugin(
+            folder.path(),
+            "max.sh",
+            r#"ls "$1" | grep -q maxpat && echo '{"type": "max-msp", "tags": ["format:maxpat"], "fields": {"patchers": 1}}'"#,
+        );
+        write_plugin(folder.path(), "broken", "echo 'not json'");
+        fs::write(folder.path().join("notes.txt"), "not a plugin").unwrap();
+        fs::write(project.path().join("synth.maxpat"), "{}").unwrap();
+
+        let plugins = find_plugins(folder.path());
+        let names: Vec<&str> = plugins.iter().map(|plugin| plugin.name.as_str()).collect();
+        assert_eq!(names, ["broken", "max"]);
+
+        let outputs = run_plugins(&plugins, &Inventory::scan(project.path()), &SecurityConfig::default());
+        assert_eq!(outputs.len(), 1);
+        let (name, output) = &outputs[0];
+        assert_eq!(name, "max");
+        assert_eq!(output.project_type.as_deref(), Some("max-msp"));
+        assert_eq!(output.tags, ["format:maxpat"]);
+        assert_eq!(output.fields["patchers"].as_integer(), Some(1));
+    }
+
+    #[test]
+    fn test_slow_plugins_are_killed() {
+        let folder = tempdir().unwrap();
+        let project = tempdir().unwrap();
+        write_plugin(folder.path(), "slow", "exec sleep 10");
+        let security = SecurityConfig {
+            plugin_timeout_secs: Some(1),
+            ..SecurityConfig::default()
+        };
+
+        let plugins = find_plugins(folder.path());
+        let error = plugins[0].run(&Inventory::scan(project.path()), &security).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::TimedOut);
+    }
+}
@@ -0,0 +1,274 @@
+//! WebAssembly detector plugins, run with wasmtime.
+//!
+//! A module cannot run native code, open files, or use the network: all it sees is
+//! what tagger hands it. It exports:
+//!
+//! * `memory` - Its linear memory.
+//! * `tagger_alloc(len: i32) -> i32` - Reserves `len` bytes and returns where they start.
+//! * `tagger_detect(ptr: i32, len: i32) -> i64` - Inspects the project described by the
+//!   JSON at `ptr` and returns where its answer is, as `ptr << 32 | len`. The answer is
+//!   the same JSON an executable plugin prints; a length of 0 means no answer.
+//!
+//! The project is described as `{"name": "...", "files": [{"path": "src/main.rs",
+//! "size": 120}, ...]}`, with the same files every other detector sees. To look inside
+//! one, the module may import `tagger.read_file(ptr: i32, len: i32) -> i64`, which takes
+//! the path from the listing, copies up to [`READ_LIMIT`] bytes of the file into memory
+//! reserved with `tagger_alloc`, and returns it as `ptr << 32 | len`, or -1 for paths
+//! not in the listing.
+//!
+//! A module gets at most [`MEMORY_LIMIT`] bytes of memory and [`FUEL`] units of work
+//! per project, so a buggy one cannot hang or exhaust a scan.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use log::debug;
+use serde::Serialize;
+use wasmtime::{Caller, Config, Engine, Extern, Instance, Linker, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+use crate::project_info::inventory::Inventory;
+use crate::project_info::relative_display;
+
+use super::{Plugin, PluginOutput};
+
+/// The most memory a module may use, in bytes.
+pub const MEMORY_LIMIT: usize = 64 * 1024 * 1024;
+
+/// The work a module may do for one project, in wasmtime fuel units (roughly one
+/// per instruction).
+pub const FUEL: u64 = 2_000_000_000;
+
+/// The most bytes of a file `tagger.read_file` hands to a module.
+pub const READ_LIMIT: u64 = 1024 * 1024;
+
+/// The project, as described to a module.
+#[derive(Serialize)]
+struct ProjectListing {
+    name: String,
+    files: Vec<FileListing>,
+}
+
+#[derive(Serialize)]
+struct FileListing {
+    path: String,
+    size: u64,
+}
+
+/// A plugin's module, compiled once with its engine and reused for every project.
+/// A module that fails to compile is not tried again.
+#[derive(Clone, Default)]
+pub struct CompiledModule(OnceLock<Result<(Engine, Module), String>>);
+
+impl CompiledModule {
+    /// Returns the compiled module at `path`, compiling it on first use.
+    fn get(&self, path: &Path) -> Result<&(Engine, Module), &str> {
+        self.0
+            .get_or_init(|| {
+                let mut config = Config::new();
+                config.consume_fuel(true);
+                let engine = Engine::new(&config).map_err(|e| format!("{:#}", e))?;
+                let module = Module::from_file(&engine, path).map_err(|e| format!("{:#}", e))?;
+                Ok((engine, module))
+            })
+            .as_ref()
+            .map_err(String::as_str)
+    }
+}
+
+impl fmt::Debug for CompiledModule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(if self.0.get().is_some() { "CompiledModule" } else { "CompiledModule(pending)" })
+    }
+}
+
+/// Plugins are equal by name and path; whether one was compiled yet does not matter.
+impl PartialEq for CompiledModule {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for CompiledModule {}
+
+/// What a running module can reach.
+struct State {
+    limits: StoreLimits,
+    /// The listed files, by the path given to the module.
+    files: HashMap<String, PathBuf>,
+}
+
+/// Runs a WebAssembly plugin for a project.
+///
+/// # Errors
+///
+/// Returns an error if the module cannot be compiled, lacks the exports described in
+/// the [module documentation](self), traps (including by running out of fuel or
+/// memory), or returns invalid JSON.
+pub fn run(plugin: &Plugin, inventory: &Inventory) -> io::Result<PluginOutput> {
+    let wasm_error = |e: wasmtime::Error| io::Error::other(format!("plugin '{}': {:#}", plugin.name, e));
+
+    let root = inventory.root();
+    let mut listing = ProjectListing {
+        name: root.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default(),
+        files: Vec::new(),
+    };
+    let mut files = HashMap::new();
+    for entry in inventory.files() {
+        let path = relative_display(root, entry.path());
+        let size = entry.metadata().map(|metadata| metadata.len()).unwrap_or_default();
+        listing.files.push(FileListing { path: path.clone(), size });
+        files.insert(path, entry.path().to_path_buf());
+    }
+    listing.files.sort_by(|a, b| a.path.cmp(&b.path));
+    let input = serde_json::to_vec(&listing).map_err(io::Error::other)?;
+
+    let (engine, module) = plugin
+        .compiled
+        .get(&plugin.path)
+        .map_err(|e| io::Error::other(format!("plugin '{}': {}", plugin.name, e)))?;
+
+    let state = State {
+        limits: StoreLimitsBuilder::new().memory_size(MEMORY_LIMIT).build(),
+        files,
+    };
+    let mut store = Store::new(engine, state);
+    store.limiter(|state| &mut state.limits);
+    store.set_fuel(FUEL).map_err(wasm_error)?;
+
+    let mut linker = Linker::new(engine);
+    linker.func_wrap("tagger", "read_file", read_file).map_err(wasm_error)?;
+    let instance = linker.instantiate(&mut store, module).map_err(wasm_error)?;
+
+    let ptr = write_to_guest(&instance, &mut store, &input).map_err(wasm_error)?;
+    let detect = instance
+        .get_typed_func::<(i32, i32), i64>(&mut store, "tagger_detect")
+        .map_err(wasm_error)?;
+    let answer = detect.call(&mut store, (ptr, input.len() as i32)).map_err(wasm_error)?;
+
+    let (ptr, len) = ((answer >> 32) as u32 as usize, answer as u32 as usize);
+    debug!("Plugin '{}' returned {} bytes", plugin.name, len);
+    if len == 0 {
+        return Ok(PluginOutput::default());
+    }
+    let memory = instance
+        .get_memory(&mut store, "memory")
+        .ok_or_else(|| io::Error::other(format!("plugin '{}' does not export its memory", plugin.name)))?;
+    let mut output = vec![0; len];
+    memory.read(&store, ptr, &mut output).map_err(|e| wasm_error(e.into()))?;
+    plugin.parse_output(&output)
+}
+
+/// Copies bytes into memory reserved with the module's `tagger_alloc`, returning
+/// where they start.
+fn write_to_guest<T>(instance: &Instance, store: &mut Store<T>, bytes: &[u8]) -> wasmtime::Result<i32> {
+    let alloc = instance.get_typed_func::<i32, i32>(&mut *store, "tagger_alloc")?;
+    let ptr = alloc.call(&mut *store, bytes.len() as i32)?;
+    let memory = instance
+        .get_memory(&mut *store, "memory")
+        .ok_or_else(|| wasmtime::Error::msg("the module does not export its memory"))?;
+    memory.write(&mut *store, ptr as u32 as usize, bytes)?;
+    Ok(ptr)
+}
+
+/// Implements `tagger.read_file` for a module.
+fn read_file(mut caller: Caller<'_, State>, ptr: i32, len: i32) -> wasmtime::Result<i64> {
+    let Some(Extern::Memory(memory)) = caller.get_export("memory") else {
+        return Err(wasmtime::Error::msg("the module does not export its memory"));
+    };
+    let mut path = vec![0; len as u32 as usize];
+    memory.read(&caller, ptr as u32 as usize, &mut path)?;
+    let path = String::from_utf8_lossy(&path).into_owned();
+
+    let Some(full_path) = caller.data().files.get(&path).cloned() else {
+        return Ok(-1);
+    };
+    let mut contents = Vec::new();
+    if File::open(&full_path)
+        .and_then(|file| file.take(READ_LIMIT).read_to_end(&mut contents))
+        .is_err()
+    {
+        return Ok(-1);
+    }
+
+    let Some(Extern::Func(alloc)) = caller.get_export("tagger_alloc") else {
+        return Err(wasmtime::Error::msg("the module does not export tagger_alloc"));
+    };
+    let ptr = alloc.typed::<i32, i32>(&caller)?.call(&mut caller, contents.len() as i32)?;
+    memory.write(&mut caller, ptr as u32 as usize, &contents)?;
+    Ok(((ptr as u32 as i64) << 32) | contents.len() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::Path;
+    use tempfile::tempdir;
+
+    /// A module that answers with the contents of the project's `answer.json`.
+    const ECHO_MODULE: &str = r#"
+        (module
+          (import "tagger" "read_file" (func $read_file (param i32 i32) (result i64)))
+          (memory (export "memory") 1)
+          (global $next (mut i32) (i32.const 1024))
+          (data (i32.const 0) "answer.json")
+          (func (export "tagger_alloc") (param $len i32) (result i32)
+            (local $ptr i32)
+            (local.set $ptr (global.get $next))
+            (global.set $next (i32.add (global.get $next) (local.get $len)))
+            (local.get $ptr))
+          (func (export "tagger_detect") (param i32 i32) (result i64)
+            (local $answer i64)
+            (local.set $answer (call $read_file (i32.const 0) (i32.const 11)))
+            (if (result i64) (i64.eq (local.get $answer) (i64.const -1))
+              (then (i64.const 0))
+              (else (local.get $answer)))))
+    "#;
+
+    fn plugin(folder: &Path, name: &str, module: &str) -> Plugin {
+        let path = folder.join(format!("{}.wat", name));
+        fs::write(&path, module).unwrap();
+        Plugin::new(name, path)
+    }
+
+    #[test]
+    fn test_wasm_plugin_reads_listed_files() {
+        let folder = tempdir().unwrap();
+        let project = tempdir().unwrap();
+        let echo = plugin(folder.path(), "echo", ECHO_MODULE);
+
+        assert_eq!(run(&echo, &Inventory::scan(project.path())).unwrap(), PluginOutput::default());
+
+        fs::write(project.path().join("answer.json"), r#"{"type": "fpga", "tags": ["vendor:xilinx"]}"#).unwrap();
+        let output = run(&echo, &Inventory::scan(project.path())).unwrap();
+        assert_eq!(output.project_type.as_deref(), Some("fpga"));
+        assert_eq!(output.tags, ["vendor:xilinx"]);
+
+        // The module is compiled once, on the first run.
+        fs::remove_file(&echo.path).unwrap();
+        assert_eq!(run(&echo, &Inventory::scan(project.path())).unwrap().tags, ["vendor:xilinx"]);
+    }
+
+    #[test]
+    fn test_wasm_plugin_runs_out_of_fuel() {
+        let folder = tempdir().unwrap();
+        let project = tempdir().unwrap();
+        let spin = plugin(
+            folder.path(),
+            "spin",
+            r#"(module
+                 (memory (export "memory") 1)
+                 (func (export "tagger_alloc") (param i32) (result i32) (i32.const 0))
+                 (func (export "tagger_detect") (param i32 i32) (result i64)
+                   (loop $forever (br $forever))
+                   (i64.const 0)))"#,
+        );
+
+        let error = run(&spin, &Inventory::scan(project.path())).unwrap_err();
+        assert!(error.to_string().contains("fuel"), "{}", error);
+    }
+}
@@ -1,59 +1,498 @@
 use chrono::{DateTime, Local};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::fs::{self, File};
 use std::io::{self, Write};
-use std::path::Path;
-use log::{info, warn}; // Removed `error` as it's unused in this module.
-
-use std::collections::HashSet;
-
+use std::path::{Path, PathBuf};
+use log::{debug, info, warn};
+use uuid::Uuid;
+use walkdir::WalkDir;
+
+use std::collections::{BTreeMap, HashSet};
+
+use crate::archive::ArchiveRecord;
+use crate::bundle;
+use crate::config::Config;
+use crate::messages::{tr, Message};
+use crate::plugins::run_plugins;
+use crate::terminal::{self, Style};
+use ignore::IgnoreRules;
+
+pub mod detector;
+#[cfg(feature = "finder-tags")]
+pub mod finder;
+pub mod fingerprint;
+pub mod git;
+pub mod ignore;
 pub mod programming;
 pub mod music;
+pub mod video;
+pub mod design;
+pub mod data_science;
+pub mod photography;
+pub mod embedded;
+pub mod infrastructure;
+pub mod inventory;
+pub mod license;
+pub mod overrides;
+pub mod plugins;
+pub mod readme;
+pub mod samples;
+pub mod related;
+pub mod spotlight;
+pub mod stats;
+pub mod status;
+pub mod tags;
+pub mod taxonomy;
+pub mod testing;
+pub mod tooling;
+pub mod vcs;
+pub mod volume;
+pub mod workspace;
+pub mod writing;
+#[cfg(feature = "xattr-tags")]
+pub mod xattr_tags;
+
+use detector::{detector_for, DetectorFields};
+use fingerprint::Fingerprint;
+use overrides::Overrides;
+use inventory::Inventory;
+use related::RelatedProject;
+use status::ProjectStatus;
+use programming::PROGRAMMING_INDICATORS;
+use music::{daw_project_format, AUDIO_EXTENSIONS};
+use video::{editor_project_format, is_video_asset};
+use design::design_file_format;
+use writing::{document_format, is_markdown};
+use data_science::{is_dataset, is_notebook, DATA_SCIENCE_INDICATORS};
+use photography::{catalog_format, is_photo_bundle, raw_format};
+use embedded::{is_arduino_sketch, is_kicad_project, EMBEDDED_INDICATORS};
+use infrastructure::{infrastructure_tool, INFRASTRUCTURE_INDICATORS};
+
+/// Returns `true` if the path is a bundle directory (e.g. `.logicx`, `.fcpbundle`,
+/// `.scriv`) that should be treated as a single project file.
+pub(crate) fn is_bundle(path: &Path) -> bool {
+    path.is_dir()
+        && (daw_project_format(path).is_some()
+            || editor_project_format(path).is_some()
+            || document_format(path).is_some()
+            || is_photo_bundle(path))
+}
+
+/// Every project type the detectors can assign, offered when the user corrects a type.
+/// The file project information is saved to, unless configured otherwise.
+pub const PROJECT_INFO_FILE: &str = "project_info.toml";
+
+pub const KNOWN_PROJECT_TYPES: &[&str] = &[
+    "programming",
+    "music",
+    "video",
+    "design",
+    "3d",
+    "writing",
+    "data-science",
+    "photography",
+    "embedded",
+    "hardware",
+    "infra",
+];
+
+/// Build output, dependency, and render cache directories that never describe the
+/// project itself.
+pub(crate) const SKIPPED_DIRECTORIES: &[&str] = &[
+    "target",
+    "node_modules",
+    "__pycache__",
+    "venv",
+    "Render Files",
+    "Media Cache Files",
+    "Peak Files",
+    "CacheClip",
+];
+
+/// Directories that hold copies of third-party code, left out of tag generation
+/// unless `vendored` is set under `[scan]`.
+pub(crate) const VENDORED_DIRECTORIES: &[&str] = &["vendor", "third_party", "third-party"];
+
+/// File names that usually hold credentials and should not live in a project tree.
+const SECRET_FILE_NAMES: &[&str] = &["id_rsa", "id_ecdsa", "id_ed25519", "credentials.json", ".env"];
+
+/// Extensions of private keys and certificate bundles.
+const SECRET_EXTENSIONS: &[&str] = &["pem", "p12", "pfx"];
+
+/// Returns `true` if the file name suggests the file holds secrets.
+fn is_suspicious_secret(file_name: &str) -> bool {
+    let is_env_file = file_name.starts_with(".env.")
+        && !matches!(file_name, ".env.example" | ".env.sample" | ".env.template");
+    let has_secret_extension = Path::new(file_name)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| SECRET_EXTENSIONS.iter().any(|secret| ext.eq_ignore_ascii_case(secret)));
+
+    SECRET_FILE_NAMES.contains(&file_name) || is_env_file || has_secret_extension
+}
+
+/// Returns `true` if the directory looks like the root of a project.
+///
+/// Only the directory's direct children are checked: a version-control directory,
+/// an existing `project_info.toml`, a manifest, or a DAW/editor/document project.
+pub fn is_project_root(directory: &Path) -> bool {
+    let Ok(entries) = fs::read_dir(directory) else {
+        return false;
+    };
+
+    entries.flatten().any(|entry| {
+        let path = entry.path();
+        let file_name = entry.file_name().to_string_lossy().to_string();
+
+        vcs::is_marker(&file_name)
+            || file_name == PROJECT_INFO_FILE
+            || file_name == overrides::OVERRIDES_FILE
+            || PROGRAMMING_INDICATORS.contains(&file_name.as_str())
+            || DATA_SCIENCE_INDICATORS.contains(&file_name.as_str())
+            || EMBEDDED_INDICATORS.contains(&file_name.as_str())
+            || INFRASTRUCTURE_INDICATORS.contains(&file_name.as_str())
+            || is_kicad_project(&path)
+            || daw_project_format(&path).is_some()
+            || editor_project_format(&path).is_some()
+            || document_format(&path).is_some()
+            || catalog_format(&path).is_some()
+    })
+}
+
+/// Finds the root of the project a directory belongs to, the way git finds the root
+/// of a repository: the directory itself if it looks like a project root (see
+/// [`is_project_root`]), else its nearest ancestor that does.
+///
+/// The search stops below the user's home directory, which often holds dotfiles
+/// repositories and manifests of its own. Returns `None` when no project root is found.
+pub fn find_enclosing_project(directory: &Path) -> Option<PathBuf> {
+    let directory = fs::canonicalize(directory).ok()?;
+    let home = std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .and_then(|home| fs::canonicalize(home).ok());
+
+    directory
+        .ancestors()
+        .take_while(|ancestor| home.as_deref() != Some(*ancestor))
+        .find(|ancestor| is_project_root(ancestor))
+        .map(Path::to_path_buf)
+}
+
+/// Displays a path relative to the project directory, using `/` separators.
+pub(crate) fn relative_display(directory: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(directory).unwrap_or(path);
+    relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Turns a project name into a filesystem-safe slug, such as `my-song-v2` for
+/// "My Song (v2)": lowercase ASCII letters and digits separated by single dashes.
+pub fn slugify(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
 
-use programming::{extract_cargo_dependencies, generate_programming_tags};
-use music::generate_music_tags;
+    if slug.is_empty() {
+        "project".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+/// Finds the project roots below a directory, not counting the directory itself.
+///
+/// Hidden, build, and ignored directories (see [`ignore::IgnoreRules`]) are skipped,
+/// and the walk does not descend into the projects it finds, so nested sub-projects
+/// are not listed separately.
+pub fn find_project_roots(directory: &Path) -> Vec<PathBuf> {
+    let mut projects = Vec::new();
+    let mut ignore_rules = IgnoreRules::load(directory);
+    let mut walker = WalkDir::new(directory).min_depth(1).sort_by_file_name().into_iter();
+
+    while let Some(entry) = walker.next() {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        if !entry.file_type().is_dir() {
+            continue;
+        }
+        let file_name = entry.file_name().to_string_lossy();
+        let relative = entry.path().strip_prefix(directory).unwrap_or(entry.path());
+        if file_name.starts_with('.')
+            || SKIPPED_DIRECTORIES.contains(&file_name.as_ref())
+            || ignore_rules.is_ignored(relative, true)
+        {
+            walker.skip_current_dir();
+            continue;
+        }
+        if is_project_root(entry.path()) {
+            projects.push(entry.path().to_path_buf());
+            walker.skip_current_dir();
+        } else {
+            ignore_rules.add_directory(directory, relative);
+        }
+    }
+
+    projects
+}
 
 /// Represents information about a project.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ProjectInfo {
     /// The name of the project.
     pub name: String,
+    /// The name of the project's directory, kept when `name` comes from a manifest.
+    #[serde(default)]
+    pub directory_name: String,
+    /// A unique identifier, generated when the project is first tagged and kept
+    /// across rescans, renames, and moves.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub id: String,
+    /// A filesystem-safe version of the name, generated and kept with the identifier.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub slug: String,
     /// An alias or nickname for the project.
     pub alias: String,
+    /// Where the project is in its lifecycle (active, paused, archived, released).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<ProjectStatus>,
+    /// A one-line summary taken from the project's README.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
     /// The type/category of the project (e.g., programming, music).
     pub project_type: String,
+    /// The version declared in the project's manifest, or its latest git tag.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
     /// A list of tags associated with the project.
     pub tags: Vec<String>,
+    /// Tags a language model suggested with `--suggest-tags`, kept apart from `tags`
+    /// until the user confirms them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub suggested_tags: Vec<String>,
     /// The creation date and time of the project.
     pub date_created: DateTime<Local>,
     /// The last modification date and time of the project.
     pub date_modified: DateTime<Local>,
+    /// When the project folder was added to its current location, as recorded by
+    /// macOS Spotlight.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date_added: Option<DateTime<Local>>,
+    /// When the project folder was last opened, as recorded by macOS Spotlight.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_opened: Option<DateTime<Local>>,
     /// A list of notes related to the project.
     pub notes: Vec<String>,
+    /// Other tagged projects this one belongs with, linked with `tagger link`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub related: Vec<RelatedProject>,
+    /// Archives made of the project with `tagger archive`, oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub archives: Vec<ArchiveRecord>,
+    /// The version control system the project is kept in, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vcs: Option<vcs::Vcs>,
+    /// The push URL of the project's preferred remote, if any. Despite the name, it
+    /// is recorded for every version control system.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_url: Option<String>,
+    /// The web page of the repository at `git_url`, such as
+    /// `https://github.com/user/repo` for `git@github.com:user/repo.git`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub web_url: Option<String>,
+    /// Every remote of the project, mapping the remote name to its push URL.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub remotes: BTreeMap<String, String>,
+    /// The git submodules checked out inside the project.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub submodules: Vec<git::Submodule>,
+    /// Branch and commit metadata, if the project is a git repository.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git: Option<git::GitInfo>,
+    /// Uncommitted and unpushed work, if the project is a git repository with commits.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_status: Option<git::GitStatus>,
+    /// The project's license as an SPDX identifier or expression (e.g. `MIT`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    /// Extra typed fields contributed by detectors, namespaced by detector name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<String, DetectorFields>,
+    /// Problems noticed while generating the information, such as unreadable
+    /// directories or files that look like secrets.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    /// The total size of every file in the project, in bytes.
+    #[serde(default)]
+    pub total_size_bytes: u64,
+    /// The number of files in the project.
+    #[serde(default)]
+    pub file_count: usize,
+    /// The largest top-level subdirectories, largest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub largest_subdirs: Vec<stats::SubdirSize>,
+    /// A fingerprint of the project's files when it was tagged, checked by
+    /// `tagger verify`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<Fingerprint>,
+    /// The drive the project was on when it was tagged, unless it is the system's
+    /// root, so the project shows as offline while the drive is unplugged.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub volume: Option<volume::Volume>,
+    /// Sub-project directories of a monorepo or workspace, relative to the project.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subprojects: Vec<String>,
+    /// Whether the type was only guessed from loose files rather than project files.
+    #[serde(skip)]
+    pub type_uncertain: bool,
 }
 
 impl ProjectInfo {
-    /// Prints the project information.
+    /// Prints the project information as aligned fields, with tags as chips and
+    /// dates followed by how long ago they were. Colors follow [`terminal::set_color`].
     pub fn print_info(&self) {
-        println!("Project Name: {}", self.name);
-        println!(
-            "Alias: {}",
-            if self.alias.is_empty() {
-                "None".to_string()
-            } else {
-                self.alias.clone()
+        print!("{}", self.format_info(Local::now()));
+    }
+
+    /// Formats the project information printed by [`ProjectInfo::print_info`].
+    fn format_info(&self, now: DateTime<Local>) -> String {
+        let mut rows = Vec::new();
+        // Every field message reads "Label: value"; the label is split off so the
+        // values line up.
+        let mut row = |line: String| {
+            let (label, value) = line.split_once(": ").unwrap_or((&line, ""));
+            rows.push((label.to_string(), value.to_string()));
+        };
+        let date = |date: &DateTime<Local>| {
+            let relative = format!("({})", terminal::relative_time(*date, now));
+            format!("{} {}", date.format("%Y-%m-%d %H:%M"), terminal::paint(&relative, Style::Dim))
+        };
+
+        row(tr(Message::ProjectName, &[&terminal::paint(&self.name, Style::Title)]));
+        if !self.directory_name.is_empty() && self.directory_name != self.name {
+            row(tr(Message::Directory, &[&self.directory_name]));
+        }
+        let alias = if self.alias.is_empty() {
+            tr(Message::None, &[])
+        } else {
+            self.alias.clone()
+        };
+        row(tr(Message::Alias, &[&alias]));
+        if let Some(status) = self.status {
+            row(tr(Message::Status, &[&status]));
+        }
+        if let Some(description) = &self.description {
+            row(tr(Message::Description, &[description]));
+        }
+        row(tr(Message::ProjectType, &[&self.project_type]));
+        if let Some(version) = &self.version {
+            row(tr(Message::Version, &[version]));
+        }
+        row(tr(Message::Tags, &[&terminal::tag_chips(&self.tags)]));
+        if !self.suggested_tags.is_empty() {
+            row(tr(Message::SuggestedTags, &[&terminal::tag_chips(&self.suggested_tags)]));
+        }
+        row(tr(Message::DateCreated, &[&date(&self.date_created)]));
+        row(tr(Message::DateModified, &[&date(&self.date_modified)]));
+        if let Some(date_added) = &self.date_added {
+            row(tr(Message::DateAdded, &[&date(date_added)]));
+        }
+        if let Some(last_opened) = &self.last_opened {
+            row(tr(Message::LastOpened, &[&date(last_opened)]));
+        }
+        if let Some(license) = &self.license {
+            row(tr(Message::License, &[license]));
+        }
+        if let Some(vcs) = &self.vcs {
+            row(tr(Message::VersionControl, &[vcs]));
+        }
+        if let Some(git_url) = &self.git_url {
+            row(tr(Message::GitUrl, &[git_url]));
+        }
+        if let Some(status) = &self.git_status {
+            let state = tr(
+                if status.dirty { Message::GitStatusDirty } else { Message::GitStatusClean },
+                &[],
+            );
+            let state = if status.dirty { terminal::paint(&state, Style::Warning) } else { state };
+            row(tr(Message::GitStatus, &[&state, &status.unpushed_commits]));
+        }
+        if self.remotes.len() > 1 {
+            for (name, url) in &self.remotes {
+                row(tr(Message::Remote, &[name, url]));
             }
-        );
-        println!("Project Type: {}", self.project_type);
-        println!("Tags: {:?}", self.tags);
-        println!("Date Created: {}", self.date_created);
-        println!("Date Modified: {}", self.date_modified);
+        }
+        for submodule in &self.submodules {
+            row(tr(Message::Submodule, &[&submodule.path, &submodule.url.as_deref().unwrap_or("-")]));
+        }
+        if let Some(git) = &self.git {
+            if let Some(branch) = &git.branch {
+                row(tr(Message::GitBranch, &[branch]));
+            }
+            if let (Some(last_commit), Some(last_commit_date)) = (&git.last_commit, &git.last_commit_date) {
+                let short = &last_commit[..last_commit.len().min(7)];
+                let relative = terminal::relative_time(*last_commit_date, now);
+                row(tr(Message::LastCommit, &[&short, &relative]));
+            }
+            if git.commit_count > 0 {
+                row(tr(Message::Commits, &[&git.commit_count]));
+            }
+            if !git.contributors.is_empty() {
+                let contributors: Vec<String> = git
+                    .contributors
+                    .iter()
+                    .map(|contributor| format!("{} ({})", contributor.name, contributor.commits))
+                    .collect();
+                row(tr(Message::Contributors, &[&contributors.join(", ")]));
+            }
+        }
+
+        row(tr(Message::Size, &[&stats::format_size(self.total_size_bytes), &self.file_count]));
+        if !self.largest_subdirs.is_empty() {
+            let subdirs: Vec<String> = self
+                .largest_subdirs
+                .iter()
+                .map(|subdir| format!("{} ({})", subdir.path, stats::format_size(subdir.size_bytes)))
+                .collect();
+            row(tr(Message::LargestSubdirs, &[&subdirs.join(", ")]));
+        }
 
-        if self.notes.is_empty() {
-            println!("Notes: None");
+        let notes = if self.notes.is_empty() {
+            tr(Message::None, &[])
         } else {
-            println!("Notes: {:?}", self.notes);
+            self.notes.join("; ")
+        };
+        row(tr(Message::Notes, &[&notes]));
+
+        if !self.related.is_empty() {
+            let related: Vec<String> = self.related.iter().map(RelatedProject::label).collect();
+            row(tr(Message::Related, &[&related.join(", ")]));
         }
+
+        if !self.subprojects.is_empty() {
+            row(tr(Message::SubProjects, &[&self.subprojects.join(", ")]));
+        }
+
+        for (namespace, fields) in &self.fields {
+            for (key, value) in fields {
+                rows.push((format!("{}.{}", namespace, key), value.to_string()));
+            }
+        }
+
+        let mut out = terminal::aligned(&rows);
+        for warning in &self.warnings {
+            out.push_str(&format!("⚠️  {}\n", terminal::paint(warning, Style::Warning)));
+        }
+        out
     }
 
     /// Sets an alias for the project.
@@ -66,31 +505,107 @@ impl ProjectInfo {
         self.notes.push(note);
     }
 
+    /// Keeps what the user recorded in an earlier `project_info.toml`: the alias,
+    /// status, notes, related projects, creation date, identifier, and slug, along with
+    /// tag suggestions still waiting for confirmation when no new ones were made.
+    pub fn keep_user_fields(&mut self, recorded: ProjectInfo) {
+        self.keep_identity(&recorded);
+        self.alias = recorded.alias;
+        self.status = recorded.status;
+        self.notes = recorded.notes;
+        self.related = recorded.related;
+        self.archives = recorded.archives;
+        self.date_created = recorded.date_created;
+        if self.suggested_tags.is_empty() {
+            self.suggested_tags = recorded
+                .suggested_tags
+                .into_iter()
+                .filter(|tag| !self.tags.contains(tag))
+                .collect();
+        }
+    }
+
+    /// Keeps the identifier and slug of an earlier `project_info.toml`, so tools that
+    /// refer to the project keep working after it is rescanned, renamed, or moved.
+    /// Files written before identifiers existed keep the newly generated ones.
+    pub fn keep_identity(&mut self, recorded: &ProjectInfo) {
+        if !recorded.id.is_empty() {
+            self.id = recorded.id.clone();
+        }
+        if !recorded.slug.is_empty() {
+            self.slug = recorded.slug.clone();
+        }
+    }
+
+    /// Changes the project type, regenerating the name, tags, and fields for it.
+    pub fn set_project_type(
+        &mut self,
+        directory: &Path,
+        project_type: &str,
+        config: &Config,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.project_type = project_type.to_string();
+        self.type_uncertain = false;
+
+        let overrides = Overrides::load(directory);
+        let inventory = Inventory::scan_with(directory, &overrides.scan_config(&config.scan));
+        if let Some(name) = detector_for(project_type).and_then(|detector| detector.project_name(&inventory)) {
+            self.name = name;
+        }
+        self.tags = Self::generate_tags(&inventory, project_type, config)?;
+        overrides.apply_tags(&mut self.tags);
+        self.fields = Self::generate_fields(&inventory, project_type);
+
+        Ok(())
+    }
+
     /// Saves the project information to a TOML file within the specified directory.
     pub fn save_to_toml_file(&self, directory: &Path) -> io::Result<()> {
+        self.save_to_file(&directory.join(PROJECT_INFO_FILE))
+    }
+
+    /// Saves the project information to a TOML file, creating its parent directories.
+    pub fn save_to_file(&self, file_path: &Path) -> io::Result<()> {
+        self.write_to_file(file_path)?;
+        println!("{}", tr(Message::Saved, &[&file_path.display()]));
+        Ok(())
+    }
+
+    /// Like [`ProjectInfo::save_to_file`], but without printing anything, for callers
+    /// whose standard output is not meant for people.
+    pub fn write_to_file(&self, file_path: &Path) -> io::Result<()> {
         // Convert the struct to a TOML string.
         let toml_string = toml::to_string(self).expect("Failed to serialize to TOML");
 
-        // Define the full path for the TOML file.
-        let file_path = directory.join("project_info.toml");
+        if let Some(parent) = file_path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
 
         // Write the TOML string to the file.
-        let mut file = File::create(&file_path)?;
-        file.write_all(toml_string.as_bytes())?;
+        let mut file = File::create(file_path)?;
+        file.write_all(toml_string.as_bytes())
+    }
 
-        println!("✅ Saved to {}", file_path.display());
-        Ok(())
+    /// Loads the project information saved in the directory's `project_info.toml`.
+    pub fn load_from_toml_file(directory: &Path) -> io::Result<Self> {
+        Self::load_from_file(&directory.join(PROJECT_INFO_FILE))
+    }
+
+    /// Loads project information saved in a TOML file.
+    pub fn load_from_file(file_path: &Path) -> io::Result<Self> {
+        let content = fs::read_to_string(file_path)?;
+        toml::from_str(&content).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
     }
 
     /// Automatically generates ProjectInfo based on the provided directory.
-    pub fn generate_project_info(directory: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+    pub fn generate_project_info(directory: &Path, config: &Config) -> Result<Self, Box<dyn std::error::Error>> {
         info!("Generating project information for directory: {}", directory.display());
 
         // Canonicalize the path to get the absolute path.
         let abs_path = fs::canonicalize(directory)?;
 
-        // Derive the project name from the absolute path.
-        let project_name = match abs_path.file_name() {
+        // Derive the directory name from the absolute path.
+        let directory_name = match abs_path.file_name() {
             Some(name) => name.to_string_lossy().to_string(),
             None => "Unnamed Project".to_string(),
         };
@@ -98,197 +613,635 @@ impl ProjectInfo {
         // Retrieve metadata from the original directory path.
         let metadata = fs::metadata(directory)?;
 
-        // Determine the project type based on directory contents.
-        let project_type = Self::generate_project_type(directory);
+        // Settings of this project in its .tagger.toml go on top of the configuration.
+        let overrides = Overrides::load(directory);
+        let scan = overrides.scan_config(&config.scan);
+
+        // List the directory once; every detector below reads from this inventory.
+        let inventory = Inventory::scan_with(directory, &scan);
+
+        // Ask the installed detector plugins what they make of the project.
+        let plugin_outputs = run_plugins(&config.plugins, &inventory, &config.security);
+
+        // Use the type the user recorded in .tagger.toml, the first type a plugin
+        // recognized, or detect it from the contents, falling back on the type of the
+        // configured root the project is in when the contents do not settle it.
+        let plugin_type = plugin_outputs.iter().find_map(|(_, output)| output.project_type.clone());
+        let (project_type, type_uncertain) = match overrides.project_type.clone().or(plugin_type) {
+            Some(project_type) => (project_type, false),
+            None => match Self::detect_project_type(&inventory) {
+                (detected, true) => match config.root_for(directory).and_then(|root| root.project_type.clone()) {
+                    Some(project_type) => (project_type, false),
+                    None => (detected, true),
+                },
+                certain => certain,
+            },
+        };
         info!("Project type determined as '{}'.", project_type);
 
-        // Generate tags based on directory contents.
-        let tags = Self::generate_tags(directory, &project_type)?;
+        // Prefer the name recorded in manifests or session files, since folders are
+        // often named things like "final_v2".
+        let project_name = detector_for(&project_type)
+            .and_then(|detector| detector.project_name(&inventory))
+            .unwrap_or_else(|| directory_name.clone());
+
+        // Generate tags, extra fields, and warnings from the directory contents. The
+        // detectors only read the inventory, so they run in parallel.
+        let (tags, (mut fields, mut warnings)) = rayon::join(
+            || Self::generate_tags(&inventory, &project_type, config).map_err(|e| e.to_string()),
+            || {
+                rayon::join(
+                    || Self::generate_fields(&inventory, &project_type),
+                    || Self::generate_warnings(&inventory, &project_type),
+                )
+            },
+        );
+        let mut tags = tags?;
         info!("Tags generated: {:?}", tags);
 
+        // Look up the version control system and its remotes, recording the preferred
+        // one's push URL and, for git repositories, its web page.
+        let vcs = vcs::detect(directory);
+        let remotes = vcs.map(|vcs| vcs::extract_remotes(directory, vcs)).unwrap_or_default();
+        let git_url = git::select_git_url(&remotes, config.git.remote.as_deref());
+        let web_url = git_url
+            .as_deref()
+            .filter(|_| vcs.is_some_and(vcs::Vcs::is_git_based))
+            .and_then(git::web_url);
+        if let Some(vcs) = vcs {
+            let tag = tags::namespaced(tags::VCS, vcs.as_str());
+            if !config.is_blacklisted(&project_type, &tag) && !tags.contains(&tag) {
+                tags.push(tag);
+                tags.sort();
+            }
+        }
+
+        // Tag the CI pipelines and development tooling, whatever the project's type.
+        tags.extend(
+            tooling::generate_tooling_tags(directory)
+                .into_iter()
+                .filter(|tag| !config.is_blacklisted(&project_type, tag)),
+        );
+        tags.sort();
+        tags.dedup();
+
+        // Flag work that still has to be committed or pushed.
+        let git_status = git::head_state(directory).map(|state| state.status);
+        if let Some(status) = &git_status {
+            if status.dirty {
+                tags.push(tags::namespaced(tags::GIT, "dirty"));
+            }
+            if status.unpushed_commits > 0 {
+                tags.push(tags::namespaced(tags::GIT, "unpushed"));
+            }
+            tags.retain(|tag| !config.is_blacklisted(&project_type, tag));
+            tags.sort();
+        }
+
+        // Tags the user gave the folder in Finder count as project tags.
+        #[cfg(feature = "finder-tags")]
+        if config.output.finder_tags {
+            tags.extend(finder::read_tags(directory));
+            tags.sort();
+            tags.dedup();
+        }
+
+        // Plugins add their tags and fields to the built-in detectors' ones.
+        if !plugin_outputs.is_empty() {
+            for (name, output) in plugin_outputs {
+                tags.extend(output.tags.into_iter().filter(|tag| !config.is_blacklisted(&project_type, tag)));
+                if !output.fields.is_empty() {
+                    fields.insert(name, output.fields);
+                }
+            }
+            tags.sort();
+            tags.dedup();
+        }
+
+        // Tag where the repository is hosted, so projects can be grouped by host.
+        if let Some(hosting) = web_url.as_deref().and_then(git::hosting) {
+            let tag = tags::namespaced(tags::HOST, hosting);
+            if !config.is_blacklisted(&project_type, &tag) && !tags.contains(&tag) {
+                tags.push(tag);
+                tags.sort();
+            }
+        }
+
+        // The project's own forced and forbidden tags have the last word.
+        overrides.apply_tags(&mut tags);
+
+        // Look up the branch and last commit, if the project is a repository.
+        let git = git::extract_git_info(directory, &config.git);
+
+        // A directory's mtime only changes when direct children are added or removed,
+        // so the date comes from the contents: the last commit of a clean repository,
+        // whose files' mtimes are only checkout times, or else the newest file.
+        let mut date_modified = Self::get_modification_time(&metadata);
+        if config.scan.modified_from_contents() {
+            let last_commit_date = git.as_ref().and_then(|git| git.last_commit_date);
+            let newest_file = || Self::newest_file_time(&inventory, &config.output.file_for(directory));
+            date_modified = match (last_commit_date, &git_status) {
+                (Some(date), Some(status)) if !status.dirty => date,
+                _ => newest_file().unwrap_or(date_modified),
+            };
+        }
+
+        // The last commit is a better "modified" date than the directory's mtime, which
+        // changes on every build, but only when the user opts in.
+        if config.git.commit_date_as_modified {
+            if let Some(last_commit_date) = git.as_ref().and_then(|git| git.last_commit_date) {
+                date_modified = last_commit_date;
+            }
+        }
+
+        // On macOS, Finder's "date added" and "last opened" follow the user's own
+        // activity better than file-system timestamps.
+        let spotlight_dates = spotlight::read_dates(directory);
+
+        // Summarize the project from its README, unless the user recorded a summary.
+        let description = overrides.description.clone().or_else(|| {
+            readme::extract_description(
+                directory,
+                config.description.max_length.unwrap_or(readme::DEFAULT_DESCRIPTION_LENGTH),
+            )
+        });
+
+        // Record the version, so copies of a project can be told apart.
+        let version = programming::extract_manifest_version(directory).or_else(|| git::latest_tag(directory));
+
+        // Record the license declared in manifests or license files.
+        let license = license::detect_license(directory);
+
+        // Measure the project, so oversized ones stand out.
+        let stats = inventory.stats().clone();
+
+        // Fingerprint the files, so later changes and bit rot can be detected.
+        let info_file = config.output.file_for(directory);
+        let fingerprint = Fingerprint::compute(directory, config.fingerprint.contents, &scan, &info_file)
+            .inspect_err(|e| warn!("Could not fingerprint {}: {}", directory.display(), e))
+            .ok();
+
+        // List the members of a monorepo so its structure is not lost.
+        let subprojects = workspace::find_subprojects(directory);
+
+        // Add repository and license problems worth surfacing to the user.
+        if directory.join(".git").exists() && remotes.is_empty() {
+            warnings.push("Git repository has no remote.".to_string());
+        }
+        if let (None, Some(license_file)) = (&license, license::license_file(directory)) {
+            warnings.push(format!(
+                "License in {} not recognized.",
+                relative_display(directory, &license_file)
+            ));
+        }
+
+        // Most Linux file systems do not record when a directory was created, so the
+        // date is estimated from the project's history instead.
+        let date_created = match metadata.created() {
+            Ok(time) => DateTime::<Local>::from(time),
+            Err(_) => {
+                let recorded = ProjectInfo::load_from_file(&config.output.file_for(directory))
+                    .ok()
+                    .map(|recorded| recorded.date_created);
+                Self::estimate_creation_time(&inventory, git.as_ref(), recorded)
+            }
+        };
+
         // Initialize ProjectInfo with empty notes.
         Ok(ProjectInfo {
+            id: Uuid::new_v4().to_string(),
+            slug: slugify(&project_name),
             name: project_name,
+            directory_name,
             alias: "".to_string(), // Empty alias
+            status: None,
+            description,
             project_type,
+            version,
             tags,
-            date_created: Self::get_creation_time(&metadata),
-            date_modified: Self::get_modification_time(&metadata),
+            suggested_tags: Vec::new(),
+            date_created,
+            date_modified,
+            date_added: spotlight_dates.date_added,
+            last_opened: spotlight_dates.last_opened,
             notes: Vec::new(), // Initialize as empty
+            related: Vec::new(),
+            archives: Vec::new(),
+            vcs,
+            git_url,
+            web_url,
+            remotes,
+            submodules: git::extract_submodules(directory),
+            git,
+            git_status,
+            license,
+            fields,
+            warnings,
+            total_size_bytes: stats.total_size_bytes,
+            file_count: stats.file_count,
+            largest_subdirs: stats.largest_subdirs,
+            fingerprint,
+            volume: volume::for_path(directory),
+            subprojects,
+            type_uncertain,
         })
     }
 
     /// Generates the project type based on the files in the directory.
+    #[cfg(test)]
     fn generate_project_type(directory: &Path) -> String {
-        // Define indicators for different project types.
-        let programming_indicators = vec![
-            "Cargo.toml",
-            "package.json",
-            "setup.py",
-            "pom.xml",
-            "build.gradle",
-            "Makefile",
-            "Gemfile",
-            "requirements.txt",
-        ];
-
-        let music_production_indicators = vec![
-            "project.als",        // Ableton Live
-            "project.flp",        // FL Studio
-            "project.logic",      // Logic Pro
-            "project.rpp",        // Reaper
-            "project.studioone",  // Presonus Studio One
-        ];
-
-        // Flags to indicate project type detection.
-        let mut is_programming = false;
-        let mut is_music = false;
-
-        // Collect all files in the directory (non-recursive).
-        let entries = fs::read_dir(directory).unwrap_or_else(|_| {
-            eprintln!("Error: Unable to read directory contents.");
-            std::process::exit(1);
-        });
+        Self::detect_project_type(&Inventory::scan(directory)).0
+    }
 
-        for entry in entries {
-            if let Ok(entry) = entry {
-                let path = entry.path();
+    /// Detects the project type, and whether the guess is uncertain.
+    ///
+    /// Types decided by project files (manifests, sessions, catalogs) are certain;
+    /// types inferred from loose media, source, or Markdown files are not, and an
+    /// unknown type is always uncertain.
+    fn detect_project_type(inventory: &Inventory) -> (String, bool) {
+        // Manifests that do not rule out a data-science project.
+        const PYTHON_MANIFESTS: &[&str] = &["setup.py", "requirements.txt"];
+
+        // Signals gathered while walking the project. Project files (manifests, DAW
+        // sessions, editor timelines, design documents) are decisive; loose source and
+        // media files are only used as hints when no project file is present.
+        let mut manifests = HashSet::new();
+        let mut notebooks = 0;
+        let mut has_data_science_indicator = false;
+        let mut dataset_files = 0;
+        let mut has_daw_project = false;
+        let mut has_editor_project = false;
+        let mut has_photo_catalog = false;
+        let mut has_firmware_manifest = false;
+        let mut has_kicad_project = false;
+        let mut has_sketch = false;
+        let mut raw_files = 0;
+        let mut design_files = 0;
+        let mut model_files = 0;
+        let mut has_source = false;
+        let mut has_video = false;
+        let mut has_audio = false;
+        let mut has_document = false;
+        let mut markdown_files = 0;
+        let mut file_count = 0;
+        let mut infra_files = 0;
+
+        for entry in inventory.entries() {
+            let path = entry.path();
+            if entry.file_type().is_file() {
+                file_count += 1;
+            }
 
-                // Check for specific indicator files.
-                if let Some(file_name) = path.file_name() {
-                    // Check for programming indicators.
-                    if programming_indicators.contains(&file_name.to_string_lossy().as_ref()) {
-                        is_programming = true;
-                        break; // Priority can be given based on needs.
-                    }
+            // Check for programming and data-science indicator files.
+            if let Some(file_name) = path.file_name() {
+                let file_name = file_name.to_string_lossy();
+                if let Some(indicator) = PROGRAMMING_INDICATORS.iter().find(|i| **i == file_name) {
+                    manifests.insert(*indicator);
+                    continue;
+                }
+                if DATA_SCIENCE_INDICATORS.contains(&file_name.as_ref()) {
+                    has_data_science_indicator = true;
+                    continue;
+                }
+                if EMBEDDED_INDICATORS.contains(&file_name.as_ref()) {
+                    has_firmware_manifest = true;
+                    continue;
+                }
+            }
 
-                    // Check for music production indicators.
-                    if music_production_indicators.contains(&file_name.to_string_lossy().as_ref()) {
-                        is_music = true;
-                        break;
-                    }
+            // Check for KiCad projects and Arduino sketches.
+            if is_kicad_project(path) {
+                has_kicad_project = true;
+                continue;
+            }
+            if is_arduino_sketch(path) {
+                has_sketch = true;
+                continue;
+            }
+
+            // Check for notebooks and datasets.
+            if is_notebook(path) {
+                notebooks += 1;
+                continue;
+            }
+            if is_dataset(path) {
+                dataset_files += 1;
+                continue;
+            }
+
+            // Check for DAW and video editor project files and bundle directories.
+            if daw_project_format(path).is_some() {
+                has_daw_project = true;
+                continue;
+            }
+            if editor_project_format(path).is_some() {
+                has_editor_project = true;
+                continue;
+            }
+
+            // Check for photo catalogs, sessions, and RAW files.
+            if catalog_format(path).is_some() {
+                has_photo_catalog = true;
+                continue;
+            }
+            if raw_format(path).is_some() {
+                raw_files += 1;
+                continue;
+            }
+
+            // Check for design and 3D tool documents.
+            if let Some((_, _, kind)) = design_file_format(path) {
+                if kind == "3d" {
+                    model_files += 1;
+                } else {
+                    design_files += 1;
                 }
+                continue;
+            }
 
-                // Additionally, check file extensions.
-                if let Some(extension) = path.extension() {
-                    match extension.to_str().unwrap_or("").to_lowercase().as_str() {
-                        // Programming file extensions.
-                        "rs" | "py" | "js" | "java" | "cpp" | "c" | "cs" | "go" | "rb" | "swift" => {
-                            is_programming = true;
-                        }
-                        // Music production file extensions.
-                        "wav" | "mp3" | "flac" | "ogg" | "aiff" | "rpp" | "flp" | "logic" | "studioone" => {
-                            is_music = true;
-                        }
-                        _ => {}
-                    }
+            // Check for LaTeX, Typst, and Scrivener documents, and count Markdown files.
+            if document_format(path).is_some() {
+                has_document = true;
+                continue;
+            }
+            if is_markdown(path) {
+                markdown_files += 1;
+                continue;
+            }
+
+            // Check for video assets.
+            if is_video_asset(path) {
+                has_video = true;
+                continue;
+            }
 
-                    // Priority: If both types are detected, decide based on preference.
-                    if is_programming && is_music {
-                        break; // Stop early if both are detected.
+            // Check for Terraform, Docker, Kubernetes, Helm, and Ansible files.
+            if entry.file_type().is_file() && infrastructure_tool(path).is_some() {
+                infra_files += 1;
+                continue;
+            }
+
+            // Additionally, check file extensions.
+            if let Some(extension) = path.extension() {
+                match extension.to_str().unwrap_or("").to_lowercase().as_str() {
+                    // Programming file extensions.
+                    "rs" | "py" | "js" | "java" | "cpp" | "c" | "cs" | "go" | "rb" | "swift" => {
+                        has_source = true;
+                    }
+                    // Audio file extensions.
+                    ext if AUDIO_EXTENSIONS.contains(&ext) => {
+                        has_audio = true;
                     }
+                    _ => {}
                 }
             }
         }
 
-        if is_programming {
-            info!("Detected as a programming project.");
-            "programming".to_string()
-        } else if is_music {
-            info!("Detected as a music project.");
-            "music".to_string()
+        // Notebook and DVC projects are data science even when they ship Python
+        // manifests, but not when they carry manifests of other ecosystems.
+        let python_manifests_only = manifests
+            .iter()
+            .all(|manifest| PYTHON_MANIFESTS.contains(manifest));
+
+        let project_type = if has_firmware_manifest {
+            // PlatformIO and Zephyr projects often also ship Makefiles or CMake files.
+            Some("embedded")
+        } else if (notebooks > 0 || has_data_science_indicator) && python_manifests_only {
+            Some("data-science")
+        } else if !manifests.is_empty() {
+            Some("programming")
+        } else if has_daw_project {
+            Some("music")
+        } else if has_editor_project {
+            Some("video")
+        } else if has_photo_catalog {
+            Some("photography")
+        } else if has_sketch {
+            Some("embedded")
+        } else if has_kicad_project {
+            Some("hardware")
+        } else if model_files > 0 && model_files >= design_files {
+            Some("3d")
+        } else if design_files > 0 {
+            Some("design")
+        } else if has_document {
+            Some("writing")
+        } else if infra_files > 0
+            && !has_source
+            && !has_video
+            && !has_audio
+            && raw_files == 0
+            && dataset_files == 0
+        {
+            // Directories holding nothing but infrastructure code (and docs).
+            Some("infra")
         } else {
-            warn!("Project type is unknown.");
-            "unknown".to_string()
+            None
+        };
+
+        if let Some(project_type) = project_type {
+            info!("Detected as a {} project.", project_type);
+            return (project_type.to_string(), false);
         }
+
+        // Without project files, guess from the kinds of loose files present.
+        let project_type = if raw_files > 0 {
+            "photography"
+        } else if dataset_files > 0 && dataset_files * 2 >= file_count {
+            // Folders that are mostly datasets are data science, even with some scripts.
+            "data-science"
+        } else if has_source {
+            "programming"
+        } else if has_video {
+            "video"
+        } else if has_audio {
+            "music"
+        } else if markdown_files > 0 && markdown_files * 2 >= file_count {
+            // Folders that are mostly Markdown are notes or documentation.
+            "writing"
+        } else {
+            info!("Project type is unknown.");
+            return ("unknown".to_string(), true);
+        };
+
+        info!("Guessed a {} project from loose files.", project_type);
+        (project_type.to_string(), true)
     }
 
     /// Generates tags based on the files in the directory and the determined project type.
-    fn generate_tags(directory: &Path, project_type: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
-        let tags = match project_type {
-            "programming" => {
-                let mut prog_tags = generate_programming_tags(directory);
-                // Extract dependencies and add as tags.
-                //let dependencies = extract_cargo_dependencies(directory);
-                //prog_tags.extend(dependencies);
-                Ok::<Vec<String>, Box<dyn std::error::Error>>(prog_tags)
-            },
-            "music" => {
-                let music_tags = generate_music_tags(directory);
-                Ok::<Vec<String>, Box<dyn std::error::Error>>(music_tags)
-            },
-            _ => {
-                let unknown_tags = Self::generate_unknown_tags(directory);
-                Ok::<Vec<String>, Box<dyn std::error::Error>>(unknown_tags)
-            },
-        }?;
+    ///
+    /// The type's general tags are added unless the configuration turns them off, and
+    /// tags blacklisted in the configuration, globally or for the project type, are removed.
+    fn generate_tags(
+        inventory: &Inventory,
+        project_type: &str,
+        config: &Config,
+    ) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let mut tags = match detector_for(project_type) {
+            Some(detector) => {
+                let mut tags = detector.generate_tags(inventory, project_type, config);
+                if config.default_tags(project_type) {
+                    tags.extend(detector.default_tags(project_type).iter().map(|tag| tag.to_string()));
+                }
+                tags
+            }
+            None => Self::generate_unknown_tags(inventory),
+        };
+
+        // Tag the license so projects can be filtered by it.
+        if let Some(license) = license::detect_license(inventory.root()) {
+            tags.extend(license::license_tags(&license).into_iter().map(|id| tags::namespaced(tags::LICENSE, id)));
+        }
+
+        // Apply the tag rules of installed bundles.
+        tags.extend(bundle::rule_tags(inventory, project_type, &config.rules));
 
-        // Remove duplicate tags by converting to a set and back.
-        let unique_tags: HashSet<_> = tags.into_iter().collect();
+        // Normalize spelling and casing, then remove duplicate and blacklisted tags by
+        // converting to a set and back.
+        let unique_tags: HashSet<_> = tags
+            .iter()
+            .map(|tag| tags::normalize(tag, &config.synonyms))
+            .filter(|tag| !config.is_blacklisted(project_type, tag))
+            .collect();
         let mut unique_tags: Vec<String> = unique_tags.into_iter().collect();
         unique_tags.sort(); // Optional: sort tags alphabetically.
 
-        info!("Tags after deduplication and sorting: {:?}", unique_tags);
+        debug!("Tags after deduplication and sorting: {:?}", unique_tags);
 
         Ok(unique_tags)
     }
 
+    /// Generates the detector-specific fields for the given project type.
+    ///
+    /// Fields are keyed by the detector's name; detectors that emit nothing are omitted.
+    fn generate_fields(inventory: &Inventory, project_type: &str) -> BTreeMap<String, DetectorFields> {
+        let mut fields = BTreeMap::new();
+
+        if let Some(detector) = detector_for(project_type) {
+            let detector_fields = detector.generate_fields(inventory, project_type);
+            if !detector_fields.is_empty() {
+                fields.insert(detector.name().to_string(), detector_fields);
+            }
+        }
+
+        fields
+    }
+
+    /// Generates warnings about unreadable directories, files that look like secrets,
+    /// and any problems reported by the project type's detector.
+    fn generate_warnings(inventory: &Inventory, project_type: &str) -> Vec<String> {
+        let directory = inventory.root();
+        let mut warnings = Vec::new();
+
+        for entry in inventory.files() {
+            let file_name = entry.file_name().to_string_lossy();
+            if is_suspicious_secret(&file_name) {
+                warnings.push(format!("Possible secret file: {}", relative_display(directory, entry.path())));
+            }
+        }
+        for e in inventory.errors() {
+            let path = e.path().map(|path| relative_display(directory, path));
+            warnings.push(format!("Unreadable path {}: {}", path.unwrap_or_default(), e));
+        }
+
+        // The inventory skips hidden files, so check for `.env` files at the top level.
+        if let Ok(entries) = fs::read_dir(directory) {
+            for entry in entries.flatten() {
+                let file_name = entry.file_name().to_string_lossy().to_string();
+                if file_name.starts_with('.') && entry.path().is_file() && is_suspicious_secret(&file_name) {
+                    warnings.push(format!("Possible secret file: {}", file_name));
+                }
+            }
+        }
+
+        if let Some(detector) = detector_for(project_type) {
+            warnings.extend(detector.generate_warnings(inventory, project_type));
+        }
+
+        for warning in &warnings {
+            warn!("{}: {}", directory.display(), warning);
+        }
+
+        warnings
+    }
+
     /// Generates generic tags for unknown project types based on file extensions.
     ///
     /// # Arguments
     ///
-    /// * `directory` - A reference to the project's directory path.
+    /// * `inventory` - The project's inventory.
     ///
     /// # Returns
     ///
     /// A vector of generic tags.
-    fn generate_unknown_tags(directory: &Path) -> Vec<String> {
+    fn generate_unknown_tags(inventory: &Inventory) -> Vec<String> {
         let mut tags = Vec::new();
 
-        let entries = fs::read_dir(directory).unwrap_or_else(|_| {
-            eprintln!("Error: Unable to read directory contents for tag generation.");
-            std::process::exit(1);
-        });
-
         let mut generic_tags = HashSet::new();
 
-        for entry in entries {
-            if let Ok(entry) = entry {
-                let path = entry.path();
+        // Only the top-level entries are considered.
+        for entry in inventory.entries().filter(|entry| entry.depth() == 1) {
+            let path = entry.path();
 
-                if let Some(extension) = path.extension() {
-                    if let Some(ext_str) = extension.to_str() {
-                        generic_tags.insert(ext_str.to_uppercase());
-                    }
+            if let Some(extension) = path.extension() {
+                if let Some(ext_str) = extension.to_str() {
+                    generic_tags.insert(tags::namespaced(tags::FORMAT, ext_str.to_uppercase()));
                 }
             }
         }
 
         // Add generic tags.
-        tags.extend(generic_tags.into_iter());
+        tags.extend(generic_tags);
 
-        info!("Unknown project tags generated: {:?}", tags);
+        debug!("Unknown project tags generated: {:?}", tags);
 
         tags
     }
 
-    /// Fetches creation time from metadata.
-    fn get_creation_time(metadata: &fs::Metadata) -> DateTime<Local> {
-        match metadata.created() {
-            Ok(time) => DateTime::<Local>::from(time),
-            Err(_) => {
-                warn!("Creation time not available. Using current time as fallback.");
+    /// Estimates when a project was created, for file systems that do not record it:
+    /// the earliest of its first commit, its oldest file, and the creation date in an
+    /// earlier `project_info.toml`. The current time is used when none is known.
+    fn estimate_creation_time(
+        inventory: &Inventory,
+        git: Option<&git::GitInfo>,
+        recorded: Option<DateTime<Local>>,
+    ) -> DateTime<Local> {
+        let oldest_file = inventory
+            .files()
+            .filter_map(|entry| entry.metadata().ok()?.modified().ok())
+            .min()
+            .map(DateTime::<Local>::from);
+        let first_commit = git.and_then(|git| git.first_commit_date);
+
+        match [first_commit, oldest_file, recorded].into_iter().flatten().min() {
+            Some(date) => {
+                info!("Creation time not available. Estimated as {}.", date);
+                date
+            }
+            None => {
+                info!("Creation time not available. Using current time as fallback.");
                 Local::now()
             }
         }
     }
 
+    /// Returns when the newest of the project's files was modified, leaving out the
+    /// output file, which tagger writes itself.
+    fn newest_file_time(inventory: &Inventory, output: &Path) -> Option<DateTime<Local>> {
+        inventory
+            .files()
+            .filter(|entry| entry.path() != output)
+            .filter_map(|entry| entry.metadata().ok()?.modified().ok())
+            .max()
+            .map(DateTime::<Local>::from)
+    }
+
     /// Fetches modification time from metadata.
     fn get_modification_time(metadata: &fs::Metadata) -> DateTime<Local> {
         match metadata.modified() {
             Ok(time) => DateTime::<Local>::from(time),
             Err(_) => {
-                warn!("Modification time not available. Using current time as fallback.");
+                info!("Modification time not available. Using current time as fallback.");
                 Local::now()
             }
         }
@@ -303,6 +1256,25 @@ mod tests {
     use std::fs::File;
     use std::io::Write;
 
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("My Song (v2)"), "my-song-v2");
+        assert_eq!(slugify("  --Café_Racer--  "), "caf-racer");
+        assert_eq!(slugify("日本"), "project");
+    }
+
+    #[test]
+    fn test_find_enclosing_project() {
+        let dir = tempdir().unwrap();
+        let song = dir.path().join("song");
+        fs::create_dir_all(song.join("Samples/kicks")).unwrap();
+        File::create(song.join("song.rpp")).unwrap();
+
+        let song = fs::canonicalize(&song).unwrap();
+        assert_eq!(find_enclosing_project(&song.join("Samples/kicks")), Some(song.clone()));
+        assert_eq!(find_enclosing_project(&song), Some(song));
+    }
+
     #[test]
     fn test_generate_project_type_programming() {
         let dir = tempdir().unwrap();
@@ -365,8 +1337,8 @@ mod tests {
         let project_type = ProjectInfo::generate_project_type(dir_path);
         assert_eq!(project_type, "programming");
 
-        let tags = ProjectInfo::generate_tags(dir_path, &project_type).unwrap();
-        assert!(tags.contains(&"rust".to_string()));
+        let tags = ProjectInfo::generate_tags(&Inventory::scan(dir_path), &project_type, &Config::default()).unwrap();
+        assert!(tags.contains(&"lang:rust".to_string()));
         assert!(tags.contains(&"cli".to_string()));
         assert!(tags.contains(&"software development".to_string()));
     }
@@ -385,8 +1357,8 @@ mod tests {
         let project_type = ProjectInfo::generate_project_type(dir_path);
         assert_eq!(project_type, "programming");
 
-        let tags = ProjectInfo::generate_tags(dir_path, &project_type).unwrap();
-        assert!(tags.contains(&"rust".to_string())); // Assuming "rust" is inferred from .rs files
+        let tags = ProjectInfo::generate_tags(&Inventory::scan(dir_path), &project_type, &Config::default()).unwrap();
+        assert!(tags.contains(&"lang:rust".to_string())); // Assuming "lang:rust" is inferred from .rs files
         assert!(tags.contains(&"cli".to_string()));
         assert!(tags.contains(&"software development".to_string()));
     }
@@ -409,15 +1381,575 @@ mod tests {
         let snare_mp3 = samples_dir.join("snare.mp3");
         File::create(&snare_mp3).unwrap();
 
-        let tags = ProjectInfo::generate_tags(dir_path, "music").unwrap();
-        assert!(tags.contains(&"WAV".to_string()));
-        assert!(tags.contains(&"MP3".to_string()));
-        assert!(tags.contains(&"RPP".to_string()));
-        assert!(tags.contains(&"Reaper".to_string()));
+        let tags = ProjectInfo::generate_tags(&Inventory::scan(dir_path), "music", &Config::default()).unwrap();
+        assert!(tags.contains(&"format:wav".to_string()));
+        assert!(tags.contains(&"format:mp3".to_string()));
+        assert!(tags.contains(&"format:rpp".to_string()));
+        assert!(tags.contains(&"daw:reaper".to_string()));
         assert!(tags.contains(&"audio".to_string()));
         assert!(tags.contains(&"production".to_string()));
     }
 
+    #[test]
+    fn test_generate_project_type_music_bundle() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+
+        // Create a Logic Pro package directory with internal files.
+        let bundle_path = dir_path.join("Song.logicx");
+        fs::create_dir(&bundle_path).unwrap();
+        File::create(bundle_path.join("ProjectData")).unwrap();
+
+        let project_type = ProjectInfo::generate_project_type(dir_path);
+        assert_eq!(project_type, "music");
+
+        let tags = ProjectInfo::generate_tags(&Inventory::scan(dir_path), &project_type, &Config::default()).unwrap();
+        assert!(tags.contains(&"daw:logic pro".to_string()));
+        assert!(tags.contains(&"format:logicx".to_string()));
+    }
+
+    #[test]
+    fn test_generate_tags_music_additional_daws() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+
+        File::create(dir_path.join("beat.bwproject")).unwrap();
+        File::create(dir_path.join("mix.cpr")).unwrap();
+        File::create(dir_path.join("session.ptx")).unwrap();
+        File::create(dir_path.join("demo.song")).unwrap();
+        fs::create_dir(dir_path.join("idea.band")).unwrap();
+
+        let tags = ProjectInfo::generate_tags(&Inventory::scan(dir_path), "music", &Config::default()).unwrap();
+        assert!(tags.contains(&"daw:bitwig studio".to_string()));
+        assert!(tags.contains(&"daw:cubase".to_string()));
+        assert!(tags.contains(&"daw:pro tools".to_string()));
+        assert!(tags.contains(&"daw:studio one".to_string()));
+        assert!(tags.contains(&"daw:garageband".to_string()));
+    }
+
+    #[test]
+    fn test_generate_project_type_video() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+
+        // A Final Cut library alongside footage and a voice-over recording.
+        fs::create_dir(dir_path.join("Edit.fcpbundle")).unwrap();
+        File::create(dir_path.join("Edit.fcpbundle").join("CurrentVersion.flexolibrary")).unwrap();
+        File::create(dir_path.join("A001_4K.braw")).unwrap();
+        File::create(dir_path.join("voiceover.wav")).unwrap();
+
+        let project_type = ProjectInfo::generate_project_type(dir_path);
+        assert_eq!(project_type, "video");
+
+        let tags = ProjectInfo::generate_tags(&Inventory::scan(dir_path), &project_type, &Config::default()).unwrap();
+        assert!(tags.contains(&"editor:final cut pro".to_string()));
+        assert!(tags.contains(&"format:braw".to_string()));
+        assert!(tags.contains(&"codec:blackmagic raw".to_string()));
+        assert!(tags.contains(&"resolution:4k".to_string()));
+        assert!(tags.contains(&"video".to_string()));
+    }
+
+    #[test]
+    fn test_generate_project_type_design() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+
+        File::create(dir_path.join("poster.psd")).unwrap();
+        File::create(dir_path.join("logo.ai")).unwrap();
+        File::create(dir_path.join("reference.png")).unwrap();
+
+        let project_type = ProjectInfo::generate_project_type(dir_path);
+        assert_eq!(project_type, "design");
+
+        let tags = ProjectInfo::generate_tags(&Inventory::scan(dir_path), &project_type, &Config::default()).unwrap();
+        assert!(tags.contains(&"tool:photoshop".to_string()));
+        assert!(tags.contains(&"tool:illustrator".to_string()));
+        assert!(tags.contains(&"design".to_string()));
+    }
+
+    #[test]
+    fn test_generate_project_type_3d() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+
+        File::create(dir_path.join("scene.blend")).unwrap();
+        File::create(dir_path.join("texture.psd")).unwrap();
+
+        let project_type = ProjectInfo::generate_project_type(dir_path);
+        assert_eq!(project_type, "3d");
+
+        let tags = ProjectInfo::generate_tags(&Inventory::scan(dir_path), &project_type, &Config::default()).unwrap();
+        assert!(tags.contains(&"tool:blender".to_string()));
+        assert!(tags.contains(&"3d".to_string()));
+    }
+
+    #[test]
+    fn test_generate_fields_music() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+
+        let mut rpp = File::create(dir_path.join("project.rpp")).unwrap();
+        writeln!(rpp, "<REAPER_PROJECT 0.1 \"6.80\"").unwrap();
+        writeln!(rpp, "  TEMPO 128 4 4").unwrap();
+        writeln!(rpp, ">").unwrap();
+        File::create(dir_path.join("bass_124bpm_F#min.wav")).unwrap();
+        File::create(dir_path.join("pad_F#min.wav")).unwrap();
+
+        let fields = ProjectInfo::generate_fields(&Inventory::scan(dir_path), "music");
+        let music = &fields["music"];
+        assert_eq!(music["bpm"].as_integer(), Some(128));
+        assert_eq!(music["key"].as_str(), Some("F# minor"));
+    }
+
+    #[test]
+    fn test_generate_fields_programming_serialized_under_namespace() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+
+        let mut cargo_toml = File::create(dir_path.join("Cargo.toml")).unwrap();
+        writeln!(cargo_toml, "[package]").unwrap();
+        writeln!(cargo_toml, "name = \"demo\"").unwrap();
+        writeln!(cargo_toml, "edition = \"2021\"").unwrap();
+        writeln!(cargo_toml, "rust-version = \"1.70\"").unwrap();
+
+        let info = ProjectInfo::generate_project_info(dir_path, &Config::default()).unwrap();
+        assert_eq!(info.fields["programming"]["msrv"].as_str(), Some("1.70"));
+
+        let toml_string = toml::to_string(&info).unwrap();
+        assert!(toml_string.contains("[fields.programming]"));
+        assert!(toml_string.contains("msrv = \"1.70\""));
+    }
+
+    #[test]
+    fn test_generate_project_type_writing_latex() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+
+        let mut main_tex = File::create(dir_path.join("main.tex")).unwrap();
+        writeln!(main_tex, "\\documentclass[11pt]{{article}}").unwrap();
+        File::create(dir_path.join("refs.bib")).unwrap();
+        File::create(dir_path.join("plot.py")).unwrap();
+
+        let project_type = ProjectInfo::generate_project_type(dir_path);
+        assert_eq!(project_type, "writing");
+
+        let tags = ProjectInfo::generate_tags(&Inventory::scan(dir_path), &project_type, &Config::default()).unwrap();
+        assert!(tags.contains(&"format:latex".to_string()));
+        assert!(tags.contains(&"paper".to_string()));
+        assert!(tags.contains(&"bibliography".to_string()));
+    }
+
+    #[test]
+    fn test_generate_project_type_writing_markdown_and_scrivener() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+
+        File::create(dir_path.join("chapter1.md")).unwrap();
+        File::create(dir_path.join("chapter2.md")).unwrap();
+        File::create(dir_path.join("cover.png")).unwrap();
+
+        assert_eq!(ProjectInfo::generate_project_type(dir_path), "writing");
+
+        let scriv_path = dir_path.join("Novel.scriv");
+        fs::create_dir(&scriv_path).unwrap();
+        File::create(scriv_path.join("Novel.scrivx")).unwrap();
+
+        let tags = ProjectInfo::generate_tags(&Inventory::scan(dir_path), "writing", &Config::default()).unwrap();
+        assert!(tags.contains(&"format:markdown".to_string()));
+        assert!(tags.contains(&"tool:scrivener".to_string()));
+        assert!(tags.contains(&"book".to_string()));
+    }
+
+    #[test]
+    fn test_generate_project_type_data_science() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+
+        File::create(dir_path.join("analysis.ipynb")).unwrap();
+        File::create(dir_path.join("dvc.yaml")).unwrap();
+        let mut requirements = File::create(dir_path.join("requirements.txt")).unwrap();
+        writeln!(requirements, "pandas>=2.0").unwrap();
+        writeln!(requirements, "torch==2.1.0").unwrap();
+        fs::create_dir(dir_path.join("data")).unwrap();
+        File::create(dir_path.join("data").join("train.parquet")).unwrap();
+
+        let project_type = ProjectInfo::generate_project_type(dir_path);
+        assert_eq!(project_type, "data-science");
+
+        let tags = ProjectInfo::generate_tags(&Inventory::scan(dir_path), &project_type, &Config::default()).unwrap();
+        for tag in ["tool:jupyter", "tool:dvc", "lib:pandas", "lib:pytorch", "dataset", "format:parquet"] {
+            assert!(tags.contains(&tag.to_string()), "missing tag {}", tag);
+        }
+
+        let fields = ProjectInfo::generate_fields(&Inventory::scan(dir_path), &project_type);
+        assert_eq!(fields["data-science"]["notebooks"].as_integer(), Some(1));
+    }
+
+    #[test]
+    fn test_generate_project_type_notebook_in_rust_project() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+
+        File::create(dir_path.join("Cargo.toml")).unwrap();
+        File::create(dir_path.join("benchmarks.ipynb")).unwrap();
+
+        assert_eq!(ProjectInfo::generate_project_type(dir_path), "programming");
+    }
+
+    #[test]
+    fn test_project_name_from_manifest() {
+        let dir = tempdir().unwrap();
+        let project_dir = dir.path().join("final_v2");
+        fs::create_dir(&project_dir).unwrap();
+
+        let mut package_json = File::create(project_dir.join("package.json")).unwrap();
+        writeln!(package_json, "{{\"name\": \"dashboard\", \"version\": \"1.0.0\"}}").unwrap();
+
+        let info = ProjectInfo::generate_project_info(&project_dir, &Config::default()).unwrap();
+        assert_eq!(info.name, "dashboard");
+        assert_eq!(info.directory_name, "final_v2");
+    }
+
+    #[test]
+    fn test_project_name_from_daw_session() {
+        let dir = tempdir().unwrap();
+        let project_dir = dir.path().join("session copy");
+        fs::create_dir(&project_dir).unwrap();
+        File::create(project_dir.join("Nightfall.als")).unwrap();
+
+        let info = ProjectInfo::generate_project_info(&project_dir, &Config::default()).unwrap();
+        assert_eq!(info.name, "Nightfall");
+
+        let mut rpp = File::create(project_dir.join("mix.rpp")).unwrap();
+        writeln!(rpp, "<REAPER_PROJECT").unwrap();
+        writeln!(rpp, "  TITLE \"Midnight Drive\"").unwrap();
+        writeln!(rpp, ">").unwrap();
+
+        let info = ProjectInfo::generate_project_info(&project_dir, &Config::default()).unwrap();
+        assert_eq!(info.name, "Midnight Drive");
+        assert_eq!(info.directory_name, "session copy");
+    }
+
+    #[test]
+    fn test_generate_project_type_photography() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+
+        File::create(dir_path.join("Wedding.lrcat")).unwrap();
+        let previews = dir_path.join("Wedding Previews.lrdata");
+        fs::create_dir(&previews).unwrap();
+        File::create(previews.join("preview.jpg")).unwrap();
+        File::create(dir_path.join("DSC_0001.NEF")).unwrap();
+        File::create(dir_path.join("DSC_0002.NEF")).unwrap();
+        File::create(dir_path.join("IMG_0003.CR3")).unwrap();
+        File::create(dir_path.join("DSC_0001.jpg")).unwrap();
+
+        let project_type = ProjectInfo::generate_project_type(dir_path);
+        assert_eq!(project_type, "photography");
+
+        let tags = ProjectInfo::generate_tags(&Inventory::scan(dir_path), &project_type, &Config::default()).unwrap();
+        for tag in ["tool:lightroom", "format:nef", "camera:nikon", "format:cr3", "camera:canon", "raw", "photography"] {
+            assert!(tags.contains(&tag.to_string()), "missing tag {}", tag);
+        }
+
+        // The preview inside the .lrdata bundle is not counted.
+        let fields = ProjectInfo::generate_fields(&Inventory::scan(dir_path), &project_type);
+        assert_eq!(fields["photography"]["assets"].as_integer(), Some(4));
+    }
+
+    #[test]
+    fn test_generate_project_type_embedded_platformio() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+
+        let mut ini = File::create(dir_path.join("platformio.ini")).unwrap();
+        writeln!(ini, "[platformio]").unwrap();
+        writeln!(ini, "default_envs = esp32dev").unwrap();
+        writeln!(ini, "[env:esp32dev]").unwrap();
+        writeln!(ini, "platform = espressif32").unwrap();
+        writeln!(ini, "board = esp32dev").unwrap();
+        writeln!(ini, "framework = arduino").unwrap();
+        File::create(dir_path.join("Makefile")).unwrap();
+
+        let project_type = ProjectInfo::generate_project_type(dir_path);
+        assert_eq!(project_type, "embedded");
+
+        let tags = ProjectInfo::generate_tags(&Inventory::scan(dir_path), &project_type, &Config::default()).unwrap();
+        for tag in ["tool:platformio", "board:espressif32", "board:esp32dev", "board:arduino", "firmware"] {
+            assert!(tags.contains(&tag.to_string()), "missing tag {}", tag);
+        }
+
+        let fields = ProjectInfo::generate_fields(&Inventory::scan(dir_path), &project_type);
+        let boards = fields["embedded"]["boards"].as_array().unwrap();
+        assert_eq!(boards[0].as_str(), Some("esp32dev"));
+    }
+
+    #[test]
+    fn test_generate_project_type_hardware_kicad() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+
+        File::create(dir_path.join("board.kicad_pro")).unwrap();
+        File::create(dir_path.join("board.kicad_pcb")).unwrap();
+        File::create(dir_path.join("board.kicad_sch")).unwrap();
+
+        let project_type = ProjectInfo::generate_project_type(dir_path);
+        assert_eq!(project_type, "hardware");
+
+        let tags = ProjectInfo::generate_tags(&Inventory::scan(dir_path), &project_type, &Config::default()).unwrap();
+        for tag in ["tool:kicad", "pcb", "schematic", "hardware"] {
+            assert!(tags.contains(&tag.to_string()), "missing tag {}", tag);
+        }
+    }
+
+    #[test]
+    fn test_project_type_override() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+
+        // A lone audio file is only a guess.
+        File::create(dir_path.join("take.wav")).unwrap();
+        let project = ProjectInfo::generate_project_info(dir_path, &Config::default()).unwrap();
+        assert_eq!(project.project_type, "music");
+        assert!(project.type_uncertain);
+
+        // A recorded correction wins over detection and is kept alongside other settings.
+        fs::write(dir_path.join(".tagger.toml"), "alias = \"demo\"\n").unwrap();
+        overrides::save_project_type(dir_path, "writing").unwrap();
+        let content = fs::read_to_string(dir_path.join(".tagger.toml")).unwrap();
+        assert!(content.contains("alias = \"demo\""));
+
+        let project = ProjectInfo::generate_project_info(dir_path, &Config::default()).unwrap();
+        assert_eq!(project.project_type, "writing");
+        assert!(!project.type_uncertain);
+        assert!(project.tags.contains(&"writing".to_string()));
+    }
+
+    #[test]
+    fn test_local_overrides() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+        File::create(dir_path.join("Cargo.toml")).unwrap();
+        fs::create_dir_all(dir_path.join("Renders")).unwrap();
+        fs::write(dir_path.join("Renders/take.wav"), "1234").unwrap();
+        fs::write(
+            dir_path.join(".tagger.toml"),
+            "[tags]\nadd = [\"client:acme\", \"cli\"]\nremove = [\"cli\", \"lang:\"]\n\n[scan]\nexclude = [\"Renders/**\"]\n",
+        )
+        .unwrap();
+
+        let mut config = Config::default();
+        config.tags.blacklist.push("client:".to_string());
+        let project = ProjectInfo::generate_project_info(dir_path, &config).unwrap();
+        assert_eq!(project.tags, ["cli", "client:acme", "software development"]);
+        assert_eq!(project.file_count, 2);
+    }
+
+    #[test]
+    fn test_infrastructure_tags() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+
+        // A service with a Dockerfile and Kubernetes manifests is still programming.
+        File::create(dir_path.join("Cargo.toml")).unwrap();
+        File::create(dir_path.join("Dockerfile")).unwrap();
+        fs::create_dir(dir_path.join("deploy")).unwrap();
+        fs::write(
+            dir_path.join("deploy/service.yaml"),
+            "apiVersion: v1\nkind: Service\nmetadata:\n  name: app\n",
+        )
+        .unwrap();
+
+        let project_type = ProjectInfo::generate_project_type(dir_path);
+        assert_eq!(project_type, "programming");
+        let tags = ProjectInfo::generate_tags(&Inventory::scan(dir_path), &project_type, &Config::default()).unwrap();
+        for tag in ["tool:docker", "tool:kubernetes", "infrastructure", "lang:rust"] {
+            assert!(tags.contains(&tag.to_string()), "missing tag {}", tag);
+        }
+
+        // A directory of Terraform modules and playbooks is an infra project.
+        let infra = tempdir().unwrap();
+        let infra_path = infra.path();
+        File::create(infra_path.join("main.tf")).unwrap();
+        File::create(infra_path.join("README.md")).unwrap();
+        fs::write(infra_path.join("site.yml"), "- hosts: web\n  tasks: []\n").unwrap();
+
+        let project_type = ProjectInfo::generate_project_type(infra_path);
+        assert_eq!(project_type, "infra");
+        let tags = ProjectInfo::generate_tags(&Inventory::scan(infra_path), &project_type, &Config::default()).unwrap();
+        for tag in ["tool:terraform", "tool:ansible", "infrastructure"] {
+            assert!(tags.contains(&tag.to_string()), "missing tag {}", tag);
+        }
+    }
+
+    #[test]
+    fn test_generate_tags_blacklist() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+        File::create(dir_path.join("Cargo.toml")).unwrap();
+
+        let mut config = Config::default();
+        config.tags.blacklist.push("software development".to_string());
+        config
+            .types
+            .entry("programming".to_string())
+            .or_default()
+            .blacklist
+            .push("CLI".to_string());
+
+        let tags = ProjectInfo::generate_tags(&Inventory::scan(dir_path), "programming", &config).unwrap();
+        assert!(tags.contains(&"lang:rust".to_string()));
+        assert!(!tags.contains(&"software development".to_string()));
+        assert!(!tags.contains(&"cli".to_string()));
+    }
+
+    #[test]
+    fn test_generate_tags_without_default_tags() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+        File::create(dir_path.join("Cargo.toml")).unwrap();
+
+        let mut config = Config::default();
+        config.tags.default_tags = Some(false);
+
+        let tags = ProjectInfo::generate_tags(&Inventory::scan(dir_path), "programming", &config).unwrap();
+        assert_eq!(tags, vec!["lang:rust"]);
+    }
+
+    #[test]
+    fn test_extension_tag_thresholds() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+
+        // Plenty of WAV stems and a single stray MP3 reference.
+        File::create(dir_path.join("mix.rpp")).unwrap();
+        for i in 0..4 {
+            File::create(dir_path.join(format!("stem{}.wav", i))).unwrap();
+        }
+        File::create(dir_path.join("reference.mp3")).unwrap();
+
+        let mut config = Config::default();
+        let tags = ProjectInfo::generate_tags(&Inventory::scan(dir_path), "music", &config).unwrap();
+        assert!(tags.contains(&"format:mp3".to_string()));
+
+        config.tags.min_files = Some(2);
+        let tags = ProjectInfo::generate_tags(&Inventory::scan(dir_path), "music", &config).unwrap();
+        assert!(tags.contains(&"format:wav".to_string()));
+        assert!(tags.contains(&"format:rpp".to_string()));
+        assert!(!tags.contains(&"format:mp3".to_string()));
+    }
+
+    #[test]
+    fn test_subprojects() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+
+        // A Cargo workspace with a glob and an explicit member.
+        fs::write(
+            dir_path.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/*\", \"tools/cli\"]\n",
+        )
+        .unwrap();
+        for member in ["crates/core", "crates/io", "tools/cli"] {
+            fs::create_dir_all(dir_path.join(member)).unwrap();
+            File::create(dir_path.join(member).join("Cargo.toml")).unwrap();
+        }
+
+        let info = ProjectInfo::generate_project_info(dir_path, &Config::default()).unwrap();
+        assert_eq!(info.subprojects, vec!["crates/core", "crates/io", "tools/cli"]);
+
+        // A pnpm workspace.
+        let js = tempdir().unwrap();
+        let js_path = js.path();
+        File::create(js_path.join("package.json")).unwrap();
+        fs::write(js_path.join("pnpm-workspace.yaml"), "packages:\n  - 'apps/*'\n").unwrap();
+        fs::create_dir_all(js_path.join("apps/web")).unwrap();
+        assert_eq!(workspace::find_subprojects(js_path), vec!["apps/web"]);
+
+        // Several nested projects without a workspace manifest.
+        let mono = tempdir().unwrap();
+        let mono_path = mono.path();
+        fs::create_dir_all(mono_path.join("firmware")).unwrap();
+        File::create(mono_path.join("firmware/platformio.ini")).unwrap();
+        fs::create_dir_all(mono_path.join("pcb")).unwrap();
+        File::create(mono_path.join("pcb/board.kicad_pro")).unwrap();
+        assert_eq!(workspace::find_subprojects(mono_path), vec!["firmware", "pcb"]);
+
+        // A single nested project is not a monorepo.
+        fs::remove_dir_all(mono_path.join("pcb")).unwrap();
+        assert!(workspace::find_subprojects(mono_path).is_empty());
+    }
+
+    #[test]
+    fn test_commit_date_as_modified() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+        File::create(dir_path.join("Cargo.toml")).unwrap();
+
+        let repo = git2::Repository::init(dir_path).unwrap();
+        let signature = git2::Signature::new("Test", "test@example.com", &git2::Time::new(1_600_000_000, 0)).unwrap();
+        let tree = repo.find_tree(repo.index().unwrap().write_tree().unwrap()).unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "Initial commit", &tree, &[])
+            .unwrap();
+
+        let info = ProjectInfo::generate_project_info(dir_path, &Config::default()).unwrap();
+        assert_eq!(info.git.as_ref().unwrap().commit_count, 1);
+
+        // Cargo.toml is untracked and the only commit was never pushed.
+        let status = info.git_status.as_ref().unwrap();
+        assert!(status.dirty);
+        assert_eq!(status.unpushed_commits, 1);
+        assert!(info.tags.contains(&"git:dirty".to_string()));
+        assert!(info.tags.contains(&"git:unpushed".to_string()));
+        assert_ne!(info.date_modified.timestamp(), 1_600_000_000);
+
+        let mut config = Config::default();
+        config.git.commit_date_as_modified = true;
+        let info = ProjectInfo::generate_project_info(dir_path, &config).unwrap();
+        assert_eq!(info.date_modified.timestamp(), 1_600_000_000);
+    }
+
+    #[test]
+    fn test_generate_warnings() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+
+        File::create(dir_path.join("Cargo.toml")).unwrap();
+        File::create(dir_path.join(".env")).unwrap();
+        File::create(dir_path.join(".env.example")).unwrap();
+        fs::create_dir(dir_path.join("certs")).unwrap();
+        File::create(dir_path.join("certs/server.pem")).unwrap();
+        git2::Repository::init(dir_path).unwrap();
+
+        let info = ProjectInfo::generate_project_info(dir_path, &Config::default()).unwrap();
+        assert!(info.warnings.contains(&"Possible secret file: .env".to_string()));
+        assert!(info.warnings.contains(&"Possible secret file: certs/server.pem".to_string()));
+        assert!(info.warnings.contains(&"Git repository has no remote.".to_string()));
+        assert_eq!(info.warnings.len(), 3);
+    }
+
+    #[test]
+    fn test_mixed_sample_rates_warning() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+
+        // Minimal WAV headers: the sample rate is stored at byte offset 24.
+        for (name, rate) in [("kick.wav", 44_100u32), ("snare.wav", 48_000u32)] {
+            let mut header = vec![0u8; 44];
+            header[..4].copy_from_slice(b"RIFF");
+            header[8..16].copy_from_slice(b"WAVEfmt ");
+            header[24..28].copy_from_slice(&rate.to_le_bytes());
+            fs::write(dir_path.join(name), header).unwrap();
+        }
+
+        let info = ProjectInfo::generate_project_info(dir_path, &Config::default()).unwrap();
+        assert_eq!(info.project_type, "music");
+        assert_eq!(
+            info.warnings,
+            vec!["Audio files use mixed sample rates: 44100 Hz, 48000 Hz.".to_string()]
+        );
+    }
+
     #[test]
     fn test_generate_tags_unknown() {
         let dir = tempdir().unwrap();
@@ -430,8 +1962,55 @@ mod tests {
         let doc_path = dir_path.join("document.pdf");
         File::create(&doc_path).unwrap();
 
-        let tags = ProjectInfo::generate_tags(dir_path, "unknown").unwrap();
-        assert!(tags.contains(&"IMAGE".to_string()));
-        assert!(tags.contains(&"PDF".to_string()));
+        let tags = ProjectInfo::generate_tags(&Inventory::scan(dir_path), "unknown", &Config::default()).unwrap();
+        assert!(tags.contains(&"format:png".to_string()));
+        assert!(tags.contains(&"format:pdf".to_string()));
+    }
+
+    #[test]
+    fn test_estimate_creation_time() {
+        use chrono::TimeZone;
+
+        let dir = tempdir().unwrap();
+        let file = File::create(dir.path().join("song.rpp")).unwrap();
+        let file_date = Local.with_ymd_and_hms(2021, 3, 1, 12, 0, 0).unwrap();
+        file.set_modified(file_date.into()).unwrap();
+        let inventory = Inventory::scan(dir.path());
+
+        let recorded = Local.with_ymd_and_hms(2022, 1, 1, 0, 0, 0).unwrap();
+        assert_eq!(ProjectInfo::estimate_creation_time(&inventory, None, Some(recorded)), file_date);
+
+        let git = git::GitInfo {
+            first_commit_date: Some(Local.with_ymd_and_hms(2020, 6, 1, 9, 0, 0).unwrap()),
+            ..Default::default()
+        };
+        assert_eq!(
+            ProjectInfo::estimate_creation_time(&inventory, Some(&git), Some(recorded)),
+            git.first_commit_date.unwrap()
+        );
+    }
+
+    #[test]
+    fn test_date_modified_from_newest_file() {
+        use chrono::TimeZone;
+
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("Stems/Drums")).unwrap();
+        let newest = Local.with_ymd_and_hms(2024, 2, 1, 8, 30, 0).unwrap();
+        for (file, date) in [
+            ("song.rpp", Local.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap()),
+            ("Stems/Drums/kick.wav", newest),
+        ] {
+            File::create(dir.path().join(file)).unwrap().set_modified(date.into()).unwrap();
+        }
+        File::create(dir.path().join(PROJECT_INFO_FILE)).unwrap();
+        let config = Config::default();
+
+        let info = ProjectInfo::generate_project_info(dir.path(), &config).unwrap();
+        assert_eq!(info.date_modified, newest);
+
+        let config: Config = toml::from_str("[scan]\nmodified_from_contents = false\n").unwrap();
+        let info = ProjectInfo::generate_project_info(dir.path(), &config).unwrap();
+        assert_ne!(info.date_modified, newest);
     }
 }
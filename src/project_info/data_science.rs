@@ -0,0 +1,180 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use log::debug;
+
+use crate::config::Config;
+
+use super::detector::{Detector, DetectorFields};
+use super::programming::extract_manifest_name;
+use super::inventory::Inventory;
+use super::tags::{self, FORMAT, LANGUAGE, LIBRARY, TOOL};
+
+/// Detector for data-science and machine-learning projects.
+pub struct DataScienceDetector;
+
+impl Detector for DataScienceDetector {
+    fn name(&self) -> &'static str {
+        "data-science"
+    }
+
+    fn handles(&self, project_type: &str) -> bool {
+        project_type == "data-science"
+    }
+
+    fn generate_tags(&self, inventory: &Inventory, _project_type: &str, _config: &Config) -> Vec<String> {
+        generate_data_science_tags(inventory)
+    }
+
+    fn default_tags(&self, _project_type: &str) -> &'static [&'static str] {
+        &["data-science"]
+    }
+
+    fn project_name(&self, inventory: &Inventory) -> Option<String> {
+        extract_manifest_name(inventory.root())
+    }
+
+    fn generate_fields(&self, inventory: &Inventory, _project_type: &str) -> DetectorFields {
+        let mut fields = DetectorFields::new();
+        let notebooks = inventory
+            .entries()
+            .filter(|entry| is_notebook(entry.path()))
+            .count();
+        fields.insert("notebooks".to_string(), toml::Value::Integer(notebooks as i64));
+        fields
+    }
+}
+
+/// Files that mark a project as data science regardless of its other contents.
+pub const DATA_SCIENCE_INDICATORS: &[&str] = &["dvc.yaml", "environment.yml", "environment.yaml"];
+
+/// Tabular and array dataset formats.
+pub const DATASET_EXTENSIONS: &[&str] = &[
+    "csv", "tsv", "parquet", "feather", "arrow", "h5", "hdf5", "npy", "npz",
+];
+
+/// Python libraries worth surfacing as tags, keyed by their package name.
+const LIBRARY_TAGS: &[(&str, &str)] = &[
+    ("pandas", "pandas"),
+    ("numpy", "numpy"),
+    ("polars", "polars"),
+    ("scikit-learn", "scikit-learn"),
+    ("sklearn", "scikit-learn"),
+    ("torch", "pytorch"),
+    ("pytorch", "pytorch"),
+    ("tensorflow", "tensorflow"),
+    ("keras", "keras"),
+    ("jax", "jax"),
+    ("matplotlib", "matplotlib"),
+    ("seaborn", "seaborn"),
+    ("xgboost", "xgboost"),
+    ("transformers", "transformers"),
+];
+
+/// Dependency files scanned for Python libraries.
+const DEPENDENCY_FILES: &[&str] = &[
+    "requirements.txt",
+    "environment.yml",
+    "environment.yaml",
+    "pyproject.toml",
+];
+
+/// Returns `true` if the path is a Jupyter notebook.
+pub fn is_notebook(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("ipynb"))
+}
+
+/// Returns `true` if the path has a dataset file extension.
+pub fn is_dataset(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            DATASET_EXTENSIONS
+                .iter()
+                .any(|data_ext| ext.eq_ignore_ascii_case(data_ext))
+        })
+}
+
+/// Generates tags specific to data-science projects based on the directory contents.
+///
+/// # Arguments
+///
+/// * `inventory` - The project's inventory.
+///
+/// # Returns
+///
+/// A vector of tags relevant to data-science projects.
+pub fn generate_data_science_tags(inventory: &Inventory) -> Vec<String> {
+    let mut tags = Vec::new();
+
+    let mut tag_set = HashSet::new();
+
+    for entry in inventory.entries() {
+        let path = entry.path();
+        let file_name = entry.file_name().to_string_lossy();
+
+        if is_notebook(path) {
+            tag_set.insert(tags::namespaced(TOOL, "jupyter"));
+            tag_set.insert(tags::namespaced(LANGUAGE, "python"));
+        } else if file_name == "dvc.yaml" {
+            tag_set.insert(tags::namespaced(TOOL, "dvc"));
+        } else if file_name.starts_with("environment.y") {
+            tag_set.insert(tags::namespaced(TOOL, "conda"));
+        } else if entry.file_type().is_dir() && file_name == "data" {
+            tag_set.insert("dataset".to_string());
+        } else if is_dataset(path) {
+            tag_set.insert("dataset".to_string());
+            if let Some(ext) = path.extension() {
+                tag_set.insert(tags::namespaced(FORMAT, ext.to_string_lossy().to_lowercase())); // e.g., "format:parquet"
+            }
+        }
+    }
+
+    // Add tags for well-known libraries listed in the dependency files.
+    tag_set.extend(extract_python_library_tags(inventory.root()));
+
+    tags.extend(tag_set);
+
+    debug!("Data-science tags generated: {:?}", tags);
+
+    tags
+}
+
+/// Extracts tags for well-known Python libraries from `requirements.txt`,
+/// `environment.yml`, and `pyproject.toml`.
+///
+/// # Arguments
+///
+/// * `directory` - A reference to the project's directory path.
+///
+/// # Returns
+///
+/// A vector of library tags (e.g. `"lib:pandas"`, `"lib:pytorch"`).
+pub fn extract_python_library_tags(directory: &Path) -> Vec<String> {
+    let mut tags = HashSet::new();
+
+    for file in DEPENDENCY_FILES {
+        let Ok(content) = fs::read_to_string(directory.join(file)) else {
+            continue;
+        };
+
+        for line in content.lines() {
+            // Reduce lines like "  - pandas>=2.0", "torch==2.1", or "\"numpy\"," to the package name.
+            let name: String = line
+                .trim()
+                .trim_start_matches(['-', '"', '\''])
+                .trim()
+                .chars()
+                .take_while(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+                .collect();
+            let name = name.to_lowercase();
+
+            if let Some((_, tag)) = LIBRARY_TAGS.iter().find(|(package, _)| *package == name) {
+                tags.insert(tags::namespaced(LIBRARY, tag));
+            }
+        }
+    }
+
+    tags.into_iter().collect()
+}
@@ -0,0 +1,102 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use log::debug;
+
+use crate::config::Config;
+
+use super::detector::Detector;
+use super::inventory::Inventory;
+use super::tags::{self, FORMAT, TOOL};
+
+/// Detector for design and 3D projects.
+pub struct DesignDetector;
+
+impl Detector for DesignDetector {
+    fn name(&self) -> &'static str {
+        "design"
+    }
+
+    fn handles(&self, project_type: &str) -> bool {
+        matches!(project_type, "design" | "3d")
+    }
+
+    fn generate_tags(&self, inventory: &Inventory, _project_type: &str, _config: &Config) -> Vec<String> {
+        generate_design_tags(inventory)
+    }
+
+    fn default_tags(&self, project_type: &str) -> &'static [&'static str] {
+        if project_type == "3d" {
+            &["3d", "modeling"]
+        } else {
+            &["design", "graphics"]
+        }
+    }
+}
+
+/// Native file formats of common design and 3D tools.
+///
+/// Each entry is `(tool name, file extension, project type)`, where the project type
+/// is either `"design"` for 2D graphics tools or `"3d"` for modelling/animation suites.
+pub const DESIGN_FORMATS: &[(&str, &str, &str)] = &[
+    ("Photoshop", "psd", "design"),
+    ("Illustrator", "ai", "design"),
+    ("Figma", "fig", "design"),
+    ("Sketch", "sketch", "design"),
+    ("Adobe XD", "xd", "design"),
+    ("Affinity Designer", "afdesign", "design"),
+    ("Blender", "blend", "3d"),
+    ("Maya", "ma", "3d"),
+    ("Maya", "mb", "3d"),
+    ("Cinema 4D", "c4d", "3d"),
+];
+
+/// Returns the design tool format matching the given path, if any.
+///
+/// # Arguments
+///
+/// * `path` - A reference to the file to check.
+///
+/// # Returns
+///
+/// The `(tool name, file extension, project type)` triple, or `None` if the path
+/// is not a known design file.
+pub fn design_file_format(path: &Path) -> Option<(&'static str, &'static str, &'static str)> {
+    let extension = path.extension()?.to_str()?;
+
+    DESIGN_FORMATS
+        .iter()
+        .find(|(_, ext, _)| extension.eq_ignore_ascii_case(ext))
+        .copied()
+}
+
+/// Generates tags specific to design and 3D projects based on the directory contents.
+///
+/// # Arguments
+///
+/// * `inventory` - The project's inventory.
+///
+/// # Returns
+///
+/// A vector of tags relevant to design projects.
+pub fn generate_design_tags(inventory: &Inventory) -> Vec<String> {
+    let mut tags = Vec::new();
+
+    let mut format_set = HashSet::new();
+    let mut tool_set = HashSet::new();
+
+    for entry in inventory.entries() {
+        if let Some((tool, ext, _)) = design_file_format(entry.path()) {
+            tool_set.insert(tags::namespaced(TOOL, tool)); // e.g., "tool:Blender"
+            format_set.insert(tags::namespaced(FORMAT, ext.to_uppercase())); // e.g., "format:BLEND"
+        }
+    }
+
+    // Add detected formats and tools as tags.
+    tags.extend(format_set);
+    tags.extend(tool_set);
+
+    debug!("Design tags generated: {:?}", tags);
+
+    tags
+}
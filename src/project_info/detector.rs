@@ -0,0 +1,85 @@
+use crate::config::Config;
+
+use super::data_science::DataScienceDetector;
+use super::design::DesignDetector;
+use super::embedded::EmbeddedDetector;
+use super::infrastructure::InfrastructureDetector;
+use super::inventory::Inventory;
+use super::music::MusicDetector;
+use super::photography::PhotographyDetector;
+use super::programming::ProgrammingDetector;
+use super::video::VideoDetector;
+use super::writing::WritingDetector;
+
+/// Extra, typed fields contributed by a detector.
+///
+/// Fields are stored in `project_info.toml` under a table named after the detector
+/// (e.g. `[fields.music]`), which keeps the core schema free of domain-specific data.
+pub type DetectorFields = toml::Table;
+
+/// A detector generates tags and extra fields for one family of project types.
+///
+/// Detectors read the project from its [`Inventory`] rather than walking the
+/// directory again.
+pub trait Detector {
+    /// The detector's name, used as the namespace for its fields.
+    fn name(&self) -> &'static str;
+
+    /// Returns `true` if this detector handles the given project type.
+    fn handles(&self, project_type: &str) -> bool;
+
+    /// Generates tags for a project of the given type.
+    fn generate_tags(&self, inventory: &Inventory, project_type: &str, _config: &Config) -> Vec<String>;
+
+    /// Returns the general tags every project of the given type gets, whatever its
+    /// contents (e.g. `audio` and `production` for music).
+    ///
+    /// They are added unless the user turns default tags off. The default has none.
+    fn default_tags(&self, _project_type: &str) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Returns the project's own name as recorded in its manifest or session files.
+    ///
+    /// The default finds no name, so the directory name is used instead.
+    fn project_name(&self, _inventory: &Inventory) -> Option<String> {
+        None
+    }
+
+    /// Generates typed extra fields for a project of the given type.
+    ///
+    /// Detectors without domain-specific data keep the default, which emits no fields.
+    fn generate_fields(&self, _inventory: &Inventory, _project_type: &str) -> DetectorFields {
+        DetectorFields::new()
+    }
+
+    /// Reports domain-specific problems with a project of the given type, such as
+    /// audio files recorded at different sample rates.
+    ///
+    /// The default reports nothing.
+    fn generate_warnings(&self, _inventory: &Inventory, _project_type: &str) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// Returns every built-in detector.
+pub fn detectors() -> Vec<Box<dyn Detector>> {
+    vec![
+        Box::new(ProgrammingDetector),
+        Box::new(MusicDetector),
+        Box::new(VideoDetector),
+        Box::new(DesignDetector),
+        Box::new(WritingDetector),
+        Box::new(DataScienceDetector),
+        Box::new(PhotographyDetector),
+        Box::new(EmbeddedDetector),
+        Box::new(InfrastructureDetector),
+    ]
+}
+
+/// Returns the detector responsible for the given project type, if any.
+pub fn detector_for(project_type: &str) -> Option<Box<dyn Detector>> {
+    detectors()
+        .into_iter()
+        .find(|detector| detector.handles(project_type))
+}
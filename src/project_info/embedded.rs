@@ -0,0 +1,187 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use log::debug;
+
+use crate::config::Config;
+
+use super::detector::{Detector, DetectorFields};
+use super::inventory::Inventory;
+use super::tags::{self, BOARD, TOOL};
+
+/// Detector for embedded firmware and electronics hardware projects.
+pub struct EmbeddedDetector;
+
+impl Detector for EmbeddedDetector {
+    fn name(&self) -> &'static str {
+        "embedded"
+    }
+
+    fn handles(&self, project_type: &str) -> bool {
+        matches!(project_type, "embedded" | "hardware")
+    }
+
+    fn generate_tags(&self, inventory: &Inventory, _project_type: &str, _config: &Config) -> Vec<String> {
+        generate_embedded_tags(inventory)
+    }
+
+    fn default_tags(&self, project_type: &str) -> &'static [&'static str] {
+        if project_type == "hardware" {
+            &["hardware", "electronics"]
+        } else {
+            &["embedded", "firmware"]
+        }
+    }
+
+    fn generate_fields(&self, inventory: &Inventory, _project_type: &str) -> DetectorFields {
+        let mut fields = DetectorFields::new();
+
+        let boards = extract_boards(inventory.root());
+        if !boards.is_empty() {
+            let boards = boards.into_iter().map(toml::Value::String).collect();
+            fields.insert("boards".to_string(), toml::Value::Array(boards));
+        }
+
+        fields
+    }
+}
+
+/// Files that identify a firmware project: PlatformIO and Zephyr (west) manifests,
+/// and Zephyr's Kconfig fragment.
+pub const EMBEDDED_INDICATORS: &[&str] = &["platformio.ini", "west.yml", "prj.conf"];
+
+/// Returns `true` if the path is a KiCad project file.
+pub fn is_kicad_project(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("kicad_pro"))
+}
+
+/// Returns `true` if the path is an Arduino sketch.
+pub fn is_arduino_sketch(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("ino"))
+}
+
+/// Generates tags specific to embedded and hardware projects, including the boards,
+/// platforms, and frameworks declared in their manifests.
+///
+/// # Arguments
+///
+/// * `inventory` - The project's inventory.
+///
+/// # Returns
+///
+/// A vector of tags relevant to embedded projects.
+pub fn generate_embedded_tags(inventory: &Inventory) -> Vec<String> {
+    let mut tags = Vec::new();
+
+    let mut tag_set = HashSet::new();
+
+    for entry in inventory.entries() {
+        let path = entry.path();
+        let file_name = entry.file_name().to_string_lossy();
+
+        if file_name == "platformio.ini" {
+            tag_set.insert(tags::namespaced(TOOL, "platformio"));
+            for (key, value) in read_platformio_settings(path) {
+                if matches!(key.as_str(), "board" | "platform" | "framework") {
+                    tag_set.insert(tags::namespaced(BOARD, value));
+                }
+            }
+        } else if file_name == "west.yml" || file_name == "prj.conf" {
+            tag_set.insert(tags::namespaced(TOOL, "zephyr"));
+        } else if is_arduino_sketch(path) {
+            tag_set.insert(tags::namespaced(TOOL, "arduino"));
+        } else if is_kicad_project(path) {
+            tag_set.insert(tags::namespaced(TOOL, "kicad"));
+        } else if path.extension().is_some_and(|ext| ext == "kicad_pcb") {
+            tag_set.insert("pcb".to_string());
+        } else if path.extension().is_some_and(|ext| ext == "kicad_sch") {
+            tag_set.insert("schematic".to_string());
+        }
+    }
+
+    tag_set.extend(extract_boards(inventory.root()).into_iter().map(|board| tags::namespaced(BOARD, board)));
+
+    tags.extend(tag_set);
+
+    debug!("Embedded tags generated: {:?}", tags);
+
+    tags
+}
+
+/// Extracts the target boards declared by the project's manifests.
+///
+/// Boards come from `board = ...` in `platformio.ini`, `set(BOARD ...)` in a Zephyr
+/// `CMakeLists.txt`, and `default_fqbn: ...` in an Arduino `sketch.yaml`.
+///
+/// # Arguments
+///
+/// * `directory` - A reference to the project's directory path.
+///
+/// # Returns
+///
+/// The sorted, deduplicated board names.
+pub fn extract_boards(directory: &Path) -> Vec<String> {
+    let mut boards = HashSet::new();
+
+    for (key, value) in read_platformio_settings(&directory.join("platformio.ini")) {
+        if key == "board" {
+            boards.insert(value);
+        }
+    }
+
+    if let Ok(content) = fs::read_to_string(directory.join("CMakeLists.txt")) {
+        for line in content.lines() {
+            if let Some(rest) = line.trim().strip_prefix("set(BOARD") {
+                let board = rest.trim().trim_end_matches(')').trim().trim_matches('"');
+                if !board.is_empty() {
+                    boards.insert(board.to_string());
+                }
+            }
+        }
+    }
+
+    if let Ok(content) = fs::read_to_string(directory.join("sketch.yaml")) {
+        for line in content.lines() {
+            if let Some(fqbn) = line.trim().strip_prefix("default_fqbn:") {
+                // A fully qualified board name looks like "arduino:avr:uno".
+                if let Some(board) = fqbn.trim().rsplit(':').next().filter(|b| !b.is_empty()) {
+                    boards.insert(board.to_string());
+                }
+            }
+        }
+    }
+
+    let mut boards: Vec<String> = boards.into_iter().collect();
+    boards.sort();
+    boards
+}
+
+/// Reads the `key = value` settings of every `[env:...]` section in a `platformio.ini`.
+fn read_platformio_settings(path: &Path) -> Vec<(String, String)> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+
+    let mut settings = Vec::new();
+    let mut in_env = false;
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_env = line.starts_with("[env:");
+            continue;
+        }
+        if !in_env || line.starts_with(';') || line.starts_with('#') {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            let value = value.trim();
+            if !value.is_empty() {
+                settings.push((key.trim().to_string(), value.to_string()));
+            }
+        }
+    }
+
+    settings
+}
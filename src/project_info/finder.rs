@@ -0,0 +1,206 @@
+//! macOS Finder tags.
+//!
+//! Finder keeps a file's tags in the `com.apple.metadata:_kMDItemUserTags` extended
+//! attribute, as a property list holding an array of strings. A tag with a color
+//! carries its color index after a newline (`Red\n6`).
+
+use std::io;
+use std::path::Path;
+
+use log::{info, warn};
+
+/// The extended attribute holding a file's Finder tags.
+pub const FINDER_TAGS_ATTRIBUTE: &str = "com.apple.metadata:_kMDItemUserTags";
+
+/// Reads the names of a directory's Finder tags, without their colors.
+///
+/// Returns no tags outside macOS or when the directory has none.
+pub fn read_tags(directory: &Path) -> Vec<String> {
+    match read_entries(directory) {
+        Ok(entries) => entries.iter().map(|entry| tag_name(entry).to_string()).collect(),
+        Err(e) => {
+            warn!("Unable to read the Finder tags of {}: {}", directory.display(), e);
+            Vec::new()
+        }
+    }
+}
+
+/// Adds tags to a directory's Finder tags, keeping the tags and colors it already has.
+///
+/// Does nothing outside macOS.
+pub fn write_tags(directory: &Path, tags: &[String]) -> io::Result<()> {
+    if !cfg!(target_os = "macos") {
+        return Ok(());
+    }
+
+    let mut entries = read_entries(directory)?;
+    let before = entries.len();
+    for tag in tags {
+        if !entries.iter().any(|entry| tag_name(entry) == tag) {
+            entries.push(tag.clone());
+        }
+    }
+    if entries.len() == before {
+        return Ok(());
+    }
+
+    xattr::set(directory, FINDER_TAGS_ATTRIBUTE, encode_plist(&entries).as_bytes())?;
+    info!("Finder tags of {} set to {:?}", directory.display(), entries);
+    Ok(())
+}
+
+/// Reads a directory's raw Finder tag entries, colors included.
+fn read_entries(directory: &Path) -> io::Result<Vec<String>> {
+    if !cfg!(target_os = "macos") {
+        return Ok(Vec::new());
+    }
+
+    match xattr::get(directory, FINDER_TAGS_ATTRIBUTE)? {
+        Some(data) => decode_plist(&data).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidData, "unreadable Finder tags property list")
+        }),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Returns the name of a Finder tag entry, without its color.
+fn tag_name(entry: &str) -> &str {
+    entry.split_once('\n').map_or(entry, |(name, _)| name)
+}
+
+/// Encodes strings as an XML property list array, which Finder reads as well as the
+/// binary lists it writes itself.
+fn encode_plist(entries: &[String]) -> String {
+    let mut plist = String::from(concat!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+        "<!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n",
+        "<plist version=\"1.0\">\n<array>\n",
+    ));
+    for entry in entries {
+        let escaped = entry.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+        plist.push_str(&format!("\t<string>{}</string>\n", escaped));
+    }
+    plist.push_str("</array>\n</plist>\n");
+    plist
+}
+
+/// Decodes an array of strings from a binary or XML property list.
+fn decode_plist(data: &[u8]) -> Option<Vec<String>> {
+    if data.starts_with(b"bplist00") {
+        return decode_binary_plist(data);
+    }
+
+    let xml = std::str::from_utf8(data).ok()?;
+    let entries = xml
+        .split("<string>")
+        .skip(1)
+        .filter_map(|rest| rest.split_once("</string>"))
+        .map(|(text, _)| {
+            text.replace("&lt;", "<")
+                .replace("&gt;", ">")
+                .replace("&quot;", "\"")
+                .replace("&apos;", "'")
+                .replace("&amp;", "&")
+        })
+        .collect();
+    Some(entries)
+}
+
+/// Decodes the top-level array of strings of a binary property list.
+///
+/// The list ends with a 32-byte trailer giving the size of offsets and object
+/// references, the object count, the top object, and where the offset table starts.
+fn decode_binary_plist(data: &[u8]) -> Option<Vec<String>> {
+    let trailer = data.get(data.len().checked_sub(32)?..)?;
+    let offset_size = usize::from(trailer[6]);
+    let reference_size = usize::from(trailer[7]);
+    let object_count = read_uint(&trailer[8..16])?;
+    let top_object = read_uint(&trailer[16..24])?;
+    let offset_table = read_uint(&trailer[24..32])?;
+
+    let object_offset = |index: usize| {
+        if index >= object_count {
+            return None;
+        }
+        let start = offset_table.checked_add(index.checked_mul(offset_size)?)?;
+        read_uint(data.get(start..start.checked_add(offset_size)?)?)
+    };
+
+    let array = object_offset(top_object)?;
+    let (marker, count, start) = read_marker(data, array)?;
+    if marker != 0xA {
+        return None;
+    }
+
+    (0..count)
+        .map(|index| {
+            let reference_start = start.checked_add(index.checked_mul(reference_size)?)?;
+            let reference = read_uint(data.get(reference_start..reference_start.checked_add(reference_size)?)?)?;
+            let (marker, length, start) = read_marker(data, object_offset(reference)?)?;
+            match marker {
+                // ASCII.
+                0x5 => Some(String::from_utf8_lossy(data.get(start..start.checked_add(length)?)?).into_owned()),
+                // UTF-16, big-endian.
+                0x6 => {
+                    let bytes = data.get(start..start.checked_add(length.checked_mul(2)?)?)?;
+                    let units: Vec<u16> = bytes.chunks_exact(2).map(|pair| u16::from_be_bytes([pair[0], pair[1]])).collect();
+                    Some(String::from_utf16_lossy(&units))
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Reads an object's type and length, returning where its contents start. A length
+/// of 15 means the real length follows as an integer object.
+fn read_marker(data: &[u8], offset: usize) -> Option<(u8, usize, usize)> {
+    let byte = *data.get(offset)?;
+    let (marker, length) = (byte >> 4, usize::from(byte & 0xF));
+    if length != 0xF {
+        return Some((marker, length, offset + 1));
+    }
+
+    let int_marker = *data.get(offset + 1)?;
+    if int_marker >> 4 != 0x1 {
+        return None;
+    }
+    let size = 1usize.checked_shl(u32::from(int_marker & 0xF))?;
+    let length = read_uint(data.get(offset + 2..offset + 2 + size)?)?;
+    Some((marker, length, offset + 2 + size))
+}
+
+/// Reads a big-endian unsigned integer of up to eight bytes.
+fn read_uint(bytes: &[u8]) -> Option<usize> {
+    if bytes.len() > 8 {
+        return None;
+    }
+    let value = bytes.iter().fold(0u64, |value, byte| (value << 8) | u64::from(*byte));
+    usize::try_from(value).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_finder_tags() {
+        // A binary list as Finder writes it: ["Red\n6", "lang:rust"].
+        let mut bplist = b"bplist00".to_vec();
+        bplist.extend([0xA2, 1, 2]);
+        bplist.extend(b"\x55Red\n6");
+        bplist.extend(b"\x59lang:rust");
+        bplist.extend([8, 11, 17]);
+        bplist.extend([0, 0, 0, 0, 0, 0, 1, 1]);
+        bplist.extend(3u64.to_be_bytes());
+        bplist.extend(0u64.to_be_bytes());
+        bplist.extend(27u64.to_be_bytes());
+
+        let entries = decode_plist(&bplist).unwrap();
+        assert_eq!(entries, vec!["Red\n6", "lang:rust"]);
+        assert_eq!(tag_name(&entries[0]), "Red");
+
+        let entries = vec!["Red\n6".to_string(), "rock & roll".to_string()];
+        assert_eq!(decode_plist(encode_plist(&entries).as_bytes()), Some(entries));
+    }
+}
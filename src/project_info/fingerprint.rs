@@ -0,0 +1,141 @@
+//! Content fingerprints, for telling whether a project changed since it was tagged.
+
+use std::io;
+use std::path::Path;
+
+use chrono::{DateTime, Local};
+use log::info;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use walkdir::WalkDir;
+
+use crate::archive::hash_file;
+use crate::config::ScanConfig;
+
+use super::{overrides, relative_display, vcs};
+
+/// A fingerprint of a project's files.
+///
+/// It is the SHA-256 checksum of a manifest with one line per file, sorted by path,
+/// holding the file's relative path and size and, for content fingerprints, the
+/// checksum of the file itself. Renaming, adding, removing, or resizing a file changes
+/// it; only a content fingerprint also catches bit rot that keeps the size.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fingerprint {
+    /// The checksum of the manifest, in hex.
+    pub sha256: String,
+    /// Whether the manifest includes the checksum of every file.
+    #[serde(default)]
+    pub contents: bool,
+    /// The number of files in the manifest.
+    pub file_count: usize,
+    /// The total size of the files in the manifest, in bytes.
+    pub total_size_bytes: u64,
+    /// When the fingerprint was taken.
+    pub date: DateTime<Local>,
+}
+
+impl Fingerprint {
+    /// Fingerprints every regular file of a project directory.
+    ///
+    /// The `.git` directory, the project's own information file (`info_file`), its
+    /// `.tagger.toml`, and files the `[scan]` settings exclude are left out, since
+    /// they change without the project's contents changing. Symbolic links are not
+    /// followed.
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - A reference to the project's directory path.
+    /// * `contents` - Whether to read and hash every file, rather than only list it.
+    /// * `scan` - The `[scan]` settings.
+    /// * `info_file` - Where the project's information is saved.
+    pub fn compute(directory: &Path, contents: bool, scan: &ScanConfig, info_file: &Path) -> io::Result<Self> {
+        let mut manifest = Sha256::new();
+        let mut file_count = 0;
+        let mut total_size_bytes = 0;
+
+        let walker = WalkDir::new(directory)
+            .min_depth(1)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|entry| {
+                let relative = entry.path().strip_prefix(directory).unwrap_or(entry.path());
+                let is_dir = entry.file_type().is_dir();
+                let is_history = is_dir && vcs::is_metadata_directory(&entry.file_name().to_string_lossy());
+                !is_history && !scan.excludes(relative, is_dir)
+            });
+        for entry in walker {
+            let entry = entry?;
+            let relative = entry.path().strip_prefix(directory).unwrap_or(entry.path());
+            if !entry.file_type().is_file()
+                || entry.path() == info_file
+                || relative == Path::new(overrides::OVERRIDES_FILE)
+            {
+                continue;
+            }
+
+            let size = entry.metadata()?.len();
+            let mut line = format!("{}\t{}", relative_display(directory, entry.path()), size);
+            if contents {
+                line.push('\t');
+                line.push_str(&hash_file(entry.path())?.0);
+            }
+            line.push('\n');
+            manifest.update(line.as_bytes());
+            file_count += 1;
+            total_size_bytes += size;
+        }
+
+        let fingerprint = Fingerprint {
+            sha256: format!("{:x}", manifest.finalize()),
+            contents,
+            file_count,
+            total_size_bytes,
+            date: Local::now(),
+        };
+        info!("Fingerprint of {}: {}", directory.display(), fingerprint.sha256);
+        Ok(fingerprint)
+    }
+
+    /// Returns `true` if both fingerprints describe the same files.
+    pub fn matches(&self, other: &Fingerprint) -> bool {
+        self.sha256 == other.sha256 && self.contents == other.contents
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_fingerprint_changes_with_files() {
+        let dir = tempdir().unwrap();
+        let info_file = dir.path().join("project_info.toml");
+        fs::create_dir_all(dir.path().join(".git")).unwrap();
+        fs::write(dir.path().join("song.rpp"), "<REAPER_PROJECT").unwrap();
+        fs::write(dir.path().join("kick.wav"), "1234").unwrap();
+        let scan = ScanConfig::default();
+        let fingerprint = |contents| Fingerprint::compute(dir.path(), contents, &scan, &info_file).unwrap();
+
+        let listing = fingerprint(false);
+        let content = fingerprint(true);
+        assert_eq!(listing.file_count, 2);
+        assert_eq!(listing.total_size_bytes, 19);
+        assert!(!listing.matches(&content));
+
+        // Saving the information or touching git does not count as a change.
+        fs::write(&info_file, "name = \"song\"").unwrap();
+        fs::write(dir.path().join(".git/HEAD"), "ref").unwrap();
+        assert!(fingerprint(false).matches(&listing));
+
+        // A same-sized rewrite is only caught by hashing the contents.
+        fs::write(dir.path().join("kick.wav"), "4321").unwrap();
+        assert!(fingerprint(false).matches(&listing));
+        assert!(!fingerprint(true).matches(&content));
+
+        fs::write(dir.path().join("snare.wav"), "").unwrap();
+        assert!(!fingerprint(false).matches(&listing));
+    }
+}
@@ -0,0 +1,742 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use chrono::{DateTime, Local, TimeZone};
+use git2::Repository;
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::config::GitConfig;
+
+/// The number of contributors kept in [`GitInfo::contributors`], unless
+/// `max_contributors` is set under `[git]`.
+const TOP_CONTRIBUTORS: usize = 5;
+
+/// Git hosting services recognized by their domain, with their `host:` tag values.
+/// Repositories on any other server are tagged `host:self-hosted`.
+const HOSTING_SERVICES: &[(&str, &str)] = &[
+    ("github.com", "github"),
+    ("gitlab.com", "gitlab"),
+    ("bitbucket.org", "bitbucket"),
+];
+
+/// Metadata and history read from a project's git repository.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GitInfo {
+    /// The branch checked out, or `None` for a detached HEAD.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// The repository's default branch, as advertised by `origin` or, failing that,
+    /// a local `main` or `master` branch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_branch: Option<String>,
+    /// The hash of the commit at HEAD.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_commit: Option<String>,
+    /// When the commit at HEAD was made.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_commit_date: Option<DateTime<Local>>,
+    /// When the oldest commit reachable from HEAD was made.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_commit_date: Option<DateTime<Local>>,
+    /// The number of commits reachable from HEAD.
+    #[serde(default)]
+    pub commit_count: usize,
+    /// The authors with the most commits, most active first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub contributors: Vec<Contributor>,
+}
+
+/// A commit author and the number of commits they made.
+///
+/// Commits are attributed by email, so an author who changed how they spell their
+/// name is counted once, under the name of their latest commit.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Contributor {
+    /// The author's name.
+    pub name: String,
+    /// The author's email address.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    /// The number of commits authored.
+    pub commits: usize,
+}
+
+/// A repository checked out inside the project as a git submodule.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Submodule {
+    /// Where the submodule is checked out, relative to the project directory.
+    pub path: String,
+    /// The URL the submodule is cloned from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+/// Extracts every remote of the repository, mapping its name to its push URL (or
+/// fetch URL, when no push URL is set).
+///
+/// The repository is read in-process; `git remote -v` is only run when the
+/// repository cannot be opened that way (e.g. it uses an unsupported extension).
+///
+/// # Arguments
+///
+/// * `directory` - A reference to the project's directory path.
+///
+/// # Returns
+///
+/// The remotes by name, empty if the directory is not a git repository or has no remotes.
+pub fn extract_git_remotes(directory: &Path) -> BTreeMap<String, String> {
+    if !directory.join(".git").exists() {
+        return BTreeMap::new();
+    }
+
+    let remotes = match Repository::open(directory) {
+        Ok(repo) => remotes_in_process(&repo),
+        Err(e) => {
+            warn!("Unable to open git repository, falling back to git: {}", e);
+            remotes_from_cli(directory)
+        }
+    };
+
+    debug!("Git remotes: {:?}", remotes);
+    remotes
+}
+
+/// Picks the URL recorded as the project's `git_url`.
+///
+/// The `preferred` remote wins when it exists; otherwise `origin` is used, then
+/// Mercurial's and Fossil's `default`, and failing that the first remote by name.
+pub fn select_git_url(remotes: &BTreeMap<String, String>, preferred: Option<&str>) -> Option<String> {
+    if let Some(preferred) = preferred {
+        match remotes.get(preferred) {
+            Some(url) => return Some(url.clone()),
+            None => warn!("Remote '{}' not found; using the default remote.", preferred),
+        }
+    }
+
+    remotes
+        .get("origin")
+        .or_else(|| remotes.get("default"))
+        .or_else(|| remotes.values().next())
+        .cloned()
+}
+
+/// Converts a remote URL into the address of the repository's web page, so the SSH
+/// and HTTPS forms of a remote agree and the result opens in a browser:
+/// `git@github.com:user/repo.git` and `https://github.com/user/repo` both become
+/// `https://github.com/user/repo`.
+///
+/// Credentials, SSH ports, and the `.git` suffix are dropped.
+///
+/// # Returns
+///
+/// The web URL, or `None` for remotes on this machine, such as `/srv/git/repo.git`
+/// or `file://` URLs.
+pub fn web_url(url: &str) -> Option<String> {
+    let url = url.trim();
+    let (scheme, rest) = match url.split_once("://") {
+        Some((scheme, rest)) => (scheme.to_lowercase(), rest.to_string()),
+        // The scp-like `user@host:path`; a single letter before the colon is a
+        // Windows drive.
+        None => {
+            let (host, path) = url.split_once(':')?;
+            if host.len() < 2 || host.contains(['/', '\\']) {
+                return None;
+            }
+            ("ssh".to_string(), format!("{}/{}", host, path.trim_start_matches('/')))
+        }
+    };
+    if scheme == "file" {
+        return None;
+    }
+
+    let (authority, path) = rest.split_once('/')?;
+    let host = authority.rsplit_once('@').map_or(authority, |(_, host)| host).to_lowercase();
+    let web_scheme = if scheme == "http" { "http" } else { "https" };
+    // An SSH or git port says nothing about where the web page is served.
+    let host = match host.split_once(':') {
+        Some((name, _)) if web_scheme == "https" && scheme != "https" => name.to_string(),
+        _ => host,
+    };
+    let path = path.trim_end_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path).trim_end_matches('/');
+    if host.is_empty() || path.is_empty() {
+        return None;
+    }
+    Some(format!("{}://{}/{}", web_scheme, host, path))
+}
+
+/// Returns where a repository is hosted, given its web URL (see [`web_url`]):
+/// `github`, `gitlab`, or `bitbucket` for those services' sites, and `self-hosted`
+/// for any other server.
+pub fn hosting(web_url: &str) -> Option<&'static str> {
+    let host = web_url.split_once("://")?.1.split(['/', ':']).next()?;
+    let host = host.strip_prefix("www.").unwrap_or(host);
+    Some(
+        HOSTING_SERVICES
+            .iter()
+            .find(|(domain, _)| host == *domain)
+            .map_or("self-hosted", |(_, service)| *service),
+    )
+}
+
+/// Extracts the current and default branches, the last commit, the commit count,
+/// and the top contributors.
+///
+/// Like [`extract_git_remotes`], this reads the repository in-process and only
+/// runs `git` as a fallback.
+///
+/// # Arguments
+///
+/// * `directory` - A reference to the project's directory path.
+/// * `config` - The `[git]` settings: how many contributors to keep, and whether
+///   the repository's mailmap applies.
+///
+/// # Returns
+///
+/// The repository metadata, or `None` if the directory is not a git repository.
+pub fn extract_git_info(directory: &Path, config: &GitConfig) -> Option<GitInfo> {
+    if !directory.join(".git").exists() {
+        return None;
+    }
+
+    let git_info = match Repository::open(directory) {
+        Ok(repo) => {
+            let (commit_count, first_commit_date, contributors) = history(&repo, config);
+            GitInfo {
+                branch: current_branch(&repo),
+                default_branch: default_branch(&repo),
+                last_commit: repo
+                    .head()
+                    .ok()
+                    .and_then(|head| head.target())
+                    .map(|oid| oid.to_string()),
+                last_commit_date: last_commit_date(&repo),
+                first_commit_date,
+                commit_count,
+                contributors,
+            }
+        }
+        Err(e) => {
+            warn!("Unable to open git repository, falling back to git: {}", e);
+            git_info_from_cli(directory, config)
+        }
+    };
+
+    debug!("Git info: {:?}", git_info);
+    Some(git_info)
+}
+
+/// Lists the submodules declared in the project's `.gitmodules`, in the order they
+/// are declared.
+///
+/// # Returns
+///
+/// The submodules, empty if the file is missing or declares none.
+pub fn extract_submodules(directory: &Path) -> Vec<Submodule> {
+    let Ok(gitmodules) = fs::read_to_string(directory.join(".gitmodules")) else {
+        return Vec::new();
+    };
+
+    // Sections look like `[submodule "ffmpeg"]`, followed by `path = ...` and `url = ...`.
+    let mut submodules: Vec<Submodule> = Vec::new();
+    let mut in_submodule = false;
+    for line in gitmodules.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_submodule = line.starts_with("[submodule");
+            if in_submodule {
+                submodules.push(Submodule::default());
+            }
+            continue;
+        }
+        let submodule = submodules.last_mut().filter(|_| in_submodule);
+        let (Some(submodule), Some((key, value))) = (submodule, line.split_once('=')) else {
+            continue;
+        };
+        let value = value.trim().trim_matches('"');
+        match key.trim() {
+            "path" => submodule.path = value.trim_end_matches('/').to_string(),
+            "url" => submodule.url = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    submodules.retain(|submodule| !submodule.path.is_empty());
+    debug!("Git submodules: {:?}", submodules);
+    submodules
+}
+
+/// Returns the most recent tag reachable from HEAD, as a version.
+///
+/// A leading `v` is dropped from tags like `v1.2.0`. Like [`extract_git_remotes`],
+/// this reads the repository in-process and only runs `git` as a fallback.
+///
+/// # Arguments
+///
+/// * `directory` - A reference to the project's directory path.
+///
+/// # Returns
+///
+/// The version, or `None` if the directory is not a git repository or has no tags.
+pub fn latest_tag(directory: &Path) -> Option<String> {
+    if !directory.join(".git").exists() {
+        return None;
+    }
+
+    let tag = match Repository::open(directory) {
+        Ok(repo) => {
+            let mut options = git2::DescribeOptions::new();
+            options.describe_tags();
+            let describe = repo.describe(&options).ok()?;
+            describe
+                .format(Some(git2::DescribeFormatOptions::new().abbreviated_size(0)))
+                .ok()?
+        }
+        Err(e) => {
+            warn!("Unable to open git repository, falling back to git: {}", e);
+            run_git(directory, &["describe", "--tags", "--abbrev=0"])?
+        }
+    };
+
+    let version = match tag.strip_prefix('v') {
+        Some(version) if version.starts_with(|c: char| c.is_ascii_digit()) => version.to_string(),
+        _ => tag,
+    };
+    info!("Latest git tag version: {}", version);
+    Some(version)
+}
+
+/// Work in a repository that has not been committed or pushed yet.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GitStatus {
+    /// Whether there are uncommitted or untracked changes, not counting the files
+    /// tagger writes itself.
+    pub dirty: bool,
+    /// The number of commits reachable from HEAD that are on no remote. Without any
+    /// remote, every commit counts as unpushed.
+    pub unpushed_commits: usize,
+}
+
+/// The commit checked out in a repository and the state of the work on top of it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeadState {
+    /// The full hash of the commit at HEAD.
+    pub commit: String,
+    /// Uncommitted and unpushed work.
+    pub status: GitStatus,
+}
+
+/// Files written by tagger, which do not make a working tree dirty.
+const TAGGER_FILES: &[&str] = &["project_info.toml", ".tagger.toml"];
+
+/// Reads the HEAD commit, dirty status, and unpushed commit count of a repository.
+///
+/// # Arguments
+///
+/// * `directory` - A reference to the project's directory path.
+///
+/// # Returns
+///
+/// The HEAD state, or `None` if the directory is not a git repository or has no commits.
+pub fn head_state(directory: &Path) -> Option<HeadState> {
+    if !directory.join(".git").exists() {
+        return None;
+    }
+
+    match Repository::open(directory) {
+        Ok(repo) => {
+            let commit = repo.head().ok()?.peel_to_commit().ok()?.id().to_string();
+            let mut options = git2::StatusOptions::new();
+            options.include_untracked(true).include_ignored(false);
+            let dirty = repo
+                .statuses(Some(&mut options))
+                .ok()?
+                .iter()
+                .any(|entry| !entry.path().is_some_and(|path| TAGGER_FILES.contains(&path)));
+            let unpushed_commits = count_unpushed(&repo).unwrap_or_default();
+            Some(HeadState {
+                commit,
+                status: GitStatus { dirty, unpushed_commits },
+            })
+        }
+        Err(e) => {
+            warn!("Unable to open git repository, falling back to git: {}", e);
+            let commit = run_git(directory, &["rev-parse", "HEAD"])?;
+            // Lines look like: "?? project_info.toml".
+            let dirty = run_git(directory, &["status", "--porcelain"]).is_some_and(|status| {
+                status
+                    .lines()
+                    .any(|line| !TAGGER_FILES.contains(&line.get(3..).unwrap_or_default()))
+            });
+            let unpushed_commits = run_git(directory, &["rev-list", "--count", "HEAD", "--not", "--remotes"])
+                .and_then(|count| count.parse().ok())
+                .unwrap_or_default();
+            Some(HeadState {
+                commit,
+                status: GitStatus { dirty, unpushed_commits },
+            })
+        }
+    }
+}
+
+/// Reads every remote's push URL (or fetch URL) from the repository.
+pub(crate) fn remotes_in_process(repo: &Repository) -> BTreeMap<String, String> {
+    let Ok(names) = repo.remotes() else {
+        return BTreeMap::new();
+    };
+
+    names
+        .iter()
+        .flatten()
+        .filter_map(|name| {
+            let remote = repo.find_remote(name).ok()?;
+            let url = remote.pushurl().or(remote.url())?;
+            Some((name.to_string(), url.to_string()))
+        })
+        .collect()
+}
+
+/// Counts the commits reachable from HEAD but from no remote-tracking branch.
+fn count_unpushed(repo: &Repository) -> Option<usize> {
+    let mut revwalk = repo.revwalk().ok()?;
+    revwalk.push_head().ok()?;
+    revwalk.hide_glob("refs/remotes/*").ok()?;
+    Some(revwalk.flatten().count())
+}
+
+/// Returns the name of the checked-out branch, including a branch without commits.
+fn current_branch(repo: &Repository) -> Option<String> {
+    let head = repo.find_reference("HEAD").ok()?;
+    let target = head.symbolic_target()?;
+    target.strip_prefix("refs/heads/").map(str::to_string)
+}
+
+/// Returns the branch `origin/HEAD` points to, or a local `main`/`master` branch.
+fn default_branch(repo: &Repository) -> Option<String> {
+    if let Some(branch) = repo
+        .find_reference("refs/remotes/origin/HEAD")
+        .ok()
+        .and_then(|head| head.symbolic_target().map(str::to_string))
+        .and_then(|target| target.strip_prefix("refs/remotes/origin/").map(str::to_string))
+    {
+        return Some(branch);
+    }
+
+    ["main", "master"]
+        .into_iter()
+        .find(|branch| repo.find_branch(branch, git2::BranchType::Local).is_ok())
+        .map(str::to_string)
+}
+
+/// Walks the history from HEAD, returning the commit count and the top contributors.
+fn history(repo: &Repository, config: &GitConfig) -> (usize, Option<DateTime<Local>>, Vec<Contributor>) {
+    let Ok(mut revwalk) = repo.revwalk() else {
+        return (0, None, Vec::new());
+    };
+    if revwalk.push_head().is_err() {
+        // No commits yet.
+        return (0, None, Vec::new());
+    }
+
+    let mailmap = if config.mailmap != Some(false) { repo.mailmap().ok() } else { None };
+    let mut commit_count = 0;
+    let mut first_commit_time: Option<i64> = None;
+    let mut authors = BTreeMap::new();
+    for oid in revwalk.flatten() {
+        commit_count += 1;
+        if let Ok(commit) = repo.find_commit(oid) {
+            let author = match &mailmap {
+                Some(mailmap) => commit.author_with_mailmap(mailmap).unwrap_or_else(|_| commit.author().to_owned()),
+                None => commit.author().to_owned(),
+            };
+            count_commit(&mut authors, author.name().unwrap_or("Unknown"), author.email().unwrap_or_default());
+            let time = commit.time().seconds();
+            first_commit_time = Some(first_commit_time.map_or(time, |first| first.min(time)));
+        }
+    }
+
+    let first_commit_date = first_commit_time.and_then(|time| Local.timestamp_opt(time, 0).single());
+    (commit_count, first_commit_date, top_contributors(authors, config))
+}
+
+/// Counts a commit towards its author, who is known by their email address or,
+/// without one, by their name. Commits arrive newest first, so the name kept is the
+/// latest one.
+fn count_commit(authors: &mut BTreeMap<String, Contributor>, name: &str, email: &str) {
+    let email = email.trim();
+    let key = if email.is_empty() { name.to_string() } else { email.to_lowercase() };
+    authors
+        .entry(key)
+        .or_insert_with(|| Contributor {
+            name: name.to_string(),
+            email: (!email.is_empty()).then(|| email.to_string()),
+            commits: 0,
+        })
+        .commits += 1;
+}
+
+/// Sorts authors by commit count (then name) and keeps the most active ones.
+fn top_contributors(authors: BTreeMap<String, Contributor>, config: &GitConfig) -> Vec<Contributor> {
+    let mut contributors: Vec<Contributor> = authors.into_values().collect();
+    contributors.sort_by(|a, b| b.commits.cmp(&a.commits).then_with(|| a.name.cmp(&b.name)));
+    contributors.truncate(config.max_contributors.unwrap_or(TOP_CONTRIBUTORS));
+    contributors
+}
+
+/// Reads the repository metadata by running `git`.
+fn git_info_from_cli(directory: &Path, config: &GitConfig) -> GitInfo {
+    // One "name\temail" line per commit, newest first; `%aN` and `%aE` apply the mailmap.
+    let format = if config.mailmap != Some(false) { "--format=%aN%x09%aE" } else { "--format=%an%x09%ae" };
+    let mut authors = BTreeMap::new();
+    for line in run_git(directory, &["log", format, "HEAD"]).unwrap_or_default().lines() {
+        let (name, email) = line.split_once('\t').unwrap_or((line, ""));
+        count_commit(&mut authors, name, email);
+    }
+
+    GitInfo {
+        branch: run_git(directory, &["symbolic-ref", "--short", "-q", "HEAD"]),
+        default_branch: run_git(directory, &["symbolic-ref", "--short", "-q", "refs/remotes/origin/HEAD"])
+            .and_then(|branch| branch.strip_prefix("origin/").map(str::to_string)),
+        last_commit: run_git(directory, &["rev-parse", "-q", "--verify", "HEAD"]),
+        last_commit_date: run_git(directory, &["log", "-1", "--format=%cI"])
+            .and_then(|date| DateTime::parse_from_rfc3339(&date).ok())
+            .map(|date| date.with_timezone(&Local)),
+        // Every root commit is listed, in case histories were merged.
+        first_commit_date: run_git(directory, &["log", "--max-parents=0", "--format=%cI", "HEAD"]).and_then(|dates| {
+            dates
+                .lines()
+                .filter_map(|date| DateTime::parse_from_rfc3339(date).ok())
+                .map(|date| date.with_timezone(&Local))
+                .min()
+        }),
+        commit_count: run_git(directory, &["rev-list", "--count", "HEAD"])
+            .and_then(|count| count.parse().ok())
+            .unwrap_or_default(),
+        contributors: top_contributors(authors, config),
+    }
+}
+
+/// Returns the commit date of HEAD, or `None` for a repository without commits.
+fn last_commit_date(repo: &Repository) -> Option<DateTime<Local>> {
+    let commit = repo.head().ok()?.peel_to_commit().ok()?;
+    Local.timestamp_opt(commit.time().seconds(), 0).single()
+}
+
+/// Reads the remotes' push URLs from the output of `git remote -v`.
+fn remotes_from_cli(directory: &Path) -> BTreeMap<String, String> {
+    // Lines look like: "origin\tgit@github.com:user/repo.git (push)".
+    let Some(stdout) = run_git(directory, &["remote", "-v"]) else {
+        return BTreeMap::new();
+    };
+
+    stdout
+        .lines()
+        .filter(|line| line.ends_with("(push)"))
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            Some((parts.next()?.to_string(), parts.next()?.to_string()))
+        })
+        .collect()
+}
+
+/// Runs a git command in the directory and returns its trimmed output.
+fn run_git(directory: &Path, args: &[&str]) -> Option<String> {
+    let output = match Command::new("git").arg("-C").arg(directory).args(args).output() {
+        Ok(output) if output.status.success() => output,
+        Ok(output) => {
+            warn!(
+                "git {} failed: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            return None;
+        }
+        Err(e) => {
+            warn!("Unable to run git: {}", e);
+            return None;
+        }
+    };
+
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!stdout.is_empty()).then_some(stdout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_read_repository_in_process() {
+        let dir = tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        repo.remote("upstream", "https://github.com/team/repo.git").unwrap();
+        repo.remote("origin", "https://github.com/user/repo.git").unwrap();
+        repo.set_head("refs/heads/main").unwrap();
+
+        // No commits yet: the branch is known but there is no commit date.
+        let git_info = extract_git_info(dir.path(), &GitConfig::default()).unwrap();
+        assert_eq!(git_info.branch.as_deref(), Some("main"));
+        assert!(git_info.last_commit_date.is_none());
+
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        let tree_id = repo.index().unwrap().write_tree().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "Initial commit", &tree, &[])
+            .unwrap();
+
+        let git_info = extract_git_info(dir.path(), &GitConfig::default()).unwrap();
+        assert!(git_info.last_commit_date.is_some());
+        assert_eq!(git_info.default_branch.as_deref(), Some("main"));
+        assert_eq!(git_info.commit_count, 1);
+        assert_eq!(git_info.last_commit.as_ref().map(String::len), Some(40));
+        assert_eq!(
+            git_info.contributors,
+            vec![Contributor {
+                name: "Test".to_string(),
+                email: Some("test@example.com".to_string()),
+                commits: 1
+            }]
+        );
+
+        let state = head_state(dir.path()).unwrap();
+        assert_eq!(state.commit.len(), 40);
+        assert!(!state.status.dirty);
+        assert_eq!(state.status.unpushed_commits, 1);
+        std::fs::write(dir.path().join("project_info.toml"), "").unwrap();
+        assert!(!head_state(dir.path()).unwrap().status.dirty);
+        std::fs::write(dir.path().join("new.txt"), "change").unwrap();
+        assert!(head_state(dir.path()).unwrap().status.dirty);
+
+        // The latest tag is the version.
+        assert!(latest_tag(dir.path()).is_none());
+        let head_commit = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.tag_lightweight("v1.2.0", head_commit.as_object(), false).unwrap();
+        assert_eq!(latest_tag(dir.path()).as_deref(), Some("1.2.0"));
+
+        // Once origin has the commit, nothing is left to push.
+        let head = repo.head().unwrap().target().unwrap();
+        repo.reference("refs/remotes/origin/main", head, false, "fetch").unwrap();
+        assert_eq!(head_state(dir.path()).unwrap().status.unpushed_commits, 0);
+
+        // Every remote is captured; origin is preferred unless another is requested.
+        let remotes = extract_git_remotes(dir.path());
+        assert_eq!(remotes.len(), 2);
+        assert_eq!(
+            select_git_url(&remotes, None).as_deref(),
+            Some("https://github.com/user/repo.git")
+        );
+        assert_eq!(
+            select_git_url(&remotes, Some("upstream")).as_deref(),
+            Some("https://github.com/team/repo.git")
+        );
+        assert_eq!(
+            select_git_url(&remotes, Some("missing")).as_deref(),
+            Some("https://github.com/user/repo.git")
+        );
+
+        // Plain directories are not repositories.
+        let plain = tempdir().unwrap();
+        assert!(extract_git_info(plain.path(), &GitConfig::default()).is_none());
+    }
+    #[test]
+    fn test_contributors_follow_mailmap() {
+        let dir = tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let commit = |name: &str, email: &str| {
+            let signature = git2::Signature::now(name, email).unwrap();
+            let tree_id = repo.index().unwrap().write_tree().unwrap();
+            let tree = repo.find_tree(tree_id).unwrap();
+            let parents: Vec<_> = repo.head().ok().and_then(|head| head.peel_to_commit().ok()).into_iter().collect();
+            let parents: Vec<_> = parents.iter().collect();
+            repo.commit(Some("HEAD"), &signature, &signature, "Change", &tree, &parents).unwrap();
+        };
+        commit("sam", "sam@old-studio.example");
+        commit("Sam Lee", "sam@old-studio.example");
+        commit("Sam Lee", "sam@studio.example");
+        commit("Alex", "alex@studio.example");
+        std::fs::write(dir.path().join(".mailmap"), "Sam Lee <sam@studio.example> <sam@old-studio.example>\n").unwrap();
+
+        let contributors = extract_git_info(dir.path(), &GitConfig::default()).unwrap().contributors;
+        assert_eq!(
+            contributors,
+            vec![
+                Contributor {
+                    name: "Sam Lee".to_string(),
+                    email: Some("sam@studio.example".to_string()),
+                    commits: 3
+                },
+                Contributor {
+                    name: "Alex".to_string(),
+                    email: Some("alex@studio.example".to_string()),
+                    commits: 1
+                },
+            ]
+        );
+
+        // Without the mailmap, each address is an author of its own, named as in their
+        // latest commit.
+        let config = GitConfig {
+            mailmap: Some(false),
+            max_contributors: Some(2),
+            ..GitConfig::default()
+        };
+        let contributors = extract_git_info(dir.path(), &config).unwrap().contributors;
+        let commits: Vec<(&str, usize)> = contributors.iter().map(|c| (c.name.as_str(), c.commits)).collect();
+        assert_eq!(commits, [("Sam Lee", 2), ("Alex", 1)]);
+    }
+
+    #[test]
+    fn test_extract_submodules() {
+        let dir = tempdir().unwrap();
+        assert!(extract_submodules(dir.path()).is_empty());
+
+        std::fs::write(
+            dir.path().join(".gitmodules"),
+            "[submodule \"ffmpeg\"]\n\tpath = third_party/ffmpeg\n\turl = https://git.ffmpeg.org/ffmpeg.git\n\
+             [core]\n\tpath = ignored\n\
+             [submodule \"juce\"]\n\tpath = \"libs/JUCE/\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            extract_submodules(dir.path()),
+            vec![
+                Submodule {
+                    path: "third_party/ffmpeg".to_string(),
+                    url: Some("https://git.ffmpeg.org/ffmpeg.git".to_string()),
+                },
+                Submodule {
+                    path: "libs/JUCE".to_string(),
+                    url: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_web_url() {
+        let github = Some("https://github.com/me/app".to_string());
+        assert_eq!(web_url("git@github.com:me/app.git"), github);
+        assert_eq!(web_url("https://github.com/me/app/"), github);
+        assert_eq!(web_url("ssh://git@github.com/me/app.git"), github);
+        assert_eq!(
+            web_url("ssh://git@gitlab.example.com:2222/team/app.git").as_deref(),
+            Some("https://gitlab.example.com/team/app")
+        );
+        assert_eq!(
+            web_url("https://token@bitbucket.org/me/app.git").as_deref(),
+            Some("https://bitbucket.org/me/app")
+        );
+        assert_eq!(web_url("/srv/git/app.git"), None);
+        assert_eq!(web_url("file:///srv/git/app.git"), None);
+        assert_eq!(web_url("C:/repos/app"), None);
+
+        assert_eq!(hosting("https://github.com/me/app"), Some("github"));
+        assert_eq!(hosting("https://www.gitlab.com/me/app"), Some("gitlab"));
+        assert_eq!(hosting("https://bitbucket.org/me/app"), Some("bitbucket"));
+        assert_eq!(hosting("https://gitlab.example.com/team/app"), Some("self-hosted"));
+    }
+}
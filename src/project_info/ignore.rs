@@ -0,0 +1,244 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use log::debug;
+
+/// The ignore files read in every directory of a project, in order of precedence: a
+/// `.taggerignore` rule overrides a `.gitignore` rule in the same directory.
+pub const IGNORE_FILES: &[&str] = &[".gitignore", ".taggerignore"];
+
+/// One line of an ignore file.
+#[derive(Debug, Clone)]
+struct Rule {
+    /// The directory holding the ignore file, relative to the project.
+    base: PathBuf,
+    pattern: String,
+    /// Whether the rule re-includes what an earlier rule ignored (`!pattern`).
+    negated: bool,
+    /// Whether the rule only matches directories (`pattern/`).
+    dir_only: bool,
+    /// Whether the pattern matches paths relative to `base` rather than any name below
+    /// it, which is the case when it contains a `/` other than a trailing one.
+    anchored: bool,
+}
+
+impl Rule {
+    fn parse(line: &str, base: &Path) -> Option<Self> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+
+        let (negated, pattern) = match line.strip_prefix('!') {
+            Some(pattern) => (true, pattern),
+            None => (false, line.strip_prefix('\\').unwrap_or(line)),
+        };
+        let (dir_only, pattern) = match pattern.strip_suffix('/') {
+            Some(pattern) => (true, pattern),
+            None => (false, pattern),
+        };
+        let anchored = pattern.contains('/');
+        let pattern = pattern.strip_prefix('/').unwrap_or(pattern);
+        if pattern.is_empty() {
+            return None;
+        }
+
+        Some(Rule {
+            base: base.to_path_buf(),
+            pattern: pattern.to_string(),
+            negated,
+            dir_only,
+            anchored,
+        })
+    }
+
+    fn matches(&self, relative: &Path, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+        let Ok(below_base) = relative.strip_prefix(&self.base) else {
+            return false;
+        };
+
+        if self.anchored {
+            glob_match(&self.pattern, &slash_path(below_base))
+        } else {
+            below_base
+                .file_name()
+                .is_some_and(|name| glob_match(&self.pattern, &name.to_string_lossy()))
+        }
+    }
+}
+
+/// The ignore rules that apply inside a project, read from the [`IGNORE_FILES`] of
+/// its directories with the syntax of `.gitignore`: `*`, `?`, `[a-z]`, and `**`
+/// wildcards, `/` to anchor a pattern or match only directories, and `!` to re-include
+/// a path.
+#[derive(Debug, Clone, Default)]
+pub struct IgnoreRules {
+    rules: Vec<Rule>,
+}
+
+impl IgnoreRules {
+    /// Reads the ignore files of the project's root directory.
+    pub fn load(root: &Path) -> Self {
+        let mut rules = IgnoreRules::default();
+        rules.add_directory(root, Path::new(""));
+        rules
+    }
+
+    /// Reads the ignore files of a directory inside the project. Their rules apply
+    /// below it and take precedence over those of its parents, so directories should
+    /// be added from the top down.
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - The project's directory.
+    /// * `relative` - The directory, relative to `root`.
+    pub fn add_directory(&mut self, root: &Path, relative: &Path) {
+        for file_name in IGNORE_FILES {
+            let path = root.join(relative).join(file_name);
+            if let Ok(content) = fs::read_to_string(&path) {
+                debug!("Reading ignore rules from {}", path.display());
+                self.add_rules(&content, relative);
+            }
+        }
+    }
+
+    /// Adds rules in ignore-file syntax that apply below `base`.
+    pub fn add_rules(&mut self, content: &str, base: &Path) {
+        self.rules.extend(content.lines().filter_map(|line| Rule::parse(line, base)));
+    }
+
+    /// Returns `true` if there are no rules.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Returns `true` if the path, relative to the project, is ignored. The last
+    /// matching rule decides.
+    ///
+    /// Only the path itself is checked, not its parent directories: callers walking the
+    /// project skip the contents of ignored directories.
+    pub fn is_ignored(&self, relative: &Path, is_dir: bool) -> bool {
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| rule.matches(relative, is_dir))
+            .is_some_and(|rule| !rule.negated)
+    }
+}
+
+/// Joins a relative path's components with `/`, whatever the platform's separator.
+pub(crate) fn slash_path(path: &Path) -> String {
+    path.components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(name.to_string_lossy()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Matches a `/`-separated path against a glob pattern.
+///
+/// `*` matches any run of characters other than `/`, `**` any run including `/` (and
+/// `**/` also nothing at all), `?` one character other than `/`, and `[...]` one
+/// character from a set such as `[abc]`, `[a-z]`, or `[!0-9]`. A backslash escapes the
+/// next character.
+pub fn glob_match(pattern: &str, path: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let path: Vec<char> = path.chars().collect();
+    match_from(&pattern, &path)
+}
+
+fn match_from(pattern: &[char], path: &[char]) -> bool {
+    match pattern {
+        [] => path.is_empty(),
+        ['*', '*', '/', rest @ ..] => {
+            match_from(rest, path)
+                || (0..path.len()).any(|i| path[i] == '/' && match_from(rest, &path[i + 1..]))
+        }
+        ['*', '*', rest @ ..] => (0..=path.len()).any(|i| match_from(rest, &path[i..])),
+        ['*', rest @ ..] => {
+            let segment_end = path.iter().position(|&c| c == '/').unwrap_or(path.len());
+            (0..=segment_end).any(|i| match_from(rest, &path[i..]))
+        }
+        ['?', rest @ ..] => path.first().is_some_and(|&c| c != '/') && match_from(rest, &path[1..]),
+        ['[', class @ ..] => match (path.first(), parse_class(class)) {
+            (Some(&c), Some((matches, rest))) => c != '/' && matches(c) && match_from(rest, &path[1..]),
+            (_, None) => path.first() == Some(&'[') && match_from(class, &path[1..]),
+            (None, _) => false,
+        },
+        ['\\', literal, rest @ ..] | [literal, rest @ ..] => {
+            path.first() == Some(literal) && match_from(rest, &path[1..])
+        }
+    }
+}
+
+/// Parses a character class after its `[`, returning a matcher and the rest of the
+/// pattern, or `None` if the class is not closed.
+fn parse_class(class: &[char]) -> Option<(impl Fn(char) -> bool + '_, &[char])> {
+    let (negated, body_start) = match class.first() {
+        Some('!' | '^') => (true, 1),
+        _ => (false, 0),
+    };
+    // A `]` right after the opening bracket is a member, not the end.
+    let end = body_start + 1 + class.get(body_start + 1..)?.iter().position(|&c| c == ']')?;
+    let body = &class[body_start..end];
+
+    let matches = move |c: char| {
+        let mut i = 0;
+        let mut found = false;
+        while i < body.len() {
+            if i + 2 < body.len() && body[i + 1] == '-' {
+                found |= (body[i]..=body[i + 2]).contains(&c);
+                i += 3;
+            } else {
+                found |= body[i] == c;
+                i += 1;
+            }
+        }
+        found != negated
+    };
+    Some((matches, &class[end + 1..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("*.log", "debug.log"));
+        assert!(!glob_match("*.log", "logs/debug.log"));
+        assert!(glob_match("**/*.log", "debug.log"));
+        assert!(glob_match("**/*.log", "logs/old/debug.log"));
+        assert!(glob_match("Samples/**", "Samples/kicks/808.wav"));
+        assert!(glob_match("a/**/b", "a/x/y/b"));
+        assert!(glob_match("take?.wav", "take1.wav"));
+        assert!(glob_match("take[0-9].wav", "take7.wav"));
+        assert!(!glob_match("take[!0-9].wav", "take7.wav"));
+        assert!(glob_match("[]].txt", "].txt"));
+        assert!(glob_match("[unclosed", "[unclosed"));
+        assert!(glob_match("\\*.txt", "*.txt"));
+        assert!(!glob_match("\\*.txt", "a.txt"));
+    }
+
+    #[test]
+    fn test_ignore_rules() {
+        let mut rules = IgnoreRules::default();
+        rules.add_rules("# build output\n*.log\n!keep.log\nbuild/\n/Renders\n", Path::new(""));
+        rules.add_rules("local.txt\n", Path::new("sub"));
+
+        assert!(rules.is_ignored(Path::new("debug.log"), false));
+        assert!(rules.is_ignored(Path::new("sub/deep/debug.log"), false));
+        assert!(!rules.is_ignored(Path::new("keep.log"), false));
+        assert!(rules.is_ignored(Path::new("sub/build"), true));
+        assert!(!rules.is_ignored(Path::new("build"), false));
+        assert!(rules.is_ignored(Path::new("Renders"), true));
+        assert!(!rules.is_ignored(Path::new("sub/Renders"), true));
+        assert!(rules.is_ignored(Path::new("sub/x/local.txt"), false));
+        assert!(!rules.is_ignored(Path::new("local.txt"), false));
+    }
+}
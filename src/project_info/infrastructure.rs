@@ -0,0 +1,123 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use log::debug;
+
+use crate::config::Config;
+
+use super::detector::Detector;
+use super::inventory::Inventory;
+use super::tags::{self, TOOL};
+
+/// Detector for infrastructure-as-code projects.
+pub struct InfrastructureDetector;
+
+impl Detector for InfrastructureDetector {
+    fn name(&self) -> &'static str {
+        "infra"
+    }
+
+    fn handles(&self, project_type: &str) -> bool {
+        project_type == "infra"
+    }
+
+    fn generate_tags(&self, inventory: &Inventory, _project_type: &str, _config: &Config) -> Vec<String> {
+        generate_infrastructure_tags(inventory)
+    }
+
+    fn default_tags(&self, _project_type: &str) -> &'static [&'static str] {
+        &["infrastructure"]
+    }
+}
+
+/// Files that mark the root of an infrastructure project.
+pub const INFRASTRUCTURE_INDICATORS: &[&str] = &["main.tf", "Chart.yaml", "ansible.cfg"];
+
+/// Returns the infrastructure tool a file belongs to, if any.
+///
+/// Terraform, Docker, and Helm files are recognized by name. YAML files are read to
+/// tell Kubernetes manifests (`apiVersion` and `kind`) and Ansible playbooks (a list
+/// of plays with `hosts`) apart from other configuration.
+pub fn infrastructure_tool(path: &Path) -> Option<&'static str> {
+    let file_name = path.file_name()?.to_str()?;
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase())
+        .unwrap_or_default();
+
+    if extension == "tf" || extension == "tfvars" {
+        return Some("terraform");
+    }
+    if file_name == "Dockerfile"
+        || file_name.starts_with("Dockerfile.")
+        || extension == "dockerfile"
+        || matches!(
+            file_name,
+            "docker-compose.yml" | "docker-compose.yaml" | "compose.yml" | "compose.yaml"
+        )
+    {
+        return Some("docker");
+    }
+    if file_name == "Chart.yaml" {
+        return Some("helm");
+    }
+    if file_name == "ansible.cfg" {
+        return Some("ansible");
+    }
+    if extension != "yml" && extension != "yaml" {
+        return None;
+    }
+
+    let content = fs::read_to_string(path).ok()?;
+    let has_key = |key: &str| content.lines().any(|line| line.starts_with(key));
+
+    if has_key("apiVersion:") && has_key("kind:") {
+        Some("kubernetes")
+    } else if content
+        .lines()
+        .any(|line| line.starts_with("- hosts:") || line.starts_with("  hosts:"))
+    {
+        Some("ansible")
+    } else {
+        None
+    }
+}
+
+/// Generates tags for the infrastructure tools used in the directory.
+///
+/// Returns an empty vector when no infrastructure files are found, so programming
+/// projects only gain these tags when they actually ship infrastructure code.
+///
+/// # Arguments
+///
+/// * `inventory` - The project's inventory.
+///
+/// # Returns
+///
+/// A vector of infrastructure tags (e.g. `"tool:terraform"`, `"tool:docker"`).
+pub fn generate_infrastructure_tags(inventory: &Inventory) -> Vec<String> {
+    let mut tag_set = HashSet::new();
+
+    for entry in inventory.entries() {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        if let Some(tool) = infrastructure_tool(entry.path()) {
+            tag_set.insert(tags::namespaced(TOOL, tool));
+            if tool == "helm" {
+                tag_set.insert(tags::namespaced(TOOL, "kubernetes"));
+            }
+        }
+    }
+
+    let mut tags: Vec<String> = tag_set.into_iter().collect();
+    if !tags.is_empty() {
+        tags.push("infrastructure".to_string());
+    }
+
+    debug!("Infrastructure tags generated: {:?}", tags);
+
+    tags
+}
@@ -0,0 +1,351 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use log::{debug, info, warn};
+use walkdir::{DirEntry, WalkDir};
+
+use super::ignore::IgnoreRules;
+use crate::config::ScanConfig;
+use super::stats::{DirectoryStats, StatsBuilder};
+use super::{git, is_bundle, SKIPPED_DIRECTORIES, VENDORED_DIRECTORIES};
+
+/// The contents of a project directory, gathered in a single walk.
+///
+/// Type detection, tag generation, detector fields, warnings, and disk usage all read
+/// from the inventory instead of walking the directory themselves, so each project is
+/// read from disk once no matter how many detectors look at it.
+#[derive(Debug)]
+pub struct Inventory {
+    root: PathBuf,
+    entries: Vec<DirEntry>,
+    errors: Vec<walkdir::Error>,
+    stats: DirectoryStats,
+}
+
+impl Inventory {
+    /// Walks the directory recursively and records what it holds.
+    ///
+    /// Symbolic links are skipped. Paths matched by the project's `.gitignore`
+    /// and `.taggerignore` files are left out of the entries like build directories.
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - A reference to the project's directory path.
+    pub fn scan(directory: &Path) -> Self {
+        Self::scan_with(directory, &ScanConfig::default())
+    }
+
+    /// Like [`Inventory::scan`], but leaving out the files the `[scan]` settings
+    /// exclude, which do not count towards the size either, following symbolic
+    /// links when they say so, and listing vendored directories and submodules when
+    /// they say so.
+    pub fn scan_with(directory: &Path, scan: &ScanConfig) -> Self {
+        let mut entries = Vec::new();
+        let mut errors = Vec::new();
+        let mut stats = StatsBuilder::default();
+        let mut ignore_rules = IgnoreRules::load(directory);
+        let mut visited = VisitedDirectories::new(directory);
+        let submodules: Vec<PathBuf> = if scan.vendored {
+            Vec::new()
+        } else {
+            git::extract_submodules(directory).into_iter().map(|submodule| PathBuf::from(submodule.path)).collect()
+        };
+
+        // Directories whose contents are measured but not part of the project: hidden,
+        // build, vendored, and ignored directories, and the insides of bundles. The walk is depth-first,
+        // so everything below one arrives right after it.
+        let mut excluded: Option<PathBuf> = None;
+        let is_excluded = |excluded: &Option<PathBuf>, path: &Path| {
+            excluded.as_ref().is_some_and(|excluded| path.starts_with(excluded))
+        };
+
+        let walker = WalkDir::new(directory)
+            .min_depth(1)
+            .follow_links(scan.follow_symlinks)
+            .into_iter()
+            .filter_entry(|entry| {
+                let relative = entry.path().strip_prefix(directory).unwrap_or(entry.path());
+                if entry.file_type().is_symlink() {
+                    debug!("Skipping symbolic link {}", entry.path().display());
+                    return false;
+                }
+                if entry.file_type().is_dir() && scan.follow_symlinks && !visited.first_visit(entry.path()) {
+                    debug!("Skipping {}, already scanned through another link", entry.path().display());
+                    return false;
+                }
+                !scan.excludes(relative, entry.file_type().is_dir())
+            });
+        for entry in walker {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) if e.loop_ancestor().is_some() => {
+                    debug!("Skipping symbolic link loop: {}", e);
+                    continue;
+                }
+                Err(e) => {
+                    if e.path().is_some_and(|path| is_excluded(&excluded, path)) {
+                        warn!("Skipping unreadable path while measuring size: {}", e);
+                    } else {
+                        errors.push(e);
+                    }
+                    continue;
+                }
+            };
+
+            if entry.file_type().is_file() {
+                if let (Ok(metadata), Ok(relative)) = (entry.metadata(), entry.path().strip_prefix(directory)) {
+                    stats.add_file(relative, metadata.len());
+                }
+            }
+
+            if is_excluded(&excluded, entry.path()) {
+                continue;
+            }
+            excluded = None;
+
+            let file_name = entry.file_name().to_string_lossy();
+            let relative = entry.path().strip_prefix(directory).unwrap_or(entry.path());
+            let is_hidden = file_name.starts_with('.');
+            let is_dir = entry.file_type().is_dir();
+            let is_skipped = is_dir && SKIPPED_DIRECTORIES.contains(&file_name.as_ref());
+            let is_vendored = is_dir
+                && !scan.vendored
+                && (VENDORED_DIRECTORIES.contains(&file_name.as_ref()) || submodules.iter().any(|path| path == relative));
+            if is_vendored {
+                debug!("Leaving out vendored {}", relative.display());
+            }
+            if is_hidden || is_skipped || is_vendored || ignore_rules.is_ignored(relative, is_dir) {
+                if is_dir {
+                    excluded = Some(entry.path().to_path_buf());
+                }
+                continue;
+            }
+
+            // Bundles are listed as a single entry.
+            if is_dir && is_bundle(entry.path()) {
+                excluded = Some(entry.path().to_path_buf());
+            } else if is_dir {
+                ignore_rules.add_directory(directory, relative);
+            }
+            entries.push(entry);
+        }
+
+        let inventory = Inventory {
+            root: directory.to_path_buf(),
+            entries,
+            errors,
+            stats: stats.finish(),
+        };
+        info!(
+            "Inventory of {}: {} entries, {} bytes in {} files",
+            directory.display(),
+            inventory.entries.len(),
+            inventory.stats.total_size_bytes,
+            inventory.stats.file_count
+        );
+        inventory
+    }
+
+    /// Returns the project's directory.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Returns every file and directory that describes the project.
+    ///
+    /// Hidden entries (e.g. `.git`), build/dependency directories (e.g. `target`),
+    /// vendored code (e.g. `third_party`, git submodules), and ignored paths are left out, and bundle directories such as Logic's `.logicx` or Final Cut's
+    /// `.fcpbundle` packages are listed as a single entry without their contents.
+    pub fn entries(&self) -> impl Iterator<Item = &DirEntry> {
+        self.entries.iter()
+    }
+
+    /// Returns the project's files, as listed by [`Inventory::entries`].
+    pub fn files(&self) -> impl Iterator<Item = &DirEntry> {
+        self.entries().filter(|entry| entry.file_type().is_file())
+    }
+
+    /// Returns the errors met while listing the project's entries, such as
+    /// directories that cannot be read.
+    pub fn errors(&self) -> &[walkdir::Error] {
+        &self.errors
+    }
+
+    /// Returns the disk usage of the whole directory.
+    ///
+    /// Unlike [`Inventory::entries`], it counts every file, including hidden
+    /// directories, build output, and the contents of bundles, since they all take up
+    /// space.
+    pub fn stats(&self) -> &DirectoryStats {
+        &self.stats
+    }
+}
+
+/// Identifies a directory whatever path it is reached by.
+#[cfg(unix)]
+type DirectoryId = (u64, u64);
+#[cfg(not(unix))]
+type DirectoryId = PathBuf;
+
+/// The directories a walk that follows symbolic links has entered, by device and
+/// inode (or canonical path where there are none), so that a directory linked from
+/// several places is only read once.
+#[derive(Debug, Default)]
+pub(crate) struct VisitedDirectories {
+    ids: HashSet<DirectoryId>,
+}
+
+impl VisitedDirectories {
+    /// Starts a walk of the given directory.
+    pub(crate) fn new(root: &Path) -> Self {
+        let mut visited = VisitedDirectories::default();
+        visited.first_visit(root);
+        visited
+    }
+
+    /// Records a directory, returning `false` if it was entered before. Directories
+    /// that cannot be identified always count as new.
+    pub(crate) fn first_visit(&mut self, path: &Path) -> bool {
+        match directory_id(path) {
+            Some(id) => self.ids.insert(id),
+            None => true,
+        }
+    }
+}
+
+#[cfg(unix)]
+fn directory_id(path: &Path) -> Option<DirectoryId> {
+    use std::os::unix::fs::MetadataExt;
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn directory_id(path: &Path) -> Option<DirectoryId> {
+    path.canonicalize().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_entries_skip_hidden_build_and_bundle_contents() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join(".git/objects")).unwrap();
+        fs::create_dir_all(dir.path().join("target/debug")).unwrap();
+        fs::create_dir_all(dir.path().join("Song.logicx/Alternatives")).unwrap();
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join(".git/objects/pack"), vec![0; 100]).unwrap();
+        fs::write(dir.path().join("target/debug/app"), vec![0; 1000]).unwrap();
+        fs::write(dir.path().join("Song.logicx/Alternatives/000"), vec![0; 10]).unwrap();
+        fs::write(dir.path().join("src/main.rs"), vec![0; 1]).unwrap();
+
+        let inventory = Inventory::scan(dir.path());
+        let mut entries: Vec<_> = inventory
+            .entries()
+            .map(|entry| entry.path().strip_prefix(dir.path()).unwrap().to_path_buf())
+            .collect();
+        entries.sort();
+        assert_eq!(
+            entries,
+            vec![PathBuf::from("Song.logicx"), PathBuf::from("src"), PathBuf::from("src/main.rs")]
+        );
+        assert_eq!(inventory.files().count(), 1);
+
+        // Everything still counts towards the size.
+        assert_eq!(inventory.stats().total_size_bytes, 1111);
+        assert_eq!(inventory.stats().file_count, 4);
+        assert!(inventory.errors().is_empty());
+    }
+
+    #[test]
+    fn test_vendored_code_is_left_out_unless_scanned() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("third_party/ffmpeg")).unwrap();
+        fs::create_dir_all(dir.path().join("libs/JUCE")).unwrap();
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("third_party/ffmpeg/avcodec.c"), vec![0; 100]).unwrap();
+        fs::write(dir.path().join("libs/JUCE/juce_core.cpp"), vec![0; 10]).unwrap();
+        fs::write(dir.path().join("src/main.rs"), vec![0; 1]).unwrap();
+        fs::write(dir.path().join(".gitmodules"), "[submodule \"juce\"]\n\tpath = libs/JUCE\n").unwrap();
+
+        let files = |inventory: &Inventory| -> Vec<String> {
+            let mut files: Vec<String> = inventory
+                .files()
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .collect();
+            files.sort();
+            files
+        };
+        let inventory = Inventory::scan(dir.path());
+        assert_eq!(files(&inventory), ["main.rs"]);
+        // Vendored code still takes up space.
+        assert_eq!(inventory.stats().total_size_bytes, 111 + 37);
+
+        let scan = ScanConfig {
+            vendored: true,
+            ..ScanConfig::default()
+        };
+        let inventory = Inventory::scan_with(dir.path(), &scan);
+        assert_eq!(files(&inventory), ["avcodec.c", "juce_core.cpp", "main.rs"]);
+    }
+
+    #[test]
+    fn test_entries_respect_ignore_files() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("Samples/kicks")).unwrap();
+        fs::create_dir_all(dir.path().join("sub/logs")).unwrap();
+        fs::write(dir.path().join(".gitignore"), "*.log\n!keep.log\n").unwrap();
+        fs::write(dir.path().join(".taggerignore"), "Samples/\n").unwrap();
+        fs::write(dir.path().join("sub/.gitignore"), "/logs\n").unwrap();
+        for file in ["Samples/kicks/808.wav", "debug.log", "keep.log", "song.rpp", "sub/logs/a.txt", "sub/b.txt"] {
+            fs::write(dir.path().join(file), "x").unwrap();
+        }
+
+        let inventory = Inventory::scan(dir.path());
+        let mut files: Vec<_> = inventory
+            .files()
+            .map(|entry| entry.path().strip_prefix(dir.path()).unwrap().to_path_buf())
+            .collect();
+        files.sort();
+        assert_eq!(
+            files,
+            vec![PathBuf::from("keep.log"), PathBuf::from("song.rpp"), PathBuf::from("sub/b.txt")]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinks_are_skipped_unless_followed() {
+        use std::os::unix::fs::symlink;
+
+        let library = tempdir().unwrap();
+        fs::create_dir_all(library.path().join("kicks")).unwrap();
+        fs::write(library.path().join("kicks/808.wav"), vec![0; 100]).unwrap();
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("song.rpp"), vec![0; 1]).unwrap();
+        symlink(library.path(), dir.path().join("Samples")).unwrap();
+        symlink(library.path(), dir.path().join("More Samples")).unwrap();
+        symlink(dir.path(), dir.path().join("loop")).unwrap();
+
+        let inventory = Inventory::scan(dir.path());
+        assert_eq!(inventory.entries().count(), 1);
+        assert_eq!(inventory.stats().total_size_bytes, 1);
+
+        let scan = ScanConfig {
+            follow_symlinks: true,
+            ..ScanConfig::default()
+        };
+        let inventory = Inventory::scan_with(dir.path(), &scan);
+        let wavs: Vec<_> = inventory
+            .files()
+            .filter(|entry| entry.file_name() == "808.wav")
+            .collect();
+        assert_eq!(wavs.len(), 1);
+        assert_eq!(inventory.stats().total_size_bytes, 101);
+        assert!(inventory.errors().is_empty());
+    }
+}
@@ -0,0 +1,178 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use log::debug;
+
+/// File names that hold a project's license text, checked in this order.
+const LICENSE_FILES: &[&str] = &[
+    "LICENSE",
+    "LICENSE.md",
+    "LICENSE.txt",
+    "LICENCE",
+    "LICENCE.md",
+    "LICENCE.txt",
+    "COPYING",
+    "COPYING.md",
+    "COPYING.txt",
+];
+
+/// Phrases that identify well-known license texts, mapped to SPDX identifiers.
+///
+/// Every phrase of an entry must appear in the text. More specific licenses come
+/// first, since e.g. the LGPL text also mentions the GPL.
+const LICENSE_TEXTS: &[(&[&str], &str)] = &[
+    (&["GNU AFFERO GENERAL PUBLIC LICENSE", "Version 3"], "AGPL-3.0"),
+    (&["GNU LESSER GENERAL PUBLIC LICENSE", "Version 3"], "LGPL-3.0"),
+    (&["GNU LESSER GENERAL PUBLIC LICENSE", "Version 2.1"], "LGPL-2.1"),
+    (&["GNU GENERAL PUBLIC LICENSE", "Version 3"], "GPL-3.0"),
+    (&["GNU GENERAL PUBLIC LICENSE", "Version 2"], "GPL-2.0"),
+    (&["Apache License", "Version 2.0"], "Apache-2.0"),
+    (&["Mozilla Public License", "2.0"], "MPL-2.0"),
+    (&["Permission is hereby granted, free of charge"], "MIT"),
+    (&["Permission to use, copy, modify, and/or distribute this software"], "ISC"),
+    (&["Redistribution and use in source and binary forms", "Neither the name"], "BSD-3-Clause"),
+    (&["Redistribution and use in source and binary forms"], "BSD-2-Clause"),
+    (&["This is free and unencumbered software released into the public domain"], "Unlicense"),
+    (&["Boost Software License"], "BSL-1.0"),
+    (&["CC0 1.0 Universal"], "CC0-1.0"),
+];
+
+/// Operators of SPDX license expressions, which are not license identifiers.
+const SPDX_OPERATORS: &[&str] = &["AND", "OR", "WITH"];
+
+/// Detects the project's license as an SPDX identifier or expression.
+///
+/// The license declared in `Cargo.toml` (`package.license`), `package.json`
+/// (`license`), or `pyproject.toml` (`project.license`) wins; otherwise the first
+/// license file is read and its `SPDX-License-Identifier` line or text is recognized.
+///
+/// # Arguments
+///
+/// * `directory` - A reference to the project's directory path.
+///
+/// # Returns
+///
+/// The license, or `None` if the project declares none or its text is not recognized.
+pub fn detect_license(directory: &Path) -> Option<String> {
+    let license = manifest_license(directory).or_else(|| {
+        let content = fs::read_to_string(license_file(directory)?).ok()?;
+        license_from_text(&content)
+    });
+
+    debug!("License: {:?}", license);
+    license
+}
+
+/// Returns the first license file in the project directory, if any.
+pub fn license_file(directory: &Path) -> Option<PathBuf> {
+    LICENSE_FILES
+        .iter()
+        .map(|file_name| directory.join(file_name))
+        .find(|path| path.is_file())
+}
+
+/// Returns the tags for a license: one per identifier of an SPDX expression, so
+/// `MIT OR Apache-2.0` yields both `MIT` and `Apache-2.0`.
+pub fn license_tags(license: &str) -> Vec<String> {
+    let mut tags = Vec::new();
+    let mut after_with = false;
+
+    for token in license.split(|c: char| c.is_whitespace() || c == '(' || c == ')' || c == '/') {
+        if token.is_empty() {
+            continue;
+        }
+        if SPDX_OPERATORS.contains(&token) {
+            // Exceptions such as `LLVM-exception` follow `WITH` and are not licenses.
+            after_with = token == "WITH";
+            continue;
+        }
+        if !after_with && !tags.iter().any(|tag: &String| tag == token) {
+            tags.push(token.to_string());
+        }
+        after_with = false;
+    }
+
+    tags
+}
+
+/// Reads the license declared in the project's manifests.
+fn manifest_license(directory: &Path) -> Option<String> {
+    let toml_license = |file: &str, table: &str| -> Option<String> {
+        let content = fs::read_to_string(directory.join(file)).ok()?;
+        let parsed = content.parse::<toml::Table>().ok()?;
+        let license = parsed.get(table)?.get("license")?;
+        // PEP 621 also allows `license = { text = "MIT" }`.
+        license
+            .as_str()
+            .or_else(|| license.get("text")?.as_str())
+            .map(str::to_string)
+    };
+
+    toml_license("Cargo.toml", "package")
+        .or_else(|| {
+            let content = fs::read_to_string(directory.join("package.json")).ok()?;
+            let parsed: serde_json::Value = serde_json::from_str(&content).ok()?;
+            let license = parsed.get("license")?;
+            // Old packages use `"license": { "type": "MIT" }`.
+            license
+                .as_str()
+                .or_else(|| license.get("type")?.as_str())
+                .map(str::to_string)
+        })
+        .or_else(|| toml_license("pyproject.toml", "project"))
+        .map(|license| license.trim().to_string())
+        .filter(|license| !license.is_empty() && license != "UNLICENSED")
+}
+
+/// Recognizes a license text by its `SPDX-License-Identifier` line or its wording.
+fn license_from_text(content: &str) -> Option<String> {
+    if let Some(identifier) = content
+        .lines()
+        .find_map(|line| line.split_once("SPDX-License-Identifier:"))
+        .map(|(_, identifier)| identifier.trim())
+        .filter(|identifier| !identifier.is_empty())
+    {
+        return Some(identifier.to_string());
+    }
+
+    // License texts are often re-wrapped, so compare with normalized whitespace.
+    let text = content.split_whitespace().collect::<Vec<_>>().join(" ");
+    LICENSE_TEXTS
+        .iter()
+        .find(|(phrases, _)| phrases.iter().all(|phrase| text.contains(phrase)))
+        .map(|(_, license)| license.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_detect_license_from_manifest_and_file() {
+        let dir = tempdir().unwrap();
+        assert!(detect_license(dir.path()).is_none());
+
+        fs::write(
+            dir.path().join("COPYING"),
+            "                    GNU GENERAL PUBLIC LICENSE\n                       Version 3, 29 June 2007\n",
+        )
+        .unwrap();
+        assert_eq!(detect_license(dir.path()).as_deref(), Some("GPL-3.0"));
+
+        // A license declared in a manifest takes precedence over the file.
+        fs::write(dir.path().join("package.json"), r#"{ "license": "MIT OR Apache-2.0" }"#).unwrap();
+        assert_eq!(detect_license(dir.path()).as_deref(), Some("MIT OR Apache-2.0"));
+    }
+
+    #[test]
+    fn test_license_tags() {
+        assert_eq!(license_tags("MIT"), vec!["MIT"]);
+        assert_eq!(license_tags("MIT OR Apache-2.0"), vec!["MIT", "Apache-2.0"]);
+        assert_eq!(license_tags("MIT/Apache-2.0"), vec!["MIT", "Apache-2.0"]);
+        assert_eq!(
+            license_tags("(Apache-2.0 WITH LLVM-exception) AND MIT"),
+            vec!["Apache-2.0", "MIT"]
+        );
+    }
+}
@@ -1,76 +1,360 @@
-use std::collections::HashSet;
-use std::ffi::OsStr;
-use std::fs;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::Read;
 use std::path::Path;
 
-use log::{info, warn};
+use log::debug;
+
+use crate::config::Config;
+
+use super::detector::{Detector, DetectorFields};
+use super::inventory::Inventory;
+use super::plugins::extract_plugins;
+use super::samples::sample_provenance;
+use super::tags::{self, DAW, FORMAT, SOURCE};
+
+/// Detector for music production projects.
+pub struct MusicDetector;
+
+impl Detector for MusicDetector {
+    fn name(&self) -> &'static str {
+        "music"
+    }
+
+    fn handles(&self, project_type: &str) -> bool {
+        project_type == "music"
+    }
+
+    fn generate_tags(&self, inventory: &Inventory, _project_type: &str, config: &Config) -> Vec<String> {
+        generate_music_tags(inventory, config)
+    }
+
+    fn default_tags(&self, _project_type: &str) -> &'static [&'static str] {
+        &["audio", "production"]
+    }
+
+    fn project_name(&self, inventory: &Inventory) -> Option<String> {
+        extract_session_title(inventory)
+    }
+
+    fn generate_fields(&self, inventory: &Inventory, _project_type: &str) -> DetectorFields {
+        extract_music_fields(inventory)
+    }
+
+    fn generate_warnings(&self, inventory: &Inventory, _project_type: &str) -> Vec<String> {
+        let sample_rates: BTreeSet<u32> = inventory
+            .entries()
+            .filter(|entry| {
+                entry
+                    .path()
+                    .extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("wav"))
+            })
+            .filter_map(|entry| read_wav_sample_rate(entry.path()))
+            .collect();
+
+        if sample_rates.len() > 1 {
+            let rates: Vec<String> = sample_rates.iter().map(|rate| format!("{} Hz", rate)).collect();
+            return vec![format!("Audio files use mixed sample rates: {}.", rates.join(", "))];
+        }
+        Vec::new()
+    }
+}
+
+/// Common audio formats found in music projects.
+pub const AUDIO_EXTENSIONS: &[&str] = &["wav", "mp3", "flac", "ogg", "aiff"];
+
+/// Native project formats of common DAWs (Digital Audio Workstations).
+///
+/// Each entry is `(DAW name, project extension, is_bundle)`. Bundle formats
+/// (e.g. Logic's `.logicx`) are directories that macOS presents as a single file.
+pub const DAW_PROJECT_FORMATS: &[(&str, &str, bool)] = &[
+    ("Ableton Live", "als", false),
+    ("FL Studio", "flp", false),
+    ("Logic Pro", "logicx", true),
+    ("Logic Pro", "logic", false),
+    ("Reaper", "rpp", false),
+    ("Studio One", "song", false),
+    ("Bitwig Studio", "bwproject", false),
+    ("Cubase", "cpr", false),
+    ("Pro Tools", "ptx", false),
+    ("GarageBand", "band", true),
+];
+
+/// Returns the DAW format matching the given path, if any.
+///
+/// Bundle formats only match directories and plain formats only match files.
+///
+/// # Arguments
+///
+/// * `path` - A reference to the file or directory to check.
+///
+/// # Returns
+///
+/// The `(DAW name, project extension)` pair, or `None` if the path is not a DAW project.
+pub fn daw_project_format(path: &Path) -> Option<(&'static str, &'static str)> {
+    let extension = path.extension()?.to_str()?;
+    let is_dir = path.is_dir();
+
+    DAW_PROJECT_FORMATS
+        .iter()
+        .find(|(_, ext, is_bundle)| *is_bundle == is_dir && extension.eq_ignore_ascii_case(ext))
+        .map(|(daw, ext, _)| (*daw, *ext))
+}
 
 /// Generates tags specific to music projects based on the directory contents.
 ///
+/// Audio-format tags are only emitted for extensions that meet the configured
+/// minimum-occurrence thresholds; DAW project formats are always tagged. Genre and
+/// mood keywords from the user's taxonomy are looked up in folder and file names, and
+/// samples from well-known libraries add e.g. `source:splice`.
+///
 /// # Arguments
 ///
-/// * `directory` - A reference to the project's directory path.
+/// * `inventory` - The project's inventory.
+/// * `config` - The user configuration holding the extension thresholds and taxonomy.
 ///
 /// # Returns
 ///
 /// A vector of tags relevant to music projects.
-pub fn generate_music_tags(directory: &Path) -> Vec<String> {
+pub fn generate_music_tags(inventory: &Inventory, config: &Config) -> Vec<String> {
     let mut tags = Vec::new();
 
-    // Define common audio formats.
-    let audio_extensions = vec!["wav", "mp3", "flac", "ogg", "aiff"];
-
-    // Define common DAWs (Digital Audio Workstations).
-    let daws = vec![
-        "ableton live",
-        "fl studio",
-        "logic pro",
-        "reaper",
-        "presonus studio one",
-    ];
-
-    let entries = fs::read_dir(directory).unwrap_or_else(|_| {
-        eprintln!("Error: Unable to read directory contents for tag generation.");
-        std::process::exit(1);
-    });
-
     let mut audio_format_set = HashSet::new();
+    let mut audio_counts: HashMap<&str, usize> = HashMap::new();
     let mut daw_set = HashSet::new();
+    let mut file_count = 0;
 
-    for entry in entries {
-        if let Ok(entry) = entry {
-            let path = entry.path();
+    for entry in inventory.entries() {
+        let path = entry.path();
+        if entry.file_type().is_file() {
+            file_count += 1;
+        }
 
-            if let Some(extension) = path.extension() {
-                if let Some(ext_str) = extension.to_str() {
-                    for &audio_ext in &audio_extensions {
-                        if ext_str.eq_ignore_ascii_case(audio_ext) {
-                            audio_format_set.insert(audio_ext.to_uppercase()); // e.g., "WAV"
-                        }
-                    }
-                }
-            }
+        // Check for DAW project files and bundles.
+        if let Some((daw, ext)) = daw_project_format(path) {
+            daw_set.insert(tags::namespaced(DAW, daw)); // e.g., "daw:Reaper"
+            audio_format_set.insert(tags::namespaced(FORMAT, ext.to_uppercase())); // e.g., "format:RPP"
+            continue;
+        }
 
-            if let Some(file_name) = path.file_name() {
-                let file_name_str = file_name.to_string_lossy().to_lowercase();
-                for daw in &daws {
-                    if file_name_str.contains(&daw.to_lowercase()) {
-                        daw_set.insert(daw.to_string()); // e.g., "reaper"
+        if let Some(extension) = path.extension() {
+            if let Some(ext_str) = extension.to_str() {
+                for &audio_ext in AUDIO_EXTENSIONS {
+                    if ext_str.eq_ignore_ascii_case(audio_ext) {
+                        *audio_counts.entry(audio_ext).or_default() += 1;
                     }
                 }
             }
         }
     }
 
+    // Keep audio formats with enough files, e.g. "format:WAV".
+    audio_format_set.extend(
+        audio_counts
+            .into_iter()
+            .filter(|(_, count)| config.meets_extension_threshold("music", *count, file_count))
+            .map(|(audio_ext, _)| tags::namespaced(FORMAT, audio_ext.to_uppercase())),
+    );
+
     // Add detected audio formats and DAWs as tags.
-    tags.extend(audio_format_set.into_iter());
-    tags.extend(daw_set.into_iter());
+    tags.extend(audio_format_set);
+    tags.extend(daw_set);
 
-    // Add general music production tags.
-    tags.push("audio".to_string());
-    tags.push("production".to_string());
+    // Add genres and moods named in folder and file names, e.g. "genre:drum-and-bass".
+    tags.extend(config.taxonomy.tags_for(inventory));
 
-    info!("Music tags generated: {:?}", tags);
+    // Add the sample libraries the project draws on, e.g. "source:splice".
+    tags.extend(sample_provenance(inventory).libraries.into_iter().map(|library| tags::namespaced(SOURCE, library)));
+
+    debug!("Music tags generated: {:?}", tags);
 
     tags
 }
+
+/// Extracts the tempo (`bpm`), musical `key`, `plugins_used`, and sample counts of a
+/// music project.
+///
+/// The tempo is read from Reaper session files when available; otherwise both values
+/// are inferred from file names such as `bass_124bpm_Fmin.wav`. When several values
+/// are found, the most common one wins. Plugins are read from DAW sessions and preset
+/// files (see [`extract_plugins`]). Audio files are counted as `external_samples` when
+/// they come from a known sample library and as `recorded_samples` otherwise.
+///
+/// # Arguments
+///
+/// * `inventory` - The project's inventory.
+///
+/// # Returns
+///
+/// A table of the fields found.
+pub fn extract_music_fields(inventory: &Inventory) -> DetectorFields {
+    let mut fields = DetectorFields::new();
+
+    let mut session_bpm = None;
+    let mut bpm_counts: HashMap<u32, usize> = HashMap::new();
+    let mut key_counts: HashMap<String, usize> = HashMap::new();
+
+    for entry in inventory.entries() {
+        let path = entry.path();
+
+        if session_bpm.is_none() && daw_project_format(path).is_some_and(|(_, ext)| ext == "rpp") {
+            session_bpm = read_reaper_tempo(path);
+        }
+
+        let Some(stem) = path.file_stem().map(|stem| stem.to_string_lossy().to_string()) else {
+            continue;
+        };
+        let tokens: Vec<&str> = stem
+            .split(|c: char| !c.is_ascii_alphanumeric() && c != '#')
+            .filter(|token| !token.is_empty())
+            .collect();
+
+        for (index, token) in tokens.iter().enumerate() {
+            let lower = token.to_lowercase();
+
+            // Either "124bpm" or "124 bpm".
+            let bpm = match lower.strip_suffix("bpm") {
+                Some("") => index.checked_sub(1).and_then(|prev| tokens[prev].parse().ok()),
+                Some(number) => number.parse().ok(),
+                None => None,
+            };
+            if let Some(bpm) = bpm.filter(|bpm| (40..=300).contains(bpm)) {
+                *bpm_counts.entry(bpm).or_default() += 1;
+            }
+
+            if let Some(key) = parse_key(token) {
+                *key_counts.entry(key).or_default() += 1;
+            }
+        }
+    }
+
+    let bpm = session_bpm.or_else(|| most_common(bpm_counts).map(f64::from));
+    if let Some(bpm) = bpm {
+        let value = if bpm.fract() == 0.0 {
+            toml::Value::Integer(bpm as i64)
+        } else {
+            toml::Value::Float(bpm)
+        };
+        fields.insert("bpm".to_string(), value);
+    }
+
+    if let Some(key) = most_common(key_counts) {
+        fields.insert("key".to_string(), toml::Value::String(key));
+    }
+
+    let plugins = extract_plugins(inventory);
+    if !plugins.is_empty() {
+        let plugins = plugins.into_iter().map(toml::Value::String).collect();
+        fields.insert("plugins_used".to_string(), toml::Value::Array(plugins));
+    }
+
+    let samples = sample_provenance(inventory);
+    if samples.external + samples.recorded > 0 {
+        fields.insert("external_samples".to_string(), toml::Value::Integer(samples.external as i64));
+        fields.insert("recorded_samples".to_string(), toml::Value::Integer(samples.recorded as i64));
+    }
+
+    debug!("Music fields extracted: {:?}", fields);
+
+    fields
+}
+
+/// Extracts the title of the project's DAW session.
+///
+/// The `TITLE` set in a Reaper session's project settings is preferred. Otherwise, if
+/// the directory holds exactly one DAW session, its file name is used (e.g. `Nightfall`
+/// for `Nightfall.als`).
+///
+/// # Arguments
+///
+/// * `inventory` - The project's inventory.
+///
+/// # Returns
+///
+/// The session title, or `None` if it cannot be determined.
+pub fn extract_session_title(inventory: &Inventory) -> Option<String> {
+    let sessions: Vec<_> = inventory
+        .entries()
+        .filter(|entry| daw_project_format(entry.path()).is_some())
+        .map(|entry| entry.path().to_path_buf())
+        .collect();
+
+    let reaper_title = sessions
+        .iter()
+        .filter(|path| daw_project_format(path).is_some_and(|(_, ext)| ext == "rpp"))
+        .find_map(|path| read_reaper_value(path, "TITLE"))
+        .map(|title| title.trim_matches('"').to_string())
+        .filter(|title| !title.is_empty());
+
+    reaper_title.or_else(|| match sessions.as_slice() {
+        [session] => session.file_stem().map(|stem| stem.to_string_lossy().to_string()),
+        _ => None,
+    })
+}
+
+/// Reads the value following a top-level key (e.g. `TEMPO`, `TITLE`) in a Reaper `.rpp` session.
+fn read_reaper_value(path: &Path, key: &str) -> Option<String> {
+    let content = fs::read_to_string(path).ok()?;
+    let prefix = format!("{} ", key);
+    content
+        .lines()
+        .find_map(|line| line.trim().strip_prefix(prefix.as_str()).map(str::to_string))
+}
+
+/// Reads the project tempo from the `TEMPO` line of a Reaper `.rpp` session.
+fn read_reaper_tempo(path: &Path) -> Option<f64> {
+    read_reaper_value(path, "TEMPO")?
+        .split_whitespace()
+        .next()
+        .and_then(|tempo| tempo.parse().ok())
+}
+
+/// Parses a key token such as `Am`, `F#min`, or `Ebmaj` into e.g. `"F# minor"`.
+///
+/// A bare note name is not accepted, since single letters are too ambiguous in file names.
+fn parse_key(token: &str) -> Option<String> {
+    let mut chars = token.chars();
+    let note = chars.next().filter(|c| ('A'..='G').contains(c))?;
+    let rest = chars.as_str();
+
+    let (accidental, quality) = match rest.chars().next() {
+        Some(c @ ('#' | 'b')) => (Some(c), &rest[1..]),
+        _ => (None, rest),
+    };
+
+    // A bare "m" must be lowercase so that e.g. "AM" in "10 AM take" is not a key.
+    let quality = match quality {
+        "m" => "minor",
+        _ => match quality.to_lowercase().as_str() {
+            "min" | "minor" => "minor",
+            "maj" | "major" => "major",
+            _ => return None,
+        },
+    };
+
+    let mut key = note.to_string();
+    if let Some(accidental) = accidental {
+        key.push(accidental);
+    }
+    Some(format!("{} {}", key, quality))
+}
+
+/// Returns the value with the highest count, breaking ties by the smallest value.
+fn most_common<T: Ord>(counts: HashMap<T, usize>) -> Option<T> {
+    counts
+        .into_iter()
+        .max_by(|(a, a_count), (b, b_count)| a_count.cmp(b_count).then_with(|| b.cmp(a)))
+        .map(|(value, _)| value)
+}
+
+/// Reads the sample rate from a WAV file's `fmt ` header.
+fn read_wav_sample_rate(path: &Path) -> Option<u32> {
+    let mut header = [0u8; 28];
+    File::open(path).ok()?.read_exact(&mut header).ok()?;
+
+    if &header[..4] != b"RIFF" || &header[8..16] != b"WAVEfmt " {
+        return None;
+    }
+    Some(u32::from_le_bytes(header[24..28].try_into().ok()?))
+}
@@ -0,0 +1,172 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use log::warn;
+use serde::Deserialize;
+
+use crate::config::ScanConfig;
+
+use super::tags;
+
+/// The per-project settings file that records corrections made by the user.
+pub const OVERRIDES_FILE: &str = ".tagger.toml";
+
+/// Settings for one project, read from its `.tagger.toml` and applied on top of the
+/// global configuration, for directories the heuristics always get wrong:
+///
+/// ```toml
+/// type = "music"
+/// description = "Cover EP recorded with the band in 2019"
+///
+/// [tags]
+/// add = ["client:acme"]
+/// remove = ["cli", "format:"]
+///
+/// [scan]
+/// exclude = ["Renders/**"]
+/// vendored = true
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Overrides {
+    /// The project type, used instead of the detected one.
+    #[serde(rename = "type")]
+    pub project_type: Option<String>,
+    /// The one-line summary, used instead of the one taken from the README.
+    pub description: Option<String>,
+    /// Tags to always or never give the project.
+    pub tags: TagOverrides,
+    /// Files to leave out of the project, on top of the global `[scan]` excludes.
+    pub scan: ScanOverrides,
+}
+
+/// Tags forced onto or kept off a project.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct TagOverrides {
+    /// Tags the project always gets, even if blacklisted in the configuration.
+    pub add: Vec<String>,
+    /// Tag patterns (see [`tags::matches`]) the project never gets.
+    pub remove: Vec<String>,
+}
+
+/// Scan settings of one project.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct ScanOverrides {
+    /// Globs of files and directories to leave out, as in the `[scan]` configuration.
+    pub exclude: Vec<String>,
+    /// Whether vendored directories and submodules are scanned, instead of the
+    /// `[scan]` setting.
+    pub vendored: Option<bool>,
+}
+
+impl Overrides {
+    /// Reads the project's `.tagger.toml`.
+    ///
+    /// A missing file is not an error; an unreadable or malformed one is logged and
+    /// ignored so detection can still run.
+    pub fn load(directory: &Path) -> Self {
+        let path = directory.join(OVERRIDES_FILE);
+        let Ok(content) = fs::read_to_string(&path) else {
+            return Overrides::default();
+        };
+
+        match toml::from_str::<Overrides>(&content) {
+            Ok(mut overrides) => {
+                overrides.project_type = overrides
+                    .project_type
+                    .map(|project_type| project_type.trim().to_string())
+                    .filter(|project_type| !project_type.is_empty());
+                overrides.description = overrides
+                    .description
+                    .map(|description| description.trim().to_string())
+                    .filter(|description| !description.is_empty());
+                overrides
+            }
+            Err(e) => {
+                warn!("Ignoring malformed {}: {}", path.display(), e);
+                Overrides::default()
+            }
+        }
+    }
+
+    /// Returns the global scan settings with the project's excludes and vendoring
+    /// setting applied.
+    pub fn scan_config(&self, global: &ScanConfig) -> ScanConfig {
+        let mut scan = global.clone();
+        scan.exclude.extend(self.scan.exclude.iter().cloned());
+        if let Some(vendored) = self.scan.vendored {
+            scan.vendored = vendored;
+        }
+        scan
+    }
+
+    /// Drops the forbidden tags from the generated ones and adds the forced ones,
+    /// keeping the tags sorted and unique. A tag both forced and forbidden is kept.
+    pub fn apply_tags(&self, generated: &mut Vec<String>) {
+        if self.tags.add.is_empty() && self.tags.remove.is_empty() {
+            return;
+        }
+        generated.retain(|tag| !self.tags.remove.iter().any(|pattern| tags::matches(tag, pattern)));
+        generated.extend(self.tags.add.iter().map(|tag| tag.trim().to_string()).filter(|tag| !tag.is_empty()));
+        generated.sort();
+        generated.dedup();
+    }
+}
+
+/// Records the project type in the project's `.tagger.toml` so future scans use it.
+///
+/// Other settings already present in the file are kept.
+pub fn save_project_type(directory: &Path, project_type: &str) -> io::Result<()> {
+    update(directory, |table| {
+        table.insert("type".to_string(), toml::Value::String(project_type.to_string()));
+    })
+}
+
+/// Records the description in the project's `.tagger.toml` so future scans use it
+/// instead of the README's.
+pub fn save_description(directory: &Path, description: &str) -> io::Result<()> {
+    update(directory, |table| {
+        table.insert("description".to_string(), toml::Value::String(description.to_string()));
+    })
+}
+
+/// Adds tags to the ones the project's `.tagger.toml` always gives it, keeping those
+/// already listed.
+pub fn save_added_tags(directory: &Path, tags: &[String]) -> io::Result<()> {
+    update(directory, |table| {
+        let section = table
+            .entry("tags")
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+        let Some(section) = section.as_table_mut() else {
+            return;
+        };
+        let added = section.entry("add").or_insert_with(|| toml::Value::Array(Vec::new()));
+        if let Some(added) = added.as_array_mut() {
+            for tag in tags {
+                if !added.iter().any(|value| value.as_str() == Some(tag.as_str())) {
+                    added.push(toml::Value::String(tag.clone()));
+                }
+            }
+        }
+    })
+}
+
+/// Rewrites the project's `.tagger.toml` with a change, creating it if needed.
+fn update(directory: &Path, change: impl FnOnce(&mut toml::Table)) -> io::Result<()> {
+    let path = directory.join(OVERRIDES_FILE);
+
+    let mut table = match fs::read_to_string(&path) {
+        Ok(content) => content
+            .parse::<toml::Table>()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => toml::Table::new(),
+        Err(e) => return Err(e),
+    };
+    change(&mut table);
+
+    let content = toml::to_string(&table).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    fs::write(path, content)
+}
@@ -0,0 +1,137 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use log::debug;
+
+use crate::config::Config;
+
+use super::detector::{Detector, DetectorFields};
+use super::inventory::Inventory;
+use super::tags::{self, CAMERA, FORMAT, TOOL};
+
+/// Detector for photography projects.
+pub struct PhotographyDetector;
+
+impl Detector for PhotographyDetector {
+    fn name(&self) -> &'static str {
+        "photography"
+    }
+
+    fn handles(&self, project_type: &str) -> bool {
+        project_type == "photography"
+    }
+
+    fn generate_tags(&self, inventory: &Inventory, _project_type: &str, _config: &Config) -> Vec<String> {
+        generate_photography_tags(inventory)
+    }
+
+    fn default_tags(&self, _project_type: &str) -> &'static [&'static str] {
+        &["photography"]
+    }
+
+    fn generate_fields(&self, inventory: &Inventory, _project_type: &str) -> DetectorFields {
+        let mut fields = DetectorFields::new();
+        let assets = inventory
+            .files()
+            .filter(|entry| is_photo_asset(entry.path()))
+            .count();
+        fields.insert("assets".to_string(), toml::Value::Integer(assets as i64));
+        fields
+    }
+}
+
+/// Catalog and session formats of photo management tools.
+///
+/// Each entry is `(tool name, extension, is_bundle)`. Lightroom preview caches
+/// (`.lrdata`) are bundles too, but are not catalogs themselves.
+pub const CATALOG_FORMATS: &[(&str, &str, bool)] = &[
+    ("Lightroom", "lrcat", false),
+    ("Capture One", "cosessiondb", false),
+    ("Capture One", "cocatalog", true),
+];
+
+/// Camera RAW formats and the camera maker (or standard) they belong to.
+pub const RAW_FORMATS: &[(&str, &str)] = &[
+    ("cr2", "Canon"),
+    ("cr3", "Canon"),
+    ("nef", "Nikon"),
+    ("arw", "Sony"),
+    ("dng", "DNG"),
+    ("raf", "Fujifilm"),
+    ("orf", "Olympus"),
+    ("rw2", "Panasonic"),
+];
+
+/// Processed image formats counted as photo assets alongside RAW files.
+const IMAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "tif", "tiff", "heic"];
+
+/// Returns the photo tool matching a catalog or session path, if any.
+pub fn catalog_format(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_str()?;
+    let is_dir = path.is_dir();
+
+    CATALOG_FORMATS
+        .iter()
+        .find(|(_, ext, is_bundle)| *is_bundle == is_dir && extension.eq_ignore_ascii_case(ext))
+        .map(|(tool, _, _)| *tool)
+}
+
+/// Returns `true` if the path is a Lightroom preview cache or Capture One catalog bundle.
+pub fn is_photo_bundle(path: &Path) -> bool {
+    path.is_dir()
+        && (catalog_format(path).is_some()
+            || path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("lrdata")))
+}
+
+/// Returns the `(extension, camera maker)` pair for a RAW file, if the path is one.
+pub fn raw_format(path: &Path) -> Option<(&'static str, &'static str)> {
+    let extension = path.extension()?.to_str()?;
+
+    RAW_FORMATS
+        .iter()
+        .find(|(ext, _)| extension.eq_ignore_ascii_case(ext))
+        .copied()
+}
+
+/// Returns `true` if the path is a RAW or processed photo.
+fn is_photo_asset(path: &Path) -> bool {
+    let is_image = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| IMAGE_EXTENSIONS.iter().any(|img| ext.eq_ignore_ascii_case(img)));
+
+    is_image || raw_format(path).is_some()
+}
+
+/// Generates tags specific to photography projects based on the directory contents.
+///
+/// # Arguments
+///
+/// * `inventory` - The project's inventory.
+///
+/// # Returns
+///
+/// A vector of tags relevant to photography projects.
+pub fn generate_photography_tags(inventory: &Inventory) -> Vec<String> {
+    let mut tags = Vec::new();
+
+    let mut tag_set = HashSet::new();
+
+    for entry in inventory.entries() {
+        let path = entry.path();
+
+        if let Some(tool) = catalog_format(path) {
+            tag_set.insert(tags::namespaced(TOOL, tool)); // e.g., "tool:Lightroom"
+        } else if let Some((ext, maker)) = raw_format(path) {
+            tag_set.insert(tags::namespaced(FORMAT, ext.to_uppercase())); // e.g., "format:NEF"
+            tag_set.insert(tags::namespaced(CAMERA, maker)); // e.g., "camera:Nikon"
+            tag_set.insert("raw".to_string());
+        }
+    }
+
+    tags.extend(tag_set);
+
+    debug!("Photography tags generated: {:?}", tags);
+
+    tags
+}
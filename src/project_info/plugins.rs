@@ -0,0 +1,283 @@
+//! Third-party plugins used by music projects.
+//!
+//! Plugin names are read from DAW sessions (Reaper, Ableton Live, and FL Studio) and
+//! from loose preset files, so an old session can be reopened with the right plugins
+//! installed.
+
+use std::collections::BTreeSet;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+
+use flate2::read::GzDecoder;
+
+use super::inventory::Inventory;
+
+/// Lists the plugins the project's sessions and presets use, sorted by name.
+///
+/// # Arguments
+///
+/// * `inventory` - The project's inventory.
+///
+/// # Returns
+///
+/// Plugin names such as `Serum` or `Valhalla Room`. A `.fxp` preset only records a
+/// four-character plugin ID, which is listed as e.g. `VST ID XfsX`.
+pub fn extract_plugins(inventory: &Inventory) -> Vec<String> {
+    let mut plugins = BTreeSet::new();
+
+    for entry in inventory.files() {
+        let path = entry.path();
+        let Some(extension) = path.extension().and_then(|ext| ext.to_str()) else {
+            continue;
+        };
+
+        let found = match extension.to_ascii_lowercase().as_str() {
+            "rpp" => read_reaper_plugins(path),
+            "als" => read_ableton_plugins(path),
+            "flp" => read_fl_studio_plugins(path),
+            "vstpreset" => read_vst3_preset_plugin(path).into_iter().collect(),
+            "fxp" | "fxb" => read_fxp_plugin(path).into_iter().collect(),
+            _ => continue,
+        };
+        plugins.extend(found.into_iter().filter(|name| !name.is_empty()));
+    }
+
+    plugins.into_iter().collect()
+}
+
+/// Reads the plugins of a Reaper `.rpp` session, from lines such as
+/// `<VST "VST3: Valhalla Room (Valhalla DSP, LLC)" ValhallaRoom.vst3 ...`.
+///
+/// JSFX effects ship with Reaper and are left out.
+fn read_reaper_plugins(path: &Path) -> Vec<String> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+
+    content
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim_start();
+            ["<VST ", "<AU ", "<CLAP ", "<LV2 ", "<DX "]
+                .iter()
+                .find_map(|prefix| line.strip_prefix(prefix))
+        })
+        .filter_map(|rest| {
+            // The display name is the first quoted string, e.g. "VSTi: Serum (Xfer Records)".
+            let name = rest.strip_prefix('"')?.split('"').next()?;
+            let name = name.split_once(": ").map_or(name, |(_, name)| name);
+            let name = match name.rfind(" (") {
+                Some(index) if name.ends_with(')') => &name[..index],
+                _ => name,
+            };
+            Some(name.trim().to_string())
+        })
+        .collect()
+}
+
+/// Reads the plugins of an Ableton Live `.als` set, a gzip-compressed XML file that
+/// describes each plugin in a `VstPluginInfo`, `Vst3PluginInfo`, or `AuPluginInfo`
+/// element.
+fn read_ableton_plugins(path: &Path) -> Vec<String> {
+    let Ok(bytes) = fs::read(path) else {
+        return Vec::new();
+    };
+    let mut xml = String::new();
+    if GzDecoder::new(bytes.as_slice()).read_to_string(&mut xml).is_err() {
+        // Very old sets are plain XML.
+        xml = String::from_utf8_lossy(&bytes).into_owned();
+    }
+
+    let mut plugins = Vec::new();
+    for (element, name_tag) in [
+        ("<VstPluginInfo", "<PlugName Value=\""),
+        ("<Vst3PluginInfo", "<Name Value=\""),
+        ("<AuPluginInfo", "<Name Value=\""),
+    ] {
+        for (start, _) in xml.match_indices(element) {
+            let rest = &xml[start..];
+            let Some(name_start) = rest.find(name_tag).map(|index| index + name_tag.len()) else {
+                continue;
+            };
+            if let Some(name) = rest[name_start..].split('"').next() {
+                plugins.push(unescape_xml(name));
+            }
+        }
+    }
+    plugins
+}
+
+/// Replaces the XML entities Ableton writes in names.
+fn unescape_xml(text: &str) -> String {
+    text.replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+/// The FL Studio event holding the FL Studio version, e.g. `20.8.4.2576`.
+const FLP_VERSION: u8 = 199;
+/// The FL Studio event holding a plugin's internal name, e.g. `Fruity Wrapper`.
+const FLP_INTERNAL_NAME: u8 = 201;
+/// The FL Studio event holding a plugin's display name.
+const FLP_NAME: u8 = 203;
+/// The internal name of the FL Studio plugin that hosts VST and AU plugins.
+const FLP_WRAPPER: &str = "Fruity Wrapper";
+
+/// Reads the third-party plugins of an FL Studio `.flp` project.
+///
+/// A project is a list of events. Plugins hosted by the `Fruity Wrapper` are
+/// third-party, and their display name is the plugin's own name unless the user
+/// renamed the channel. FL Studio's own plugins are left out.
+fn read_fl_studio_plugins(path: &Path) -> Vec<String> {
+    let Ok(bytes) = fs::read(path) else {
+        return Vec::new();
+    };
+    // "FLhd", the header length (6), the header, then "FLdt" and the data length.
+    if bytes.len() < 22 || &bytes[..4] != b"FLhd" || &bytes[14..18] != b"FLdt" {
+        return Vec::new();
+    }
+
+    let mut plugins = Vec::new();
+    let mut utf16 = false;
+    let mut in_wrapper = false;
+    let mut data = &bytes[22..];
+
+    while let Some((&id, rest)) = data.split_first() {
+        let (value, rest) = match id {
+            0..=63 => rest.split_at_checked(1),
+            64..=127 => rest.split_at_checked(2),
+            128..=191 => rest.split_at_checked(4),
+            _ => read_varint(rest).and_then(|(length, rest)| rest.split_at_checked(length)),
+        }
+        .unwrap_or((&[], &[]));
+        data = rest;
+
+        match id {
+            FLP_VERSION => {
+                let version = String::from_utf8_lossy(value);
+                let major: u32 = version.split('.').next().and_then(|major| major.parse().ok()).unwrap_or(0);
+                utf16 = major >= 11;
+            }
+            FLP_INTERNAL_NAME => in_wrapper = decode_flp_text(value, utf16) == FLP_WRAPPER,
+            FLP_NAME if in_wrapper => {
+                plugins.push(decode_flp_text(value, utf16));
+                in_wrapper = false;
+            }
+            _ => {}
+        }
+    }
+    plugins
+}
+
+/// Reads the 7-bit variable-length size that precedes an FL Studio text or data event.
+fn read_varint(data: &[u8]) -> Option<(usize, &[u8])> {
+    let mut length = 0usize;
+    for (index, byte) in data.iter().enumerate().take(4) {
+        length |= usize::from(byte & 0x7f) << (7 * index);
+        if byte & 0x80 == 0 {
+            return Some((length, &data[index + 1..]));
+        }
+    }
+    None
+}
+
+/// Decodes a null-terminated FL Studio text, which is UTF-16 since FL Studio 11.
+fn decode_flp_text(value: &[u8], utf16: bool) -> String {
+    let text = if utf16 {
+        let units: Vec<u16> = value.chunks_exact(2).map(|pair| u16::from_le_bytes([pair[0], pair[1]])).collect();
+        String::from_utf16_lossy(&units)
+    } else {
+        String::from_utf8_lossy(value).into_owned()
+    };
+    text.trim_end_matches('\0').trim().to_string()
+}
+
+/// Reads the plugin name from a VST3 `.vstpreset`'s metadata, which not every
+/// preset has.
+fn read_vst3_preset_plugin(path: &Path) -> Option<String> {
+    let bytes = fs::read(path).ok()?;
+    if !bytes.starts_with(b"VST3") {
+        return None;
+    }
+
+    let content = String::from_utf8_lossy(&bytes);
+    let marker = "id=\"PlugInName\" value=\"";
+    let start = content.find(marker)? + marker.len();
+    content[start..].split('"').next().map(unescape_xml)
+}
+
+/// Reads the four-character plugin ID of a VST2 `.fxp` preset or `.fxb` bank.
+fn read_fxp_plugin(path: &Path) -> Option<String> {
+    let mut header = [0u8; 20];
+    File::open(path).ok()?.read_exact(&mut header).ok()?;
+
+    if &header[..4] != b"CcnK" {
+        return None;
+    }
+    let id = &header[16..20];
+    id.iter()
+        .all(u8::is_ascii_graphic)
+        .then(|| format!("VST ID {}", String::from_utf8_lossy(id)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+    use tempfile::tempdir;
+
+    /// Encodes an FL Studio text event, in UTF-16 as FL Studio 11 and later write it.
+    fn flp_text(id: u8, text: &str) -> Vec<u8> {
+        let mut value: Vec<u8> = text.encode_utf16().chain([0]).flat_map(u16::to_le_bytes).collect();
+        let mut event = vec![id, value.len() as u8];
+        event.append(&mut value);
+        event
+    }
+
+    #[test]
+    fn test_extract_plugins() {
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+
+        fs::write(
+            dir_path.join("song.rpp"),
+            "<REAPER_PROJECT 0.1 \"6.80\"\n  <TRACK\n    <FXCHAIN\n      <VST \"VSTi: Serum (Xfer Records)\" Serum.dll 0 \"\"\n      >\n      <VST \"VST3: Valhalla Room (Valhalla DSP, LLC)\" ValhallaRoom.vst3 0 \"\"\n      >\n      <JS loser/3BandEQ \"\"\n      >\n    >\n  >\n>\n",
+        )
+        .unwrap();
+
+        let mut als = GzEncoder::new(File::create(dir_path.join("set.als")).unwrap(), Compression::default());
+        als.write_all(
+            b"<Ableton><PluginDesc><VstPluginInfo Id=\"0\"><Path Value=\"Serum.dll\" /><PlugName Value=\"Serum\" /></VstPluginInfo></PluginDesc><PluginDesc><Vst3PluginInfo Id=\"0\"><Name Value=\"Pro-Q 3\" /></Vst3PluginInfo></PluginDesc></Ableton>",
+        )
+        .unwrap();
+        als.finish().unwrap();
+
+        let mut events = vec![FLP_VERSION, 11];
+        events.extend_from_slice(b"20.8.4.257\0");
+        events.extend(flp_text(FLP_INTERNAL_NAME, "Fruity Wrapper"));
+        events.extend(flp_text(FLP_NAME, "Sylenth1"));
+        events.extend(flp_text(FLP_INTERNAL_NAME, "Fruity Limiter"));
+        events.extend(flp_text(FLP_NAME, "Fruity Limiter"));
+        let mut flp = b"FLhd\x06\0\0\0\0\0\x01\0\x60\0FLdt".to_vec();
+        flp.extend((events.len() as u32).to_le_bytes());
+        flp.extend(events);
+        fs::write(dir_path.join("beat.flp"), flp).unwrap();
+
+        fs::write(
+            dir_path.join("pad.vstpreset"),
+            b"VST3\x01\0\0\0<MetaInfo><Attr id=\"PlugInName\" value=\"Diva\" type=\"string\"/></MetaInfo>",
+        )
+        .unwrap();
+        fs::write(dir_path.join("bass.fxp"), b"CcnK\0\0\0\0FxCk\0\0\0\x01XfsX\0\0\0\0").unwrap();
+
+        assert_eq!(
+            extract_plugins(&Inventory::scan(dir_path)),
+            vec!["Diva", "Pro-Q 3", "Serum", "Sylenth1", "VST ID XfsX", "Valhalla Room"]
+        );
+    }
+}
@@ -1,24 +1,81 @@
-use std::collections::HashSet;
-use std::ffi::OsStr;
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 
-use log::{info, warn};
+use log::debug;
+
+use crate::config::Config;
+
+use super::detector::{Detector, DetectorFields};
+use super::infrastructure::generate_infrastructure_tags;
+use super::inventory::Inventory;
+use super::tags::{self, LANGUAGE};
+use super::testing::{count_test_files, generate_test_tags};
+
+/// Manifest and build files that identify a software project.
+pub const PROGRAMMING_INDICATORS: &[&str] = &[
+    "Cargo.toml",
+    "package.json",
+    "setup.py",
+    "pom.xml",
+    "build.gradle",
+    "Makefile",
+    "Gemfile",
+    "requirements.txt",
+];
+
+/// Detector for software projects.
+pub struct ProgrammingDetector;
+
+impl Detector for ProgrammingDetector {
+    fn name(&self) -> &'static str {
+        "programming"
+    }
+
+    fn handles(&self, project_type: &str) -> bool {
+        project_type == "programming"
+    }
+
+    fn generate_tags(&self, inventory: &Inventory, _project_type: &str, config: &Config) -> Vec<String> {
+        generate_programming_tags(inventory, config)
+    }
+
+    fn default_tags(&self, _project_type: &str) -> &'static [&'static str] {
+        &["cli", "software development"]
+    }
+
+    fn project_name(&self, inventory: &Inventory) -> Option<String> {
+        extract_manifest_name(inventory.root())
+    }
+
+    fn generate_fields(&self, inventory: &Inventory, _project_type: &str) -> DetectorFields {
+        let mut fields = extract_cargo_fields(inventory.root());
+        fields.insert(
+            "test_files".to_string(),
+            toml::Value::Integer(count_test_files(inventory) as i64),
+        );
+        fields
+    }
+}
 
 /// Generates tags specific to programming projects based on the directory contents.
 ///
+/// Language tags are only emitted for extensions that meet the configured
+/// minimum-occurrence thresholds.
+///
 /// # Arguments
 ///
-/// * `directory` - A reference to the project's directory path.
+/// * `inventory` - The project's inventory.
+/// * `config` - The user configuration holding the extension thresholds.
 ///
 /// # Returns
 ///
 /// A vector of tags relevant to programming projects.
-pub fn generate_programming_tags(directory: &Path) -> Vec<String> {
+pub fn generate_programming_tags(inventory: &Inventory, config: &Config) -> Vec<String> {
     let mut tags = Vec::new();
 
     // Define programming languages and their corresponding file extensions.
-    let programming_extensions = vec![
+    let programming_extensions = [
         ("rust", "rs"),
         ("python", "py"),
         ("javascript", "js"),
@@ -31,47 +88,51 @@ pub fn generate_programming_tags(directory: &Path) -> Vec<String> {
         ("swift", "swift"),
     ];
 
-    // Collect tags based on detected file extensions.
-    let entries = fs::read_dir(directory).unwrap_or_else(|_| {
-        eprintln!("Error: Unable to read directory contents for tag generation.");
-        std::process::exit(1);
-    });
+    // Count the files of each language, and all files, to apply the thresholds.
+    let mut language_counts: HashMap<&str, usize> = HashMap::new();
+    let mut file_count = 0;
 
-    let mut language_set = HashSet::new();
-
-    for entry in entries {
-        if let Ok(entry) = entry {
-            let path = entry.path();
+    for entry in inventory.entries() {
+        let path = entry.path();
+        if entry.file_type().is_file() {
+            file_count += 1;
+        }
 
-            if let Some(extension) = path.extension() {
-                if let Some(ext_str) = extension.to_str() {
-                    for (language, ext) in &programming_extensions {
-                        if ext_str.eq_ignore_ascii_case(ext) {
-                            language_set.insert(language.to_string());
-                        }
+        if let Some(extension) = path.extension() {
+            if let Some(ext_str) = extension.to_str() {
+                for (language, ext) in &programming_extensions {
+                    if ext_str.eq_ignore_ascii_case(ext) {
+                        *language_counts.entry(language).or_default() += 1;
                     }
                 }
             }
         }
     }
 
-    // Add detected languages as tags.
-    tags.extend(language_set.into_iter());
+    // Add languages with enough files as tags.
+    tags.extend(
+        language_counts
+            .into_iter()
+            .filter(|(_, count)| config.meets_extension_threshold("programming", *count, file_count))
+            .map(|(language, _)| tags::namespaced(LANGUAGE, language)),
+    );
+
+    // Add tags for infrastructure code shipped with the project (e.g. Dockerfiles).
+    tags.extend(generate_infrastructure_tags(inventory));
 
-    // Add general programming tags.
-    tags.push("cli".to_string());
-    tags.push("software development".to_string());
+    // Add the `tests` tag if the project has a test suite.
+    tags.extend(generate_test_tags(inventory));
 
     // **Condition**: If `Cargo.toml` exists, add the "rust" tag.
-    let cargo_toml_path = directory.join("Cargo.toml");
+    let cargo_toml_path = inventory.root().join("Cargo.toml");
     if cargo_toml_path.exists() {
-        tags.push("rust".to_string());
-        info!("Detected Cargo.toml. Added 'rust' tag.");
+        tags.push(tags::namespaced(LANGUAGE, "rust"));
+        debug!("Detected Cargo.toml. Added 'lang:rust' tag.");
     } else {
-        info!("Cargo.toml not found. 'rust' tag not added.");
+        debug!("Cargo.toml not found. 'lang:rust' tag not added.");
     }
 
-    info!("Programming tags generated: {:?}", tags);
+    debug!("Programming tags generated: {:?}", tags);
 
     tags
 }
@@ -105,3 +166,117 @@ pub fn extract_cargo_dependencies(directory: &Path) -> Vec<String> {
 
     dependencies
 }
+
+/// Extracts toolchain fields (`msrv`, `edition`) from the `[package]` table of Cargo.toml.
+///
+/// # Arguments
+///
+/// * `directory` - A reference to the project's directory path.
+///
+/// # Returns
+///
+/// A table of the fields found, empty if there is no readable Cargo.toml.
+pub fn extract_cargo_fields(directory: &Path) -> DetectorFields {
+    let mut fields = DetectorFields::new();
+
+    let cargo_toml_path = directory.join("Cargo.toml");
+    let Ok(content) = fs::read_to_string(&cargo_toml_path) else {
+        return fields;
+    };
+    let Ok(parsed) = content.parse::<toml::Table>() else {
+        return fields;
+    };
+
+    if let Some(package) = parsed.get("package").and_then(|package| package.as_table()) {
+        if let Some(msrv) = package.get("rust-version").and_then(|value| value.as_str()) {
+            fields.insert("msrv".to_string(), toml::Value::String(msrv.to_string()));
+        }
+        if let Some(edition) = package.get("edition").and_then(|value| value.as_str()) {
+            fields.insert("edition".to_string(), toml::Value::String(edition.to_string()));
+        }
+    }
+
+    fields
+}
+
+/// Extracts the package name from the project's manifest.
+///
+/// Checks `Cargo.toml` (`package.name`), `package.json` (`name`), and `pyproject.toml`
+/// (`project.name` or `tool.poetry.name`), in that order.
+///
+/// # Arguments
+///
+/// * `directory` - A reference to the project's directory path.
+///
+/// # Returns
+///
+/// The package name, or `None` if no manifest declares one.
+pub fn extract_manifest_name(directory: &Path) -> Option<String> {
+    let toml_name = |file: &str, keys: &[&[&str]]| -> Option<String> {
+        let content = fs::read_to_string(directory.join(file)).ok()?;
+        let parsed = content.parse::<toml::Table>().ok()?;
+        keys.iter().find_map(|path| {
+            let mut value = parsed.get(path[0])?;
+            for key in &path[1..] {
+                value = value.get(key)?;
+            }
+            value.as_str().map(str::to_string)
+        })
+    };
+
+    let name = toml_name("Cargo.toml", &[&["package", "name"]])
+        .or_else(|| {
+            let content = fs::read_to_string(directory.join("package.json")).ok()?;
+            let parsed: serde_json::Value = serde_json::from_str(&content).ok()?;
+            parsed.get("name")?.as_str().map(str::to_string)
+        })
+        .or_else(|| toml_name("pyproject.toml", &[&["project", "name"], &["tool", "poetry", "name"]]))
+        .filter(|name| !name.trim().is_empty());
+
+    debug!("Manifest name: {:?}", name);
+    name
+}
+
+/// Extracts the package version from the project's manifest.
+///
+/// Checks `Cargo.toml` (`package.version`, or `workspace.package.version` for crates
+/// that inherit it), `package.json` (`version`), and `pyproject.toml`
+/// (`project.version` or `tool.poetry.version`), in that order.
+///
+/// # Arguments
+///
+/// * `directory` - A reference to the project's directory path.
+///
+/// # Returns
+///
+/// The version, or `None` if no manifest declares one.
+pub fn extract_manifest_version(directory: &Path) -> Option<String> {
+    let toml_version = |file: &str, keys: &[&[&str]]| -> Option<String> {
+        let content = fs::read_to_string(directory.join(file)).ok()?;
+        let parsed = content.parse::<toml::Table>().ok()?;
+        keys.iter().find_map(|path| {
+            let mut value = parsed.get(path[0])?;
+            for key in &path[1..] {
+                value = value.get(key)?;
+            }
+            value.as_str().map(str::to_string)
+        })
+    };
+
+    let version = toml_version("Cargo.toml", &[&["package", "version"], &["workspace", "package", "version"]])
+        .or_else(|| {
+            let content = fs::read_to_string(directory.join("package.json")).ok()?;
+            let parsed: serde_json::Value = serde_json::from_str(&content).ok()?;
+            parsed.get("version")?.as_str().map(str::to_string)
+        })
+        .or_else(|| {
+            toml_version(
+                "pyproject.toml",
+                &[&["project", "version"], &["tool", "poetry", "version"]],
+            )
+        })
+        .filter(|version| !version.trim().is_empty());
+
+    debug!("Manifest version: {:?}", version);
+    version
+}
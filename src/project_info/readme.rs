@@ -0,0 +1,234 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use log::debug;
+
+/// The description length used when the configuration sets none.
+pub const DEFAULT_DESCRIPTION_LENGTH: usize = 200;
+
+/// README file names, checked in this order and compared case-insensitively.
+const README_FILES: &[&str] = &["README.md", "README.markdown", "README.rst", "README.txt", "README"];
+
+/// Extracts a one-line description from the project's README.
+///
+/// The title (the first heading) and the first paragraph of prose are joined with a
+/// dash; badges, images, HTML, and reStructuredText directives are skipped, and
+/// Markdown links and emphasis are reduced to their text.
+///
+/// # Arguments
+///
+/// * `directory` - A reference to the project's directory path.
+/// * `max_length` - The maximum number of characters, after which the description is
+///   cut at a word boundary and ends with an ellipsis.
+///
+/// # Returns
+///
+/// The description, or `None` if there is no README or it holds no text.
+pub fn extract_description(directory: &Path, max_length: usize) -> Option<String> {
+    let path = readme_file(directory)?;
+    let content = fs::read_to_string(&path).ok()?;
+    let is_rst = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("rst"));
+
+    let (_, body) = split_frontmatter(&content);
+    let (title, paragraph) = title_and_paragraph(body, is_rst);
+    let description = match (title, paragraph) {
+        (Some(title), Some(paragraph)) => format!("{} — {}", title, paragraph),
+        (Some(text), None) | (None, Some(text)) => text,
+        (None, None) => return None,
+    };
+
+    let description = truncate(&description, max_length);
+    debug!("Description: {}", description);
+    Some(description)
+}
+
+/// Splits a YAML frontmatter block, delimited by `---` lines at the top of the file,
+/// from the rest of a Markdown document.
+///
+/// # Returns
+///
+/// The frontmatter without its delimiters, if there is one, and the rest of the document.
+pub fn split_frontmatter(content: &str) -> (Option<&str>, &str) {
+    let Some(rest) = content.strip_prefix("---\n").or_else(|| content.strip_prefix("---\r\n")) else {
+        return (None, content);
+    };
+
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            return (Some(&rest[..offset]), &rest[offset + line.len()..]);
+        }
+        offset += line.len();
+    }
+    (None, content)
+}
+
+/// Returns the project's README file, if any.
+pub fn readme_file(directory: &Path) -> Option<PathBuf> {
+    let entries: Vec<_> = fs::read_dir(directory).ok()?.flatten().collect();
+    README_FILES.iter().find_map(|readme| {
+        entries
+            .iter()
+            .find(|entry| entry.file_name().to_string_lossy().eq_ignore_ascii_case(readme))
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file())
+    })
+}
+
+/// Finds the first heading and the first paragraph of prose in a README.
+fn title_and_paragraph(content: &str, is_rst: bool) -> (Option<String>, Option<String>) {
+    let lines: Vec<&str> = content.lines().map(str::trim).collect();
+    let mut title = None;
+    let mut paragraph: Vec<String> = Vec::new();
+
+    let mut index = 0;
+    while index < lines.len() {
+        let line = lines[index];
+        let next = lines.get(index + 1).copied().unwrap_or_default();
+
+        // Setext (Markdown) and reStructuredText headings are underlined.
+        let is_underlined = !line.is_empty() && is_underline(next, is_rst);
+        let heading = if let Some(heading) = line.strip_prefix('#') {
+            Some(heading.trim_start_matches('#').trim())
+        } else if is_underlined {
+            index += 1;
+            Some(line)
+        } else {
+            None
+        };
+
+        if let Some(heading) = heading {
+            if !paragraph.is_empty() {
+                break;
+            }
+            if title.is_none() && !heading.is_empty() {
+                title = Some(plain_text(heading));
+            }
+        } else if line.is_empty() || is_underline(line, is_rst) {
+            if !paragraph.is_empty() {
+                break;
+            }
+        } else if !is_decoration(line) {
+            paragraph.push(plain_text(line));
+        }
+
+        index += 1;
+    }
+
+    let paragraph = paragraph.join(" ");
+    (title, (!paragraph.is_empty()).then_some(paragraph))
+}
+
+/// Returns `true` for a heading underline: `===` or `---` in Markdown, and any
+/// repeated punctuation in reStructuredText.
+fn is_underline(line: &str, is_rst: bool) -> bool {
+    let Some(first) = line.chars().next() else {
+        return false;
+    };
+    let allowed = if is_rst {
+        first.is_ascii_punctuation()
+    } else {
+        first == '=' || first == '-'
+    };
+    allowed && line.len() >= 3 && line.chars().all(|c| c == first)
+}
+
+/// Returns `true` for lines that are not prose: badges, images, HTML, and
+/// reStructuredText directives or comments.
+fn is_decoration(line: &str) -> bool {
+    line.starts_with("[![")
+        || line.starts_with("![")
+        || line.starts_with('<')
+        || line.starts_with("..")
+        || line.starts_with(":")
+        || line.starts_with("```")
+}
+
+/// Reduces Markdown links to their text and removes emphasis and code markers.
+fn plain_text(line: &str) -> String {
+    let mut text = String::new();
+    let mut rest = line;
+
+    // Replace `[text](url)` with `text`.
+    while let Some(start) = rest.find('[') {
+        let Some(middle) = rest[start..].find("](") else {
+            break;
+        };
+        let Some(end) = rest[start + middle..].find(')') else {
+            break;
+        };
+        text.push_str(&rest[..start]);
+        text.push_str(&rest[start + 1..start + middle]);
+        rest = &rest[start + middle + end + 1..];
+    }
+    text.push_str(rest);
+
+    text.replace("**", "")
+        .replace("__", "")
+        .replace('`', "")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Cuts the text to at most `max_length` characters at a word boundary.
+fn truncate(text: &str, max_length: usize) -> String {
+    if text.chars().count() <= max_length {
+        return text.to_string();
+    }
+
+    // Leave room for the ellipsis.
+    let cut: String = text.chars().take(max_length.saturating_sub(1)).collect();
+    let cut = match cut.rfind(' ') {
+        Some(space) if space > 0 => &cut[..space],
+        _ => cut.as_str(),
+    };
+    format!("{}…", cut.trim_end_matches(|c: char| c.is_whitespace() || c == ',' || c == '.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_extract_description_markdown() {
+        let dir = tempdir().unwrap();
+        assert!(extract_description(dir.path(), 200).is_none());
+
+        fs::write(
+            dir.path().join("readme.md"),
+            "[![CI](https://example.com/badge.svg)](https://example.com)\n\n\
+             # Beat Box\n\n\
+             A **tiny** drum machine written in\n[Rust](https://www.rust-lang.org).\n\n\
+             ## Usage\n\nRun it.\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            extract_description(dir.path(), 200).as_deref(),
+            Some("Beat Box — A tiny drum machine written in Rust.")
+        );
+        assert_eq!(
+            extract_description(dir.path(), 25).as_deref(),
+            Some("Beat Box — A tiny drum…")
+        );
+    }
+
+    #[test]
+    fn test_extract_description_rst() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("README.rst"),
+            "=========\nAstroplot\n=========\n\n.. image:: logo.png\n\nPlots the night sky.\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            extract_description(dir.path(), 200).as_deref(),
+            Some("Astroplot — Plots the night sky.")
+        );
+    }
+}
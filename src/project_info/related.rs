@@ -0,0 +1,103 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::ProjectInfo;
+
+/// A link to another tagged project.
+///
+/// The identifier is what the link follows; the name, alias, and path are a snapshot
+/// taken when the link was made, so the file stays readable on its own and the
+/// project can still be found if it is moved.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelatedProject {
+    /// The linked project's `id`.
+    pub id: String,
+    /// The linked project's name.
+    pub name: String,
+    /// The linked project's alias, if it had one.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub alias: String,
+    /// The absolute path of the linked project's directory.
+    pub path: PathBuf,
+}
+
+impl RelatedProject {
+    /// Describes the linked project, preferring its alias over its name.
+    pub fn label(&self) -> String {
+        let name = if self.alias.is_empty() { &self.name } else { &self.alias };
+        format!("{} ({})", name, self.path.display())
+    }
+}
+
+/// Links two projects to each other, recording each in the other's `related` list.
+///
+/// Projects saved before identifiers existed are given one first. Linking projects
+/// that are already linked refreshes the recorded names and paths.
+///
+/// # Arguments
+///
+/// * `a`, `b` - The two projects' information.
+/// * `a_dir`, `b_dir` - The two projects' directories.
+pub fn link(a: &mut ProjectInfo, a_dir: &Path, b: &mut ProjectInfo, b_dir: &Path) {
+    for project in [&mut *a, &mut *b] {
+        if project.id.is_empty() {
+            project.id = Uuid::new_v4().to_string();
+        }
+    }
+
+    let b_reference = reference(b, b_dir);
+    let a_reference = reference(a, a_dir);
+    add(a, b_reference);
+    add(b, a_reference);
+}
+
+fn reference(project: &ProjectInfo, directory: &Path) -> RelatedProject {
+    RelatedProject {
+        id: project.id.clone(),
+        name: project.name.clone(),
+        alias: project.alias.clone(),
+        path: fs::canonicalize(directory).unwrap_or_else(|_| directory.to_path_buf()),
+    }
+}
+
+/// Adds or replaces the link with the same identifier.
+fn add(project: &mut ProjectInfo, reference: RelatedProject) {
+    match project.related.iter_mut().find(|related| related.id == reference.id) {
+        Some(existing) => *existing = reference,
+        None => project.related.push(reference),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_link_is_bidirectional_and_idempotent() {
+        let mut pack = ProjectInfo {
+            name: "drum-pack".to_string(),
+            id: "pack-id".to_string(),
+            ..Default::default()
+        };
+        let mut album = ProjectInfo {
+            name: "album".to_string(),
+            alias: "lp".to_string(),
+            ..Default::default()
+        };
+
+        link(&mut pack, Path::new("/samples/drum-pack"), &mut album, Path::new("/music/album"));
+        link(&mut pack, Path::new("/samples/drum-pack"), &mut album, Path::new("/music/album"));
+
+        // The album had no identifier yet, so it was given one.
+        assert!(!album.id.is_empty());
+        assert_eq!(pack.related.len(), 1);
+        assert_eq!(pack.related[0].id, album.id);
+        assert_eq!(pack.related[0].label(), "lp (/music/album)");
+        assert_eq!(album.related.len(), 1);
+        assert_eq!(album.related[0].id, "pack-id");
+        assert_eq!(album.related[0].label(), "drum-pack (/samples/drum-pack)");
+    }
+}
@@ -0,0 +1,116 @@
+//! Where a music project's samples come from.
+//!
+//! Samples from commercial libraries are recognized by the folder they were
+//! downloaded to (`Splice/`, `Loopmasters/`) or by the library's naming pattern
+//! (`VEC1 Kick 01.wav`), so their licenses can be checked before a release. Every
+//! other audio file counts as recorded in the project.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use super::inventory::Inventory;
+use super::music::AUDIO_EXTENSIONS;
+
+/// Well-known sample libraries and the words that mark their folders and files.
+///
+/// Markers are compared as whole lowercase words of each folder and file name.
+pub const SAMPLE_LIBRARIES: &[(&str, &[&str])] = &[
+    ("splice", &["splice"]),
+    ("loopmasters", &["loopmasters"]),
+    ("vengeance", &["vengeance", "vec1", "vec2", "vec3", "vec4"]),
+    ("cymatics", &["cymatics"]),
+    ("black octopus", &["black octopus"]),
+    ("native instruments", &["native instruments"]),
+    ("ghosthack", &["ghosthack"]),
+    ("samplephonics", &["samplephonics"]),
+    ("looperman", &["looperman"]),
+];
+
+/// The provenance of a project's audio files.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SampleProvenance {
+    /// The libraries samples were taken from, e.g. `splice`.
+    pub libraries: BTreeSet<&'static str>,
+    /// The number of audio files from a known library.
+    pub external: usize,
+    /// The number of other audio files.
+    pub recorded: usize,
+}
+
+/// Returns the library a sample belongs to, judging by its path within the project.
+pub fn sample_library(relative_path: &Path) -> Option<&'static str> {
+    let names: Vec<String> = relative_path
+        .iter()
+        .map(|name| {
+            let name = Path::new(name).file_stem().unwrap_or(name).to_string_lossy();
+            let words: Vec<String> = name
+                .split(|c: char| !c.is_alphanumeric())
+                .filter(|word| !word.is_empty())
+                .map(str::to_lowercase)
+                .collect();
+            format!(" {} ", words.join(" "))
+        })
+        .collect();
+
+    SAMPLE_LIBRARIES
+        .iter()
+        .find(|(_, markers)| {
+            markers
+                .iter()
+                .any(|marker| names.iter().any(|name| name.contains(&format!(" {} ", marker))))
+        })
+        .map(|(library, _)| *library)
+}
+
+/// Sorts the project's audio files into samples from known libraries and recordings.
+pub fn sample_provenance(inventory: &Inventory) -> SampleProvenance {
+    let mut provenance = SampleProvenance::default();
+
+    for entry in inventory.files() {
+        let path = entry.path();
+        let is_audio = path.extension().is_some_and(|ext| {
+            AUDIO_EXTENSIONS.iter().any(|audio_ext| ext.eq_ignore_ascii_case(audio_ext))
+        });
+        if !is_audio {
+            continue;
+        }
+
+        let relative = path.strip_prefix(inventory.root()).unwrap_or(path);
+        match sample_library(relative) {
+            Some(library) => {
+                provenance.libraries.insert(library);
+                provenance.external += 1;
+            }
+            None => provenance.recorded += 1,
+        }
+    }
+    provenance
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{self, File};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_sample_provenance() {
+        assert_eq!(sample_library(Path::new("Splice/Samples/packs/Lofi Keys/keys_90.wav")), Some("splice"));
+        assert_eq!(sample_library(Path::new("drums/VEC1 Kick 01.wav")), Some("vengeance"));
+        assert_eq!(sample_library(Path::new("Black_Octopus-Bass/sub.wav")), Some("black octopus"));
+        assert_eq!(sample_library(Path::new("vocals/spliced_take.wav")), None);
+
+        let dir = tempdir().unwrap();
+        let dir_path = dir.path();
+        fs::create_dir_all(dir_path.join("Loopmasters/Deep House")).unwrap();
+        File::create(dir_path.join("Loopmasters/Deep House/hat_loop.wav")).unwrap();
+        File::create(dir_path.join("VEC2 Snare 12.wav")).unwrap();
+        File::create(dir_path.join("vocal_take_3.wav")).unwrap();
+        File::create(dir_path.join("notes.txt")).unwrap();
+
+        let provenance = sample_provenance(&Inventory::scan(dir_path));
+        assert_eq!(provenance.libraries, BTreeSet::from(["loopmasters", "vengeance"]));
+        assert_eq!(provenance.external, 2);
+        assert_eq!(provenance.recorded, 1);
+    }
+}
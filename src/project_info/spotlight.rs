@@ -0,0 +1,92 @@
+use std::path::Path;
+use std::process::Command;
+
+use chrono::{DateTime, Local};
+use log::{debug, warn};
+
+/// Spotlight attribute holding when an item was added to its current folder.
+pub const DATE_ADDED_ATTRIBUTE: &str = "kMDItemDateAdded";
+
+/// Spotlight attribute holding when an item was last opened.
+pub const LAST_OPENED_ATTRIBUTE: &str = "kMDItemLastUsedDate";
+
+/// Dates macOS records in Spotlight metadata, which follow the user's own activity
+/// more closely than file-system timestamps that builds and syncs keep touching.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpotlightDates {
+    /// When the directory was added to its current folder (e.g. copied or moved there).
+    pub date_added: Option<DateTime<Local>>,
+    /// When the directory was last opened in Finder or an application.
+    pub last_opened: Option<DateTime<Local>>,
+}
+
+/// Reads the "date added" and "last opened" dates of a directory with `mdls`.
+///
+/// Dates are only available on macOS, and only when Spotlight has indexed the
+/// directory; elsewhere both are `None`.
+///
+/// # Arguments
+///
+/// * `directory` - A reference to the project's directory path.
+pub fn read_dates(directory: &Path) -> SpotlightDates {
+    if !cfg!(target_os = "macos") {
+        return SpotlightDates::default();
+    }
+
+    let output = match Command::new("mdls")
+        .args(["-raw", "-name", DATE_ADDED_ATTRIBUTE, "-name", LAST_OPENED_ATTRIBUTE])
+        .arg(directory)
+        .output()
+    {
+        Ok(output) if output.status.success() => output,
+        Ok(output) => {
+            warn!(
+                "mdls failed for {}: {}",
+                directory.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            return SpotlightDates::default();
+        }
+        Err(e) => {
+            warn!("Unable to run mdls: {}", e);
+            return SpotlightDates::default();
+        }
+    };
+
+    let dates = parse_mdls_output(&String::from_utf8_lossy(&output.stdout));
+    debug!("Spotlight dates for {}: {:?}", directory.display(), dates);
+    dates
+}
+
+/// Parses the output of `mdls -raw -name kMDItemDateAdded -name kMDItemLastUsedDate`.
+///
+/// With `-raw`, values are separated by NUL bytes in the order they were requested,
+/// and missing attributes print as `(null)`.
+fn parse_mdls_output(output: &str) -> SpotlightDates {
+    let mut values = output.split('\0').map(parse_mdls_date);
+    SpotlightDates {
+        date_added: values.next().flatten(),
+        last_opened: values.next().flatten(),
+    }
+}
+
+/// Parses a date printed by `mdls`, such as `2024-03-09 18:22:41 +0000`.
+fn parse_mdls_date(value: &str) -> Option<DateTime<Local>> {
+    DateTime::parse_from_str(value.trim(), "%Y-%m-%d %H:%M:%S %z")
+        .ok()
+        .map(|date| date.with_timezone(&Local))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mdls_output() {
+        let dates = parse_mdls_output("2024-03-09 18:22:41 +0000\0(null)");
+        assert_eq!(dates.date_added.map(|date| date.timestamp()), Some(1_710_008_561));
+        assert_eq!(dates.last_opened, None);
+
+        assert_eq!(parse_mdls_output(""), SpotlightDates::default());
+    }
+}
@@ -0,0 +1,127 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// The number of subdirectories kept in [`DirectoryStats::largest_subdirs`].
+const LARGEST_SUBDIRS: usize = 5;
+
+/// A top-level subdirectory and the size of everything below it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubdirSize {
+    /// The subdirectory, relative to the project.
+    pub path: String,
+    /// The total size of the files below it, in bytes.
+    pub size_bytes: u64,
+}
+
+/// Disk usage of a project directory.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DirectoryStats {
+    /// The total size of every file below the directory, in bytes.
+    pub total_size_bytes: u64,
+    /// The number of files below the directory.
+    pub file_count: usize,
+    /// The largest top-level subdirectories holding any files, largest first.
+    pub largest_subdirs: Vec<SubdirSize>,
+}
+
+/// Accumulates [`DirectoryStats`] from the files met while walking a directory.
+#[derive(Debug, Default)]
+pub(crate) struct StatsBuilder {
+    stats: DirectoryStats,
+    subdir_sizes: BTreeMap<String, u64>,
+}
+
+impl StatsBuilder {
+    /// Counts a file, given its path relative to the directory and its size.
+    pub(crate) fn add_file(&mut self, relative: &Path, size_bytes: u64) {
+        self.stats.total_size_bytes += size_bytes;
+        self.stats.file_count += 1;
+
+        let mut components = relative.components();
+        if let (Some(subdir), Some(_)) = (components.next(), components.next()) {
+            *self
+                .subdir_sizes
+                .entry(subdir.as_os_str().to_string_lossy().to_string())
+                .or_default() += size_bytes;
+        }
+    }
+
+    /// Returns the statistics, keeping only the largest subdirectories.
+    pub(crate) fn finish(self) -> DirectoryStats {
+        let mut stats = self.stats;
+        let mut largest_subdirs: Vec<SubdirSize> = self
+            .subdir_sizes
+            .into_iter()
+            .map(|(path, size_bytes)| SubdirSize { path, size_bytes })
+            .collect();
+        largest_subdirs.sort_by(|a, b| b.size_bytes.cmp(&a.size_bytes).then_with(|| a.path.cmp(&b.path)));
+        largest_subdirs.truncate(LARGEST_SUBDIRS);
+        stats.largest_subdirs = largest_subdirs;
+        stats
+    }
+}
+
+/// Formats a size in bytes for display, e.g. `40.2 GB`, using decimal units.
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: &[&str] = &["KB", "MB", "GB", "TB"];
+
+    if bytes < 1000 {
+        return format!("{} B", bytes);
+    }
+
+    let mut size = bytes as f64;
+    let mut unit = "B";
+    for next_unit in UNITS {
+        if size < 1000.0 {
+            break;
+        }
+        size /= 1000.0;
+        unit = next_unit;
+    }
+    format!("{:.1} {}", size, unit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project_info::inventory::Inventory;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_compute_stats() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("renders/final")).unwrap();
+        fs::create_dir_all(dir.path().join("stems")).unwrap();
+        fs::create_dir_all(dir.path().join("empty")).unwrap();
+        fs::write(dir.path().join("song.rpp"), vec![0; 10]).unwrap();
+        fs::write(dir.path().join("renders/final/mix.wav"), vec![0; 3000]).unwrap();
+        fs::write(dir.path().join("stems/kick.wav"), vec![0; 500]).unwrap();
+
+        let stats = Inventory::scan(dir.path()).stats().clone();
+        assert_eq!(stats.total_size_bytes, 3510);
+        assert_eq!(stats.file_count, 3);
+        assert_eq!(
+            stats.largest_subdirs,
+            vec![
+                SubdirSize {
+                    path: "renders".to_string(),
+                    size_bytes: 3000
+                },
+                SubdirSize {
+                    path: "stems".to_string(),
+                    size_bytes: 500
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1_500), "1.5 KB");
+        assert_eq!(format_size(40_200_000_000), "40.2 GB");
+    }
+}
@@ -0,0 +1,73 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// Where a project is in its lifecycle, as set by the user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProjectStatus {
+    /// Being worked on.
+    Active,
+    /// Set aside for now, to be picked up again.
+    Paused,
+    /// Finished or abandoned, and kept for reference.
+    Archived,
+    /// Published, shipped, or otherwise released.
+    Released,
+}
+
+impl ProjectStatus {
+    /// Every status, in lifecycle order.
+    pub const ALL: [ProjectStatus; 4] = [
+        ProjectStatus::Active,
+        ProjectStatus::Paused,
+        ProjectStatus::Archived,
+        ProjectStatus::Released,
+    ];
+
+    /// Returns the name used in `project_info.toml` and on the command line.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ProjectStatus::Active => "active",
+            ProjectStatus::Paused => "paused",
+            ProjectStatus::Archived => "archived",
+            ProjectStatus::Released => "released",
+        }
+    }
+}
+
+impl fmt::Display for ProjectStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ProjectStatus {
+    type Err = String;
+
+    /// Parses a status name, ignoring case.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim().to_lowercase();
+        Self::ALL.into_iter().find(|status| status.as_str() == value).ok_or_else(|| {
+            let names: Vec<&str> = Self::ALL.iter().map(|status| status.as_str()).collect();
+            format!("unknown status '{}' (expected one of: {})", value, names.join(", "))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_status() {
+        assert_eq!("Archived".parse(), Ok(ProjectStatus::Archived));
+        assert_eq!(" paused ".parse(), Ok(ProjectStatus::Paused));
+        assert!("done".parse::<ProjectStatus>().unwrap_err().contains("active, paused, archived, released"));
+
+        for status in ProjectStatus::ALL {
+            assert_eq!(status.to_string().parse(), Ok(status));
+        }
+    }
+}
@@ -0,0 +1,176 @@
+//! Namespaced tags.
+//!
+//! Tags that name something specific carry a namespace, written `namespace:value`
+//! (e.g. `lang:rust`, `format:WAV`, `daw:Reaper`), so they can be searched and
+//! deduplicated by kind. General tags that describe the project as a whole, such as
+//! `audio` or `software development`, stay flat.
+//!
+//! Every generated tag goes through [`normalize`], so tags are lowercase and spelled
+//! one way (`format:aif` and `format:AIFF` both become `format:aiff`).
+
+use std::collections::BTreeMap;
+
+/// Programming languages, e.g. `lang:rust`.
+pub const LANGUAGE: &str = "lang";
+/// File formats, e.g. `format:WAV` or `format:markdown`.
+pub const FORMAT: &str = "format";
+/// Digital audio workstations, e.g. `daw:Reaper`.
+pub const DAW: &str = "daw";
+/// Video editors, e.g. `editor:Premiere Pro`.
+pub const EDITOR: &str = "editor";
+/// Other applications and tool chains, e.g. `tool:docker` or `tool:Blender`.
+pub const TOOL: &str = "tool";
+/// Libraries the project depends on, e.g. `lib:pandas`.
+pub const LIBRARY: &str = "lib";
+/// SPDX license identifiers, e.g. `license:MIT`.
+pub const LICENSE: &str = "license";
+/// Where a project's git repository is hosted, e.g. `host:github` or
+/// `host:self-hosted`.
+pub const HOST: &str = "host";
+/// CI services a project has pipelines for, e.g. `ci:github-actions`.
+pub const CI: &str = "ci";
+/// Version control systems, e.g. `vcs:git` or `vcs:svn`.
+pub const VCS: &str = "vcs";
+/// Video codecs, e.g. `codec:prores`.
+pub const CODEC: &str = "codec";
+/// Video resolutions, e.g. `resolution:4k`.
+pub const RESOLUTION: &str = "resolution";
+/// Camera makers, e.g. `camera:Nikon`.
+pub const CAMERA: &str = "camera";
+/// Embedded boards, platforms, and frameworks, e.g. `board:esp32dev`.
+pub const BOARD: &str = "board";
+/// Repository state, e.g. `git:dirty`.
+pub const GIT: &str = "git";
+/// Musical genres from the user's taxonomy, e.g. `genre:drum-and-bass`.
+pub const GENRE: &str = "genre";
+/// Moods from the user's taxonomy, e.g. `mood:dark`.
+pub const MOOD: &str = "mood";
+/// Sample libraries a music project's samples come from, e.g. `source:splice`.
+pub const SOURCE: &str = "source";
+
+/// Built-in synonyms, applied after the user's own. A key without a namespace
+/// matches the value of a tag in any namespace.
+pub const SYNONYMS: &[(&str, &str)] = &[
+    // Languages.
+    ("js", "javascript"),
+    ("ts", "typescript"),
+    ("py", "python"),
+    ("golang", "go"),
+    ("c++", "cpp"),
+    ("csharp", "c#"),
+    ("rs", "rust"),
+    // Formats.
+    ("aif", "aiff"),
+    ("jpeg", "jpg"),
+    ("tif", "tiff"),
+    ("yml", "yaml"),
+    ("md", "markdown"),
+    ("tex", "latex"),
+    // Tools.
+    ("k8s", "kubernetes"),
+    ("tf", "terraform"),
+    ("ableton", "ableton live"),
+    ("logic", "logic pro"),
+    ("fcp", "final cut pro"),
+    ("fcpx", "final cut pro"),
+    ("davinci resolve", "resolve"),
+];
+
+/// Builds a namespaced tag.
+pub fn namespaced(namespace: &str, value: impl AsRef<str>) -> String {
+    format!("{}:{}", namespace, value.as_ref())
+}
+
+/// Splits a tag into its namespace, if it has one, and its value.
+pub fn split(tag: &str) -> (Option<&str>, &str) {
+    match tag.split_once(':') {
+        Some((namespace, value)) if !namespace.is_empty() && !namespace.contains(' ') => {
+            (Some(namespace), value)
+        }
+        _ => (None, tag),
+    }
+}
+
+/// Normalizes a tag: lowercases it, collapses runs of whitespace, and replaces
+/// synonyms.
+///
+/// `synonyms` are the user's own, checked before [`SYNONYMS`]. In either table a key
+/// with a namespace (`lang:js`) matches that exact tag, and a key without one (`js`)
+/// matches the value of a tag in any namespace. A replacement with a namespace
+/// replaces the whole tag; one without keeps the tag's namespace.
+pub fn normalize(tag: &str, synonyms: &BTreeMap<String, String>) -> String {
+    let tag = tag.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+    let (namespace, value) = split(&tag);
+
+    let lookup = |key: &str| {
+        synonyms
+            .iter()
+            .find(|(synonym, _)| synonym.eq_ignore_ascii_case(key))
+            .map(|(_, replacement)| replacement.as_str())
+            .or_else(|| SYNONYMS.iter().find(|(synonym, _)| *synonym == key).map(|(_, replacement)| *replacement))
+    };
+
+    if let Some(replacement) = lookup(&tag) {
+        return replacement.to_lowercase();
+    }
+    match (namespace, lookup(value)) {
+        (Some(namespace), Some(replacement)) if split(replacement).0.is_none() => {
+            namespaced(namespace, replacement.to_lowercase())
+        }
+        (_, Some(replacement)) => replacement.to_lowercase(),
+        (_, None) => tag,
+    }
+}
+
+/// Returns `true` if the tag matches a search pattern, ignoring case.
+///
+/// * `lang:` or `lang:*` matches every tag in the namespace.
+/// * `lang:rust` matches that exact tag.
+/// * `rust` matches the flat tag `rust` and the value of any namespaced tag, such as
+///   `lang:rust`.
+pub fn matches(tag: &str, pattern: &str) -> bool {
+    let (namespace, value) = split(tag);
+    match split(pattern) {
+        (Some(pattern_namespace), pattern_value) => {
+            namespace.is_some_and(|namespace| namespace.eq_ignore_ascii_case(pattern_namespace))
+                && (pattern_value.is_empty() || pattern_value == "*" || value.eq_ignore_ascii_case(pattern_value))
+        }
+        (None, pattern_value) => value.eq_ignore_ascii_case(pattern_value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_and_match() {
+        assert_eq!(split("lang:rust"), (Some("lang"), "rust"));
+        assert_eq!(split("software development"), (None, "software development"));
+        assert_eq!(split("mixed up: things"), (None, "mixed up: things"));
+
+        assert!(matches("lang:rust", "rust"));
+        assert!(matches("lang:rust", "LANG:Rust"));
+        assert!(matches("lang:rust", "lang:"));
+        assert!(matches("lang:rust", "lang:*"));
+        assert!(matches("cli", "cli"));
+        assert!(!matches("lang:rust", "format:"));
+        assert!(!matches("rust", "lang:rust"));
+        assert!(!matches("format:WAV", "lang:wav"));
+    }
+
+    #[test]
+    fn test_normalize() {
+        let mut synonyms = BTreeMap::new();
+        assert_eq!(normalize("format:AIF", &synonyms), "format:aiff");
+        assert_eq!(normalize("lang:js", &synonyms), "lang:javascript");
+        assert_eq!(normalize("daw:Logic  Pro", &synonyms), "daw:logic pro");
+        assert_eq!(normalize("Software Development", &synonyms), "software development");
+
+        // The user's synonyms win, and may move a tag to another namespace.
+        synonyms.insert("js".to_string(), "lang:ecmascript".to_string());
+        synonyms.insert("lang:go".to_string(), "lang:golang".to_string());
+        assert_eq!(normalize("JS", &synonyms), "lang:ecmascript");
+        assert_eq!(normalize("lang:Go", &synonyms), "lang:golang");
+    }
+}
@@ -0,0 +1,138 @@
+//! A user taxonomy of keywords found in folder and file names.
+//!
+//! Producers often organize by genre and mood (`DnB/`, `lofi_chords_84bpm.wav`), which
+//! no file format records. The taxonomy maps such keywords to tags, for example:
+//!
+//! ```toml
+//! dnb = "genre:drum-and-bass"
+//! "drum and bass" = "genre:drum-and-bass"
+//! lofi = ["genre:lo-fi", "mood:chill"]
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::config::Config;
+
+use super::inventory::Inventory;
+
+/// The tag or tags a keyword stands for.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+enum Tags {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Tags {
+    fn as_slice(&self) -> &[String] {
+        match self {
+            Tags::One(tag) => std::slice::from_ref(tag),
+            Tags::Many(tags) => tags,
+        }
+    }
+}
+
+/// Keywords mapped to the tags they stand for, such as `dnb` to
+/// `genre:drum-and-bass`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(transparent)]
+pub struct Taxonomy {
+    keywords: BTreeMap<String, Tags>,
+}
+
+impl Taxonomy {
+    /// Loads a taxonomy from a TOML file of `keyword = "tag"` or
+    /// `keyword = ["tag", ...]` entries.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let content = fs::read_to_string(path)?;
+        toml::from_str(&content).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid taxonomy in {}: {}", path.display(), e),
+            )
+        })
+    }
+
+    /// Returns the default taxonomy location: `taxonomy.toml` next to the
+    /// configuration file.
+    pub fn default_location() -> io::Result<PathBuf> {
+        Ok(Config::default_location()?.with_file_name("taxonomy.toml"))
+    }
+
+    /// Returns `true` if the taxonomy has no keywords.
+    pub fn is_empty(&self) -> bool {
+        self.keywords.is_empty()
+    }
+
+    /// Generates the tags of the keywords found in the project's directory name and
+    /// the names of its files and folders.
+    ///
+    /// Names are compared as lowercase words, so `dnb` matches `DnB_Rollers` and
+    /// `drum and bass` matches `Drum-and-Bass`, but `dnb` does not match `dnbx`.
+    pub fn tags_for(&self, inventory: &Inventory) -> Vec<String> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+
+        let keywords: Vec<(String, &Tags)> =
+            self.keywords.iter().map(|(keyword, tags)| (words(keyword), tags)).collect();
+        let names = inventory.root().file_name().into_iter().chain(inventory.entries().map(|entry| {
+            let path = entry.path();
+            match path.file_stem() {
+                Some(stem) if entry.file_type().is_file() => stem,
+                _ => entry.file_name(),
+            }
+        }));
+
+        let mut found = BTreeSet::new();
+        for name in names {
+            let name = words(&name.to_string_lossy());
+            for (keyword, tags) in &keywords {
+                if !keyword.trim().is_empty() && name.contains(keyword.as_str()) {
+                    found.extend(tags.as_slice().iter().cloned());
+                }
+            }
+        }
+        found.into_iter().collect()
+    }
+}
+
+/// Lowercases a name and splits it into words, padded with spaces so a word can be
+/// searched for as ` word `.
+fn words(name: &str) -> String {
+    let words: Vec<String> = name
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    format!(" {} ", words.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_taxonomy_tags() {
+        let taxonomy: Taxonomy = toml::from_str(
+            "dnb = \"genre:drum-and-bass\"\n\"drum and bass\" = \"genre:drum-and-bass\"\nlofi = [\"genre:lo-fi\", \"mood:chill\"]\ndark = \"mood:dark\"\n",
+        )
+        .unwrap();
+
+        let dir = tempdir().unwrap();
+        let project = dir.path().join("DnB_Rollers");
+        fs::create_dir_all(project.join("Drum-and-Bass stems")).unwrap();
+        File::create(project.join("lofi_chords_84bpm.wav")).unwrap();
+        File::create(project.join("darkness.wav")).unwrap();
+
+        let tags = taxonomy.tags_for(&Inventory::scan(&project));
+        assert_eq!(tags, vec!["genre:drum-and-bass", "genre:lo-fi", "mood:chill"]);
+    }
+}
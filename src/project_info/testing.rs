@@ -0,0 +1,156 @@
+use std::fs;
+use std::path::{Component, Path};
+
+use log::debug;
+
+use super::inventory::Inventory;
+use super::tags::{self, TOOL};
+
+/// Test runners, by a configuration file at the project root that sets them up.
+const TEST_FRAMEWORKS: &[(&str, &str)] = &[
+    ("pytest.ini", "pytest"),
+    ("conftest.py", "pytest"),
+    ("jest.config.js", "jest"),
+    ("jest.config.ts", "jest"),
+    ("jest.config.mjs", "jest"),
+    ("jest.config.cjs", "jest"),
+    ("jest.config.json", "jest"),
+    ("vitest.config.js", "vitest"),
+    ("vitest.config.ts", "vitest"),
+    ("vitest.config.mts", "vitest"),
+];
+
+/// JavaScript and TypeScript extensions, whose test files are named `*.test.*` or
+/// `*.spec.*`.
+const SCRIPT_EXTENSIONS: &[&str] = &["js", "jsx", "mjs", "cjs", "ts", "tsx", "mts"];
+
+/// Returns `true` if a file looks like it holds tests, going by the conventions of
+/// each language's test runner:
+///
+/// - Rust files in a `tests` directory or with a `#[test]` function
+/// - Python `test_*.py` and `*_test.py` files
+/// - JavaScript and TypeScript `*.test.*` and `*.spec.*` files, and files in a
+///   `__tests__` directory
+/// - Go `*_test.go` files
+///
+/// # Arguments
+///
+/// * `path` - The file's path.
+/// * `relative` - The file's path relative to the project directory.
+pub fn is_test_file(path: &Path, relative: &Path) -> bool {
+    let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+    let Some((stem, extension)) = file_name.rsplit_once('.') else {
+        return false;
+    };
+    let in_directory = |name: &str| {
+        relative
+            .parent()
+            .is_some_and(|parent| parent.components().any(|component| component == Component::Normal(name.as_ref())))
+    };
+
+    match extension {
+        "rs" => {
+            in_directory("tests")
+                || fs::read_to_string(path).is_ok_and(|content| content.contains("#[test]") || content.contains("::test]"))
+        }
+        "py" => stem.starts_with("test_") || stem.ends_with("_test"),
+        "go" => stem.ends_with("_test"),
+        extension if SCRIPT_EXTENSIONS.contains(&extension) => {
+            stem.ends_with(".test") || stem.ends_with(".spec") || in_directory("__tests__")
+        }
+        _ => false,
+    }
+}
+
+/// Counts the project's test files (see [`is_test_file`]).
+pub fn count_test_files(inventory: &Inventory) -> usize {
+    inventory
+        .files()
+        .filter(|entry| {
+            let relative = entry.path().strip_prefix(inventory.root()).unwrap_or(entry.path());
+            is_test_file(entry.path(), relative)
+        })
+        .count()
+}
+
+/// Generates the `tests` tag for projects with test files or a configured test
+/// runner, and a `tool:` tag for each runner, such as `tool:pytest` or `tool:jest`.
+///
+/// # Arguments
+///
+/// * `inventory` - The project's inventory.
+///
+/// # Returns
+///
+/// A vector of test tags, empty when the project has no tests.
+pub fn generate_test_tags(inventory: &Inventory) -> Vec<String> {
+    let root = inventory.root();
+    let mut tags: Vec<String> = TEST_FRAMEWORKS
+        .iter()
+        .filter(|(file_name, _)| root.join(file_name).is_file())
+        .map(|(_, framework)| tags::namespaced(TOOL, framework))
+        .collect();
+    let pytest_configured = fs::read_to_string(root.join("pyproject.toml"))
+        .is_ok_and(|content| content.contains("[tool.pytest.ini_options]"));
+    if pytest_configured {
+        tags.push(tags::namespaced(TOOL, "pytest"));
+    }
+
+    if !tags.is_empty() || count_test_files(inventory) > 0 {
+        tags.push("tests".to_string());
+    }
+    tags.sort();
+    tags.dedup();
+
+    debug!("Test tags generated: {:?}", tags);
+
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_is_test_file() {
+        let dir = tempdir().unwrap();
+        let file = |relative: &str, content: &str| {
+            let path = dir.path().join(relative);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, content).unwrap();
+            is_test_file(&path, Path::new(relative))
+        };
+
+        assert!(file("tests/cli.rs", "fn main() {}"));
+        assert!(file("src/parser.rs", "#[cfg(test)]\nmod tests {\n    #[test]\n    fn parses() {}\n}\n"));
+        assert!(file("src/server.rs", "#[tokio::test]\nasync fn serves() {}\n"));
+        assert!(!file("src/main.rs", "fn main() {}"));
+        assert!(file("tests/test_api.py", ""));
+        assert!(file("pkg/api_test.go", ""));
+        assert!(!file("pkg/api.go", ""));
+        assert!(file("src/App.test.tsx", ""));
+        assert!(file("src/__tests__/app.js", ""));
+        assert!(!file("src/app.js", ""));
+    }
+
+    #[test]
+    fn test_generate_test_tags() {
+        let dir = tempdir().unwrap();
+        fs::create_dir(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("src/main.rs"), "fn main() {}").unwrap();
+        assert!(generate_test_tags(&Inventory::scan(dir.path())).is_empty());
+
+        fs::write(dir.path().join("pytest.ini"), "[pytest]\n").unwrap();
+        assert_eq!(generate_test_tags(&Inventory::scan(dir.path())), ["tests", "tool:pytest"]);
+
+        fs::remove_file(dir.path().join("pytest.ini")).unwrap();
+        fs::create_dir(dir.path().join("tests")).unwrap();
+        fs::write(dir.path().join("tests/cli.rs"), "#[test]\nfn runs() {}\n").unwrap();
+        let inventory = Inventory::scan(dir.path());
+        assert_eq!(generate_test_tags(&inventory), ["tests"]);
+        assert_eq!(count_test_files(&inventory), 1);
+    }
+}
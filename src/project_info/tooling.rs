@@ -0,0 +1,110 @@
+use std::fs;
+use std::path::Path;
+
+use log::debug;
+
+use super::tags::{self, CI, TOOL};
+
+/// CI services, by the file or directory at the project root that configures them.
+const CI_SERVICES: &[(&str, &str)] = &[
+    (".github/workflows", "github-actions"),
+    (".gitlab-ci.yml", "gitlab-ci"),
+    ("Jenkinsfile", "jenkins"),
+    (".circleci/config.yml", "circleci"),
+    (".travis.yml", "travis"),
+    ("azure-pipelines.yml", "azure-pipelines"),
+    ("bitbucket-pipelines.yml", "bitbucket-pipelines"),
+];
+
+/// Task runners and development tools, by the file at the project root that
+/// configures them.
+const DEVELOPMENT_TOOLS: &[(&str, &str)] = &[
+    ("justfile", "just"),
+    ("Justfile", "just"),
+    (".justfile", "just"),
+    ("Taskfile.yml", "task"),
+    ("Taskfile.yaml", "task"),
+    (".pre-commit-config.yaml", "pre-commit"),
+    (".editorconfig", "editorconfig"),
+];
+
+/// Generates tags for the CI pipelines and development tooling a project is set up
+/// with, whatever its type.
+///
+/// Most of these live in hidden files, which the inventory leaves out, so the project
+/// root is checked directly.
+///
+/// # Arguments
+///
+/// * `directory` - A reference to the project's directory path.
+///
+/// # Returns
+///
+/// A vector of tags such as `"ci:github-actions"` and `"tool:just"`, plus `"ci"`
+/// when any pipeline is configured.
+pub fn generate_tooling_tags(directory: &Path) -> Vec<String> {
+    let mut tags: Vec<String> = CI_SERVICES
+        .iter()
+        .filter(|(path, _)| is_configured(&directory.join(path)))
+        .map(|(_, service)| tags::namespaced(CI, service))
+        .collect();
+    if !tags.is_empty() {
+        tags.push("ci".to_string());
+    }
+
+    tags.extend(
+        DEVELOPMENT_TOOLS
+            .iter()
+            .filter(|(file_name, _)| directory.join(file_name).is_file())
+            .map(|(_, tool)| tags::namespaced(TOOL, tool)),
+    );
+    tags.sort();
+    tags.dedup();
+
+    debug!("Tooling tags generated: {:?}", tags);
+
+    tags
+}
+
+/// Returns `true` if a CI configuration file exists, or a workflow directory holds at
+/// least one workflow.
+fn is_configured(path: &Path) -> bool {
+    if path.is_file() {
+        return true;
+    }
+    let Ok(entries) = fs::read_dir(path) else {
+        return false;
+    };
+    entries.flatten().any(|entry| {
+        let path = entry.path();
+        path.is_file()
+            && path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("yml") || ext.eq_ignore_ascii_case("yaml"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_generate_tooling_tags() {
+        let dir = tempdir().unwrap();
+        assert!(generate_tooling_tags(dir.path()).is_empty());
+
+        // A workflow directory without workflows sets nothing up.
+        fs::create_dir_all(dir.path().join(".github/workflows")).unwrap();
+        fs::write(dir.path().join("justfile"), "test:\n    cargo test\n").unwrap();
+        fs::write(dir.path().join(".editorconfig"), "root = true\n").unwrap();
+        assert_eq!(generate_tooling_tags(dir.path()), ["tool:editorconfig", "tool:just"]);
+
+        fs::write(dir.path().join(".github/workflows/ci.yml"), "on: push\n").unwrap();
+        fs::write(dir.path().join(".gitlab-ci.yml"), "test:\n  script: make\n").unwrap();
+        assert_eq!(
+            generate_tooling_tags(dir.path()),
+            ["ci", "ci:github-actions", "ci:gitlab-ci", "tool:editorconfig", "tool:just"]
+        );
+    }
+}
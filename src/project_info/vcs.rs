@@ -0,0 +1,331 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use git2::Repository;
+use log::{debug, warn};
+use rusqlite::{Connection, OpenFlags};
+use serde::{Deserialize, Serialize};
+
+use super::git;
+
+/// The version control system a project is kept in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Vcs {
+    /// Git.
+    #[serde(rename = "git")]
+    Git,
+    /// Jujutsu, which keeps its history in a git repository.
+    #[serde(rename = "jj")]
+    Jujutsu,
+    /// Mercurial.
+    #[serde(rename = "hg")]
+    Mercurial,
+    /// Subversion, whose working copies remember a single repository URL.
+    #[serde(rename = "svn")]
+    Subversion,
+    /// Fossil, which keeps a whole repository in one `*.fossil` file.
+    #[serde(rename = "fossil")]
+    Fossil,
+}
+
+impl Vcs {
+    /// Every system, in the order they are looked for: a Jujutsu repository that
+    /// shares its folder with git is Jujutsu's.
+    pub const ALL: [Vcs; 5] = [Vcs::Jujutsu, Vcs::Git, Vcs::Mercurial, Vcs::Subversion, Vcs::Fossil];
+
+    /// Returns the name used in `project_info.toml` and in `vcs:` tags.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Vcs::Git => "git",
+            Vcs::Jujutsu => "jj",
+            Vcs::Mercurial => "hg",
+            Vcs::Subversion => "svn",
+            Vcs::Fossil => "fossil",
+        }
+    }
+
+    /// Returns the files or directories that mark the root of a working copy.
+    fn markers(self) -> &'static [&'static str] {
+        match self {
+            Vcs::Git => &[".git"],
+            Vcs::Jujutsu => &[".jj"],
+            Vcs::Mercurial => &[".hg"],
+            Vcs::Subversion => &[".svn"],
+            // `_FOSSIL_` is the name used on Windows.
+            Vcs::Fossil => &[".fslckout", "_FOSSIL_"],
+        }
+    }
+
+    /// Returns `true` if the system's history is a git repository, so git tools and
+    /// hosting services work with it.
+    pub fn is_git_based(self) -> bool {
+        matches!(self, Vcs::Git | Vcs::Jujutsu)
+    }
+}
+
+impl fmt::Display for Vcs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Returns `true` if a file name marks the root of a working copy, or is a Fossil
+/// repository file.
+pub fn is_marker(file_name: &str) -> bool {
+    Vcs::ALL.iter().any(|vcs| vcs.markers().contains(&file_name)) || is_fossil_repository(file_name)
+}
+
+/// Returns `true` if a directory name is where a version control system keeps its
+/// history, which is not part of the project's files.
+pub fn is_metadata_directory(file_name: &str) -> bool {
+    [".git", ".jj", ".hg", ".svn"].contains(&file_name)
+}
+
+/// Returns `true` for `*.fossil` files, which hold a whole Fossil repository.
+fn is_fossil_repository(file_name: &str) -> bool {
+    file_name.len() > ".fossil".len() && file_name.ends_with(".fossil")
+}
+
+/// Finds which version control system the directory is the root of a working copy
+/// of, if any.
+///
+/// A folder holding a `*.fossil` repository file counts as a Fossil project even
+/// when nothing is checked out.
+pub fn detect(directory: &Path) -> Option<Vcs> {
+    let found = Vcs::ALL
+        .into_iter()
+        .find(|vcs| vcs.markers().iter().any(|marker| directory.join(marker).exists()))
+        .or_else(|| {
+            fs::read_dir(directory)
+                .ok()?
+                .flatten()
+                .any(|entry| is_fossil_repository(&entry.file_name().to_string_lossy()))
+                .then_some(Vcs::Fossil)
+        });
+    debug!("Version control: {:?}", found);
+    found
+}
+
+/// Extracts the remotes of a working copy, mapping their names to their push URLs.
+///
+/// Git and Jujutsu repositories list their git remotes, and Mercurial ones the
+/// `[paths]` of `.hg/hgrc`. A Subversion working copy has a single `repository`,
+/// read from its `.svn/wc.db` or, failing that, from `svn info`; a Fossil checkout
+/// has its `default` remote, as `fossil remote-url` reports it.
+///
+/// # Returns
+///
+/// The remotes by name, empty if none are configured or they cannot be read.
+pub fn extract_remotes(directory: &Path, vcs: Vcs) -> BTreeMap<String, String> {
+    let remotes = match vcs {
+        Vcs::Git => return git::extract_git_remotes(directory),
+        Vcs::Jujutsu => jujutsu_remotes(directory),
+        Vcs::Mercurial => fs::read_to_string(directory.join(".hg").join("hgrc"))
+            .map(|hgrc| mercurial_paths(&hgrc))
+            .unwrap_or_default(),
+        Vcs::Subversion => subversion_url(directory)
+            .map(|url| BTreeMap::from([("repository".to_string(), url)]))
+            .unwrap_or_default(),
+        // A repository file without a checkout has nowhere to run `fossil` from.
+        Vcs::Fossil if !Vcs::Fossil.markers().iter().any(|marker| directory.join(marker).exists()) => {
+            BTreeMap::new()
+        }
+        Vcs::Fossil => run(directory, "fossil", &["remote-url"])
+            .filter(|url| url != "off")
+            .map(|url| BTreeMap::from([("default".to_string(), url)]))
+            .unwrap_or_default(),
+    };
+
+    debug!("{} remotes: {:?}", vcs, remotes);
+    remotes
+}
+
+/// Reads the git remotes of a Jujutsu repository from the git repository it
+/// stores its history in.
+fn jujutsu_remotes(directory: &Path) -> BTreeMap<String, String> {
+    let mut repo = directory.join(".jj").join("repo");
+    // In a secondary workspace, `repo` is a file holding the path of the main one.
+    if repo.is_file() {
+        match fs::read_to_string(&repo) {
+            Ok(path) => repo = repo.parent().unwrap_or(directory).join(path.trim()),
+            Err(_) => return BTreeMap::new(),
+        }
+    }
+    let store = repo.join("store");
+    // `git_target` holds the path of the git repository, relative to the store.
+    let target = fs::read_to_string(store.join("git_target"))
+        .map(|target| store.join(target.trim()))
+        .unwrap_or_else(|_| store.join("git"));
+
+    match Repository::open(&target) {
+        Ok(repo) => git::remotes_in_process(&repo),
+        Err(e) => {
+            warn!("Unable to open the git repository of {}: {}", directory.display(), e);
+            BTreeMap::new()
+        }
+    }
+}
+
+/// Reads the `[paths]` section of a Mercurial `hgrc`. A `default-push` path is the
+/// push URL of `default`.
+fn mercurial_paths(hgrc: &str) -> BTreeMap<String, String> {
+    let mut paths = BTreeMap::new();
+    let mut in_paths = false;
+
+    for line in hgrc.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_paths = line == "[paths]";
+            continue;
+        }
+        if !in_paths || line.starts_with(['#', ';']) {
+            continue;
+        }
+        // Sub-options such as `default:pushurl` configure a path rather than name one.
+        if let Some((name, url)) = line.split_once('=') {
+            let (name, url) = (name.trim(), url.trim());
+            if !name.contains(':') && !url.is_empty() {
+                paths.insert(name.to_string(), url.to_string());
+            }
+        }
+    }
+
+    if let Some(push) = paths.remove("default-push") {
+        paths.insert("default".to_string(), push);
+    }
+    paths
+}
+
+/// Returns the repository URL of a Subversion working copy.
+///
+/// The working copy database is read in-process; `svn info` is only run when it
+/// cannot be read, such as for working copies older than Subversion 1.7.
+fn subversion_url(directory: &Path) -> Option<String> {
+    let database = directory.join(".svn").join("wc.db");
+    match working_copy_url(&database) {
+        Ok(url) => url,
+        Err(e) => {
+            warn!("Unable to read {}, falling back to svn: {}", database.display(), e);
+            run(directory, "svn", &["info", "--show-item", "url"])
+        }
+    }
+}
+
+/// Reads the URL the root of a working copy was checked out from.
+fn working_copy_url(database: &Path) -> rusqlite::Result<Option<String>> {
+    let connection = Connection::open_with_flags(database, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    let mut statement = connection.prepare(
+        "SELECT repository.root, nodes.repos_path FROM nodes
+         JOIN repository ON repository.id = nodes.repos_id
+         WHERE nodes.local_relpath = '' AND nodes.op_depth = 0",
+    )?;
+    let mut rows = statement.query([])?;
+    let Some(row) = rows.next()? else {
+        return Ok(None);
+    };
+
+    let root: String = row.get(0)?;
+    let path: String = row.get(1)?;
+    let root = root.trim_end_matches('/');
+    Ok(Some(if path.is_empty() { root.to_string() } else { format!("{}/{}", root, path) }))
+}
+
+/// Runs a version control command in the directory and returns its trimmed output.
+fn run(directory: &Path, program: &str, args: &[&str]) -> Option<String> {
+    let output = match Command::new(program).args(args).current_dir(directory).output() {
+        Ok(output) if output.status.success() => output,
+        Ok(output) => {
+            warn!(
+                "{} {} failed: {}",
+                program,
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            return None;
+        }
+        Err(e) => {
+            warn!("Unable to run {}: {}", program, e);
+            return None;
+        }
+    };
+
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!stdout.is_empty()).then_some(stdout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use tempfile::tempdir;
+
+    /// Returns where a working copy keeps its history.
+    fn metadata(directory: &Path, vcs: Vcs) -> PathBuf {
+        directory.join(vcs.markers()[0])
+    }
+
+    #[test]
+    fn test_detect() {
+        let dir = tempdir().unwrap();
+        assert_eq!(detect(dir.path()), None);
+
+        fs::write(dir.path().join("plugin.fossil"), "").unwrap();
+        assert_eq!(detect(dir.path()), Some(Vcs::Fossil));
+        fs::create_dir(metadata(dir.path(), Vcs::Subversion)).unwrap();
+        assert_eq!(detect(dir.path()), Some(Vcs::Subversion));
+        fs::create_dir(metadata(dir.path(), Vcs::Git)).unwrap();
+        assert_eq!(detect(dir.path()), Some(Vcs::Git));
+        // A Jujutsu repository colocated with git is Jujutsu's.
+        fs::create_dir(metadata(dir.path(), Vcs::Jujutsu)).unwrap();
+        assert_eq!(detect(dir.path()), Some(Vcs::Jujutsu));
+
+        assert!(is_marker(".hg") && is_marker("_FOSSIL_") && is_marker("plugin.fossil"));
+        assert!(!is_marker(".fossil") && !is_marker("README.md"));
+    }
+
+    #[test]
+    fn test_mercurial_paths() {
+        let hgrc = "[ui]\nusername = Me\n\n[paths]\n# where it came from\ndefault = https://hg.example.com/plugin\n\
+            default-push = ssh://hg@hg.example.com/plugin\ndefault:pushrev = .\nmirror = https://mirror.example.com/plugin\n";
+        let paths = mercurial_paths(hgrc);
+        assert_eq!(paths.len(), 2);
+        assert_eq!(paths["default"], "ssh://hg@hg.example.com/plugin");
+        assert_eq!(paths["mirror"], "https://mirror.example.com/plugin");
+    }
+
+    #[test]
+    fn test_subversion_url() {
+        let dir = tempdir().unwrap();
+        let svn = metadata(dir.path(), Vcs::Subversion);
+        fs::create_dir(&svn).unwrap();
+        let connection = Connection::open(svn.join("wc.db")).unwrap();
+        connection
+            .execute_batch(
+                "CREATE TABLE repository (id INTEGER PRIMARY KEY, root TEXT, uuid TEXT);
+                 CREATE TABLE nodes (wc_id INTEGER, local_relpath TEXT, op_depth INTEGER, repos_id INTEGER, repos_path TEXT);
+                 INSERT INTO repository VALUES (1, 'https://svn.example.com/repos', 'uuid');
+                 INSERT INTO nodes VALUES (1, '', 0, 1, 'plugin/trunk');
+                 INSERT INTO nodes VALUES (1, 'src', 0, 1, 'plugin/trunk/src');",
+            )
+            .unwrap();
+        drop(connection);
+
+        let remotes = extract_remotes(dir.path(), Vcs::Subversion);
+        assert_eq!(remotes["repository"], "https://svn.example.com/repos/plugin/trunk");
+    }
+
+    #[test]
+    fn test_jujutsu_remotes() {
+        let dir = tempdir().unwrap();
+        let store = dir.path().join(".jj").join("repo").join("store");
+        fs::create_dir_all(&store).unwrap();
+        let repo = Repository::init_bare(store.join("git")).unwrap();
+        repo.remote("origin", "git@github.com:me/plugin.git").unwrap();
+
+        let remotes = extract_remotes(dir.path(), Vcs::Jujutsu);
+        assert_eq!(remotes["origin"], "git@github.com:me/plugin.git");
+    }
+}
@@ -0,0 +1,158 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use log::debug;
+
+use crate::config::Config;
+
+use super::detector::Detector;
+use super::inventory::Inventory;
+use super::tags::{self, CODEC, EDITOR, FORMAT, RESOLUTION};
+
+/// Detector for video editing projects.
+pub struct VideoDetector;
+
+impl Detector for VideoDetector {
+    fn name(&self) -> &'static str {
+        "video"
+    }
+
+    fn handles(&self, project_type: &str) -> bool {
+        project_type == "video"
+    }
+
+    fn generate_tags(&self, inventory: &Inventory, _project_type: &str, _config: &Config) -> Vec<String> {
+        generate_video_tags(inventory)
+    }
+
+    fn default_tags(&self, _project_type: &str) -> &'static [&'static str] {
+        &["video", "editing"]
+    }
+}
+
+/// Common video asset formats and the codec family they usually carry.
+pub const VIDEO_EXTENSIONS: &[(&str, Option<&str>)] = &[
+    ("mp4", None),
+    ("mov", None),
+    ("mkv", None),
+    ("braw", Some("Blackmagic RAW")),
+];
+
+/// Native project formats of common video editors.
+///
+/// Each entry is `(editor name, project extension, is_bundle)`. Bundle formats
+/// (e.g. Final Cut's `.fcpbundle`) are directories that macOS presents as a single file.
+pub const EDITOR_PROJECT_FORMATS: &[(&str, &str, bool)] = &[
+    ("Premiere Pro", "prproj", false),
+    ("Final Cut Pro", "fcpbundle", true),
+    ("DaVinci Resolve", "drp", false),
+];
+
+/// Resolution hints commonly embedded in video file names, and the tag they map to.
+const RESOLUTION_HINTS: &[(&str, &str)] = &[
+    ("8k", "8K"),
+    ("4320p", "8K"),
+    ("4k", "4K"),
+    ("uhd", "4K"),
+    ("2160p", "4K"),
+    ("1440p", "1440p"),
+    ("1080p", "1080p"),
+    ("1080i", "1080p"),
+    ("720p", "720p"),
+];
+
+/// Returns the video editor format matching the given path, if any.
+///
+/// Bundle formats only match directories and plain formats only match files.
+///
+/// # Arguments
+///
+/// * `path` - A reference to the file or directory to check.
+///
+/// # Returns
+///
+/// The `(editor name, project extension)` pair, or `None` if the path is not an editor project.
+pub fn editor_project_format(path: &Path) -> Option<(&'static str, &'static str)> {
+    let extension = path.extension()?.to_str()?;
+    let is_dir = path.is_dir();
+
+    EDITOR_PROJECT_FORMATS
+        .iter()
+        .find(|(_, ext, is_bundle)| *is_bundle == is_dir && extension.eq_ignore_ascii_case(ext))
+        .map(|(editor, ext, _)| (*editor, *ext))
+}
+
+/// Returns `true` if the path has a known video asset extension.
+pub fn is_video_asset(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            VIDEO_EXTENSIONS
+                .iter()
+                .any(|(video_ext, _)| ext.eq_ignore_ascii_case(video_ext))
+        })
+}
+
+/// Generates tags specific to video projects based on the directory contents.
+///
+/// Codec and resolution tags are only derived from information that is cheap to
+/// obtain, i.e. the file extension and resolution hints in file names.
+///
+/// # Arguments
+///
+/// * `inventory` - The project's inventory.
+///
+/// # Returns
+///
+/// A vector of tags relevant to video projects.
+pub fn generate_video_tags(inventory: &Inventory) -> Vec<String> {
+    let mut tags = Vec::new();
+
+    let mut format_set = HashSet::new();
+    let mut editor_set = HashSet::new();
+    let mut detail_set = HashSet::new();
+
+    for entry in inventory.entries() {
+        let path = entry.path();
+
+        // Check for editor project files and bundles.
+        if let Some((editor, ext)) = editor_project_format(path) {
+            editor_set.insert(tags::namespaced(EDITOR, editor)); // e.g., "editor:Premiere Pro"
+            format_set.insert(tags::namespaced(FORMAT, ext.to_uppercase())); // e.g., "format:PRPROJ"
+            continue;
+        }
+
+        let Some(ext_str) = path.extension().and_then(|ext| ext.to_str()) else {
+            continue;
+        };
+
+        for (video_ext, codec) in VIDEO_EXTENSIONS {
+            if ext_str.eq_ignore_ascii_case(video_ext) {
+                format_set.insert(tags::namespaced(FORMAT, video_ext.to_uppercase())); // e.g., "format:MOV"
+                if let Some(codec) = codec {
+                    detail_set.insert(tags::namespaced(CODEC, codec));
+                }
+
+                // Look for resolution hints such as "4k" or "1080p" in the file name.
+                let stem = path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().to_lowercase())
+                    .unwrap_or_default();
+                for token in stem.split(|c: char| !c.is_ascii_alphanumeric()) {
+                    if let Some((_, resolution)) = RESOLUTION_HINTS.iter().find(|(hint, _)| *hint == token) {
+                        detail_set.insert(tags::namespaced(RESOLUTION, resolution));
+                    }
+                }
+            }
+        }
+    }
+
+    // Add detected formats, editors, codecs, and resolutions as tags.
+    tags.extend(format_set);
+    tags.extend(editor_set);
+    tags.extend(detail_set);
+
+    debug!("Video tags generated: {:?}", tags);
+
+    tags
+}
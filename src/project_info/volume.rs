@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// The drive or partition a project is stored on, recorded so that projects on an
+/// external drive that is not plugged in can be told from projects that are gone.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Volume {
+    /// Where the volume was mounted, such as `/Volumes/Archive` or `/media/me/ARCHIVE`.
+    pub mount_point: PathBuf,
+    /// The file system's UUID, where the platform reports it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<String>,
+    /// The volume's label or name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+impl Volume {
+    /// Returns the volume's label, or else its mount point, for the user.
+    pub fn name(&self) -> String {
+        match &self.label {
+            Some(label) => label.clone(),
+            None => self.mount_point.display().to_string(),
+        }
+    }
+
+    /// Returns the mounted volume that is this one, found by UUID, label, or mount
+    /// point, in that order of preference.
+    pub fn find_in<'a>(&self, mounted: &'a [Volume]) -> Option<&'a Volume> {
+        if let Some(uuid) = &self.uuid {
+            return mounted.iter().find(|volume| volume.uuid.as_ref() == Some(uuid));
+        }
+        if let Some(label) = &self.label {
+            return mounted.iter().find(|volume| volume.label.as_ref() == Some(label));
+        }
+        mounted.iter().find(|volume| volume.mount_point == self.mount_point)
+    }
+}
+
+/// Returns the volume a project directory is on, if it is a volume other than the
+/// system's root that can be recognized later by its UUID or label.
+pub fn for_path(directory: &Path) -> Option<Volume> {
+    let directory = fs::canonicalize(directory).ok()?;
+    containing(&directory, &mounted())
+}
+
+/// Returns the volume among `mounted` with the deepest mount point holding `path`,
+/// unless that is the system's root or a volume with neither UUID nor label.
+pub fn containing(path: &Path, mounted: &[Volume]) -> Option<Volume> {
+    mounted
+        .iter()
+        .filter(|volume| path.starts_with(&volume.mount_point))
+        .max_by_key(|volume| volume.mount_point.components().count())
+        .filter(|volume| volume.mount_point.parent().is_some())
+        .filter(|volume| volume.uuid.is_some() || volume.label.is_some())
+        .cloned()
+}
+
+/// Lists the volumes mounted now. Platforms other than Linux and macOS report none.
+pub fn mounted() -> Vec<Volume> {
+    if cfg!(target_os = "linux") {
+        let Ok(mountinfo) = fs::read_to_string("/proc/self/mountinfo") else {
+            return Vec::new();
+        };
+        parse_mountinfo(&mountinfo, &disk_links("/dev/disk/by-uuid"), &disk_links("/dev/disk/by-label"))
+    } else if cfg!(target_os = "macos") {
+        // Every mounted volume appears in /Volumes under its name.
+        fs::read_dir("/Volumes")
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| Volume {
+                        mount_point: entry.path(),
+                        uuid: None,
+                        label: Some(entry.file_name().to_string_lossy().into_owned()),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    } else {
+        Vec::new()
+    }
+}
+
+/// Maps each device in a `/dev/disk/by-*` directory to the name of its link, such as
+/// `/dev/sdb1` to its UUID.
+fn disk_links(directory: &str) -> HashMap<PathBuf, String> {
+    let Ok(entries) = fs::read_dir(directory) else {
+        return HashMap::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let device = fs::canonicalize(entry.path()).ok()?;
+            Some((device, unescape(&entry.file_name().to_string_lossy())))
+        })
+        .collect()
+}
+
+/// Reads the volumes out of Linux's `/proc/self/mountinfo`, naming each by the UUID
+/// and label links of its device.
+fn parse_mountinfo(
+    mountinfo: &str,
+    uuids: &HashMap<PathBuf, String>,
+    labels: &HashMap<PathBuf, String>,
+) -> Vec<Volume> {
+    mountinfo
+        .lines()
+        .filter_map(|line| {
+            // `36 35 98:0 /mnt1 /mnt/parent rw,noatime master:1 - ext3 /dev/root rw`
+            let (mount, source) = line.split_once(" - ")?;
+            let mount_point = PathBuf::from(unescape(mount.split(' ').nth(4)?));
+            let device = source.split(' ').nth(1).map(PathBuf::from)?;
+            let device = fs::canonicalize(&device).unwrap_or(device);
+            Some(Volume {
+                mount_point,
+                uuid: uuids.get(&device).cloned(),
+                label: labels.get(&device).cloned(),
+            })
+        })
+        .collect()
+}
+
+/// Decodes the octal (`\040`) and hexadecimal (`\x20`) escapes Linux uses for spaces
+/// and other special characters in mount points and disk labels.
+fn unescape(value: &str) -> String {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut rest = value.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        let escape = match tail {
+            [b'x', a, b, ..] if byte == b'\\' => {
+                u8::from_str_radix(std::str::from_utf8(&[*a, *b]).unwrap_or_default(), 16).ok().map(|c| (c, 3))
+            }
+            [a, b, c, ..] if byte == b'\\' => {
+                u8::from_str_radix(std::str::from_utf8(&[*a, *b, *c]).unwrap_or_default(), 8).ok().map(|c| (c, 3))
+            }
+            _ => None,
+        };
+        match escape {
+            Some((decoded, length)) => {
+                bytes.push(decoded);
+                rest = &tail[length..];
+            }
+            None => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mountinfo() {
+        let mountinfo = "\
+28 1 254:0 / / rw,relatime - ext4 /dev/vda rw
+26 25 0:24 / /dev/shm rw,relatime - tmpfs tmpfs rw
+91 28 8:17 / /media/me/Music\\040Archive rw,nosuid - exfat /dev/sdb1 rw
+";
+        let uuids = HashMap::from([
+            (PathBuf::from("/dev/vda"), "0f3c".to_string()),
+            (PathBuf::from("/dev/sdb1"), "64A1-2B7C".to_string()),
+        ]);
+        let labels = HashMap::from([(PathBuf::from("/dev/sdb1"), unescape("Music\\x20Archive"))]);
+        let mounted = parse_mountinfo(mountinfo, &uuids, &labels);
+
+        let archive = Volume {
+            mount_point: PathBuf::from("/media/me/Music Archive"),
+            uuid: Some("64A1-2B7C".to_string()),
+            label: Some("Music Archive".to_string()),
+        };
+        assert_eq!(mounted[2], archive);
+        assert_eq!(containing(Path::new("/media/me/Music Archive/2019/ep"), &mounted), Some(archive.clone()));
+        // Neither the system's root nor a volume without UUID or label is recorded.
+        assert_eq!(containing(Path::new("/home/me/song"), &mounted), None);
+        assert_eq!(containing(Path::new("/dev/shm/song"), &mounted), None);
+
+        // The drive is recognized by its UUID wherever it is mounted next.
+        let remounted = Volume { mount_point: PathBuf::from("/media/me/ARCHIVE1"), ..archive.clone() };
+        assert_eq!(archive.find_in(std::slice::from_ref(&remounted)), Some(&remounted));
+        assert_eq!(archive.find_in(&mounted[..2]), None);
+    }
+}
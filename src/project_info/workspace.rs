@@ -0,0 +1,172 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use log::debug;
+
+use super::find_project_roots;
+
+/// Finds the sub-projects of a monorepo or workspace.
+///
+/// Members declared by a Cargo workspace (`[workspace] members`), a pnpm workspace
+/// (`pnpm-workspace.yaml`), or npm/yarn workspaces (`workspaces` in `package.json`)
+/// are used when present. Otherwise a directory containing two or more nested project
+/// roots is treated as a monorepo of those projects.
+///
+/// # Arguments
+///
+/// * `directory` - A reference to the project's directory path.
+///
+/// # Returns
+///
+/// The sub-project directories relative to `directory`, sorted, using `/` separators.
+pub fn find_subprojects(directory: &Path) -> Vec<String> {
+    let mut patterns = Vec::new();
+    patterns.extend(cargo_workspace_members(directory));
+    patterns.extend(pnpm_workspace_packages(directory));
+    patterns.extend(package_json_workspaces(directory));
+
+    let members: BTreeSet<PathBuf> = if patterns.is_empty() {
+        let nested = find_project_roots(directory);
+        if nested.len() < 2 {
+            return Vec::new();
+        }
+        nested.into_iter().collect()
+    } else {
+        patterns
+            .iter()
+            .flat_map(|pattern| expand_member_pattern(directory, pattern))
+            .collect()
+    };
+
+    let subprojects: Vec<String> = members
+        .iter()
+        .filter_map(|member| member.strip_prefix(directory).ok())
+        .map(|relative| {
+            relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/")
+        })
+        .filter(|relative| !relative.is_empty())
+        .collect();
+
+    debug!("Sub-projects found: {:?}", subprojects);
+
+    subprojects
+}
+
+/// Expands a workspace member pattern into existing directories.
+///
+/// Plain paths and patterns with a trailing `*` or `**` component (e.g. `crates/*`,
+/// `packages/**`) are supported; other glob syntax is ignored.
+fn expand_member_pattern(directory: &Path, pattern: &str) -> Vec<PathBuf> {
+    let pattern = pattern.trim().trim_start_matches("./").trim_end_matches('/');
+    if pattern.is_empty() || pattern.starts_with('!') {
+        return Vec::new();
+    }
+
+    if let Some(parent) = pattern
+        .strip_suffix("/**")
+        .or_else(|| pattern.strip_suffix("/*"))
+        .or(if pattern == "*" { Some("") } else { None })
+    {
+        let Ok(entries) = fs::read_dir(directory.join(parent)) else {
+            return Vec::new();
+        };
+        return entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.is_dir()
+                    && !path
+                        .file_name()
+                        .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+            })
+            .collect();
+    }
+
+    if pattern.contains('*') {
+        return Vec::new();
+    }
+
+    let member = directory.join(pattern);
+    if member.is_dir() {
+        vec![member]
+    } else {
+        Vec::new()
+    }
+}
+
+/// Reads the `members` of a Cargo workspace.
+fn cargo_workspace_members(directory: &Path) -> Vec<String> {
+    let Ok(content) = fs::read_to_string(directory.join("Cargo.toml")) else {
+        return Vec::new();
+    };
+    let Ok(manifest) = content.parse::<toml::Table>() else {
+        return Vec::new();
+    };
+
+    manifest
+        .get("workspace")
+        .and_then(|workspace| workspace.get("members"))
+        .and_then(|members| members.as_array())
+        .map(|members| {
+            members
+                .iter()
+                .filter_map(|member| member.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Reads the `packages` list of a `pnpm-workspace.yaml`.
+fn pnpm_workspace_packages(directory: &Path) -> Vec<String> {
+    let Ok(content) = fs::read_to_string(directory.join("pnpm-workspace.yaml")) else {
+        return Vec::new();
+    };
+
+    let mut packages = Vec::new();
+    let mut in_packages = false;
+
+    for line in content.lines() {
+        if !line.starts_with(' ') && !line.starts_with('-') {
+            in_packages = line.trim_end() == "packages:";
+            continue;
+        }
+        if in_packages {
+            if let Some(package) = line.trim().strip_prefix('-') {
+                packages.push(package.trim().trim_matches(['"', '\'']).to_string());
+            }
+        }
+    }
+
+    packages
+}
+
+/// Reads the npm/yarn `workspaces` of a `package.json`, in either the array form or
+/// the `{ "packages": [...] }` form.
+fn package_json_workspaces(directory: &Path) -> Vec<String> {
+    let Ok(content) = fs::read_to_string(directory.join("package.json")) else {
+        return Vec::new();
+    };
+    let Ok(manifest) = serde_json::from_str::<serde_json::Value>(&content) else {
+        return Vec::new();
+    };
+
+    let workspaces = match manifest.get("workspaces") {
+        Some(serde_json::Value::Object(workspaces)) => workspaces.get("packages"),
+        workspaces => workspaces,
+    };
+
+    workspaces
+        .and_then(|workspaces| workspaces.as_array())
+        .map(|workspaces| {
+            workspaces
+                .iter()
+                .filter_map(|workspace| workspace.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
@@ -0,0 +1,156 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use log::debug;
+
+use crate::config::Config;
+
+use super::detector::Detector;
+use super::inventory::Inventory;
+use super::tags::{self, FORMAT, TOOL};
+
+/// Detector for writing and documentation projects.
+pub struct WritingDetector;
+
+impl Detector for WritingDetector {
+    fn name(&self) -> &'static str {
+        "writing"
+    }
+
+    fn handles(&self, project_type: &str) -> bool {
+        project_type == "writing"
+    }
+
+    fn generate_tags(&self, inventory: &Inventory, _project_type: &str, _config: &Config) -> Vec<String> {
+        generate_writing_tags(inventory)
+    }
+
+    fn default_tags(&self, _project_type: &str) -> &'static [&'static str] {
+        &["writing"]
+    }
+}
+
+/// Document formats that identify a writing project, and the tag each one maps to.
+pub const DOCUMENT_FORMATS: &[(&str, &str)] = &[
+    ("tex", "latex"),
+    ("typ", "typst"),
+    ("scriv", "scrivener"),
+];
+
+/// Markdown file extensions.
+pub const MARKDOWN_EXTENSIONS: &[&str] = &["md", "markdown"];
+
+/// LaTeX document classes and the kind of document they indicate.
+const DOCUMENT_CLASSES: &[(&str, &str)] = &[
+    ("book", "book"),
+    ("memoir", "book"),
+    ("scrbook", "book"),
+    ("report", "book"),
+    ("article", "paper"),
+    ("scrartcl", "paper"),
+    ("IEEEtran", "paper"),
+    ("acmart", "paper"),
+    ("revtex4-2", "paper"),
+    ("llncs", "paper"),
+    ("beamer", "slides"),
+];
+
+/// Returns the document format tag matching the given path, if any.
+///
+/// Scrivener projects (`.scriv`) are bundle directories; the other formats are files.
+///
+/// # Arguments
+///
+/// * `path` - A reference to the file or directory to check.
+///
+/// # Returns
+///
+/// The tag for the document format (e.g. `"latex"`), or `None`.
+pub fn document_format(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_str()?;
+    let is_dir = path.is_dir();
+
+    DOCUMENT_FORMATS
+        .iter()
+        .find(|(ext, _)| (*ext == "scriv") == is_dir && extension.eq_ignore_ascii_case(ext))
+        .map(|(_, tag)| *tag)
+}
+
+/// Returns `true` if the path has a Markdown extension.
+pub fn is_markdown(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| MARKDOWN_EXTENSIONS.iter().any(|md| ext.eq_ignore_ascii_case(md)))
+}
+
+/// Generates tags specific to writing projects based on the directory contents.
+///
+/// # Arguments
+///
+/// * `inventory` - The project's inventory.
+///
+/// # Returns
+///
+/// A vector of tags relevant to writing projects.
+pub fn generate_writing_tags(inventory: &Inventory) -> Vec<String> {
+    let mut tags = Vec::new();
+
+    let mut tag_set = HashSet::new();
+
+    for entry in inventory.entries() {
+        let path = entry.path();
+
+        if is_markdown(path) {
+            tag_set.insert(tags::namespaced(FORMAT, "markdown"));
+            continue;
+        }
+
+        if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("bib")) {
+            tag_set.insert("bibliography".to_string());
+            continue;
+        }
+
+        match document_format(path) {
+            Some("latex") => {
+                tag_set.insert(tags::namespaced(FORMAT, "latex"));
+                if let Some(kind) = latex_document_kind(path) {
+                    tag_set.insert(kind.to_string());
+                }
+            }
+            Some("scrivener") => {
+                tag_set.insert(tags::namespaced(TOOL, "scrivener"));
+                tag_set.insert("book".to_string());
+            }
+            Some(format) => {
+                tag_set.insert(tags::namespaced(FORMAT, format));
+            }
+            None => {}
+        }
+    }
+
+    // Add detected formats and document kinds as tags.
+    tags.extend(tag_set);
+
+    debug!("Writing tags generated: {:?}", tags);
+
+    tags
+}
+
+/// Determines the kind of document (`book`, `paper`, `slides`) from a LaTeX file's
+/// `\documentclass` declaration.
+fn latex_document_kind(path: &Path) -> Option<&'static str> {
+    let content = fs::read_to_string(path).ok()?;
+    let line = content
+        .lines()
+        .map(str::trim)
+        .find(|line| line.starts_with("\\documentclass"))?;
+
+    // The class name is the argument in braces, after any [options].
+    let class = line.split('{').nth(1)?.split('}').next()?.trim();
+
+    DOCUMENT_CLASSES
+        .iter()
+        .find(|(name, _)| *name == class)
+        .map(|(_, kind)| *kind)
+}
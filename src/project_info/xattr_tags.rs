@@ -0,0 +1,103 @@
+//! Tags stored with the project folder itself, where file managers and desktop search
+//! can find them.
+//!
+//! On Linux and other Unix systems the tags go in the `user.xdg.tags` extended
+//! attribute, a comma-separated list as the freedesktop.org convention describes. On
+//! Windows, which has no such attribute, they go in an NTFS alternate data stream of
+//! the same name.
+
+use std::io;
+use std::path::Path;
+
+use log::info;
+
+/// The extended attribute (or, on Windows, the alternate data stream) holding the tags.
+pub const XDG_TAGS_ATTRIBUTE: &str = "user.xdg.tags";
+
+/// Adds tags to the directory's `user.xdg.tags`, keeping the tags it already has.
+pub fn write_tags(directory: &Path, tags: &[String]) -> io::Result<()> {
+    let existing = read_value(directory)?.unwrap_or_default();
+    let Some(value) = merge(&existing, tags) else {
+        return Ok(());
+    };
+
+    write_value(directory, &value)?;
+    info!("{} of {} set to {:?}", XDG_TAGS_ATTRIBUTE, directory.display(), value);
+    Ok(())
+}
+
+/// Returns the comma-separated list with the missing tags added, or `None` if every
+/// tag is already listed. Commas in tags are replaced, as they would split the tag.
+fn merge(existing: &str, tags: &[String]) -> Option<String> {
+    let mut entries: Vec<String> = existing
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(str::to_string)
+        .collect();
+    let before = entries.len();
+
+    for tag in tags {
+        let tag = tag.split(',').map(str::trim).collect::<Vec<_>>().join(" ");
+        if !entries.contains(&tag) {
+            entries.push(tag);
+        }
+    }
+    (entries.len() != before).then(|| entries.join(","))
+}
+
+#[cfg(unix)]
+fn read_value(directory: &Path) -> io::Result<Option<String>> {
+    Ok(xattr::get(directory, XDG_TAGS_ATTRIBUTE)?.map(|value| String::from_utf8_lossy(&value).into_owned()))
+}
+
+#[cfg(unix)]
+fn write_value(directory: &Path, value: &str) -> io::Result<()> {
+    xattr::set(directory, XDG_TAGS_ATTRIBUTE, value.as_bytes())
+}
+
+/// Returns the path of the directory's alternate data stream, e.g. `C:\song:user.xdg.tags`.
+#[cfg(windows)]
+fn stream_path(directory: &Path) -> std::path::PathBuf {
+    let mut path = directory.as_os_str().to_owned();
+    path.push(":");
+    path.push(XDG_TAGS_ATTRIBUTE);
+    path.into()
+}
+
+#[cfg(windows)]
+fn read_value(directory: &Path) -> io::Result<Option<String>> {
+    match std::fs::read(stream_path(directory)) {
+        Ok(value) => Ok(Some(String::from_utf8_lossy(&value).into_owned())),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(windows)]
+fn write_value(directory: &Path, value: &str) -> io::Result<()> {
+    std::fs::write(stream_path(directory), value)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn read_value(_directory: &Path) -> io::Result<Option<String>> {
+    Ok(None)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn write_value(_directory: &Path, _value: &str) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "extended attributes are not supported on this platform"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_tags() {
+        let tags = vec!["lang:rust".to_string(), "cli".to_string(), "a, b".to_string()];
+        assert_eq!(merge("", &tags), Some("lang:rust,cli,a b".to_string()));
+        assert_eq!(merge("work, cli", &tags), Some("work,cli,lang:rust,a b".to_string()));
+        assert_eq!(merge("cli,lang:rust,a b", &tags), None);
+    }
+}
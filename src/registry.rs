@@ -0,0 +1,403 @@
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use log::{info, warn};
+
+use crate::config::dirs;
+use crate::project_info::volume::Volume;
+use crate::project_info::ProjectInfo;
+
+pub mod duplicates;
+pub mod health;
+pub mod relocation;
+pub mod remote;
+pub mod similar;
+pub mod stats;
+pub mod timeline;
+
+/// A project known to the registry, together with the directory it lives in.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegistryEntry {
+    /// The absolute path of the project's directory.
+    pub path: PathBuf,
+    /// The project information recorded at the last save.
+    #[serde(flatten)]
+    pub info: ProjectInfo,
+}
+
+impl RegistryEntry {
+    /// Returns the volume the project was recorded on if the project's directory is
+    /// gone because that volume is not among the `mounted` ones, such as an external
+    /// drive that is not plugged in.
+    pub fn offline_volume(&self, mounted: &[Volume]) -> Option<&Volume> {
+        let volume = self.info.volume.as_ref()?;
+        (!self.path.exists() && volume.find_in(mounted).is_none()).then_some(volume)
+    }
+}
+
+/// An index of every tagged project on this machine.
+///
+/// The registry is stored as a directory with one small TOML file per project rather
+/// than a single monolithic file, so that a registry synced through git merges cleanly
+/// when two machines update different projects.
+pub struct Registry {
+    root: PathBuf,
+}
+
+impl Registry {
+    /// Opens the registry stored in the given directory, creating it if necessary.
+    pub fn open(root: &Path) -> io::Result<Self> {
+        fs::create_dir_all(root)?;
+        Ok(Registry {
+            root: root.to_path_buf(),
+        })
+    }
+
+    /// Opens the registry at its default location.
+    ///
+    /// The location can be overridden with the `TAGGER_REGISTRY` environment variable
+    /// and otherwise defaults to `registry` in the data directory (see
+    /// [`dirs`](crate::config::dirs)).
+    pub fn open_default() -> io::Result<Self> {
+        Self::open(&Self::default_location()?)
+    }
+
+    /// Returns the directory the registry is stored in.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Returns the default registry location.
+    pub fn default_location() -> io::Result<PathBuf> {
+        if let Some(path) = env::var_os("TAGGER_REGISTRY") {
+            return Ok(PathBuf::from(path));
+        }
+
+        Ok(dirs::data_dir()?.join("registry"))
+    }
+
+    /// Adds or updates the entry for a project directory.
+    ///
+    /// # Returns
+    ///
+    /// The path of the entry file that was written.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `AlreadyExists` error, rather than overwriting it, when the entry
+    /// file belongs to another project whose file name is the same.
+    pub fn register(&self, directory: &Path, info: &ProjectInfo) -> io::Result<PathBuf> {
+        let path = fs::canonicalize(directory)?;
+        let entry = RegistryEntry {
+            path,
+            info: info.clone(),
+        };
+
+        let toml_string = toml::to_string(&entry)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        let entry_path = self.entry_path(&entry.path, &entry.info.id);
+        let is_other = |other: &RegistryEntry| {
+            other.path != entry.path && (entry.info.id.is_empty() || other.info.id != entry.info.id)
+        };
+        if let Some(other) = self.entry_at(&entry_path).filter(is_other) {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!(
+                    "{} and {} share the registry entry {}",
+                    entry.path.display(),
+                    other.path.display(),
+                    entry_path.display()
+                ),
+            ));
+        }
+        fs::write(&entry_path, toml_string)?;
+
+        // A project registered before it had an id has an entry named by its path.
+        let path_entry = self.entry_path(&entry.path, "");
+        if path_entry != entry_path && self.entry_at(&path_entry).is_some_and(|other| other.path == entry.path) {
+            fs::remove_file(&path_entry)?;
+        }
+
+        info!("Registered '{}' at {}", entry.info.name, entry_path.display());
+        Ok(entry_path)
+    }
+
+    /// Removes the entry for a project directory, if there is one.
+    ///
+    /// The path must be the absolute path the project was registered under, which
+    /// need not exist anymore.
+    pub fn unregister(&self, project_path: &Path) -> io::Result<()> {
+        for dir_entry in fs::read_dir(&self.root)?.flatten() {
+            let entry_path = dir_entry.path();
+            let is_entry = entry_path.extension().and_then(|ext| ext.to_str()) == Some("toml");
+            if !is_entry || self.entry_at(&entry_path).is_none_or(|entry| entry.path != project_path) {
+                continue;
+            }
+            match fs::remove_file(&entry_path) {
+                Ok(()) => info!("Unregistered {}", project_path.display()),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    /// Loads every entry in the registry, sorted by project name.
+    ///
+    /// Entry files that cannot be read or parsed are skipped with a warning.
+    pub fn entries(&self) -> io::Result<Vec<RegistryEntry>> {
+        let mut entries = Vec::new();
+
+        for dir_entry in fs::read_dir(&self.root)?.flatten() {
+            let file_path = dir_entry.path();
+            if file_path.extension().and_then(|ext| ext.to_str()) != Some("toml") {
+                continue;
+            }
+
+            let content = match fs::read_to_string(&file_path) {
+                Ok(content) => content,
+                Err(e) => {
+                    warn!("Skipping unreadable registry entry {}: {}", file_path.display(), e);
+                    continue;
+                }
+            };
+            match toml::from_str::<RegistryEntry>(&content) {
+                Ok(entry) => entries.push(entry),
+                Err(e) => warn!("Skipping unreadable registry entry {}: {}", file_path.display(), e),
+            }
+        }
+
+        entries.sort_by(|a, b| a.info.name.cmp(&b.info.name).then_with(|| a.path.cmp(&b.path)));
+        Ok(entries)
+    }
+
+    /// Finds the projects a user-supplied name refers to: an id or directory path, else
+    /// an alias, else a slug, else a project name. Names are compared ignoring case.
+    ///
+    /// # Returns
+    ///
+    /// Every entry that matches at the first level with a match, so a result with more
+    /// than one entry is ambiguous, and an empty one means nothing matched.
+    pub fn lookup(&self, key: &str) -> io::Result<Vec<RegistryEntry>> {
+        let entries = self.entries()?;
+        let named = |name: &str| !name.is_empty() && name.eq_ignore_ascii_case(key);
+
+        let levels: [&dyn Fn(&RegistryEntry) -> bool; 4] = [
+            &|entry| (!entry.info.id.is_empty() && entry.info.id == key) || entry.path.as_os_str() == key,
+            &|entry| named(&entry.info.alias),
+            &|entry| named(&entry.info.slug),
+            &|entry| named(&entry.info.name),
+        ];
+        for matches in levels {
+            let found: Vec<RegistryEntry> = entries.iter().filter(|entry| matches(entry)).cloned().collect();
+            if !found.is_empty() {
+                return Ok(found);
+            }
+        }
+        Ok(Vec::new())
+    }
+
+    /// Reads the entry stored in an entry file, if it exists and parses.
+    fn entry_at(&self, entry_path: &Path) -> Option<RegistryEntry> {
+        toml::from_str(&fs::read_to_string(entry_path).ok()?).ok()
+    }
+
+    /// Returns the entry file path for a project.
+    ///
+    /// Entries are named by the project's id, so a project registered on several
+    /// machines, at a different path on each, has one entry file on all of them.
+    /// Projects without an id fall back to [`file_stem`] of their path.
+    fn entry_path(&self, project_path: &Path, id: &str) -> PathBuf {
+        let stem = if id.is_empty() {
+            file_stem(project_path)
+        } else if id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            id.to_string()
+        } else {
+            format!("id-{:016x}", fnv1a(id.as_bytes()))
+        };
+        self.root.join(format!("{}.toml", stem))
+    }
+}
+
+/// Returns a file name, without extension, for a project directory: a readable slug
+/// of the directory name followed by the 64-bit hash of the full path, which keeps
+/// directories with the same name apart.
+///
+/// The path is the one on this machine, so the name differs between machines that
+/// mount the project at different paths.
+pub(crate) fn file_stem(project_path: &Path) -> String {
+    let dir_name = project_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let slug: String = dir_name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+        .collect();
+    let slug = slug.trim_matches('-');
+
+    format!("{}-{:016x}", slug, fnv1a(project_path.to_string_lossy().as_bytes()))
+}
+
+/// Computes the 64-bit FNV-1a hash of the given bytes.
+///
+/// Used instead of `DefaultHasher`, whose output is not guaranteed to be stable
+/// across Rust releases.
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn sample_info(name: &str) -> ProjectInfo {
+        let mut music_fields = toml::Table::new();
+        music_fields.insert("bpm".to_string(), toml::Value::Integer(120));
+
+        ProjectInfo {
+            name: name.to_string(),
+            project_type: "music".to_string(),
+            tags: vec!["audio".to_string()],
+            fields: [("music".to_string(), music_fields)].into_iter().collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_register_writes_one_file_per_project() {
+        let registry_dir = tempdir().unwrap();
+        let projects_dir = tempdir().unwrap();
+        let registry = Registry::open(registry_dir.path()).unwrap();
+
+        let beat = projects_dir.path().join("Beat 42");
+        let album = projects_dir.path().join("album");
+        fs::create_dir(&beat).unwrap();
+        fs::create_dir(&album).unwrap();
+
+        let beat_entry = registry.register(&beat, &sample_info("Beat 42")).unwrap();
+        registry.register(&album, &sample_info("album")).unwrap();
+
+        let file_name = beat_entry.file_name().unwrap().to_string_lossy().to_string();
+        assert!(file_name.starts_with("beat-42-"));
+        assert_eq!(fs::read_dir(registry_dir.path()).unwrap().count(), 2);
+
+        let entries = registry.entries().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].info.name, "Beat 42");
+        assert_eq!(entries[0].path, fs::canonicalize(&beat).unwrap());
+        assert_eq!(entries[0].info.fields["music"]["bpm"].as_integer(), Some(120));
+    }
+
+    #[test]
+    fn test_lookup_prefers_aliases_over_names() {
+        let registry_dir = tempdir().unwrap();
+        let projects_dir = tempdir().unwrap();
+        let registry = Registry::open(registry_dir.path()).unwrap();
+
+        for (dir, name, alias) in [("a", "beat42", ""), ("b", "Song", "BEAT42"), ("c", "song", "")] {
+            let directory = projects_dir.path().join(dir);
+            fs::create_dir(&directory).unwrap();
+            let info = ProjectInfo {
+                name: name.to_string(),
+                alias: alias.to_string(),
+                id: format!("{}-id", dir),
+                ..Default::default()
+            };
+            registry.register(&directory, &info).unwrap();
+        }
+
+        let names = |key: &str| -> Vec<String> {
+            registry.lookup(key).unwrap().into_iter().map(|entry| entry.info.name).collect()
+        };
+        assert_eq!(names("beat42"), vec!["Song"]);
+        assert_eq!(names("a-id"), vec!["beat42"]);
+        assert_eq!(names("SONG").len(), 2);
+        assert!(names("missing").is_empty());
+    }
+
+    #[test]
+    fn test_register_overwrites_existing_entry() {
+        let registry_dir = tempdir().unwrap();
+        let project_dir = tempdir().unwrap();
+        let registry = Registry::open(registry_dir.path()).unwrap();
+
+        let first = registry.register(project_dir.path(), &sample_info("one")).unwrap();
+        let second = registry.register(project_dir.path(), &sample_info("two")).unwrap();
+
+        assert_eq!(first, second);
+        let entries = registry.entries().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].info.name, "two");
+    }
+
+    #[test]
+    fn test_entries_skip_unreadable_files() {
+        let registry_dir = tempdir().unwrap();
+        let project_dir = tempdir().unwrap();
+        let registry = Registry::open(registry_dir.path()).unwrap();
+        registry.register(project_dir.path(), &sample_info("one")).unwrap();
+        fs::write(registry_dir.path().join("binary.toml"), [0xff, 0xfe, 0x00]).unwrap();
+
+        let entries = registry.entries().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].info.name, "one");
+    }
+
+    #[test]
+    fn test_entries_are_named_by_id() {
+        let registry_dir = tempdir().unwrap();
+        let projects_dir = tempdir().unwrap();
+        let registry = Registry::open(registry_dir.path()).unwrap();
+        let studio = projects_dir.path().join("studio/album");
+        let laptop = projects_dir.path().join("laptop/album");
+        fs::create_dir_all(&studio).unwrap();
+        fs::create_dir_all(&laptop).unwrap();
+
+        // Registered before it had an id, then again with one.
+        let by_path = registry.register(&studio, &sample_info("album")).unwrap();
+        let info = ProjectInfo { id: "0b8e2c1a-5d4f".to_string(), ..sample_info("album") };
+        let by_id = registry.register(&studio, &info).unwrap();
+        assert_eq!(by_id.file_name().unwrap(), "0b8e2c1a-5d4f.toml");
+        assert!(!by_path.exists());
+
+        // The same project at another path shares the entry.
+        assert_eq!(registry.register(&laptop, &info).unwrap(), by_id);
+        let entries = registry.entries().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].path, fs::canonicalize(&laptop).unwrap());
+
+        registry.unregister(&fs::canonicalize(&laptop).unwrap()).unwrap();
+        assert!(registry.entries().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_register_refuses_to_overwrite_another_directory() {
+        let registry_dir = tempdir().unwrap();
+        let project_dir = tempdir().unwrap();
+        let registry = Registry::open(registry_dir.path()).unwrap();
+
+        // Stand in for a name collision: the entry file records another directory.
+        let entry_path = registry.register(project_dir.path(), &sample_info("one")).unwrap();
+        let other = RegistryEntry {
+            path: PathBuf::from("/elsewhere/song"),
+            info: sample_info("other"),
+        };
+        fs::write(&entry_path, toml::to_string(&other).unwrap()).unwrap();
+
+        let error = registry.register(project_dir.path(), &sample_info("one")).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::AlreadyExists);
+        registry.unregister(&fs::canonicalize(project_dir.path()).unwrap()).unwrap();
+        assert_eq!(registry.entries().unwrap()[0].info.name, "other");
+    }
+}
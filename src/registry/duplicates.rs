@@ -0,0 +1,315 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use log::debug;
+
+use super::{fnv1a, RegistryEntry};
+use crate::project_info::design::design_file_format;
+use crate::project_info::music::daw_project_format;
+use crate::project_info::photography::catalog_format;
+use crate::project_info::programming::PROGRAMMING_INDICATORS;
+use crate::project_info::video::editor_project_format;
+use crate::project_info::writing::document_format;
+
+/// Words that copies of a folder tend to gain, like `final_v2_REAL` or `song copy 3`,
+/// ignored when comparing names.
+const COPY_WORDS: &[&str] = &[
+    "copy", "final", "real", "old", "new", "backup", "bak", "latest", "edit", "dup", "duplicate",
+];
+
+/// How much two projects' sizes may differ, as a fraction of the larger one, for a
+/// shared name to count as a duplicate.
+const SIZE_TOLERANCE: f64 = 0.1;
+
+/// Why two projects look like copies of each other.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Reason {
+    /// Both are clones of the same git repository.
+    SameRemote(String),
+    /// Both have the same name, ignoring copy suffixes, and a similar size.
+    SameName(String),
+    /// Both hold an identical session, manifest, or document.
+    SameFile(String),
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Reason::SameRemote(remote) => write!(f, "same git remote {}", remote),
+            Reason::SameName(name) => write!(f, "same name '{}' and similar size", name),
+            Reason::SameFile(file) => write!(f, "identical {}", file),
+        }
+    }
+}
+
+/// Projects that are probably copies of one another.
+#[derive(Debug, Clone)]
+pub struct DuplicateGroup {
+    /// The projects, most recently modified first.
+    pub projects: Vec<RegistryEntry>,
+    /// Every reason that linked two of them.
+    pub reasons: BTreeSet<Reason>,
+}
+
+/// Groups the entries that are probably copies of one another: clones of the same git
+/// remote, projects with the same name (ignoring suffixes like `_final_v2`) and a
+/// similar size, and projects holding an identical key file, i.e. a DAW or editor
+/// session, a manifest, a design file, or a document.
+///
+/// Projects are grouped transitively, so a copy of a copy lands in its original's
+/// group. Groups are sorted by the path of their most recently modified project.
+pub fn find_duplicates(entries: &[RegistryEntry]) -> Vec<DuplicateGroup> {
+    let mut groups = UnionFind::new(entries.len());
+    let mut reasons: Vec<(usize, Reason)> = Vec::new();
+    let mut link = |members: &[usize], reason: Reason| {
+        for &member in &members[1..] {
+            groups.union(members[0], member);
+        }
+        reasons.push((members[0], reason));
+    };
+
+    let mut by_remote: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    let mut by_name: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    let mut by_file: BTreeMap<(String, u64), Vec<usize>> = BTreeMap::new();
+    for (index, entry) in entries.iter().enumerate() {
+        let remotes: BTreeSet<String> = entry
+            .info
+            .git_url
+            .iter()
+            .chain(entry.info.remotes.values())
+            .map(|url| normalize_remote(url))
+            .collect();
+        for remote in remotes {
+            by_remote.entry(remote).or_default().push(index);
+        }
+        let name = base_name(&entry.info.name);
+        if !name.is_empty() {
+            by_name.entry(name).or_default().push(index);
+        }
+        for key_file in key_files(&entry.path) {
+            by_file.entry(key_file).or_default().push(index);
+        }
+    }
+
+    for (remote, members) in by_remote.into_iter().filter(|(_, members)| members.len() > 1) {
+        link(&members, Reason::SameRemote(remote));
+    }
+    for (name, members) in by_name {
+        // Same-named projects only count when their sizes are close.
+        for (i, &a) in members.iter().enumerate() {
+            for &b in &members[i + 1..] {
+                if similar_size(entries[a].info.total_size_bytes, entries[b].info.total_size_bytes) {
+                    link(&[a, b], Reason::SameName(name.clone()));
+                }
+            }
+        }
+    }
+    for ((file, _), members) in by_file.into_iter().filter(|(_, members)| members.len() > 1) {
+        link(&members, Reason::SameFile(file));
+    }
+
+    let mut grouped: BTreeMap<usize, DuplicateGroup> = BTreeMap::new();
+    for (index, entry) in entries.iter().enumerate() {
+        grouped
+            .entry(groups.find(index))
+            .or_insert_with(|| DuplicateGroup {
+                projects: Vec::new(),
+                reasons: BTreeSet::new(),
+            })
+            .projects
+            .push(entry.clone());
+    }
+    for (index, reason) in reasons {
+        if let Some(group) = grouped.get_mut(&groups.find(index)) {
+            group.reasons.insert(reason);
+        }
+    }
+
+    let mut duplicates: Vec<DuplicateGroup> = grouped
+        .into_values()
+        .filter(|group| group.projects.len() > 1)
+        .map(|mut group| {
+            group
+                .projects
+                .sort_by_key(|entry| std::cmp::Reverse(entry.info.date_modified));
+            group
+        })
+        .collect();
+    duplicates.sort_by(|a, b| a.projects[0].path.cmp(&b.projects[0].path));
+    duplicates
+}
+
+/// Reduces a git remote URL to its host and path, so the SSH and HTTPS URLs of a
+/// repository compare equal: `git@github.com:me/song.git` and
+/// `https://github.com/me/song` both become `github.com/me/song`.
+fn normalize_remote(url: &str) -> String {
+    let url = url.trim();
+    let without_scheme = match url.split_once("://") {
+        Some((_, rest)) => rest.to_string(),
+        // scp-like syntax: user@host:path
+        None => url.replacen(':', "/", 1),
+    };
+    let without_user = match without_scheme.split_once('@') {
+        Some((user, rest)) if !user.contains('/') => rest,
+        _ => &without_scheme,
+    };
+    let path = without_user.trim_end_matches('/');
+    path.strip_suffix(".git").unwrap_or(path).to_lowercase()
+}
+
+/// Returns a project name without case, punctuation, or the words and version
+/// numbers copies gain, e.g. `Song_final_v2_REAL` becomes `song` and `Beat 42 copy 3`
+/// becomes `beat 42`.
+fn base_name(name: &str) -> String {
+    let mut words: Vec<String> = Vec::new();
+    let mut after_copy_word = false;
+    for word in name.split(|c: char| !c.is_alphanumeric()).filter(|word| !word.is_empty()) {
+        let word = word.to_lowercase();
+        let is_number = word.chars().all(|c| c.is_ascii_digit());
+        let is_version = word
+            .strip_prefix('v')
+            .is_some_and(|digits| !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()));
+        let is_copy_word = COPY_WORDS.contains(&word.as_str());
+        let is_copy_number = is_number && after_copy_word;
+        if !(is_copy_word || is_version || is_copy_number) {
+            words.push(word);
+        }
+        after_copy_word = is_copy_word || (after_copy_word && is_number);
+    }
+    words.join(" ")
+}
+
+fn similar_size(a: u64, b: u64) -> bool {
+    let larger = a.max(b) as f64;
+    larger == 0.0 || (a.abs_diff(b) as f64) <= larger * SIZE_TOLERANCE
+}
+
+/// Returns the name and content hash of each key file at the top of a project:
+/// sessions, manifests, design files, and documents.
+fn key_files(directory: &Path) -> Vec<(String, u64)> {
+    let Ok(entries) = fs::read_dir(directory) else {
+        debug!("Skipping the files of missing project {}", directory.display());
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_file()))
+        .filter(|entry| is_key_file(&entry.path()))
+        .filter_map(|entry| {
+            let content = fs::read(entry.path()).ok()?;
+            (!content.is_empty())
+                .then(|| (entry.file_name().to_string_lossy().to_string(), fnv1a(&content)))
+        })
+        .collect()
+}
+
+fn is_key_file(path: &Path) -> bool {
+    let file_name = path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+    PROGRAMMING_INDICATORS.contains(&file_name.as_ref())
+        || daw_project_format(path).is_some()
+        || editor_project_format(path).is_some()
+        || design_file_format(path).is_some()
+        || catalog_format(path).is_some()
+        || document_format(path).is_some()
+}
+
+/// A disjoint-set forest over entry indices.
+struct UnionFind {
+    parents: Vec<usize>,
+}
+
+impl UnionFind {
+    fn new(len: usize) -> Self {
+        UnionFind {
+            parents: (0..len).collect(),
+        }
+    }
+
+    fn find(&mut self, index: usize) -> usize {
+        let parent = self.parents[index];
+        if parent == index {
+            return index;
+        }
+        let root = self.find(parent);
+        self.parents[index] = root;
+        root
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        self.parents[b.max(a)] = b.min(a);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project_info::ProjectInfo;
+    use tempfile::tempdir;
+
+    fn entry(path: &Path, name: &str, size: u64) -> RegistryEntry {
+        RegistryEntry {
+            path: path.to_path_buf(),
+            info: ProjectInfo {
+                name: name.to_string(),
+                total_size_bytes: size,
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_base_name_and_remote() {
+        assert_eq!(base_name("Song_final_v2_REAL"), "song");
+        assert_eq!(base_name("Beat 42 (copy 3)"), "beat 42");
+        assert_eq!(base_name("Beat 43"), "beat 43");
+        assert_eq!(
+            normalize_remote("git@github.com:Me/Song.git"),
+            normalize_remote("https://github.com/me/song/")
+        );
+    }
+
+    #[test]
+    fn test_find_duplicates() {
+        let dir = tempdir().unwrap();
+        let paths: Vec<_> = ["song", "song_final_v2", "other", "other-copy", "clone", "unrelated"]
+            .iter()
+            .map(|name| {
+                let path = dir.path().join(name);
+                fs::create_dir_all(&path).unwrap();
+                path
+            })
+            .collect();
+        fs::write(paths[2].join("mix.rpp"), "<REAPER_PROJECT").unwrap();
+        fs::write(paths[3].join("mix.rpp"), "<REAPER_PROJECT").unwrap();
+
+        let mut entries = vec![
+            entry(&paths[0], "song", 1000),
+            entry(&paths[1], "Song_final_v2", 1050),
+            entry(&paths[2], "other", 10),
+            entry(&paths[3], "mixdown", 99999),
+            entry(&paths[4], "clone", 5),
+            entry(&paths[5], "song", 1),
+        ];
+        entries[0].info.git_url = Some("git@github.com:me/song.git".to_string());
+        entries[4].info.remotes.insert("origin".to_string(), "https://github.com/me/song".to_string());
+
+        let groups = find_duplicates(&entries);
+        assert_eq!(groups.len(), 2);
+
+        let paths_of = |group: &DuplicateGroup| {
+            let mut paths: Vec<_> = group.projects.iter().map(|entry| entry.path.clone()).collect();
+            paths.sort();
+            paths
+        };
+        assert_eq!(paths_of(&groups[0]), vec![paths[2].clone(), paths[3].clone()]);
+        assert_eq!(
+            groups[0].reasons.iter().collect::<Vec<_>>(),
+            vec![&Reason::SameFile("mix.rpp".to_string())]
+        );
+        assert_eq!(paths_of(&groups[1]), vec![paths[4].clone(), paths[0].clone(), paths[1].clone()]);
+        assert_eq!(groups[1].reasons.len(), 2);
+    }
+}
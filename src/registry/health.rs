@@ -0,0 +1,208 @@
+use std::fmt;
+
+use chrono::{DateTime, Local};
+use serde::Serialize;
+
+use super::RegistryEntry;
+use crate::project_info::status::ProjectStatus;
+use crate::project_info::volume::{self, Volume};
+
+/// How long a project may go without changes before it counts as untouched.
+pub const STALE_AFTER_DAYS: i64 = 365;
+
+/// Something about a project that deserves a look.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "issue", rename_all = "snake_case")]
+pub enum Issue {
+    /// The project's directory no longer exists where it was registered.
+    Missing,
+    /// The git repository has uncommitted or untracked changes.
+    Uncommitted,
+    /// The git repository has no remote to push to.
+    NoRemote,
+    /// The git repository has commits on no remote.
+    Unpushed { commits: usize },
+    /// Neither an archive nor a git remote holds a copy of the project.
+    NoBackup,
+    /// A programming project has no license.
+    NoLicense,
+    /// The project has not changed for a long time but is not archived or released.
+    Untouched { days: i64 },
+}
+
+impl Issue {
+    /// How urgent the issue is, from 1 to 5: losing work weighs more than tidiness.
+    pub fn weight(&self) -> u32 {
+        match self {
+            Issue::Missing => 5,
+            Issue::Uncommitted | Issue::NoBackup => 3,
+            Issue::NoRemote | Issue::Unpushed { .. } | Issue::Untouched { .. } => 2,
+            Issue::NoLicense => 1,
+        }
+    }
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Issue::Missing => write!(f, "directory missing; moved or deleted?"),
+            Issue::Uncommitted => write!(f, "uncommitted changes"),
+            Issue::NoRemote => write!(f, "git repository without a remote"),
+            Issue::Unpushed { commits } => write!(f, "{} unpushed commits", commits),
+            Issue::NoBackup => write!(f, "no backup recorded; see `tagger archive`"),
+            Issue::NoLicense => write!(f, "no license"),
+            Issue::Untouched { days } => {
+                write!(f, "untouched for {} days; archive it or pick it up again", days)
+            }
+        }
+    }
+}
+
+/// A registered project and what it needs.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectHealth {
+    /// The registered project.
+    #[serde(flatten)]
+    pub entry: RegistryEntry,
+    /// The project's issues, most urgent first.
+    pub issues: Vec<Issue>,
+    /// The sum of the issues' weights; higher needs attention sooner.
+    pub score: u32,
+}
+
+/// Checks the registered projects and returns those with issues, as a to-do list:
+/// highest score first, then least recently modified.
+///
+/// The checks read what was recorded when each project was last saved, apart from
+/// whether its directory still exists. Projects on drives that are not mounted are
+/// left out, as nothing about them can be checked.
+///
+/// # Arguments
+///
+/// * `entries` - The registered projects.
+/// * `now` - The current time, to measure how long projects were left untouched.
+pub fn assess(entries: &[RegistryEntry], now: DateTime<Local>) -> Vec<ProjectHealth> {
+    let mounted = volume::mounted();
+    let mut report: Vec<ProjectHealth> = entries
+        .iter()
+        .filter_map(|entry| {
+            let mut issues = issues(entry, now, &mounted);
+            if issues.is_empty() {
+                return None;
+            }
+            issues.sort_by_key(|issue| std::cmp::Reverse(issue.weight()));
+            let score = issues.iter().map(Issue::weight).sum();
+            Some(ProjectHealth { entry: entry.clone(), issues, score })
+        })
+        .collect();
+    report.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| a.entry.info.date_modified.cmp(&b.entry.info.date_modified))
+    });
+    report
+}
+
+/// Returns the issues of one project.
+fn issues(entry: &RegistryEntry, now: DateTime<Local>, mounted: &[Volume]) -> Vec<Issue> {
+    if entry.offline_volume(mounted).is_some() {
+        return Vec::new();
+    }
+    if !entry.path.is_dir() {
+        return vec![Issue::Missing];
+    }
+
+    let info = &entry.info;
+    let mut issues = Vec::new();
+    let has_remote = info.git_url.is_some() || !info.remotes.is_empty();
+    if info.git.is_some() && !has_remote {
+        issues.push(Issue::NoRemote);
+    }
+    if let Some(status) = &info.git_status {
+        if status.dirty {
+            issues.push(Issue::Uncommitted);
+        }
+        if has_remote && status.unpushed_commits > 0 {
+            issues.push(Issue::Unpushed { commits: status.unpushed_commits });
+        }
+    }
+    if info.archives.is_empty() && !has_remote {
+        issues.push(Issue::NoBackup);
+    }
+    if info.project_type == "programming" && info.license.is_none() {
+        issues.push(Issue::NoLicense);
+    }
+
+    let finished = matches!(info.status, Some(ProjectStatus::Archived | ProjectStatus::Released));
+    let days = (now - info.date_modified).num_days();
+    if !finished && days >= STALE_AFTER_DAYS {
+        issues.push(Issue::Untouched { days });
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project_info::git::{GitInfo, GitStatus};
+    use crate::project_info::ProjectInfo;
+    use chrono::Duration;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_assess_prioritizes_issues() {
+        let dir = tempdir().unwrap();
+        let now = Local::now();
+        let entry = |name: &str, info: ProjectInfo| RegistryEntry {
+            path: dir.path().join(name),
+            info: ProjectInfo { name: name.to_string(), date_modified: now, ..info },
+        };
+        for name in ["repo", "session", "released", "healthy"] {
+            std::fs::create_dir(dir.path().join(name)).unwrap();
+        }
+
+        let entries = [
+            entry(
+                "repo",
+                ProjectInfo {
+                    project_type: "programming".to_string(),
+                    git: Some(GitInfo::default()),
+                    git_status: Some(GitStatus { dirty: true, unpushed_commits: 4 }),
+                    ..Default::default()
+                },
+            ),
+            entry("gone", ProjectInfo::default()),
+            entry("session", ProjectInfo { project_type: "music".to_string(), ..Default::default() }),
+            RegistryEntry {
+                info: ProjectInfo {
+                    status: Some(ProjectStatus::Released),
+                    git_url: Some("https://example.com/released.git".to_string()),
+                    date_modified: now - Duration::days(800),
+                    ..Default::default()
+                },
+                ..entry("released", ProjectInfo::default())
+            },
+            entry(
+                "healthy",
+                ProjectInfo { git_url: Some("https://example.com/healthy.git".to_string()), ..Default::default() },
+            ),
+        ];
+
+        let report = assess(&entries, now);
+        let names: Vec<&str> = report.iter().map(|health| health.entry.info.name.as_str()).collect();
+        assert_eq!(names, ["repo", "gone", "session"]);
+        assert_eq!(
+            report[0].issues,
+            [Issue::Uncommitted, Issue::NoBackup, Issue::NoRemote, Issue::NoLicense]
+        );
+        assert_eq!(report[0].score, 9);
+        assert_eq!(report[1].issues, [Issue::Missing]);
+        assert_eq!(report[2].issues, [Issue::NoBackup]);
+
+        let stale = RegistryEntry {
+            info: ProjectInfo { date_modified: now - Duration::days(400), ..entries[4].info.clone() },
+            ..entries[4].clone()
+        };
+        assert_eq!(assess(&[stale], now)[0].issues, [Issue::Untouched { days: 400 }]);
+    }
+}
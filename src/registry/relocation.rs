@@ -0,0 +1,556 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use log::{info, warn};
+use walkdir::WalkDir;
+
+use super::{Registry, RegistryEntry};
+use crate::config::Config;
+use crate::messages::{tr, Message};
+use crate::project_info::fingerprint::Fingerprint;
+use crate::project_info::overrides::Overrides;
+use crate::project_info::volume;
+use crate::project_info::ProjectInfo;
+
+/// How many levels below each search directory a moved project is looked for.
+pub const SEARCH_DEPTH: usize = 4;
+
+/// How a `project_info.toml` ended up in a directory it was not written for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Relocation {
+    /// The project was moved or renamed. `previous` is the directory it is still
+    /// registered under, if the registry knows it.
+    Moved { previous: Option<PathBuf> },
+    /// The file was copied (or the folder duplicated) from a project that still exists.
+    Copied { original: PathBuf },
+}
+
+/// A `project_info.toml` whose recorded identity does not match its directory.
+#[derive(Debug, Clone)]
+pub struct Mismatch {
+    /// The project information found in the directory.
+    pub recorded: ProjectInfo,
+    /// What most likely happened to the file.
+    pub relocation: Relocation,
+}
+
+impl Mismatch {
+    /// Describes the mismatch for the user.
+    pub fn describe(&self) -> String {
+        match &self.relocation {
+            Relocation::Moved { previous: Some(previous) } => tr(
+                Message::RelocationMoved,
+                &[&self.recorded.name, &previous.display()],
+            ),
+            Relocation::Moved { previous: None } => {
+                tr(Message::RelocationRenamed, &[&self.recorded.directory_name])
+            }
+            Relocation::Copied { original } => tr(Message::RelocationCopied, &[&original.display()]),
+        }
+    }
+
+    /// Keeps the recorded identity: the identifier, slug, alias, status, notes,
+    /// related projects, and creation date carry over to `project`, and the other directory's registry entry is
+    /// removed so the two directories do not share one identity.
+    pub fn relink(&self, project: &mut ProjectInfo, registry: &Registry) -> io::Result<()> {
+        project.keep_identity(&self.recorded);
+        project.alias = self.recorded.alias.clone();
+        project.status = self.recorded.status;
+        project.notes = self.recorded.notes.clone();
+        project.related = self.recorded.related.clone();
+        project.date_created = self.recorded.date_created;
+
+        match &self.relocation {
+            Relocation::Moved { previous: Some(path) } | Relocation::Copied { original: path } => {
+                registry.unregister(path)
+            }
+            Relocation::Moved { previous: None } => Ok(()),
+        }
+    }
+}
+
+/// Checks whether the directory's `project_info.toml` was written for another directory.
+///
+/// A file is considered relocated when the registry holds the same project (same
+/// identifier, or same name and creation date for files written before identifiers
+/// existed) under a different path, or when the directory name it
+/// records differs from the directory it is in.
+///
+/// # Arguments
+///
+/// * `directory` - A reference to the project's directory path.
+/// * `registry` - The registry to look for the project's other locations in.
+///
+/// # Returns
+///
+/// The mismatch, or `None` if there is no `project_info.toml` or it matches its directory.
+pub fn detect(directory: &Path, registry: &Registry) -> Option<Mismatch> {
+    let recorded = ProjectInfo::load_from_toml_file(directory).ok()?;
+    let path = fs::canonicalize(directory).ok()?;
+
+    let other = registry
+        .entries()
+        .ok()?
+        .into_iter()
+        .find(|entry| entry.path != path && is_same_project(&entry.info, &recorded));
+
+    let relocation = match other {
+        Some(entry) if entry.path.join("project_info.toml").exists() => {
+            Relocation::Copied { original: entry.path }
+        }
+        Some(entry) => Relocation::Moved {
+            previous: Some(entry.path),
+        },
+        None => {
+            let directory_name = path.file_name()?.to_string_lossy();
+            if recorded.directory_name.is_empty() || recorded.directory_name == directory_name {
+                return None;
+            }
+            Relocation::Moved { previous: None }
+        }
+    };
+
+    info!("{}: {:?}", directory.display(), relocation);
+    Some(Mismatch { recorded, relocation })
+}
+
+/// Returns `true` if two project records describe the same project.
+fn is_same_project(a: &ProjectInfo, b: &ProjectInfo) -> bool {
+    if !a.id.is_empty() && !b.id.is_empty() {
+        return a.id == b.id;
+    }
+    a.name == b.name && a.date_created == b.date_created
+}
+
+/// What gave away a missing project's new directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Evidence {
+    /// Its `project_info.toml` records the project's identifier.
+    Id,
+    /// It has the project's directory name and files matching its fingerprint.
+    Fingerprint,
+    /// It is the only directory with the project's directory name, and the project
+    /// has no saved fingerprint to check it against.
+    Name,
+}
+
+impl Evidence {
+    /// Describes the evidence for the user.
+    pub fn describe(self) -> &'static str {
+        match self {
+            Evidence::Id => "id",
+            Evidence::Fingerprint => "fingerprint",
+            Evidence::Name => "name",
+        }
+    }
+}
+
+/// What the search for a missing project turned up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Search {
+    /// The project was found in a single directory.
+    Found { path: PathBuf, evidence: Evidence },
+    /// Several directories could be the project; none was picked.
+    Ambiguous { candidates: Vec<PathBuf> },
+    /// No directory looks like the project.
+    NotFound,
+}
+
+/// A registered project whose directory no longer exists, and where it went.
+#[derive(Debug, Clone)]
+pub struct Repair {
+    /// The project's registry entry, at its old path.
+    pub entry: RegistryEntry,
+    /// The outcome of the search.
+    pub search: Search,
+}
+
+impl Repair {
+    /// Returns `true` if the project was found only by its directory name, which
+    /// the user should confirm before the registry is changed.
+    pub fn needs_confirmation(&self) -> bool {
+        matches!(self.search, Search::Found { evidence: Evidence::Name, .. })
+    }
+
+    /// Registers the project under the directory it was found in instead of its
+    /// old path. Does nothing if it was not found.
+    pub fn apply(&self, registry: &Registry) -> io::Result<()> {
+        if let Search::Found { path, .. } = &self.search {
+            registry.register(path, &self.entry.info)?;
+            registry.unregister(&self.entry.path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Looks for the registered projects whose directories no longer exist, and
+/// registers each one found under its new directory instead of the old one. Projects
+/// found by name alone are left for the caller to confirm and [`Repair::apply`].
+///
+/// Projects are looked for up to [`SEARCH_DEPTH`] levels below the given directories,
+/// the configured roots, the directories holding the other registered projects, the
+/// closest remaining parent of each missing directory, and the same place on drives
+/// now mounted elsewhere. Projects on drives that are not mounted are offline rather
+/// than missing, and are left alone. See [`find_moved`] for how they are recognized.
+///
+/// # Arguments
+///
+/// * `registry` - The registry to repair.
+/// * `search_dirs` - More directories to look in, such as another drive.
+/// * `config` - The configuration naming the information file and scan settings.
+///
+/// # Returns
+///
+/// A repair per missing project, whether or not it was found.
+pub fn repair(registry: &Registry, search_dirs: &[PathBuf], config: &Config) -> io::Result<Vec<Repair>> {
+    let mounted = volume::mounted();
+    let entries = registry.entries()?;
+    let (present, missing): (Vec<RegistryEntry>, Vec<RegistryEntry>) = entries
+        .into_iter()
+        .filter(|entry| entry.offline_volume(&mounted).is_none())
+        .partition(|entry| entry.path.is_dir());
+    if missing.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut dirs: Vec<PathBuf> = search_dirs.to_vec();
+    dirs.extend(config.roots.iter().map(|root| root.directory()));
+    dirs.extend(present.iter().filter_map(|entry| entry.path.parent().map(Path::to_path_buf)));
+    dirs.extend(
+        missing
+            .iter()
+            .filter_map(|entry| entry.path.ancestors().skip(1).find(|dir| dir.is_dir()))
+            // The file system root would mean searching the whole disk.
+            .filter(|dir| dir.parent().is_some())
+            .map(Path::to_path_buf),
+    );
+    // A drive mounted somewhere else this time holds its projects at the same place
+    // below its new mount point.
+    dirs.extend(missing.iter().filter_map(|entry| {
+        let recorded = entry.info.volume.as_ref()?;
+        let remounted = recorded.find_in(&mounted)?;
+        let relative = entry.path.parent()?.strip_prefix(&recorded.mount_point).ok()?;
+        Some(remounted.mount_point.join(relative)).filter(|dir| dir.is_dir())
+    }));
+    let registered: Vec<PathBuf> = present.into_iter().map(|entry| entry.path).collect();
+
+    let repairs = find_moved(missing, &dirs, &registered, config);
+    for repair in repairs.iter().filter(|repair| !repair.needs_confirmation()) {
+        repair.apply(registry)?;
+    }
+    Ok(repairs)
+}
+
+/// Searches directories for projects that are gone from their registered paths.
+///
+/// A directory is taken for a project when its information file records the
+/// project's identifier. Failing that, directories named like the project are
+/// considered: the one whose files match the project's saved fingerprint, or, for a
+/// project without one, the only one. Directories whose information file records
+/// another identifier are never taken. Each directory is given to one project at
+/// most, and registered projects are neither taken nor searched.
+///
+/// # Arguments
+///
+/// * `missing` - The registry entries of the missing projects.
+/// * `search_dirs` - The directories to search, [`SEARCH_DEPTH`] levels deep.
+/// * `registered` - The directories of the projects that are where the registry says.
+/// * `config` - The configuration naming the information file and scan settings.
+pub fn find_moved(
+    missing: Vec<RegistryEntry>,
+    search_dirs: &[PathBuf],
+    registered: &[PathBuf],
+    config: &Config,
+) -> Vec<Repair> {
+    let candidates = candidate_dirs(search_dirs, registered, config);
+    let mut taken: Vec<PathBuf> = Vec::new();
+
+    missing
+        .into_iter()
+        .map(|entry| {
+            let available = candidates.iter().filter(|(path, _)| !taken.contains(path));
+            let search = search(&entry, available, config);
+            if let Search::Found { path, .. } = &search {
+                taken.push(path.clone());
+            }
+            info!("{}: {:?}", entry.path.display(), search);
+            Repair { entry, search }
+        })
+        .collect()
+}
+
+/// Returns every directory under the search directories that is not a registered
+/// project, with the project information found in it. Directories holding project
+/// information, hidden directories, and registered projects are not descended into.
+fn candidate_dirs(
+    search_dirs: &[PathBuf],
+    registered: &[PathBuf],
+    config: &Config,
+) -> Vec<(PathBuf, Option<ProjectInfo>)> {
+    let mut candidates: Vec<(PathBuf, Option<ProjectInfo>)> = Vec::new();
+    for search_dir in search_dirs {
+        let mut walker = WalkDir::new(search_dir).min_depth(1).max_depth(SEARCH_DEPTH).into_iter();
+        while let Some(entry) = walker.next() {
+            let Ok(entry) = entry else {
+                continue;
+            };
+            if !entry.file_type().is_dir() {
+                continue;
+            }
+            let hidden = entry.file_name().to_string_lossy().starts_with('.');
+            let Ok(path) = fs::canonicalize(entry.path()) else {
+                continue;
+            };
+            if hidden || registered.contains(&path) {
+                walker.skip_current_dir();
+                continue;
+            }
+            if candidates.iter().any(|(candidate, _)| *candidate == path) {
+                continue;
+            }
+            let info_file = config.output.file_for(&path);
+            let info = info_file
+                .exists()
+                .then(|| ProjectInfo::load_from_file(&info_file))
+                .and_then(|info| info.inspect_err(|e| warn!("{}: {}", info_file.display(), e)).ok());
+            if info.is_some() {
+                walker.skip_current_dir();
+            }
+            candidates.push((path, info));
+        }
+    }
+    candidates
+}
+
+/// Picks the directory a missing project moved to among the candidates.
+fn search<'a>(
+    entry: &RegistryEntry,
+    candidates: impl Iterator<Item = &'a (PathBuf, Option<ProjectInfo>)>,
+    config: &Config,
+) -> Search {
+    let recorded = &entry.info;
+    let directory_name = match recorded.directory_name.as_str() {
+        "" => entry.path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default(),
+        name => name.to_string(),
+    };
+
+    let mut named: Vec<&PathBuf> = Vec::new();
+    for (path, info) in candidates {
+        if let Some(info) = info {
+            if !recorded.id.is_empty() && info.id == recorded.id {
+                return Search::Found { path: path.clone(), evidence: Evidence::Id };
+            }
+            // Another project's directory, whatever it is called.
+            if !recorded.id.is_empty() && !info.id.is_empty() {
+                continue;
+            }
+        }
+        if path.file_name().is_some_and(|name| name.to_string_lossy() == directory_name) {
+            named.push(path);
+        }
+    }
+
+    if let Some(saved) = &recorded.fingerprint {
+        let matching: Vec<&PathBuf> = named
+            .iter()
+            .copied()
+            .filter(|path| {
+                let scan = Overrides::load(path).scan_config(&config.scan);
+                Fingerprint::compute(path, saved.contents, &scan, &config.output.file_for(path))
+                    .is_ok_and(|current| current.matches(saved))
+            })
+            .collect();
+        // A saved fingerprint that no directory matches rules them all out.
+        return match matching[..] {
+            [] => Search::NotFound,
+            [path] => Search::Found { path: path.clone(), evidence: Evidence::Fingerprint },
+            _ => Search::Ambiguous { candidates: matching.into_iter().cloned().collect() },
+        };
+    }
+    match named[..] {
+        [] => Search::NotFound,
+        [path] => Search::Found { path: path.clone(), evidence: Evidence::Name },
+        _ => Search::Ambiguous { candidates: named.into_iter().cloned().collect() },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeZone};
+    use tempfile::tempdir;
+
+    fn write_info(directory: &Path, info: &ProjectInfo) {
+        fs::write(directory.join("project_info.toml"), toml::to_string(info).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_detect_copied_and_moved_projects() {
+        let registry_dir = tempdir().unwrap();
+        let projects_dir = tempdir().unwrap();
+        let registry = Registry::open(registry_dir.path()).unwrap();
+
+        let original = projects_dir.path().join("song");
+        let copy = projects_dir.path().join("song copy");
+        fs::create_dir(&original).unwrap();
+        fs::create_dir(&copy).unwrap();
+
+        let info = ProjectInfo {
+            name: "song".to_string(),
+            directory_name: "song".to_string(),
+            id: "0d4f6f9e-3c1a-4b8e-9a57-2f0c8d1e6b3a".to_string(),
+            alias: "demo".to_string(),
+            date_created: Local.with_ymd_and_hms(2023, 5, 1, 12, 0, 0).unwrap(),
+            ..Default::default()
+        };
+        write_info(&original, &info);
+        write_info(&copy, &info);
+        registry.register(&original, &info).unwrap();
+
+        // The registered project matches its own directory.
+        assert!(detect(&original, &registry).is_none());
+
+        let original_path = fs::canonicalize(&original).unwrap();
+        let mismatch = detect(&copy, &registry).unwrap();
+        assert_eq!(
+            mismatch.relocation,
+            Relocation::Copied {
+                original: original_path.clone()
+            }
+        );
+
+        // Once the original is gone, the copy is the moved project.
+        fs::remove_dir_all(&original).unwrap();
+        let mismatch = detect(&copy, &registry).unwrap();
+        assert_eq!(
+            mismatch.relocation,
+            Relocation::Moved {
+                previous: Some(original_path)
+            }
+        );
+
+        // Relinking keeps the identity and drops the stale registry entry.
+        let mut project = ProjectInfo {
+            name: "song".to_string(),
+            ..Default::default()
+        };
+        mismatch.relink(&mut project, &registry).unwrap();
+        assert_eq!(project.id, info.id);
+        assert_eq!(project.alias, "demo");
+        assert_eq!(project.date_created, info.date_created);
+        assert!(registry.entries().unwrap().is_empty());
+
+        // Without a registry entry, only the recorded directory name gives it away.
+        let mismatch = detect(&copy, &registry).unwrap();
+        assert_eq!(mismatch.relocation, Relocation::Moved { previous: None });
+    }
+
+    #[test]
+    fn test_repair_finds_moved_projects() {
+        let registry_dir = tempdir().unwrap();
+        let old_drive = tempdir().unwrap();
+        let new_drive = tempdir().unwrap();
+        let registry = Registry::open(registry_dir.path()).unwrap();
+        let config = Config::default();
+
+        let project = |name: &str, id: &str| ProjectInfo {
+            name: name.to_string(),
+            directory_name: name.to_string(),
+            id: id.to_string(),
+            ..Default::default()
+        };
+        let song = project("song", "0d4f6f9e-3c1a-4b8e-9a57-2f0c8d1e6b3a");
+        let beat = project("beat", "");
+        let demo = project("demo", "");
+        for info in [&song, &beat, &demo] {
+            let directory = old_drive.path().join(&info.name);
+            fs::create_dir(&directory).unwrap();
+            write_info(&directory, info);
+            registry.register(&directory, info).unwrap();
+        }
+
+        // The song was renamed on the way, the beat kept its name, and there are two demos.
+        fs::create_dir_all(new_drive.path().join("music/2024")).unwrap();
+        fs::rename(old_drive.path().join("song"), new_drive.path().join("music/2024/song (final)")).unwrap();
+        fs::rename(old_drive.path().join("beat"), new_drive.path().join("music/beat")).unwrap();
+        fs::remove_dir_all(old_drive.path().join("demo")).unwrap();
+        fs::create_dir_all(new_drive.path().join("a/demo")).unwrap();
+        fs::create_dir_all(new_drive.path().join("b/demo")).unwrap();
+
+        let mut repairs = repair(&registry, &[new_drive.path().to_path_buf()], &config).unwrap();
+        repairs.sort_by(|a, b| a.entry.info.name.cmp(&b.entry.info.name));
+        let new_drive = fs::canonicalize(new_drive.path()).unwrap();
+        assert_eq!(
+            repairs[0].search,
+            Search::Found { path: new_drive.join("music/beat"), evidence: Evidence::Name }
+        );
+        assert!(matches!(&repairs[1].search, Search::Ambiguous { candidates } if candidates.len() == 2));
+        assert_eq!(
+            repairs[2].search,
+            Search::Found { path: new_drive.join("music/2024/song (final)"), evidence: Evidence::Id }
+        );
+
+        // The beat was found by name alone, so it waits for confirmation.
+        assert!(repairs[0].needs_confirmation());
+        assert!(!repairs[2].needs_confirmation());
+        let old_drive = fs::canonicalize(old_drive.path()).unwrap();
+        let paths: Vec<PathBuf> = registry.entries().unwrap().into_iter().map(|entry| entry.path).collect();
+        assert!(paths.contains(&old_drive.join("beat")));
+        repairs[0].apply(&registry).unwrap();
+
+        let mut paths: Vec<PathBuf> = registry.entries().unwrap().into_iter().map(|entry| entry.path).collect();
+        paths.sort();
+        assert_eq!(paths.len(), 3);
+        assert!(paths.contains(&new_drive.join("music/beat")));
+        assert!(paths.contains(&new_drive.join("music/2024/song (final)")));
+        assert!(paths.contains(&old_drive.join("demo")));
+
+        // Nothing is left to repair but the ambiguous demo.
+        let repairs = repair(&registry, &[new_drive], &config).unwrap();
+        assert_eq!(repairs.len(), 1);
+    }
+
+    #[test]
+    fn test_repair_does_not_take_unrelated_directories() {
+        let registry_dir = tempdir().unwrap();
+        let projects_dir = tempdir().unwrap();
+        let registry = Registry::open(registry_dir.path()).unwrap();
+        let config = Config::default();
+
+        let code = projects_dir.path().join("code/src");
+        fs::create_dir_all(&code).unwrap();
+        fs::write(code.join("main.rs"), "fn main() {}").unwrap();
+        let scan = Overrides::load(&code).scan_config(&config.scan);
+        let info = ProjectInfo {
+            name: "src".to_string(),
+            directory_name: "src".to_string(),
+            id: "0d4f6f9e-3c1a-4b8e-9a57-2f0c8d1e6b3a".to_string(),
+            fingerprint: Some(Fingerprint::compute(&code, false, &scan, &code.join("project_info.toml")).unwrap()),
+            ..Default::default()
+        };
+        registry.register(&code, &info).unwrap();
+        fs::remove_dir_all(&code).unwrap();
+
+        // Another project's `src`, with other files.
+        let other = projects_dir.path().join("other/lib/src");
+        fs::create_dir_all(&other).unwrap();
+        fs::write(other.join("lib.rs"), "pub fn lib() {}").unwrap();
+        let repairs = repair(&registry, &[projects_dir.path().to_path_buf()], &config).unwrap();
+        assert_eq!(repairs[0].search, Search::NotFound);
+
+        // Same files, but the information file records a different project.
+        fs::remove_file(other.join("lib.rs")).unwrap();
+        fs::write(other.join("main.rs"), "fn main() {}").unwrap();
+        let unrelated = ProjectInfo {
+            id: "7c9e6679-7425-40de-944b-e07fc1f90ae7".to_string(),
+            ..info.clone()
+        };
+        write_info(&other, &unrelated);
+        let repairs = repair(&registry, &[projects_dir.path().to_path_buf()], &config).unwrap();
+        assert_eq!(repairs[0].search, Search::NotFound);
+
+        let paths: Vec<PathBuf> = registry.entries().unwrap().into_iter().map(|entry| entry.path).collect();
+        assert_eq!(paths, vec![fs::canonicalize(projects_dir.path()).unwrap().join("code/src")]);
+    }
+}
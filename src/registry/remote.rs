@@ -0,0 +1,485 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use log::{debug, info, warn};
+use sha2::{Digest, Sha256};
+
+use super::{Registry, RegistryEntry};
+use crate::config::{Config, RemoteConfig};
+use crate::enrichment::Enrichment;
+use crate::project_info::ProjectInfo;
+
+pub mod git;
+pub mod s3;
+pub mod webdav;
+
+/// The file on the remote that lists every shared file with the SHA-256 checksum of
+/// its contents, so only changed files are transferred.
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// The file in the registry directory recording, for every shared file, the checksum
+/// both sides last agreed on, so a push or pull can tell which side changed.
+pub const BASE_FILE: &str = ".sync.json";
+
+/// A place the registry is shared through.
+///
+/// Files are named by slash-separated paths relative to the remote, such as
+/// `registry/0b8e2c1a-5d4f-4a7e-9c1b-2f6d8e3a4b5c.toml`.
+pub trait Backend {
+    /// Returns the contents of a file, or `None` if the remote has no such file.
+    fn get(&mut self, name: &str) -> io::Result<Option<Vec<u8>>>;
+
+    /// Stores a file, replacing the remote's copy.
+    fn put(&mut self, name: &str, contents: &[u8]) -> io::Result<()>;
+
+    /// Publishes the files stored so far, for remotes that do not publish each file
+    /// as it is stored.
+    fn publish(&mut self, _message: &str) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Opens the remote the configuration names under `[remote]`.
+///
+/// # Errors
+///
+/// Returns an `InvalidInput` error when no remote is configured or its URL is not
+/// one tagger understands, an error when the remote is on the network and tagger is
+/// offline (see [`Enrichment::client`]), and the backend's error when it cannot be
+/// reached.
+pub fn open(config: &RemoteConfig, enrichment: &Enrichment) -> io::Result<Box<dyn Backend>> {
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidInput, message);
+    let url = config.url.as_deref().ok_or_else(|| invalid("no remote url is configured".to_string()))?;
+    let what = format!("syncing with {}", url);
+
+    if let Some(location) = url.strip_prefix("s3://") {
+        let (bucket, prefix) = location.split_once('/').unwrap_or((location, ""));
+        return Ok(Box::new(s3::S3::new(bucket, prefix, config, enrichment.client(&what)?)?));
+    }
+    let is_git = url.ends_with(".git") || url.starts_with("git@") || url.starts_with("ssh://");
+    if let Some(url) = url.strip_prefix("git+").or(is_git.then_some(url)) {
+        // A repository in a local folder needs no network.
+        if !Path::new(url).exists() {
+            enrichment.ensure_online(&what)?;
+        }
+        return Ok(Box::new(git::Git::open(url)?));
+    }
+    if url.starts_with("https://") || url.starts_with("http://") {
+        return Ok(Box::new(webdav::WebDav::new(url, config, enrichment.client(&what)?)));
+    }
+    Err(invalid(format!(
+        "unknown remote '{}' (expected s3://, a WebDAV https:// URL, or a git repository)",
+        url
+    )))
+}
+
+/// What a push or pull did.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Transfer {
+    /// The files copied, by their names on the remote.
+    pub copied: Vec<String>,
+    /// The number of files that were already the same on both sides.
+    pub unchanged: usize,
+    /// The files left alone because they changed on both sides since the last push or
+    /// pull, by their names on the remote.
+    pub conflicts: Vec<String>,
+}
+
+/// Uploads the registry, and with `projects` each project's information file, to the
+/// remote, replacing the remote's copies of files that changed.
+///
+/// Registry entries are stored as `registry/<entry file>` and information files as
+/// `projects/<project id>.toml`. Files only the remote has are left alone, so each
+/// machine adds its own projects to the shared registry. A remote copy that changed
+/// since this machine last pushed or pulled it is not replaced, but reported as a
+/// conflict.
+pub fn push(backend: &mut dyn Backend, registry: &Registry, config: &Config, projects: bool) -> io::Result<Transfer> {
+    let mut manifest = read_manifest(backend)?;
+    let mut base = read_base(registry);
+    let mut transfer = Transfer::default();
+
+    for (name, path) in local_files(registry, config, projects)? {
+        let contents = fs::read(&path)?;
+        let checksum = checksum(&contents);
+        match manifest.get(&name) {
+            Some(remote) if *remote == checksum => {
+                transfer.unchanged += 1;
+                base.insert(name, checksum);
+                continue;
+            }
+            Some(remote) if base.get(&name) != Some(remote) => {
+                warn!("{} changed on the remote and here; not pushing it", name);
+                transfer.conflicts.push(name);
+                continue;
+            }
+            _ => {}
+        }
+        info!("Pushing {} as {}", path.display(), name);
+        backend.put(&name, &contents)?;
+        manifest.insert(name.clone(), checksum.clone());
+        base.insert(name.clone(), checksum);
+        transfer.copied.push(name);
+    }
+
+    if !transfer.copied.is_empty() {
+        let json = serde_json::to_vec_pretty(&manifest).map_err(io::Error::from)?;
+        backend.put(MANIFEST_FILE, &json)?;
+        backend.publish(&format!("Update {} tagger files", transfer.copied.len()))?;
+    }
+    write_base(registry, &base)?;
+    Ok(transfer)
+}
+
+/// Downloads the registry, and with `projects` the information files of the projects
+/// registered on this machine, from the remote, replacing local files that differ.
+///
+/// Registry entries only this machine has are kept, and files this machine deleted
+/// since it last pushed or pulled them, such as the entry of a project that moved,
+/// stay deleted. A local file that changed, or was deleted while the remote copy
+/// changed, since this machine last pushed or pulled it is not replaced, but reported
+/// as a conflict.
+/// Files that do not parse as a registry entry or project information are skipped
+/// with a warning.
+pub fn pull(backend: &mut dyn Backend, registry: &Registry, config: &Config, projects: bool) -> io::Result<Transfer> {
+    let manifest = read_manifest(backend)?;
+    let mut base = read_base(registry);
+    let mut transfer = Transfer::default();
+
+    // Pull the registry first, so that project files reach projects registered on
+    // another machine too.
+    for (name, checksum) in &manifest {
+        match name.strip_prefix("registry/") {
+            Some(file) if is_file_name(file) => {
+                let destinations = vec![registry.root().join(file)];
+                pull_file(backend, name, checksum, destinations, &mut base, &mut transfer)?;
+            }
+            _ if name.starts_with("projects/") => {}
+            _ => warn!("Ignoring unexpected remote file {}", name),
+        }
+    }
+
+    if projects {
+        let entries = registry.entries()?;
+        for (name, checksum) in &manifest {
+            let Some(id) = name.strip_prefix("projects/").and_then(|file| file.strip_suffix(".toml")) else {
+                continue;
+            };
+            let destinations = entries
+                .iter()
+                .filter(|entry| !id.is_empty() && entry.info.id == id && entry.path.is_dir())
+                .map(|entry| config.output.file_for(&entry.path))
+                .collect();
+            pull_file(backend, name, checksum, destinations, &mut base, &mut transfer)?;
+        }
+    }
+    write_base(registry, &base)?;
+    Ok(transfer)
+}
+
+/// Downloads one remote file into the destinations whose contents differ from it,
+/// unless they changed since the last push or pull.
+fn pull_file(
+    backend: &mut dyn Backend,
+    name: &str,
+    checksum: &str,
+    destinations: Vec<PathBuf>,
+    base: &mut BTreeMap<String, String>,
+    transfer: &mut Transfer,
+) -> io::Result<()> {
+    let mut stale = Vec::new();
+    let mut conflicting = false;
+    let mut deleted = false;
+    for path in destinations {
+        let local = fs::read(&path).map(|contents| self::checksum(&contents)).ok();
+        match local {
+            Some(local) if local == checksum => {}
+            // Only the remote changed, or the file is new here.
+            Some(local) if base.get(name) == Some(&local) => stale.push(path),
+            None if !base.contains_key(name) => stale.push(path),
+            // Deleted here since the last push or pull, such as the entry of a
+            // project that moved; the deletion stands unless the remote changed too.
+            None if base.get(name).is_some_and(|base| base == checksum) => {
+                debug!("{} was deleted here; not pulling it into {}", name, path.display());
+                deleted = true;
+            }
+            None => {
+                warn!("{} was deleted here and changed on the remote; not pulling it into {}", name, path.display());
+                conflicting = true;
+            }
+            Some(_) => {
+                warn!("{} changed here and on the remote; not pulling it into {}", name, path.display());
+                conflicting = true;
+            }
+        }
+    }
+    if conflicting {
+        transfer.conflicts.push(name.to_string());
+    }
+    if stale.is_empty() {
+        if !conflicting && !deleted {
+            transfer.unchanged += 1;
+            base.insert(name.to_string(), checksum.to_string());
+        }
+        return Ok(());
+    }
+
+    let Some(contents) = backend.get(name)? else {
+        warn!("The remote lists {} but does not have it", name);
+        return Ok(());
+    };
+    let text = String::from_utf8_lossy(&contents);
+    let parsed = match name.starts_with("registry/") {
+        true => toml::from_str::<RegistryEntry>(&text).map(drop),
+        false => toml::from_str::<ProjectInfo>(&text).map(drop),
+    };
+    if let Err(e) = parsed {
+        warn!("Skipping unreadable remote file {}: {}", name, e);
+        return Ok(());
+    }
+
+    for path in stale {
+        info!("Pulling {} into {}", name, path.display());
+        fs::write(&path, &contents)?;
+    }
+    if !conflicting && !deleted {
+        base.insert(name.to_string(), checksum.to_string());
+    }
+    transfer.copied.push(name.to_string());
+    Ok(())
+}
+
+/// Lists the local files to share, by their names on the remote.
+fn local_files(registry: &Registry, config: &Config, projects: bool) -> io::Result<BTreeMap<String, PathBuf>> {
+    let mut files = BTreeMap::new();
+    for dir_entry in fs::read_dir(registry.root())?.flatten() {
+        let path = dir_entry.path();
+        if path.extension().is_some_and(|extension| extension == "toml") {
+            files.insert(format!("registry/{}", dir_entry.file_name().to_string_lossy()), path);
+        }
+    }
+
+    if projects {
+        for entry in registry.entries()? {
+            let file = config.output.file_for(&entry.path);
+            if !entry.info.id.is_empty() && file.is_file() {
+                files.insert(format!("projects/{}.toml", entry.info.id), file);
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// Reads the remote's manifest; a remote without one is empty.
+fn read_manifest(backend: &mut dyn Backend) -> io::Result<BTreeMap<String, String>> {
+    match backend.get(MANIFEST_FILE)? {
+        Some(json) => serde_json::from_slice(&json).map_err(|e| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("unreadable {} on the remote: {}", MANIFEST_FILE, e))
+        }),
+        None => Ok(BTreeMap::new()),
+    }
+}
+
+/// Reads the checksums recorded at the last push or pull; none were recorded before
+/// the first one.
+fn read_base(registry: &Registry) -> BTreeMap<String, String> {
+    let path = registry.root().join(BASE_FILE);
+    fs::read(&path)
+        .ok()
+        .and_then(|json| serde_json::from_slice(&json).inspect_err(|e| warn!("{}: {}", path.display(), e)).ok())
+        .unwrap_or_default()
+}
+
+/// Records the checksums both sides agree on after a push or pull.
+fn write_base(registry: &Registry, base: &BTreeMap<String, String>) -> io::Result<()> {
+    let json = serde_json::to_vec_pretty(base).map_err(io::Error::from)?;
+    fs::write(registry.root().join(BASE_FILE), json)
+}
+
+/// Reads the body of a response about the remote file `name`, or `None` if the file
+/// was not found.
+fn response_body(
+    response: Result<ureq::http::Response<ureq::Body>, ureq::Error>,
+    name: &str,
+) -> io::Result<Option<Vec<u8>>> {
+    let mut response = response.map_err(|e| io::Error::other(format!("unable to reach the remote: {}", e)))?;
+    let status = response.status();
+    if status == ureq::http::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    let body = response.body_mut().read_to_vec().map_err(io::Error::other)?;
+    if !status.is_success() {
+        let reason = String::from_utf8_lossy(&body);
+        return Err(io::Error::other(format!("the remote answered {} for {}: {}", status, name, reason.trim())));
+    }
+    Ok(Some(body))
+}
+
+/// Returns the hex SHA-256 checksum of a file's contents.
+fn checksum(contents: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(contents))
+}
+
+/// Returns `true` if the remote name is a plain file name, so that it cannot write
+/// outside of the registry.
+fn is_file_name(name: &str) -> bool {
+    Path::new(name).file_name().is_some_and(|file_name| file_name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use tempfile::tempdir;
+
+    /// A remote kept in memory.
+    #[derive(Default)]
+    struct Memory {
+        files: HashMap<String, Vec<u8>>,
+        puts: usize,
+    }
+
+    impl Backend for Memory {
+        fn get(&mut self, name: &str) -> io::Result<Option<Vec<u8>>> {
+            Ok(self.files.get(name).cloned())
+        }
+
+        fn put(&mut self, name: &str, contents: &[u8]) -> io::Result<()> {
+            self.puts += 1;
+            self.files.insert(name.to_string(), contents.to_vec());
+            Ok(())
+        }
+    }
+
+    fn project(directory: &Path, name: &str, id: &str) -> ProjectInfo {
+        fs::create_dir_all(directory).unwrap();
+        let info = ProjectInfo { name: name.to_string(), id: id.to_string(), ..Default::default() };
+        fs::write(directory.join("project_info.toml"), toml::to_string(&info).unwrap()).unwrap();
+        info
+    }
+
+    #[test]
+    fn test_push_and_pull_between_machines() {
+        let studio = tempdir().unwrap();
+        let laptop = tempdir().unwrap();
+        let projects = tempdir().unwrap();
+        let config = Config::default();
+        let mut remote = Memory::default();
+
+        // The studio registers an album on a shared drive and pushes.
+        let studio_registry = Registry::open(&studio.path().join("registry")).unwrap();
+        let album = projects.path().join("album");
+        studio_registry.register(&album, &project(&album, "album", "a1")).unwrap();
+        let pushed = push(&mut remote, &studio_registry, &config, true).unwrap();
+        assert_eq!(pushed.copied.len(), 2);
+        assert!(remote.files.contains_key("projects/a1.toml"));
+
+        // Pushing again only sends what changed.
+        let pushes = remote.puts;
+        assert_eq!(push(&mut remote, &studio_registry, &config, true).unwrap().unchanged, 2);
+        assert_eq!(remote.puts, pushes);
+
+        // The laptop has its own project, pulls the album, and pushes its project.
+        let laptop_registry = Registry::open(&laptop.path().join("registry")).unwrap();
+        let sketch = projects.path().join("sketch");
+        laptop_registry.register(&sketch, &project(&sketch, "sketch", "s1")).unwrap();
+        let pulled = pull(&mut remote, &laptop_registry, &config, true).unwrap();
+        assert_eq!(pulled.copied.len(), 1);
+        let names: Vec<String> = laptop_registry.entries().unwrap().into_iter().map(|entry| entry.info.name).collect();
+        assert_eq!(names, ["album", "sketch"]);
+        push(&mut remote, &laptop_registry, &config, false).unwrap();
+
+        // The album's notes changed on the laptop's side of the shared drive.
+        let mut changed = project(&album, "album", "a1");
+        changed.notes.push("mastered".to_string());
+        remote.files.insert("projects/a1.toml".to_string(), toml::to_string(&changed).unwrap().into_bytes());
+        let mut manifest = read_manifest(&mut remote).unwrap();
+        manifest.insert("projects/a1.toml".to_string(), checksum(&remote.files["projects/a1.toml"]));
+        remote.files.insert(MANIFEST_FILE.to_string(), serde_json::to_vec(&manifest).unwrap());
+
+        let pulled = pull(&mut remote, &studio_registry, &config, true).unwrap();
+        assert!(pulled.copied.contains(&"projects/a1.toml".to_string()));
+        assert_eq!(studio_registry.entries().unwrap().len(), 2);
+        assert_eq!(ProjectInfo::load_from_toml_file(&album).unwrap().notes, ["mastered"]);
+    }
+
+    #[test]
+    fn test_changes_on_both_sides_are_conflicts() {
+        let studio = tempdir().unwrap();
+        let projects = tempdir().unwrap();
+        let config = Config::default();
+        let mut remote = Memory::default();
+
+        let registry = Registry::open(&studio.path().join("registry")).unwrap();
+        let album = projects.path().join("album");
+        registry.register(&album, &project(&album, "album", "a1")).unwrap();
+        push(&mut remote, &registry, &config, true).unwrap();
+
+        // Another machine pushes new notes while this one edits its own copy.
+        let mut theirs = ProjectInfo { name: "album".to_string(), id: "a1".to_string(), ..Default::default() };
+        theirs.notes.push("mastered".to_string());
+        let theirs = toml::to_string(&theirs).unwrap().into_bytes();
+        let mut manifest = read_manifest(&mut remote).unwrap();
+        manifest.insert("projects/a1.toml".to_string(), checksum(&theirs));
+        remote.files.insert("projects/a1.toml".to_string(), theirs.clone());
+        remote.files.insert(MANIFEST_FILE.to_string(), serde_json::to_vec(&manifest).unwrap());
+        let mut ours = ProjectInfo::load_from_toml_file(&album).unwrap();
+        ours.notes.push("needs a remix".to_string());
+        fs::write(album.join("project_info.toml"), toml::to_string(&ours).unwrap()).unwrap();
+
+        let pushed = push(&mut remote, &registry, &config, true).unwrap();
+        assert_eq!(pushed.conflicts, ["projects/a1.toml"]);
+        assert_eq!(remote.files["projects/a1.toml"], theirs);
+
+        let pulled = pull(&mut remote, &registry, &config, true).unwrap();
+        assert_eq!(pulled.conflicts, ["projects/a1.toml"]);
+        assert_eq!(ProjectInfo::load_from_toml_file(&album).unwrap().notes, ["needs a remix"]);
+    }
+
+    #[test]
+    fn test_pull_keeps_local_deletions() {
+        let studio = tempdir().unwrap();
+        let laptop = tempdir().unwrap();
+        let projects = tempdir().unwrap();
+        let config = Config::default();
+        let mut remote = Memory::default();
+
+        let studio_registry = Registry::open(&studio.path().join("registry")).unwrap();
+        let album = projects.path().join("album");
+        studio_registry.register(&album, &project(&album, "album", "a1")).unwrap();
+        push(&mut remote, &studio_registry, &config, false).unwrap();
+
+        let laptop_registry = Registry::open(&laptop.path().join("registry")).unwrap();
+        pull(&mut remote, &laptop_registry, &config, false).unwrap();
+        assert_eq!(laptop_registry.entries().unwrap().len(), 1);
+
+        // The laptop drops the entry, as it does for a project that moved.
+        laptop_registry.unregister(&fs::canonicalize(&album).unwrap()).unwrap();
+        let pulled = pull(&mut remote, &laptop_registry, &config, false).unwrap();
+        assert!(pulled.copied.is_empty());
+        assert!(laptop_registry.entries().unwrap().is_empty());
+
+        // Unless the other machine changed the entry in the meantime.
+        let mut changed = project(&album, "album", "a1");
+        changed.notes.push("mastered".to_string());
+        studio_registry.register(&album, &changed).unwrap();
+        push(&mut remote, &studio_registry, &config, false).unwrap();
+        let pulled = pull(&mut remote, &laptop_registry, &config, false).unwrap();
+        assert_eq!(pulled.conflicts.len(), 1);
+        assert!(laptop_registry.entries().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_pull_ignores_unsafe_names() {
+        let dir = tempdir().unwrap();
+        let registry = Registry::open(&dir.path().join("registry")).unwrap();
+        let mut remote = Memory::default();
+        let manifest = BTreeMap::from([("registry/../config.toml".to_string(), checksum(b""))]);
+        remote.files.insert(MANIFEST_FILE.to_string(), serde_json::to_vec(&manifest).unwrap());
+
+        let pulled = pull(&mut remote, &registry, &Config::default(), false).unwrap();
+        assert!(pulled.copied.is_empty());
+        assert!(!dir.path().join("config.toml").exists());
+    }
+}
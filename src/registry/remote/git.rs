@@ -0,0 +1,131 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use log::info;
+
+use super::Backend;
+use crate::config::dirs;
+use crate::registry::fnv1a;
+
+/// A git repository, kept checked out in the cache directory.
+///
+/// Files are stored in the checkout and published as one commit pushed to the
+/// repository. The `git` command does the work, so its credential helpers and SSH
+/// keys apply.
+pub struct Git {
+    checkout: PathBuf,
+}
+
+impl Git {
+    /// Opens the repository at `url`, cloning it into the cache directory the first
+    /// time and otherwise updating the checkout to the repository's latest commit.
+    pub fn open(url: &str) -> io::Result<Self> {
+        let checkout = dirs::cache_dir()?.join("remote").join(format!("{:016x}", fnv1a(url.as_bytes())));
+        Self::open_in(url, &checkout)
+    }
+
+    /// Opens the repository at `url` using the given checkout.
+    fn open_in(url: &str, checkout: &Path) -> io::Result<Self> {
+        if !checkout.join(".git").is_dir() {
+            info!("Cloning {} into {}", url, checkout.display());
+            if let Some(parent) = checkout.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let checkout = checkout.to_string_lossy();
+            git(Path::new("."), &["clone", "--quiet", "--", url, &checkout])?;
+        } else {
+            git(checkout, &["fetch", "--quiet", "origin"])?;
+            let branch = git(checkout, &["symbolic-ref", "--short", "HEAD"])?;
+            let upstream = format!("origin/{}", branch);
+            // A repository nothing was pushed to yet has no branch to update from.
+            if git(checkout, &["rev-parse", "--verify", "--quiet", &upstream]).is_ok() {
+                git(checkout, &["reset", "--quiet", "--hard", &upstream])?;
+            }
+        }
+        Ok(Git { checkout: checkout.to_path_buf() })
+    }
+}
+
+impl Backend for Git {
+    fn get(&mut self, name: &str) -> io::Result<Option<Vec<u8>>> {
+        match fs::read(self.checkout.join(name)) {
+            Ok(contents) => Ok(Some(contents)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn put(&mut self, name: &str, contents: &[u8]) -> io::Result<()> {
+        let path = self.checkout.join(name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, contents)
+    }
+
+    fn publish(&mut self, message: &str) -> io::Result<()> {
+        let checkout = self.checkout.as_path();
+        git(checkout, &["add", "--all"])?;
+        if git(checkout, &["status", "--porcelain"])?.is_empty() {
+            return Ok(());
+        }
+        // Commit as tagger on machines without a git identity.
+        let identity: &[&str] = match git(checkout, &["config", "user.email"]) {
+            Ok(_) => &[],
+            Err(_) => &["-c", "user.name=tagger", "-c", "user.email=tagger@localhost"],
+        };
+        git(checkout, &[identity, &["commit", "--quiet", "-m", message]].concat())?;
+        git(checkout, &["push", "--quiet", "origin", "HEAD"])?;
+        Ok(())
+    }
+}
+
+/// Runs a git command in the directory and returns its trimmed output, or an error
+/// with what git reported.
+fn git(directory: &Path, args: &[&str]) -> io::Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(directory)
+        .args(args)
+        .output()
+        .map_err(|e| io::Error::new(e.kind(), format!("unable to run git: {}", e)))?;
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_files_travel_through_the_repository() {
+        let dir = tempdir().unwrap();
+        let repository = dir.path().join("remote.git");
+        git(dir.path(), &["init", "--quiet", "--bare", &repository.to_string_lossy()]).unwrap();
+        let url = repository.to_string_lossy();
+
+        let mut studio = Git::open_in(&url, &dir.path().join("studio")).unwrap();
+        assert_eq!(studio.get("manifest.json").unwrap(), None);
+        studio.put("registry/album-0a1b2c3d.toml", b"name = \"album\"").unwrap();
+        studio.publish("Add album").unwrap();
+
+        let mut laptop = Git::open_in(&url, &dir.path().join("laptop")).unwrap();
+        assert_eq!(laptop.get("registry/album-0a1b2c3d.toml").unwrap().unwrap(), b"name = \"album\"");
+        laptop.put("registry/album-0a1b2c3d.toml", b"name = \"album (remastered)\"").unwrap();
+        laptop.publish("Rename album").unwrap();
+        // Publishing without changes does nothing.
+        laptop.publish("Nothing").unwrap();
+
+        let mut studio = Git::open_in(&url, &dir.path().join("studio")).unwrap();
+        assert_eq!(studio.get("registry/album-0a1b2c3d.toml").unwrap().unwrap(), b"name = \"album (remastered)\"");
+    }
+}
@@ -0,0 +1,87 @@
+use std::collections::HashSet;
+use std::env;
+use std::io;
+
+use base64::Engine;
+use log::info;
+
+use super::{response_body, Backend};
+use crate::config::RemoteConfig;
+
+/// A folder on a WebDAV server, such as Nextcloud or a NAS.
+pub struct WebDav {
+    agent: ureq::Agent,
+    /// The folder's URL, without a trailing slash.
+    base_url: String,
+    /// The `Authorization` header, when a user name is configured.
+    authorization: Option<String>,
+    /// The folders known to exist, so each is created at most once per run.
+    folders: HashSet<String>,
+}
+
+impl WebDav {
+    /// Connects to the folder at `url`.
+    ///
+    /// With a `username` configured, requests are sent with basic authentication, the
+    /// password read from the environment variable `password_env` names. Requests go
+    /// through `agent`.
+    pub fn new(url: &str, config: &RemoteConfig, agent: ureq::Agent) -> Self {
+        let authorization = config.username.as_ref().map(|username| {
+            let variable = config.password_env.as_deref().unwrap_or("TAGGER_WEBDAV_PASSWORD");
+            let password = env::var(variable).unwrap_or_default();
+            let credentials = format!("{}:{}", username, password);
+            format!("Basic {}", base64::engine::general_purpose::STANDARD.encode(credentials))
+        });
+        WebDav {
+            agent,
+            base_url: url.trim_end_matches('/').to_string(),
+            authorization,
+            folders: HashSet::new(),
+        }
+    }
+
+    /// Sends a request with the credentials, if any.
+    fn send(&self, method: &str, name: &str, body: &[u8]) -> Result<ureq::http::Response<ureq::Body>, ureq::Error> {
+        let mut request = ureq::http::Request::builder().method(method).uri(format!("{}/{}", self.base_url, name));
+        if let Some(authorization) = &self.authorization {
+            request = request.header("Authorization", authorization);
+        }
+        self.agent.run(request.body(body)?)
+    }
+
+    /// Creates the folders above the file `name` that were not created yet.
+    fn create_folders(&mut self, name: &str) -> io::Result<()> {
+        let Some((parent, _)) = name.rsplit_once('/') else {
+            return Ok(());
+        };
+        let mut folder = String::new();
+        for part in parent.split('/') {
+            folder = if folder.is_empty() { part.to_string() } else { format!("{}/{}", folder, part) };
+            if self.folders.contains(&folder) {
+                continue;
+            }
+            info!("Creating {}/{}", self.base_url, folder);
+            // Servers answer 405 Method Not Allowed for folders that exist.
+            let status = self
+                .send("MKCOL", &format!("{}/", folder), b"")
+                .map(|response| response.status())
+                .map_err(|e| io::Error::other(format!("unable to reach the remote: {}", e)))?;
+            if !status.is_success() && status != ureq::http::StatusCode::METHOD_NOT_ALLOWED {
+                return Err(io::Error::other(format!("the remote answered {} creating {}", status, folder)));
+            }
+            self.folders.insert(folder.clone());
+        }
+        Ok(())
+    }
+}
+
+impl Backend for WebDav {
+    fn get(&mut self, name: &str) -> io::Result<Option<Vec<u8>>> {
+        response_body(self.send("GET", name, b""), name)
+    }
+
+    fn put(&mut self, name: &str, contents: &[u8]) -> io::Result<()> {
+        self.create_folders(name)?;
+        response_body(self.send("PUT", name, contents), name).map(drop)
+    }
+}
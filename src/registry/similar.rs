@@ -0,0 +1,130 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use super::RegistryEntry;
+use crate::project_info::ProjectInfo;
+
+/// A registered project and how much it has in common with another one.
+#[derive(Debug, Clone)]
+pub struct SimilarProject {
+    /// The registered project.
+    pub entry: RegistryEntry,
+    /// The Jaccard similarity of the two projects' tags and types, from 0 to 1.
+    pub score: f64,
+    /// The tags both projects have, sorted.
+    pub shared_tags: Vec<String>,
+}
+
+/// Ranks registered projects by how similar they are to a project: the Jaccard
+/// similarity (shared over combined) of their tags, with each project's type counted
+/// as one more tag, so projects of the same type rank above others sharing as many tags.
+///
+/// The project itself, found by its directory or id, and projects with nothing in
+/// common are left out. Ties are broken by the most recently modified project.
+///
+/// # Arguments
+///
+/// * `directory` - The project's directory.
+/// * `info` - The project's information.
+/// * `entries` - The registered projects to rank.
+/// * `limit` - The most projects returned.
+pub fn find_similar(
+    directory: &Path,
+    info: &ProjectInfo,
+    entries: &[RegistryEntry],
+    limit: usize,
+) -> Vec<SimilarProject> {
+    let compared = features(info);
+
+    let mut similar: Vec<SimilarProject> = entries
+        .iter()
+        .filter(|entry| entry.path != directory && (info.id.is_empty() || entry.info.id != info.id))
+        .filter_map(|entry| {
+            let other = features(&entry.info);
+            let score = jaccard(&compared, &other);
+            (score > 0.0).then(|| SimilarProject {
+                entry: entry.clone(),
+                score,
+                shared_tags: info
+                    .tags
+                    .iter()
+                    .filter(|tag| entry.info.tags.contains(tag))
+                    .cloned()
+                    .collect::<BTreeSet<_>>()
+                    .into_iter()
+                    .collect(),
+            })
+        })
+        .collect();
+    similar.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| b.entry.info.date_modified.cmp(&a.entry.info.date_modified))
+    });
+    similar.truncate(limit);
+    similar
+}
+
+/// Returns what a project is compared by: its tags, and its type as an extra item
+/// that cannot clash with a tag.
+fn features(info: &ProjectInfo) -> BTreeSet<String> {
+    let mut features: BTreeSet<String> = info.tags.iter().cloned().collect();
+    if !info.project_type.is_empty() && info.project_type != "unknown" {
+        features.insert(format!("\0type {}", info.project_type));
+    }
+    features
+}
+
+/// Returns the Jaccard similarity of two sets: the size of their intersection over the
+/// size of their union, or 0 when both are empty.
+pub fn jaccard<T: Ord>(a: &BTreeSet<T>, b: &BTreeSet<T>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn entry(path: &str, project_type: &str, tags: &[&str]) -> RegistryEntry {
+        RegistryEntry {
+            path: PathBuf::from(path),
+            info: ProjectInfo {
+                name: path.trim_start_matches('/').to_string(),
+                project_type: project_type.to_string(),
+                tags: tags.iter().map(|tag| tag.to_string()).collect(),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_jaccard() {
+        let set = |items: &[i32]| items.iter().copied().collect::<BTreeSet<_>>();
+        assert_eq!(jaccard(&set(&[1, 2, 3]), &set(&[2, 3, 4])), 0.5);
+        assert_eq!(jaccard(&set(&[]), &set(&[])), 0.0);
+    }
+
+    #[test]
+    fn test_find_similar_ranks_by_tags_and_type() {
+        let target = entry("/song", "music", &["daw:reaper", "genre:synth-pop", "format:wav"]);
+        let entries = [
+            target.clone(),
+            entry("/same-type", "music", &["daw:reaper", "genre:synth-pop"]),
+            entry("/other-type", "video", &["daw:reaper", "genre:synth-pop"]),
+            entry("/unrelated", "programming", &["lang:rust"]),
+        ];
+
+        let similar = find_similar(&target.path, &target.info, &entries, 10);
+        let paths: Vec<&Path> = similar.iter().map(|similar| similar.entry.path.as_path()).collect();
+        assert_eq!(paths, [Path::new("/same-type"), Path::new("/other-type")]);
+        assert_eq!(similar[0].score, 0.75);
+        assert_eq!(similar[0].shared_tags, ["daw:reaper", "genre:synth-pop"]);
+
+        assert_eq!(find_similar(&target.path, &target.info, &entries, 1).len(), 1);
+    }
+}
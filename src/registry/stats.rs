@@ -0,0 +1,137 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use super::RegistryEntry;
+
+/// How many registered projects use a tag.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TagCount {
+    /// The tag.
+    pub tag: String,
+    /// The number of projects with the tag.
+    pub projects: usize,
+}
+
+/// The registered projects of one type.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TypeStats {
+    /// The project type.
+    pub project_type: String,
+    /// The number of projects of the type.
+    pub projects: usize,
+    /// The disk space they use together, in bytes.
+    pub total_size_bytes: u64,
+}
+
+/// Figures about every registered project, for pruning the tag vocabulary and seeing
+/// where the disk space goes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct RegistryStats {
+    /// The number of registered projects.
+    pub projects: usize,
+    /// Every tag in use, most used first, then alphabetically.
+    pub tags: Vec<TagCount>,
+    /// Every project type, with the most projects first, then alphabetically.
+    pub types: Vec<TypeStats>,
+    /// The number of projects created in each month, keyed by `YYYY-MM`.
+    pub created_per_month: BTreeMap<String, usize>,
+    /// Tags only one project uses, alphabetically: typos, one-off spellings, and
+    /// candidates for a synonym or the blacklist.
+    pub orphaned_tags: Vec<String>,
+}
+
+impl RegistryStats {
+    /// Computes the figures for the given registered projects.
+    pub fn compute(entries: &[RegistryEntry]) -> Self {
+        let mut tags: BTreeMap<&str, usize> = BTreeMap::new();
+        let mut types: BTreeMap<&str, (usize, u64)> = BTreeMap::new();
+        let mut created_per_month: BTreeMap<String, usize> = BTreeMap::new();
+
+        for entry in entries {
+            let info = &entry.info;
+            let mut project_tags: Vec<&str> = info.tags.iter().map(String::as_str).collect();
+            project_tags.sort_unstable();
+            project_tags.dedup();
+            for tag in project_tags {
+                *tags.entry(tag).or_default() += 1;
+            }
+
+            let project_type = types.entry(info.project_type.as_str()).or_default();
+            project_type.0 += 1;
+            project_type.1 += info.total_size_bytes;
+
+            *created_per_month
+                .entry(info.date_created.format("%Y-%m").to_string())
+                .or_default() += 1;
+        }
+
+        let orphaned_tags = tags
+            .iter()
+            .filter(|(_, count)| **count == 1)
+            .map(|(tag, _)| tag.to_string())
+            .collect();
+        let mut tags: Vec<TagCount> = tags
+            .into_iter()
+            .map(|(tag, projects)| TagCount { tag: tag.to_string(), projects })
+            .collect();
+        tags.sort_by(|a, b| b.projects.cmp(&a.projects).then_with(|| a.tag.cmp(&b.tag)));
+        let mut types: Vec<TypeStats> = types
+            .into_iter()
+            .map(|(project_type, (projects, total_size_bytes))| TypeStats {
+                project_type: project_type.to_string(),
+                projects,
+                total_size_bytes,
+            })
+            .collect();
+        types.sort_by(|a, b| b.projects.cmp(&a.projects).then_with(|| a.project_type.cmp(&b.project_type)));
+
+        RegistryStats {
+            projects: entries.len(),
+            tags,
+            types,
+            created_per_month,
+            orphaned_tags,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project_info::ProjectInfo;
+    use chrono::{Local, TimeZone};
+    use std::path::PathBuf;
+
+    fn entry(project_type: &str, tags: &[&str], month: u32, size: u64) -> RegistryEntry {
+        RegistryEntry {
+            path: PathBuf::from("/projects"),
+            info: ProjectInfo {
+                project_type: project_type.to_string(),
+                tags: tags.iter().map(|tag| tag.to_string()).collect(),
+                date_created: Local.with_ymd_and_hms(2024, month, 10, 12, 0, 0).unwrap(),
+                total_size_bytes: size,
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_compute() {
+        let stats = RegistryStats::compute(&[
+            entry("music", &["daw:reaper", "genre:techno"], 1, 300),
+            entry("music", &["daw:reaper", "genre:tecno"], 1, 200),
+            entry("programming", &["lang:rust"], 3, 50),
+        ]);
+
+        assert_eq!(stats.projects, 3);
+        assert_eq!(stats.tags[0], TagCount { tag: "daw:reaper".to_string(), projects: 2 });
+        assert_eq!(stats.orphaned_tags, ["genre:techno", "genre:tecno", "lang:rust"]);
+        assert_eq!(
+            stats.types[0],
+            TypeStats { project_type: "music".to_string(), projects: 2, total_size_bytes: 500 }
+        );
+        assert_eq!(stats.created_per_month["2024-01"], 2);
+        assert_eq!(stats.created_per_month["2024-03"], 1);
+    }
+}
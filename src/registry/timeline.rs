@@ -0,0 +1,270 @@
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use chrono::{DateTime, Datelike, Local, NaiveDate};
+use serde::Serialize;
+
+use super::RegistryEntry;
+
+/// The span of time each column of a timeline covers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Period {
+    Month,
+    #[default]
+    Quarter,
+    Year,
+}
+
+impl Period {
+    /// Every period.
+    pub const ALL: [Period; 3] = [Period::Month, Period::Quarter, Period::Year];
+
+    /// Returns the period's name on the command line.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Period::Month => "month",
+            Period::Quarter => "quarter",
+            Period::Year => "year",
+        }
+    }
+
+    /// Returns the number of periods in a year.
+    pub fn per_year(self) -> i32 {
+        match self {
+            Period::Month => 12,
+            Period::Quarter => 4,
+            Period::Year => 1,
+        }
+    }
+
+    /// Numbers the period a date falls in, counting from year 0, so that consecutive
+    /// periods get consecutive numbers.
+    fn index(self, date: NaiveDate) -> i32 {
+        let month = date.month0() as i32;
+        date.year() * self.per_year() + month * self.per_year() / 12
+    }
+
+    /// Names a numbered period: `2024-03`, `2024-Q1`, or `2024`.
+    fn label(self, index: i32) -> String {
+        let (year, part) = (index.div_euclid(self.per_year()), index.rem_euclid(self.per_year()));
+        match self {
+            Period::Month => format!("{}-{:02}", year, part + 1),
+            Period::Quarter => format!("{}-Q{}", year, part + 1),
+            Period::Year => year.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Period {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Period {
+    type Err = String;
+
+    /// Parses a period name, ignoring case.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim().to_lowercase();
+        Self::ALL.into_iter().find(|period| period.as_str() == value).ok_or_else(|| {
+            let names: Vec<&str> = Self::ALL.iter().map(|period| period.as_str()).collect();
+            format!("unknown period '{}' (expected one of: {})", value, names.join(", "))
+        })
+    }
+}
+
+/// A project on the timeline.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TimelineProject {
+    /// The project's name.
+    pub name: String,
+    /// The project's directory.
+    pub path: PathBuf,
+    /// The project's type.
+    pub project_type: String,
+    /// When the project was created.
+    pub date_created: DateTime<Local>,
+    /// When the project was last modified.
+    pub date_modified: DateTime<Local>,
+    /// The index in [`Timeline::periods`] of the first period the project was worked on.
+    pub first_period: usize,
+    /// The index in [`Timeline::periods`] of the last period the project was worked on.
+    pub last_period: usize,
+}
+
+/// Registered projects laid out over the periods between their creation and last
+/// modification.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Timeline {
+    /// The span of time each period covers.
+    pub period: Period,
+    /// The periods, oldest first, such as `2024-Q1`.
+    pub periods: Vec<String>,
+    /// The projects, oldest first.
+    pub projects: Vec<TimelineProject>,
+}
+
+impl Timeline {
+    /// Lays out the registered projects, leaving out those last modified before
+    /// `since`. A project counts as worked on in every period from its creation to
+    /// its last modification.
+    pub fn new(entries: &[RegistryEntry], period: Period, since: Option<NaiveDate>) -> Self {
+        let first = since.map(|since| period.index(since));
+        let mut spans: Vec<(i32, i32, &RegistryEntry)> = entries
+            .iter()
+            .filter_map(|entry| {
+                let created = entry.info.date_created.date_naive();
+                let modified = entry.info.date_modified.date_naive();
+                let (start, end) = (created.min(modified), created.max(modified));
+                let (start, end) = (period.index(start), period.index(end));
+                match first {
+                    Some(first) if end < first => None,
+                    Some(first) => Some((start.max(first), end, entry)),
+                    None => Some((start, end, entry)),
+                }
+            })
+            .collect();
+        spans.sort_by(|a, b| {
+            (a.0, a.2.info.date_created, &a.2.info.name).cmp(&(b.0, b.2.info.date_created, &b.2.info.name))
+        });
+
+        let Some(start) = spans.iter().map(|span| span.0).min() else {
+            return Timeline { period, periods: Vec::new(), projects: Vec::new() };
+        };
+        let end = spans.iter().map(|span| span.1).max().unwrap_or(start);
+
+        Timeline {
+            period,
+            periods: (start..=end).map(|index| period.label(index)).collect(),
+            projects: spans
+                .into_iter()
+                .map(|(first, last, entry)| TimelineProject {
+                    name: entry.info.name.clone(),
+                    path: entry.path.clone(),
+                    project_type: entry.info.project_type.clone(),
+                    date_created: entry.info.date_created,
+                    date_modified: entry.info.date_modified,
+                    first_period: (first - start) as usize,
+                    last_period: (last - start) as usize,
+                })
+                .collect(),
+        }
+    }
+
+    /// Draws the timeline as text: a row per project with a bar over the periods it
+    /// was worked on, under a header that marks where each year starts. With
+    /// `by_type`, projects are grouped under their type.
+    pub fn render(&self, by_type: bool) -> String {
+        // Every column gets the same number of characters; a year's label needs 4.
+        let cell = match self.period {
+            Period::Month => 1,
+            Period::Quarter => 2,
+            Period::Year => 5,
+        };
+        let projects: Vec<&TimelineProject> = if by_type {
+            let mut projects: Vec<&TimelineProject> = self.projects.iter().collect();
+            projects.sort_by(|a, b| a.project_type.cmp(&b.project_type));
+            projects
+        } else {
+            self.projects.iter().collect()
+        };
+        let width = projects.iter().map(|project| project.name.chars().count()).max().unwrap_or_default();
+
+        // Label each year at its first column, shortened to `24` or left out when the
+        // timeline shows too little of it for the label and a space.
+        let mut header = vec![' '; self.periods.len() * cell];
+        let mut years: Vec<(&str, usize)> = Vec::new();
+        for (index, label) in self.periods.iter().enumerate() {
+            let year = &label[..label.len().min(4)];
+            if years.last().is_none_or(|(last, _)| *last != year) {
+                years.push((year, index * cell));
+            }
+        }
+        for (position, (year, column)) in years.iter().enumerate() {
+            let room = years.get(position + 1).map_or(usize::MAX, |(_, next)| next - column - 1);
+            let label = if room >= year.len() { *year } else if room >= 2 { &year[year.len() - 2..] } else { "" };
+            for (offset, c) in label.chars().enumerate() {
+                match header.get_mut(column + offset) {
+                    Some(slot) => *slot = c,
+                    None => header.push(c),
+                }
+            }
+        }
+        let header: String = header.into_iter().collect();
+        let mut out = format!("{:width$}  {}\n", "", header.trim_end(), width = width);
+
+        let mut group: Option<&str> = None;
+        for project in projects {
+            if by_type && group != Some(project.project_type.as_str()) {
+                group = Some(&project.project_type);
+                out.push_str(&format!("{}:\n", project.project_type));
+            }
+            let bar: String = (0..self.periods.len())
+                .map(|index| {
+                    let mark = if (project.first_period..=project.last_period).contains(&index) { "█" } else { "·" };
+                    mark.repeat(cell)
+                })
+                .collect();
+            out.push_str(&format!(
+                "{:width$}  {}\n",
+                project.name,
+                bar,
+                width = width + (project.name.len() - project.name.chars().count())
+            ));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project_info::ProjectInfo;
+    use chrono::TimeZone;
+
+    fn entry(name: &str, project_type: &str, created: (i32, u32), modified: (i32, u32)) -> RegistryEntry {
+        let date = |(year, month): (i32, u32)| Local.with_ymd_and_hms(year, month, 15, 12, 0, 0).unwrap();
+        RegistryEntry {
+            path: PathBuf::from(format!("/projects/{}", name)),
+            info: ProjectInfo {
+                name: name.to_string(),
+                project_type: project_type.to_string(),
+                date_created: date(created),
+                date_modified: date(modified),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_quarterly_timeline() {
+        let entries = [
+            entry("parser", "programming", (2024, 2), (2024, 8)),
+            entry("ep", "music", (2023, 11), (2024, 1)),
+            entry("old", "music", (2020, 1), (2020, 2)),
+        ];
+
+        let timeline = Timeline::new(&entries, Period::Quarter, NaiveDate::from_ymd_opt(2023, 10, 1));
+        assert_eq!(timeline.periods, ["2023-Q4", "2024-Q1", "2024-Q2", "2024-Q3"]);
+        let names: Vec<&str> = timeline.projects.iter().map(|project| project.name.as_str()).collect();
+        assert_eq!(names, ["ep", "parser"]);
+        assert_eq!((timeline.projects[1].first_period, timeline.projects[1].last_period), (1, 3));
+
+        assert_eq!(
+            timeline.render(true),
+            "          2024\nmusic:\nep      ████····\nprogramming:\nparser  ··██████\n"
+        );
+    }
+
+    #[test]
+    fn test_period_labels() {
+        let date = NaiveDate::from_ymd_opt(2024, 12, 31).unwrap();
+        assert_eq!(Period::Month.label(Period::Month.index(date)), "2024-12");
+        assert_eq!(Period::Quarter.label(Period::Quarter.index(date)), "2024-Q4");
+        assert_eq!(Period::Year.label(Period::Year.index(date)), "2024");
+        assert_eq!("Month".parse(), Ok(Period::Month));
+    }
+}
@@ -0,0 +1,354 @@
+use std::env;
+use std::ffi::OsStr;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Output};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use log::{info, warn};
+
+use crate::config::SecurityConfig;
+
+/// Environment variables passed to external commands unless the configuration
+/// allows more.
+pub const DEFAULT_ENV_ALLOWLIST: &[&str] = &["PATH", "LANG", "LC_ALL", "LC_CTYPE", "TERM", "TMPDIR"];
+
+/// The environment variable that tells an external command which project it runs for.
+pub const PROJECT_DIR_VAR: &str = "TAGGER_PROJECT_DIR";
+
+/// Prepares external commands, such as hooks and plugins, so their environment only
+/// holds what they need.
+///
+/// Commands start in the project directory with an empty environment, apart from the
+/// allowlisted variables and [`PROJECT_DIR_VAR`], so credentials held in the user's
+/// environment (tokens, SSH agent sockets, cloud profiles) are not handed to scripts
+/// that came with a shared configuration.
+///
+/// With `restrict_paths`, commands can also only write to the project and the
+/// configured `allowed_paths`, and read nothing else of the user's: Landlock enforces
+/// this on Linux and `sandbox-exec` on macOS. Where neither is available, commands
+/// refuse to run rather than run unrestricted. Without it, a command can read and
+/// write any file the user can.
+pub struct Sandbox<'a> {
+    config: &'a SecurityConfig,
+}
+
+impl<'a> Sandbox<'a> {
+    /// Creates a sandbox with the given security settings.
+    pub fn new(config: &'a SecurityConfig) -> Self {
+        Sandbox { config }
+    }
+
+    /// Returns `true` if external commands may run at all.
+    pub fn allows_external_commands(&self) -> bool {
+        !self.config.disable_external_commands
+    }
+
+    /// Builds the command that runs `program` for the project in `project_dir`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PermissionDenied` error when external commands are disabled in the
+    /// configuration, and an `Unsupported` one when `restrict_paths` is set but file
+    /// access cannot be restricted on this system.
+    pub fn command(&self, program: impl AsRef<OsStr>, project_dir: &Path) -> io::Result<Command> {
+        let program = program.as_ref();
+        if !self.allows_external_commands() {
+            warn!(
+                "Not running {}: external commands are disabled.",
+                Path::new(program).display()
+            );
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "external commands are disabled by `disable_external_commands`",
+            ));
+        }
+
+        // A project scanned inside an archive runs commands next to the archive.
+        let working_dir = if project_dir.is_file() {
+            project_dir
+                .parent()
+                .filter(|parent| !parent.as_os_str().is_empty())
+                .unwrap_or(Path::new("."))
+        } else {
+            project_dir
+        };
+        let mut command = if self.config.restrict_paths {
+            restricted_command(Path::new(program), project_dir, &self.config.allowed_paths)?
+        } else {
+            Command::new(program)
+        };
+        command.env_clear().current_dir(working_dir);
+        for name in self.env_allowlist() {
+            if let Some(value) = env::var_os(name) {
+                command.env(name, value);
+            }
+        }
+        command.env(PROJECT_DIR_VAR, project_dir);
+        // Start a process group, so a timeout also stops the processes it starts.
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(&mut command, 0);
+
+        info!(
+            "Running external command {} in {}",
+            Path::new(program).display(),
+            project_dir.display()
+        );
+        Ok(command)
+    }
+
+    /// Returns the names of the environment variables passed to external commands.
+    fn env_allowlist(&self) -> impl Iterator<Item = &str> {
+        DEFAULT_ENV_ALLOWLIST
+            .iter()
+            .copied()
+            .chain(self.config.env_allowlist.iter().map(String::as_str))
+    }
+}
+
+/// Directories holding the system's programs, libraries, and settings, which
+/// commands may read when their file access is restricted.
+#[cfg(target_os = "linux")]
+const SYSTEM_PATHS: &[&str] = &["/bin", "/sbin", "/usr", "/lib", "/lib32", "/lib64", "/etc", "/opt", "/nix/store", "/dev"];
+
+/// Builds a command that can only write to the project and `allowed` paths, and
+/// read those, the program, and the system's own files, using Landlock.
+///
+/// The rules are prepared here and applied in the child just before the program
+/// starts, so tagger itself is not restricted.
+#[cfg(target_os = "linux")]
+fn restricted_command(program: &Path, project_dir: &Path, allowed: &[PathBuf]) -> io::Result<Command> {
+    use landlock::{
+        path_beneath_rules, Access, AccessFs, CompatLevel, Compatible, Ruleset, RulesetAttr, RulesetCreatedAttr,
+        RulesetError, ABI,
+    };
+    use std::os::unix::process::CommandExt;
+
+    let unsupported = |e: RulesetError| {
+        io::Error::new(io::ErrorKind::Unsupported, format!("unable to restrict file access with Landlock: {}", e))
+    };
+    let abi = ABI::V1;
+    let program_file = program.parent().filter(|parent| !parent.as_os_str().is_empty()).map(|_| program);
+    let writable = std::iter::once(project_dir).chain(allowed.iter().map(PathBuf::as_path)).chain([Path::new("/dev/null")]);
+    let mut ruleset = Some(
+        Ruleset::default()
+            .set_compatibility(CompatLevel::HardRequirement)
+            .handle_access(AccessFs::from_all(abi))
+            .and_then(Ruleset::create)
+            .and_then(|ruleset| ruleset.add_rules(path_beneath_rules(SYSTEM_PATHS, AccessFs::from_read(abi))))
+            .and_then(|ruleset| ruleset.add_rules(path_beneath_rules(program_file, AccessFs::from_read(abi))))
+            .and_then(|ruleset| ruleset.add_rules(path_beneath_rules(writable, AccessFs::from_all(abi))))
+            .map_err(unsupported)?,
+    );
+
+    let mut command = Command::new(program);
+    // SAFETY: the closure only makes the prctl and landlock_restrict_self system
+    // calls, which are safe between fork and exec, and allocates nothing.
+    unsafe {
+        command.pre_exec(move || match ruleset.take().map(|ruleset| ruleset.restrict_self()) {
+            Some(Ok(_)) => Ok(()),
+            _ => Err(io::Error::from(io::ErrorKind::PermissionDenied)),
+        });
+    }
+    Ok(command)
+}
+
+/// Builds a command that can only write to the project and `allowed` paths, and read
+/// nothing else in the user's home or on other volumes, using `sandbox-exec`.
+#[cfg(target_os = "macos")]
+fn restricted_command(program: &Path, project_dir: &Path, allowed: &[PathBuf]) -> io::Result<Command> {
+    const SANDBOX_EXEC: &str = "/usr/bin/sandbox-exec";
+    if !Path::new(SANDBOX_EXEC).exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("unable to restrict file access: {} is missing", SANDBOX_EXEC),
+        ));
+    }
+
+    // Profiles match real paths, such as /private/tmp for /tmp.
+    let quote = |path: &Path| {
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        format!("\"{}\"", path.to_string_lossy().replace('\\', "\\\\").replace('"', "\\\""))
+    };
+    let mut writable = format!("(subpath {}) (literal \"/dev/null\")", quote(project_dir));
+    for path in allowed {
+        writable.push_str(&format!(" (subpath {})", quote(path)));
+    }
+    let mut profile = String::from(
+        "(version 1)\n(allow default)\n(deny file-write*)\n(deny file-read* (subpath \"/Users\") (subpath \"/Volumes\"))\n",
+    );
+    if program.parent().is_some_and(|parent| !parent.as_os_str().is_empty()) {
+        profile.push_str(&format!("(allow file-read* (literal {}))\n", quote(program)));
+    }
+    profile.push_str(&format!("(allow file-read* file-write* {})\n", writable));
+
+    let mut command = Command::new(SANDBOX_EXEC);
+    command.arg("-p").arg(profile).arg(program);
+    Ok(command)
+}
+
+/// Refuses to build a command with restricted file access, which this system
+/// cannot enforce.
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn restricted_command(_program: &Path, _project_dir: &Path, _allowed: &[PathBuf]) -> io::Result<Command> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "`restrict_paths` is only supported on Linux and macOS",
+    ))
+}
+
+/// Waits for a command to finish and collects its output, killing it once it has run
+/// for longer than `timeout`.
+///
+/// Standard output and error must be piped; they are read while the command runs, so
+/// it cannot block on a full pipe. On Unix, a command from [`Sandbox::command`] leads
+/// its own process group, and the whole group is killed.
+///
+/// # Errors
+///
+/// Returns a `TimedOut` error when the command was killed, and any error met while
+/// waiting for it.
+pub fn wait_with_timeout(mut child: Child, timeout: Duration) -> io::Result<Output> {
+    let read = |pipe: Option<Box<dyn Read + Send>>| {
+        thread::spawn(move || {
+            let mut contents = Vec::new();
+            if let Some(mut pipe) = pipe {
+                pipe.read_to_end(&mut contents)?;
+            }
+            Ok::<_, io::Error>(contents)
+        })
+    };
+    let stdout = read(child.stdout.take().map(|pipe| Box::new(pipe) as Box<dyn Read + Send>));
+    let stderr = read(child.stderr.take().map(|pipe| Box::new(pipe) as Box<dyn Read + Send>));
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            kill(&mut child)?;
+            child.wait()?;
+            // The readers are left behind: a process the command started may still
+            // hold the pipes open.
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("killed after {} seconds", timeout.as_secs()),
+            ));
+        }
+        thread::sleep(Duration::from_millis(10));
+    };
+
+    let join = |reader: thread::JoinHandle<io::Result<Vec<u8>>>| reader.join().expect("the output reader panicked");
+    Ok(Output {
+        status,
+        stdout: join(stdout)?,
+        stderr: join(stderr)?,
+    })
+}
+
+/// Kills a command and, on Unix, the rest of the process group it leads.
+fn kill(child: &mut Child) -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        // SAFETY: kill only sends a signal. A negative pid names the process group
+        // led by the child, and fails if the child leads none.
+        if unsafe { libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL) } == 0 {
+            return Ok(());
+        }
+    }
+    child.kill()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::process::Stdio;
+    use tempfile::tempdir;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_command_gets_only_allowlisted_environment() {
+        let dir = tempdir().unwrap();
+        let run_env = |config: &SecurityConfig| {
+            let mut command = Sandbox::new(config).command("env", dir.path()).unwrap();
+            String::from_utf8(command.output().unwrap().stdout).unwrap()
+        };
+
+        // HOME is not passed on by default, but can be allowed.
+        let output = run_env(&SecurityConfig::default());
+        assert!(output.contains(&format!("{}={}", PROJECT_DIR_VAR, dir.path().display())));
+        assert!(!output.lines().any(|line| line.starts_with("HOME=")));
+
+        let config = SecurityConfig {
+            env_allowlist: vec!["HOME".to_string()],
+            ..SecurityConfig::default()
+        };
+        assert!(run_env(&config).lines().any(|line| line.starts_with("HOME=")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_commands_are_killed_after_the_timeout() {
+        let dir = tempdir().unwrap();
+        let config = SecurityConfig::default();
+        let spawn = |script: &str| {
+            let mut command = Sandbox::new(&config).command("sh", dir.path()).unwrap();
+            command.arg("-c").arg(script).stdout(Stdio::piped()).stderr(Stdio::piped()).spawn().unwrap()
+        };
+
+        let output = wait_with_timeout(spawn("echo done"), Duration::from_secs(10)).unwrap();
+        assert_eq!(output.stdout, b"done\n");
+
+        let started = Instant::now();
+        let error = wait_with_timeout(spawn("exec sleep 10"), Duration::from_millis(200)).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::TimedOut);
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        // Processes the command started are killed with it.
+        let error = wait_with_timeout(spawn("(sleep 1; touch late) & sleep 10"), Duration::from_millis(200)).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::TimedOut);
+        thread::sleep(Duration::from_millis(1500));
+        assert!(!dir.path().join("late").exists());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_file_access_can_be_restricted() {
+        let project = tempdir().unwrap();
+        let elsewhere = tempdir().unwrap();
+        let secret = elsewhere.path().join("secret");
+        fs::write(&secret, "token").unwrap();
+        let config = SecurityConfig {
+            restrict_paths: true,
+            ..SecurityConfig::default()
+        };
+        let run = |script: &str| {
+            let mut command = Sandbox::new(&config).command("sh", project.path())?;
+            command.arg("-c").arg(script).arg("sh").arg(&secret).stderr(Stdio::null()).status()
+        };
+
+        match run("echo done > result") {
+            Err(e) if e.kind() == io::ErrorKind::Unsupported => return,
+            status => assert!(status.unwrap().success()),
+        }
+        assert_eq!(fs::read_to_string(project.path().join("result")).unwrap(), "done\n");
+        assert!(!run("cat \"$1\" > /dev/null").unwrap().success());
+        assert!(!run("echo leaked > \"$1\"").unwrap().success());
+        assert_eq!(fs::read_to_string(&secret).unwrap(), "token");
+    }
+
+    #[test]
+    fn test_external_commands_can_be_disabled() {
+        let dir = tempdir().unwrap();
+        let config = SecurityConfig {
+            disable_external_commands: true,
+            ..SecurityConfig::default()
+        };
+
+        let error = Sandbox::new(&config).command("env", dir.path()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::PermissionDenied);
+    }
+}
@@ -0,0 +1,448 @@
+use serde::Serialize;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use chrono::{DateTime, Local};
+use log::{info, warn};
+use rayon::prelude::*;
+use rayon::ThreadPoolBuilder;
+
+use crate::archive::contents::{self, Skeleton};
+use crate::config::Config;
+use crate::enrichment::Enrichment;
+use crate::hooks::{self, Hook};
+use crate::messages::{tr, Message};
+use crate::project_info::volume;
+use crate::project_info::{find_project_roots, is_project_root, ProjectInfo, PROJECT_INFO_FILE};
+use crate::registry::{relocation, Registry};
+
+pub mod cache;
+pub mod report;
+pub mod retag;
+pub mod sync;
+
+use cache::ScanCache;
+
+/// Settings for a non-interactive scan.
+#[derive(Debug, Clone, Default)]
+pub struct ScanOptions {
+    /// Discover every project below the root instead of scanning the root itself.
+    pub recursive: bool,
+    /// Save `project_info.toml` for every project without prompting.
+    pub save: bool,
+    /// Also scan each sub-project of a monorepo or workspace as its own project.
+    pub per_subproject: bool,
+    /// Directory of the scan result cache; `None` disables caching.
+    pub cache_dir: Option<PathBuf>,
+    /// Number of projects scanned concurrently; `0` uses one worker per CPU.
+    pub jobs: usize,
+    /// Also scan the zip and tar archives found below the root, outside of projects,
+    /// as projects of their own.
+    pub archives: bool,
+    /// Save without printing a confirmation for every project, for callers whose
+    /// standard output is meant for other programs.
+    pub quiet: bool,
+}
+
+/// The outcome of scanning a single project.
+#[derive(Debug, Serialize)]
+pub struct ProjectScan {
+    /// The project's directory.
+    pub path: PathBuf,
+    /// The generated project information, if the scan succeeded.
+    pub info: Option<ProjectInfo>,
+    /// Problems noticed while scanning that did not stop it.
+    pub warnings: Vec<String>,
+    /// The error that stopped the scan, if any.
+    pub error: Option<String>,
+    /// Whether `project_info.toml` was written.
+    pub saved: bool,
+    /// Whether the result came from the scan cache instead of a fresh scan.
+    pub cached: bool,
+    /// How long the scan took, in milliseconds.
+    pub duration_ms: u64,
+}
+
+/// A summary of every project processed in one scan.
+#[derive(Debug, Serialize)]
+pub struct ScanReport {
+    /// The directory the scan started from.
+    pub root: PathBuf,
+    /// When the scan started.
+    pub started_at: DateTime<Local>,
+    /// How long the whole scan took, in milliseconds.
+    pub duration_ms: u64,
+    /// The per-project results, in discovery order.
+    pub projects: Vec<ProjectScan>,
+}
+
+/// Finds the project directories to scan below `root`.
+///
+/// Without `recursive`, the root itself is the only project, which may also be an
+/// archive file. Otherwise the tree is walked and every directory that looks like a
+/// project root is returned, followed by the archives outside of them when `archives`
+/// is set; the walk does not descend into projects, so nested sub-projects are not
+/// listed separately.
+pub fn discover_projects(root: &Path, recursive: bool, archives: bool) -> Vec<PathBuf> {
+    if !recursive || root.is_file() || is_project_root(root) {
+        return vec![root.to_path_buf()];
+    }
+
+    let mut projects = find_project_roots(root);
+    if archives {
+        let found = contents::find_archives(root, &projects);
+        projects.extend(found);
+    }
+
+    info!("Discovered {} projects under {}", projects.len(), root.display());
+    projects
+}
+
+/// Scans every project under `root`, optionally saving the results.
+pub fn scan(root: &Path, options: &ScanOptions, config: &Config, enrichment: &Enrichment) -> ScanReport {
+    scan_list(root, &[root.to_path_buf()], options, config, enrichment)
+}
+
+/// Scans every project under each of several directories, as [`scan`] does for one,
+/// such as a list of directories read from standard input.
+///
+/// # Arguments
+///
+/// * `label` - What the report names as the scan's root, such as `-` for standard input.
+/// * `roots` - The directories to scan.
+pub fn scan_list(
+    label: &Path,
+    roots: &[PathBuf],
+    options: &ScanOptions,
+    config: &Config,
+    enrichment: &Enrichment,
+) -> ScanReport {
+    scan_streaming(label, roots, options, config, enrichment, |_| {})
+}
+
+/// Like [`scan_list`], but also hands every project to `on_scan` as soon as it is
+/// scanned, so results can be streamed while the scan goes on.
+///
+/// Projects are scanned concurrently, so `on_scan` sees them in the order they finish,
+/// from several threads; the report lists them in discovery order.
+pub fn scan_streaming(
+    label: &Path,
+    roots: &[PathBuf],
+    options: &ScanOptions,
+    config: &Config,
+    enrichment: &Enrichment,
+    on_scan: impl Fn(&ProjectScan) + Sync,
+) -> ScanReport {
+    let started_at = Local::now();
+    let start = Instant::now();
+
+    let scan_all = |paths: &[PathBuf]| {
+        run_pool(paths, options.jobs, |path| {
+            let scan = scan_project(path, options, config, enrichment);
+            on_scan(&scan);
+            scan
+        })
+    };
+
+    let paths: Vec<PathBuf> = roots
+        .iter()
+        .flat_map(|root| discover_projects(root, options.recursive, options.archives))
+        .collect();
+    let scans = scan_all(&paths);
+
+    // Sub-projects are only known once their monorepo has been scanned, so they are
+    // scanned in a second pass and listed right after their parent.
+    let subproject_paths: Vec<Vec<PathBuf>> = scans
+        .iter()
+        .map(|scan| match (&scan.info, options.per_subproject) {
+            (Some(info), true) => info.subprojects.iter().map(|sub| scan.path.join(sub)).collect(),
+            _ => Vec::new(),
+        })
+        .collect();
+    let mut subproject_scans = scan_all(&subproject_paths.concat()).into_iter();
+
+    let mut projects = Vec::new();
+    for (scan, subprojects) in scans.into_iter().zip(&subproject_paths) {
+        projects.push(scan);
+        projects.extend(subproject_scans.by_ref().take(subprojects.len()));
+    }
+
+    ScanReport {
+        root: label.to_path_buf(),
+        started_at,
+        duration_ms: start.elapsed().as_millis() as u64,
+        projects,
+    }
+}
+
+/// Runs `task` on every item with a pool of `jobs` worker threads, returning the
+/// results in the order of the items.
+///
+/// With `jobs` set to zero, the pool has one thread per CPU. Work started by a task
+/// (e.g. detectors run in parallel within a project) shares the same pool.
+fn run_pool<T, R, F>(items: &[T], jobs: usize, task: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    match ThreadPoolBuilder::new().num_threads(jobs).build() {
+        Ok(pool) => pool.install(|| items.par_iter().map(&task).collect()),
+        Err(e) => {
+            warn!("Unable to start worker threads, scanning serially: {}", e);
+            items.iter().map(task).collect()
+        }
+    }
+}
+
+/// Scans a single project directory.
+fn scan_project(path: &Path, options: &ScanOptions, config: &Config, enrichment: &Enrichment) -> ProjectScan {
+    let start = Instant::now();
+    let mut scan = ProjectScan {
+        path: path.to_path_buf(),
+        info: None,
+        warnings: Vec::new(),
+        error: None,
+        saved: false,
+        cached: false,
+        duration_ms: 0,
+    };
+
+    // Reuse the result of an earlier scan while the type's refresh policy allows it.
+    // Archives are listed again every time, which is cheap next to unpacking them.
+    let is_archive = contents::is_archive(path);
+    let cache = options.cache_dir.as_deref().filter(|_| !is_archive).map(ScanCache::open);
+    let cached = cache.as_ref().and_then(|cache| cache.get(path, config));
+    scan.cached = cached.is_some();
+
+    let generated = match cached {
+        Some(project) => Ok(project),
+        None if is_archive => scan_archive(path, config),
+        None => ProjectInfo::generate_project_info(path, config).inspect(|project| {
+            if let Some(cache) = &cache {
+                cache.put(path, config, project);
+            }
+        }),
+    };
+
+    match generated {
+        Ok(mut project) => {
+            enrichment.enrich(path, &mut project);
+
+            scan.warnings.extend(project.warnings.iter().cloned());
+            if project.project_type == "unknown" {
+                scan.warnings.push(tr(Message::TypeUnknown, &[]));
+            }
+            match detect_relocation(path) {
+                Some(mismatch) => scan.warnings.push(format!(
+                    "{} {}",
+                    mismatch.describe(),
+                    tr(Message::RelinkInteractively, &[])
+                )),
+                // Keep what was recorded for a project tagged before (its id, status, etc.).
+                None => {
+                    if let Ok(recorded) = ProjectInfo::load_from_file(&config.output.file_for(path)) {
+                        project.keep_user_fields(recorded);
+                    }
+                }
+            }
+
+            if options.save {
+                match write_project(path, &project, config, None, !options.quiet) {
+                    Ok(()) => scan.saved = true,
+                    Err(e) => scan.error = Some(format!("Error saving project_info.toml: {}", e)),
+                }
+            }
+
+            scan.info = Some(project);
+        }
+        Err(e) => {
+            warn!("Failed to scan {}: {}", path.display(), e);
+            scan.error = Some(e.to_string());
+        }
+    }
+
+    scan.duration_ms = start.elapsed().as_millis() as u64;
+    scan
+}
+
+/// Generates the information of a project packed in an archive from a skeleton of
+/// its entries, keeping what the `project_info.toml` inside the archive recorded.
+fn scan_archive(path: &Path, config: &Config) -> Result<ProjectInfo, Box<dyn std::error::Error>> {
+    let skeleton = Skeleton::build(path)?;
+    let mut project = ProjectInfo::generate_project_info(skeleton.root(), config)?;
+
+    // The skeleton's files were only just written, so the archive's dates are used.
+    if let Some(oldest) = skeleton.oldest_modified() {
+        project.date_created = project.date_created.min(oldest);
+    }
+    if let Ok(recorded) = ProjectInfo::load_from_toml_file(skeleton.root()) {
+        project.keep_user_fields(recorded);
+    }
+    project.volume = volume::for_path(path);
+    Ok(project)
+}
+
+/// Checks whether the project's existing `project_info.toml` was written for
+/// another directory.
+fn detect_relocation(path: &Path) -> Option<relocation::Mismatch> {
+    if !path.join(PROJECT_INFO_FILE).exists() {
+        return None;
+    }
+    let registry = Registry::open_default().ok()?;
+    relocation::detect(path, &registry)
+}
+
+/// Saves the project information where the configuration's `[output]` section says
+/// (`project_info.toml` in the project directory by default) and records the project
+/// in the registry. Registry failures are logged but do not fail the save.
+///
+/// The configured `pre_save` hook runs first and cancels the save if it fails; the
+/// `post_save` hook runs last, and its failure is only logged.
+pub fn save_project(directory: &Path, project: &ProjectInfo, config: &Config) -> io::Result<()> {
+    write_project(directory, project, config, None, true)
+}
+
+/// Saves the project information like [`save_project`], but records it in the given
+/// registry and prints nothing, for callers whose standard output is not meant for
+/// people.
+pub fn save_project_in(directory: &Path, project: &ProjectInfo, config: &Config, registry: &Registry) -> io::Result<()> {
+    write_project(directory, project, config, Some(registry), false)
+}
+
+/// Saves the project information like [`save_project`], recording it in `registry`
+/// or else the default one, and printing where it was saved only when `announce` is
+/// set.
+fn write_project(
+    directory: &Path,
+    project: &ProjectInfo,
+    config: &Config,
+    registry: Option<&Registry>,
+    announce: bool,
+) -> io::Result<()> {
+    if let Some(command) = &config.hooks.pre_save {
+        hooks::run(Hook::PreSave, command, directory, project, &config.security)?;
+    }
+
+    let file = config.output.file_for(directory);
+    if announce {
+        project.save_to_file(&file)?;
+    } else {
+        project.write_to_file(&file)?;
+    }
+    info!("Project information saved successfully.");
+
+    #[cfg(feature = "finder-tags")]
+    if config.output.finder_tags {
+        if let Err(e) = crate::project_info::finder::write_tags(directory, &project.tags) {
+            warn!("Could not write the Finder tags of {}: {}", directory.display(), e);
+        }
+    }
+    #[cfg(feature = "xattr-tags")]
+    if config.output.xattr_tags {
+        if let Err(e) = crate::project_info::xattr_tags::write_tags(directory, &project.tags) {
+            warn!("Could not write the user.xdg.tags of {}: {}", directory.display(), e);
+        }
+    }
+
+    // Record the project in the registry so it can be found later.
+    let registered = match registry {
+        Some(registry) => registry.register(directory, project),
+        None => Registry::open_default().and_then(|registry| registry.register(directory, project)),
+    };
+    match registered {
+        Ok(entry_path) => info!("Registry entry written to {}", entry_path.display()),
+        Err(e) => warn!("Could not update the project registry: {}", e),
+    }
+
+    if let Some(command) = &config.hooks.post_save {
+        if let Err(e) = hooks::run(Hook::PostSave, command, directory, project, &config.security) {
+            warn!("{}", e);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enrichment::EnrichmentConfig;
+    use std::fs::{self, File};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_discover_projects_recursive() {
+        let root = tempdir().unwrap();
+        let root_path = root.path();
+
+        // Two projects, one of which contains a nested crate, plus a plain folder.
+        fs::create_dir_all(root_path.join("code/app/crates/inner")).unwrap();
+        File::create(root_path.join("code/app/Cargo.toml")).unwrap();
+        File::create(root_path.join("code/app/crates/inner/Cargo.toml")).unwrap();
+        fs::create_dir_all(root_path.join("music/beat")).unwrap();
+        File::create(root_path.join("music/beat/beat.rpp")).unwrap();
+        fs::create_dir_all(root_path.join("misc")).unwrap();
+
+        let projects = discover_projects(root_path, true, false);
+        assert_eq!(
+            projects,
+            vec![root_path.join("code/app"), root_path.join("music/beat")]
+        );
+
+        // Without recursion only the root is scanned.
+        assert_eq!(discover_projects(root_path, false, false), vec![root_path.to_path_buf()]);
+
+        // Archives outside of projects are listed after them on request.
+        File::create(root_path.join("music/old-beat.zip")).unwrap();
+        File::create(root_path.join("code/app/release.zip")).unwrap();
+        assert_eq!(
+            discover_projects(root_path, true, true),
+            vec![root_path.join("code/app"), root_path.join("music/beat"), root_path.join("music/old-beat.zip")]
+        );
+    }
+
+    #[test]
+    fn test_run_pool_keeps_order() {
+        let items: Vec<u64> = (0..50).collect();
+        let results = run_pool(&items, 4, |item| {
+            std::thread::sleep(std::time::Duration::from_millis(50 - item));
+            item * 2
+        });
+        assert_eq!(results, items.iter().map(|item| item * 2).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_scan_per_subproject() {
+        let root = tempdir().unwrap();
+        let root_path = root.path();
+        let cache = tempdir().unwrap();
+
+        fs::write(root_path.join("Cargo.toml"), "[workspace]\nmembers = [\"crates/*\"]\n").unwrap();
+        for member in ["crates/a", "crates/b"] {
+            fs::create_dir_all(root_path.join(member)).unwrap();
+            File::create(root_path.join(member).join("Cargo.toml")).unwrap();
+        }
+
+        let enrichment = Enrichment::new(EnrichmentConfig {
+            offline: true,
+            cache_dir: Some(cache.path().to_path_buf()),
+            ..EnrichmentConfig::default()
+        });
+        let options = ScanOptions {
+            per_subproject: true,
+            jobs: 2,
+            ..ScanOptions::default()
+        };
+
+        let report = scan(root_path, &options, &Config::default(), &enrichment);
+        let paths: Vec<_> = report.projects.iter().map(|p| p.path.clone()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                root_path.to_path_buf(),
+                root_path.join("crates/a"),
+                root_path.join("crates/b"),
+            ]
+        );
+    }
+}
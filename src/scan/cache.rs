@@ -0,0 +1,323 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use chrono::{DateTime, Local};
+use log::{info, warn};
+use walkdir::WalkDir;
+
+use crate::config::{dirs, Config, RefreshPolicy};
+use crate::project_info::git::head_state;
+use crate::project_info::ignore::IgnoreRules;
+use crate::project_info::inventory::VisitedDirectories;
+use crate::project_info::overrides::OVERRIDES_FILE;
+use crate::project_info::{ProjectInfo, SKIPPED_DIRECTORIES};
+use crate::registry::fnv1a;
+
+/// A cached scan result together with the state it was generated from.
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedScan {
+    path: PathBuf,
+    /// The HEAD-based key from [`ScanCache::key`], if the project had one.
+    key: Option<String>,
+    /// A hash of the tagger version, the configuration, and the project's
+    /// `.tagger.toml`.
+    settings: String,
+    scanned_at: DateTime<Local>,
+    info: ProjectInfo,
+}
+
+/// An on-disk cache of generated project information.
+///
+/// When a cached result is regenerated depends on the refresh policy configured for
+/// the project's type. By default, clean git repositories are keyed on their HEAD
+/// commit, so re-scanning an unchanged repository skips the detectors entirely, while
+/// a pull or a new commit changes the key and forces a refresh. Other directories are
+/// keyed on a fingerprint of their files' sizes and modification times.
+pub struct ScanCache {
+    root: PathBuf,
+}
+
+impl ScanCache {
+    /// Opens the cache stored in the given directory.
+    pub fn open(root: &Path) -> Self {
+        ScanCache {
+            root: root.to_path_buf(),
+        }
+    }
+
+    /// Returns the default cache location, `scan` in the cache directory (see
+    /// [`dirs`]), e.g. `~/.cache/tagger/scan` on Linux.
+    pub fn default_location() -> io::Result<PathBuf> {
+        Ok(dirs::cache_dir()?.join("scan"))
+    }
+
+    /// Returns the cache key for a project, or `None` if its files cannot be listed.
+    ///
+    /// Clean git repositories are identified by their HEAD commit and the number of
+    /// unpushed commits. Other directories, and repositories with uncommitted changes,
+    /// are identified by a fingerprint of their files. The tagger version,
+    /// configuration, and project's `.tagger.toml` are part of every key, so a push,
+    /// upgrading tagger, changing a blacklist, or editing the project's overrides also
+    /// invalidates cached results.
+    pub fn key(directory: &Path, config: &Config) -> Option<String> {
+        let output = config.output.file_for(directory);
+        let state = match head_state(directory) {
+            Some(state) if !state.status.dirty => {
+                format!("{}-{}", state.commit, state.status.unpushed_commits)
+            }
+            Some(state) => format!("{}-{:016x}", state.commit, fingerprint(directory, &output, config.scan.follow_symlinks)?),
+            None => format!("files-{:016x}", fingerprint(directory, &output, config.scan.follow_symlinks)?),
+        };
+        Some(format!("{}-{}", state, settings_hash(config, directory)))
+    }
+
+    /// Returns the cached project information if the refresh policy of its project
+    /// type says it is still current.
+    pub fn get(&self, directory: &Path, config: &Config) -> Option<ProjectInfo> {
+        let content = fs::read_to_string(self.entry_path(directory)).ok()?;
+        let cached: CachedScan = serde_json::from_str(&content).ok()?;
+
+        if !Self::is_fresh(&cached, directory, config, Local::now()) {
+            info!("Scan cache for {} is out of date.", directory.display());
+            return None;
+        }
+        Some(cached.info)
+    }
+
+    /// Stores the project information, unless its type is regenerated on every scan.
+    pub fn put(&self, directory: &Path, config: &Config, info: &ProjectInfo) {
+        let key = match config.refresh_policy(&info.project_type) {
+            RefreshPolicy::Always => return,
+            RefreshPolicy::OnChange => Self::key(directory, config),
+            RefreshPolicy::Never | RefreshPolicy::Every(_) => None,
+        };
+
+        let cached = CachedScan {
+            path: directory.to_path_buf(),
+            key,
+            settings: settings_hash(config, directory),
+            scanned_at: Local::now(),
+            info: info.clone(),
+        };
+
+        let result = fs::create_dir_all(&self.root).and_then(|_| {
+            let json = serde_json::to_string(&cached).map_err(io::Error::from)?;
+            fs::write(self.entry_path(directory), json)
+        });
+        if let Err(e) = result {
+            warn!("Unable to cache scan of {}: {}", directory.display(), e);
+        }
+    }
+
+    /// Returns `true` if a cached result can be reused at `now`.
+    fn is_fresh(cached: &CachedScan, directory: &Path, config: &Config, now: DateTime<Local>) -> bool {
+        let same_settings = cached.settings == settings_hash(config, directory);
+        match config.refresh_policy(&cached.info.project_type) {
+            RefreshPolicy::OnChange => {
+                cached.key.is_some() && cached.key == Self::key(directory, config)
+            }
+            RefreshPolicy::Always => false,
+            RefreshPolicy::Never => same_settings,
+            RefreshPolicy::Every(interval) => {
+                same_settings && (now - cached.scanned_at).to_std().is_ok_and(|age| age < interval)
+            }
+        }
+    }
+
+    /// Returns the cache file for a project directory.
+    fn entry_path(&self, directory: &Path) -> PathBuf {
+        let path = fs::canonicalize(directory).unwrap_or_else(|_| directory.to_path_buf());
+        let hash = fnv1a(path.to_string_lossy().as_bytes());
+        self.root.join(format!("{:016x}.json", hash))
+    }
+}
+
+/// Fingerprints a directory by the paths, sizes, and modification times of its files.
+///
+/// Hidden, build, and ignored directories (e.g. `.git`, `target`) are left out, so
+/// builds and git operations do not count as changes, and so is the output file, which
+/// tagger writes itself. When symbolic links are followed, the files they lead to
+/// count as well.
+fn fingerprint(directory: &Path, output: &Path, follow_symlinks: bool) -> Option<u64> {
+    let mut listing = String::new();
+    let mut ignore_rules = IgnoreRules::load(directory);
+    let mut visited = VisitedDirectories::new(directory);
+    let walker = WalkDir::new(directory)
+        .min_depth(1)
+        .follow_links(follow_symlinks)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| {
+            if !entry.file_type().is_dir() {
+                return true;
+            }
+            if follow_symlinks && !visited.first_visit(entry.path()) {
+                return false;
+            }
+            let file_name = entry.file_name().to_string_lossy();
+            let relative = entry.path().strip_prefix(directory).unwrap_or(entry.path());
+            if file_name.starts_with('.')
+                || SKIPPED_DIRECTORIES.contains(&file_name.as_ref())
+                || ignore_rules.is_ignored(relative, true)
+            {
+                return false;
+            }
+            ignore_rules.add_directory(directory, relative);
+            true
+        });
+
+    for entry in walker {
+        let entry = match entry {
+            Err(e) if e.loop_ancestor().is_some() => continue,
+            entry => entry.ok()?,
+        };
+        if entry.path() == output {
+            continue;
+        }
+
+        let relative = entry.path().strip_prefix(directory).ok()?;
+        listing.push_str(&relative.to_string_lossy());
+        if entry.file_type().is_file() {
+            let metadata = entry.metadata().ok()?;
+            let modified = metadata
+                .modified()
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .unwrap_or_default();
+            listing.push_str(&format!(":{}:{}", metadata.len(), modified.as_nanos()));
+        }
+        listing.push('\n');
+    }
+
+    Some(fnv1a(listing.as_bytes()))
+}
+
+/// Hashes the tagger version, the configuration, and the project's `.tagger.toml`,
+/// which all change generated results. The overrides file is left out of the dirty
+/// check and the file fingerprint, so its contents are hashed here instead.
+fn settings_hash(config: &Config, directory: &Path) -> String {
+    let overrides = fs::read(directory.join(OVERRIDES_FILE)).unwrap_or_default();
+    let mut settings = format!("{}:{:?}:", env!("CARGO_PKG_VERSION"), config).into_bytes();
+    settings.extend(overrides);
+    format!("{:016x}", fnv1a(&settings))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::{Repository, Signature};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_key_follows_head_and_dirty_state() {
+        let dir = tempdir().unwrap();
+        let cache_dir = tempdir().unwrap();
+        let cache = ScanCache::open(cache_dir.path());
+        let config = Config::default();
+
+        // Directories that are not repositories are keyed on their files.
+        let plain_dir = tempdir().unwrap();
+        let files_key = ScanCache::key(plain_dir.path(), &config).unwrap();
+        fs::write(plain_dir.path().join("song.rpp"), "<REAPER_PROJECT").unwrap();
+        assert_ne!(ScanCache::key(plain_dir.path(), &config).unwrap(), files_key);
+
+        let repo = Repository::init(dir.path()).unwrap();
+        let signature = Signature::now("Test", "test@example.com").unwrap();
+        let tree = repo.find_tree(repo.index().unwrap().write_tree().unwrap()).unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "First", &tree, &[]).unwrap();
+
+        let key = ScanCache::key(dir.path(), &config).unwrap();
+        let info = ProjectInfo {
+            name: "cached".to_string(),
+            ..Default::default()
+        };
+        cache.put(dir.path(), &config, &info);
+        assert_eq!(cache.get(dir.path(), &config).unwrap().name, "cached");
+
+        // A new commit changes the key, so the cached result is stale.
+        let parent = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "Second", &tree, &[&parent])
+            .unwrap();
+        let new_key = ScanCache::key(dir.path(), &config).unwrap();
+        assert_ne!(key, new_key);
+        assert!(cache.get(dir.path(), &config).is_none());
+
+        // Uncommitted changes are keyed on the files, so every further edit counts,
+        // but saving project_info.toml does not.
+        fs::write(dir.path().join("notes.md"), "draft").unwrap();
+        let dirty_key = ScanCache::key(dir.path(), &config).unwrap();
+        assert_ne!(dirty_key, new_key);
+        fs::write(dir.path().join("project_info.toml"), "name = \"cached\"").unwrap();
+        assert_eq!(ScanCache::key(dir.path(), &config).unwrap(), dirty_key);
+        fs::write(dir.path().join("notes.md"), "second draft").unwrap();
+        assert_ne!(ScanCache::key(dir.path(), &config).unwrap(), dirty_key);
+    }
+
+    #[test]
+    fn test_overrides_invalidate_clean_repositories() {
+        let dir = tempdir().unwrap();
+        let cache_dir = tempdir().unwrap();
+        let cache = ScanCache::open(cache_dir.path());
+        let config = Config::default();
+
+        let repo = Repository::init(dir.path()).unwrap();
+        let signature = Signature::now("Test", "test@example.com").unwrap();
+        let tree = repo.find_tree(repo.index().unwrap().write_tree().unwrap()).unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "First", &tree, &[]).unwrap();
+
+        let info = ProjectInfo {
+            project_type: "programming".to_string(),
+            ..Default::default()
+        };
+        cache.put(dir.path(), &config, &info);
+        assert!(cache.get(dir.path(), &config).is_some());
+
+        // Editing .tagger.toml leaves the repository clean but changes the result.
+        fs::write(dir.path().join(OVERRIDES_FILE), "type = \"music\"\n").unwrap();
+        assert!(!head_state(dir.path()).unwrap().status.dirty);
+        assert!(cache.get(dir.path(), &config).is_none());
+
+        cache.put(dir.path(), &config, &info);
+        fs::write(dir.path().join(OVERRIDES_FILE), "[tags]\nadd = [\"client:acme\"]\n").unwrap();
+        assert!(cache.get(dir.path(), &config).is_none());
+    }
+
+    #[test]
+    fn test_refresh_policies() {
+        let dir = tempdir().unwrap();
+        let mut config = Config::default();
+        config.refresh.insert("music".to_string(), RefreshPolicy::try_from("weekly".to_string()).unwrap());
+        config.refresh.insert("default".to_string(), RefreshPolicy::Never);
+
+        let scanned_at = Local::now();
+        let cached = |project_type: &str| CachedScan {
+            path: dir.path().to_path_buf(),
+            key: None,
+            settings: settings_hash(&config, dir.path()),
+            scanned_at,
+            info: ProjectInfo {
+                project_type: project_type.to_string(),
+                ..Default::default()
+            },
+        };
+        let fresh = |cached: &CachedScan, config: &Config, age_hours: i64| {
+            let now = scanned_at + chrono::Duration::hours(age_hours);
+            ScanCache::is_fresh(cached, dir.path(), config, now)
+        };
+
+        // Music is regenerated weekly; every other type is kept.
+        assert!(fresh(&cached("music"), &config, 24));
+        assert!(!fresh(&cached("music"), &config, 8 * 24));
+        assert!(fresh(&cached("archive"), &config, 365 * 24));
+
+        // Other settings changes invalidate results whatever the policy.
+        let mut changed = config.clone();
+        changed.tags.blacklist.push("audio".to_string());
+        assert!(!fresh(&cached("archive"), &changed, 1));
+
+        // By default, projects that are not git repositories are always regenerated.
+        assert!(!fresh(&cached("music"), &Config::default(), 0));
+    }
+}
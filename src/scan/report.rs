@@ -0,0 +1,147 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use super::ScanReport;
+
+impl ScanReport {
+    /// Writes the report to a file, as Markdown for `.md`/`.markdown` paths and as
+    /// JSON otherwise.
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        let is_markdown = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown"));
+
+        let content = if is_markdown {
+            self.to_markdown()
+        } else {
+            serde_json::to_string_pretty(self)?
+        };
+
+        fs::write(path, content)
+    }
+
+    /// Renders the report as a Markdown document.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+
+        let failed = self.projects.iter().filter(|p| p.error.is_some()).count();
+        let warnings: usize = self.projects.iter().map(|p| p.warnings.len()).sum();
+
+        let _ = writeln!(out, "# Tagger scan report\n");
+        let _ = writeln!(out, "- Root: `{}`", self.root.display());
+        let _ = writeln!(out, "- Started: {}", self.started_at.format("%Y-%m-%d %H:%M:%S %z"));
+        let _ = writeln!(out, "- Duration: {} ms", self.duration_ms);
+        let _ = writeln!(
+            out,
+            "- Projects: {} ({} failed, {} warnings)\n",
+            self.projects.len(),
+            failed,
+            warnings
+        );
+
+        let _ = writeln!(out, "| Project | Type | Tags | Saved | Time (ms) |");
+        let _ = writeln!(out, "|---|---|---|---|---|");
+        for project in &self.projects {
+            let (name, project_type, tags) = match &project.info {
+                Some(info) => (info.name.clone(), info.project_type.clone(), info.tags.join(", ")),
+                None => (project.path.display().to_string(), "-".to_string(), "-".to_string()),
+            };
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} | {} | {}{} |",
+                name,
+                project_type,
+                tags,
+                if project.saved { "yes" } else { "no" },
+                project.duration_ms,
+                if project.cached { " (cached)" } else { "" }
+            );
+        }
+
+        let problems: Vec<_> = self
+            .projects
+            .iter()
+            .filter(|p| p.error.is_some() || !p.warnings.is_empty())
+            .collect();
+        if !problems.is_empty() {
+            let _ = writeln!(out, "\n## Problems\n");
+            for project in problems {
+                let _ = writeln!(out, "### `{}`\n", project.path.display());
+                if let Some(error) = &project.error {
+                    let _ = writeln!(out, "- **Error:** {}", error);
+                }
+                for warning in &project.warnings {
+                    let _ = writeln!(out, "- Warning: {}", warning);
+                }
+                let _ = writeln!(out);
+            }
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project_info::ProjectInfo;
+    use crate::scan::ProjectScan;
+    use chrono::Local;
+    use std::path::PathBuf;
+    use tempfile::tempdir;
+
+    fn sample_report() -> ScanReport {
+        ScanReport {
+            root: PathBuf::from("/projects"),
+            started_at: Local::now(),
+            duration_ms: 12,
+            projects: vec![
+                ProjectScan {
+                    path: PathBuf::from("/projects/beat"),
+                    info: Some(ProjectInfo {
+                        name: "beat".to_string(),
+                        project_type: "music".to_string(),
+                        tags: vec!["RPP".to_string(), "audio".to_string()],
+                        ..Default::default()
+                    }),
+                    warnings: Vec::new(),
+                    error: None,
+                    saved: true,
+                    cached: false,
+                    duration_ms: 5,
+                },
+                ProjectScan {
+                    path: PathBuf::from("/projects/broken"),
+                    info: None,
+                    warnings: vec!["Project type could not be determined.".to_string()],
+                    error: Some("permission denied".to_string()),
+                    saved: false,
+                    cached: false,
+                    duration_ms: 1,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_markdown_report() {
+        let markdown = sample_report().to_markdown();
+        assert!(markdown.contains("| beat | music | RPP, audio | yes | 5 |"));
+        assert!(markdown.contains("- Projects: 2 (1 failed, 1 warnings)"));
+        assert!(markdown.contains("- **Error:** permission denied"));
+    }
+
+    #[test]
+    fn test_write_json_report() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("report.json");
+        sample_report().write(&path).unwrap();
+
+        let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(json["projects"].as_array().unwrap().len(), 2);
+        assert_eq!(json["projects"][0]["info"]["project_type"], "music");
+    }
+}
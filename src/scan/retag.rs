@@ -0,0 +1,234 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use log::{info, warn};
+use walkdir::WalkDir;
+
+use super::{detect_relocation, run_pool};
+use crate::config::Config;
+use crate::enrichment::Enrichment;
+use crate::project_info::{ProjectInfo, SKIPPED_DIRECTORIES};
+
+/// How a project's saved information would change if it were regenerated.
+#[derive(Debug, Clone)]
+pub struct Retag {
+    /// The project's directory.
+    pub directory: PathBuf,
+    /// The type recorded before.
+    pub old_type: String,
+    /// The regenerated information, keeping what the user recorded.
+    pub info: ProjectInfo,
+    /// Tags the regeneration adds.
+    pub added_tags: Vec<String>,
+    /// Tags the regeneration drops.
+    pub removed_tags: Vec<String>,
+    /// Top-level fields the saved file would gain, such as fields added to the
+    /// schema since it was written.
+    pub added_fields: Vec<String>,
+    /// Top-level fields the saved file would lose, such as fields no longer written.
+    pub removed_fields: Vec<String>,
+}
+
+impl Retag {
+    /// Returns `true` if the type, tags, or fields would change. Other regenerated
+    /// values, such as sizes and dates, are saved too but do not count as changes.
+    pub fn is_change(&self) -> bool {
+        self.old_type != self.info.project_type
+            || !self.added_tags.is_empty()
+            || !self.removed_tags.is_empty()
+            || !self.added_fields.is_empty()
+            || !self.removed_fields.is_empty()
+    }
+}
+
+/// The regeneration of every tagged project below a root.
+#[derive(Debug, Default)]
+pub struct RetagPlan {
+    /// The projects that could be regenerated, in directory order.
+    pub projects: Vec<Retag>,
+    /// The projects that could not, with the reason.
+    pub errors: Vec<(PathBuf, String)>,
+}
+
+impl RetagPlan {
+    /// Finds every tagged project below `root` and regenerates its information with
+    /// the current detectors, without saving anything.
+    ///
+    /// Projects whose `project_info.toml` was written for another directory are left
+    /// out with an error, since they should be relinked interactively first.
+    pub fn new(root: &Path, config: &Config, enrichment: &Enrichment) -> Self {
+        let directories = find_tagged_projects(root, config);
+        info!("Regenerating {} tagged projects under {}", directories.len(), root.display());
+
+        let mut plan = RetagPlan::default();
+        for (directory, result) in directories
+            .iter()
+            .zip(run_pool(&directories, 0, |directory| retag(directory, config, enrichment)))
+        {
+            match result {
+                Ok(project) => plan.projects.push(project),
+                Err(e) => {
+                    warn!("Unable to retag {}: {}", directory.display(), e);
+                    plan.errors.push((directory.clone(), e));
+                }
+            }
+        }
+        plan
+    }
+
+    /// Returns the projects whose type, tags, or fields would change.
+    pub fn changes(&self) -> impl Iterator<Item = &Retag> {
+        self.projects.iter().filter(|project| project.is_change())
+    }
+
+    /// Summarizes the changes across every project, one line per type change, tag, or
+    /// field, with the number of projects it affects:
+    ///
+    /// ```text
+    ///   type unknown → music (2)
+    ///   + tag daw:reaper (14)
+    ///   - tag format:wav (3)
+    ///   + field slug (9)
+    /// ```
+    pub fn summary(&self) -> String {
+        let mut types: BTreeMap<(String, String), usize> = BTreeMap::new();
+        let mut lines: BTreeMap<(u8, String), usize> = BTreeMap::new();
+        for project in self.changes() {
+            if project.old_type != project.info.project_type {
+                *types
+                    .entry((project.old_type.clone(), project.info.project_type.clone()))
+                    .or_default() += 1;
+            }
+            let kinds = [
+                (0, "+ tag", &project.added_tags),
+                (1, "- tag", &project.removed_tags),
+                (2, "+ field", &project.added_fields),
+                (3, "- field", &project.removed_fields),
+            ];
+            for (order, prefix, values) in kinds {
+                for value in values {
+                    *lines.entry((order, format!("{} {}", prefix, value))).or_default() += 1;
+                }
+            }
+        }
+
+        let mut out = String::new();
+        for ((from, to), count) in types {
+            let _ = writeln!(out, "  type {} → {} ({})", from, to, count);
+        }
+        for ((_, line), count) in lines {
+            let _ = writeln!(out, "  {} ({})", line, count);
+        }
+        out
+    }
+}
+
+/// Returns every directory below `root`, itself included, whose project information
+/// has been saved. Hidden and build directories are skipped; tagged projects nested in
+/// other tagged projects are included.
+pub fn find_tagged_projects(root: &Path, config: &Config) -> Vec<PathBuf> {
+    WalkDir::new(root)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| {
+            let file_name = entry.file_name().to_string_lossy();
+            entry.depth() == 0
+                || (entry.file_type().is_dir()
+                    && !file_name.starts_with('.')
+                    && !SKIPPED_DIRECTORIES.contains(&file_name.as_ref()))
+        })
+        .flatten()
+        .filter(|entry| entry.file_type().is_dir() && config.output.file_for(entry.path()).is_file())
+        .map(|entry| entry.into_path())
+        .collect()
+}
+
+/// Regenerates one project's information, keeping what the user recorded.
+fn retag(directory: &Path, config: &Config, enrichment: &Enrichment) -> Result<Retag, String> {
+    if let Some(mismatch) = detect_relocation(directory) {
+        return Err(mismatch.describe());
+    }
+
+    let file = config.output.file_for(directory);
+    let content = fs::read_to_string(&file).map_err(|e| e.to_string())?;
+    let recorded: ProjectInfo = toml::from_str(&content).map_err(|e| e.to_string())?;
+    let old_fields = top_level_keys(toml::from_str(&content).ok());
+
+    let mut info = ProjectInfo::generate_project_info(directory, config).map_err(|e| e.to_string())?;
+    enrichment.enrich(directory, &mut info);
+    let old_type = recorded.project_type.clone();
+    let old_tags: BTreeSet<String> = recorded.tags.iter().cloned().collect();
+    info.keep_user_fields(recorded);
+    let new_tags: BTreeSet<String> = info.tags.iter().cloned().collect();
+    let new_fields = top_level_keys(toml::Value::try_from(&info).ok());
+
+    Ok(Retag {
+        directory: directory.to_path_buf(),
+        old_type,
+        added_tags: new_tags.difference(&old_tags).cloned().collect(),
+        removed_tags: old_tags.difference(&new_tags).cloned().collect(),
+        added_fields: new_fields.difference(&old_fields).cloned().collect(),
+        removed_fields: old_fields.difference(&new_fields).cloned().collect(),
+        info,
+    })
+}
+
+fn top_level_keys(value: Option<toml::Value>) -> BTreeSet<String> {
+    match value {
+        Some(toml::Value::Table(table)) => table.keys().cloned().collect(),
+        _ => BTreeSet::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enrichment::EnrichmentConfig;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_plan_reports_aggregate_changes() {
+        let dir = tempdir().unwrap();
+        let cache = tempdir().unwrap();
+        let config = Config::default();
+        for name in ["a", "b"] {
+            let project = dir.path().join(name);
+            fs::create_dir_all(&project).unwrap();
+            fs::write(project.join("song.rpp"), "<REAPER_PROJECT").unwrap();
+            // An old file: no identifier, a stale tag, and a field no longer written.
+            let old = ProjectInfo {
+                name: name.to_string(),
+                project_type: "unknown".to_string(),
+                tags: vec!["stale".to_string()],
+                notes: vec!["keep me".to_string()],
+                ..Default::default()
+            };
+            let content = toml::to_string(&old).unwrap();
+            fs::write(project.join("project_info.toml"), format!("legacy = true\n{}", content)).unwrap();
+        }
+        fs::create_dir_all(dir.path().join("untagged")).unwrap();
+
+        let enrichment = Enrichment::new(EnrichmentConfig {
+            offline: true,
+            cache_dir: Some(cache.path().to_path_buf()),
+            ..EnrichmentConfig::default()
+        });
+        let plan = RetagPlan::new(dir.path(), &config, &enrichment);
+
+        assert!(plan.errors.is_empty(), "{:?}", plan.errors);
+        assert_eq!(plan.projects.len(), 2);
+        assert_eq!(plan.changes().count(), 2);
+        let project = &plan.projects[0];
+        assert_eq!(project.info.project_type, "music");
+        assert_eq!(project.info.notes, vec!["keep me"]);
+        assert_eq!(project.removed_tags, vec!["stale"]);
+        assert_eq!(project.removed_fields, vec!["legacy"]);
+
+        let summary = plan.summary();
+        assert!(summary.contains("  type unknown → music (2)\n"), "{}", summary);
+        assert!(summary.contains("  - tag stale (2)\n"));
+        assert!(summary.contains("  - field legacy (2)\n"));
+    }
+}
@@ -0,0 +1,187 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+use super::{scan, ScanOptions, ScanReport};
+use crate::config::{dirs, Config, RefreshPolicy, RootConfig};
+use crate::enrichment::Enrichment;
+use crate::project_info::volume;
+use crate::registry::Registry;
+
+/// When each configured root was last synced.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SyncState {
+    /// The time of each root's last sync, keyed by its directory.
+    pub synced: BTreeMap<PathBuf, DateTime<Local>>,
+}
+
+impl SyncState {
+    /// Returns the default location, `sync.json` in the data directory (see [`dirs`]),
+    /// e.g. `~/.local/share/tagger/sync.json` on Linux.
+    pub fn default_location() -> io::Result<PathBuf> {
+        Ok(dirs::data_dir()?.join("sync.json"))
+    }
+
+    /// Reads the state; a missing file means nothing was synced yet, and a malformed
+    /// one is logged and ignored, which only makes every root due.
+    pub fn load(path: &Path) -> Self {
+        let Ok(content) = fs::read_to_string(path) else {
+            return SyncState::default();
+        };
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+            warn!("Ignoring malformed {}: {}", path.display(), e);
+            SyncState::default()
+        })
+    }
+
+    /// Writes the state, creating its directory if needed.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(io::Error::from)?;
+        fs::write(path, json)
+    }
+}
+
+/// What syncing one root did.
+#[derive(Debug)]
+pub enum RootSync {
+    /// The root was rescanned. `missing` counts the projects registered below it
+    /// whose directories are gone.
+    Scanned { report: ScanReport, missing: usize },
+    /// The root's schedule says it is not due yet.
+    NotDue { last_synced: DateTime<Local> },
+    /// The root's directory does not exist, such as a drive that is not mounted.
+    Unavailable,
+}
+
+/// Returns `true` if a root with the given schedule is due for a sync at `now`.
+///
+/// `on-change` and `always` roots are synced every time, `never` roots only once,
+/// and roots with an interval once it has passed since their last sync.
+pub fn is_due(schedule: RefreshPolicy, last_synced: Option<DateTime<Local>>, now: DateTime<Local>) -> bool {
+    let Some(last_synced) = last_synced else {
+        return true;
+    };
+    match schedule {
+        RefreshPolicy::OnChange | RefreshPolicy::Always => true,
+        RefreshPolicy::Never => false,
+        RefreshPolicy::Every(interval) => !(now - last_synced).to_std().is_ok_and(|age| age < interval),
+    }
+}
+
+/// Rescans every configured root that is due, saving each project found below it.
+///
+/// The scan cache in `options` keeps the sync incremental: only projects that changed
+/// since they were last scanned are regenerated, as their types' refresh policies
+/// allow. Roots scheduled `always` bypass the cache.
+///
+/// # Arguments
+///
+/// * `options` - The scan settings; every root is scanned recursively and saved.
+/// * `state` - When each root was last synced, updated for the roots synced now.
+/// * `force` - Sync every available root, whatever its schedule.
+///
+/// # Returns
+///
+/// Each root with what syncing it did, in the order they are configured.
+pub fn sync(
+    config: &Config,
+    options: &ScanOptions,
+    enrichment: &Enrichment,
+    state: &mut SyncState,
+    force: bool,
+) -> Vec<(PathBuf, RootSync)> {
+    let now = Local::now();
+    config
+        .roots
+        .iter()
+        .map(|root| {
+            let directory = root.directory();
+            let last_synced = state.synced.get(&directory).copied();
+            let outcome = match last_synced {
+                _ if !directory.is_dir() => {
+                    info!("Root {} is not available", directory.display());
+                    RootSync::Unavailable
+                }
+                Some(last_synced) if !force && !is_due(root.schedule, Some(last_synced), now) => {
+                    RootSync::NotDue { last_synced }
+                }
+                _ => {
+                    let outcome = rescan(root, &directory, config, options, enrichment);
+                    state.synced.insert(directory.clone(), now);
+                    outcome
+                }
+            };
+            (directory, outcome)
+        })
+        .collect()
+}
+
+/// Rescans one root and counts the projects registered below it that are gone.
+fn rescan(
+    root: &RootConfig,
+    directory: &Path,
+    config: &Config,
+    options: &ScanOptions,
+    enrichment: &Enrichment,
+) -> RootSync {
+    let options = ScanOptions {
+        recursive: true,
+        save: true,
+        cache_dir: options.cache_dir.clone().filter(|_| root.schedule != RefreshPolicy::Always),
+        quiet: true,
+        ..options.clone()
+    };
+    let report = scan(directory, &options, config, enrichment);
+
+    let base = fs::canonicalize(directory).unwrap_or_else(|_| directory.to_path_buf());
+    let mounted = volume::mounted();
+    let missing = Registry::open_default()
+        .and_then(|registry| registry.entries())
+        .map(|entries| {
+            entries
+                .iter()
+                .filter(|entry| entry.path.starts_with(&base) && !entry.path.is_dir())
+                .filter(|entry| entry.offline_volume(&mounted).is_none())
+                .count()
+        })
+        .unwrap_or_default();
+    RootSync::Scanned { report, missing }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_is_due() {
+        let now = Local::now();
+        let daily = RefreshPolicy::Every(std::time::Duration::from_secs(24 * 60 * 60));
+        assert!(is_due(RefreshPolicy::Never, None, now));
+        assert!(!is_due(RefreshPolicy::Never, Some(now - Duration::days(30)), now));
+        assert!(is_due(RefreshPolicy::OnChange, Some(now), now));
+        assert!(!is_due(daily, Some(now - Duration::hours(3)), now));
+        assert!(is_due(daily, Some(now - Duration::hours(25)), now));
+    }
+
+    #[test]
+    fn test_state_round_trip() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("data/sync.json");
+        assert_eq!(SyncState::load(&path), SyncState::default());
+
+        let mut state = SyncState::default();
+        state.synced.insert(PathBuf::from("/Volumes/Sessions"), Local::now());
+        state.save(&path).unwrap();
+        assert_eq!(SyncState::load(&path), state);
+    }
+}
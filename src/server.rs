@@ -0,0 +1,570 @@
+//! A small local HTTP API over the registry, for dashboards and launcher extensions
+//! that want live project metadata without parsing TOML files.
+//!
+//! Every response is JSON. The routes are:
+//!
+//! - `GET /projects`: every registered project, narrowed by `?tag=PATTERN` (repeatable,
+//!   matched like `tagger list --tag`) and `?status=STATUS`.
+//! - `GET /projects/{id}`: one project, by its `id`.
+//! - `POST /projects/{id}/rescan`: rescans and saves one project, returning it.
+//! - `POST /rescan`: rescans and saves every registered project.
+//!
+//! Requests must name a loopback host, or one listed in `[server] allowed_hosts`, so a
+//! web page cannot reach the API by rebinding its own domain to `127.0.0.1`. Browsers
+//! may only call it from the origins in `[server] allowed_origins`, and `POST` requests
+//! must carry a bearer token: the one from `[server] token_env`, or else one generated
+//! when the server starts.
+
+use std::env;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{IpAddr, TcpListener, TcpStream};
+use std::path::Path;
+use std::time::Duration;
+
+use log::{info, warn};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::enrichment::Enrichment;
+use crate::project_info::status::ProjectStatus;
+use crate::project_info::tags;
+use crate::registry::{Registry, RegistryEntry};
+use crate::scan::{self, ScanOptions};
+
+/// The address `tagger serve` listens on unless told otherwise. Only local clients can
+/// connect to it.
+pub const DEFAULT_ADDRESS: &str = "127.0.0.1:7878";
+
+/// The largest request body read before it is discarded; the API takes no bodies.
+const MAX_BODY_BYTES: usize = 64 * 1024;
+
+/// The largest request line and headers read; larger requests are refused.
+const MAX_HEADER_BYTES: u64 = 16 * 1024;
+
+/// How long a client may take to send its request, or to take the response, before
+/// it is dropped so the next client is answered.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// An HTTP request, reduced to what the API routes on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    pub method: String,
+    /// The decoded path segments, e.g. `["projects", "<id>"]`.
+    pub segments: Vec<String>,
+    /// The decoded query parameters, in order.
+    pub query: Vec<(String, String)>,
+    /// The headers, with lowercase names.
+    pub headers: Vec<(String, String)>,
+}
+
+impl Request {
+    /// Parses a request line's method and target, such as `GET /projects?tag=rust`.
+    pub fn new(method: &str, target: &str) -> Self {
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        Request {
+            method: method.to_ascii_uppercase(),
+            segments: path
+                .split('/')
+                .filter(|segment| !segment.is_empty())
+                .map(percent_decode)
+                .collect(),
+            query: query
+                .split('&')
+                .filter(|pair| !pair.is_empty())
+                .map(|pair| {
+                    let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                    (percent_decode(key), percent_decode(value))
+                })
+                .collect(),
+            headers: Vec::new(),
+        }
+    }
+
+    /// Returns the value of a header, looked up case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns every value of a query parameter.
+    fn query_values<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a str> {
+        self.query.iter().filter(move |(k, _)| k == key).map(|(_, value)| value.as_str())
+    }
+}
+
+/// A JSON response and its HTTP status code.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub body: Value,
+}
+
+impl Response {
+    fn ok(body: Value) -> Self {
+        Response { status: 200, body }
+    }
+
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        Response {
+            status,
+            body: json!({ "error": message.into() }),
+        }
+    }
+}
+
+/// Answers API requests from a registry.
+pub struct Api<'a> {
+    registry: Registry,
+    config: &'a Config,
+    enrichment: &'a Enrichment,
+    token: String,
+    is_generated_token: bool,
+}
+
+impl<'a> Api<'a> {
+    /// Creates the API. Rescanned projects are saved, and so registered, as `scan --yes`
+    /// would save them.
+    ///
+    /// `POST` requests must send the token from `[server] token_env`; without one, a
+    /// random token is generated, and [`Api::generated_token`] returns it.
+    pub fn new(registry: Registry, config: &'a Config, enrichment: &'a Enrichment) -> Self {
+        let configured = config.server.token_env.as_ref().and_then(|name| {
+            env::var(name)
+                .ok()
+                .filter(|token| !token.is_empty())
+                .or_else(|| {
+                    warn!("{} is not set; generating a token instead", name);
+                    None
+                })
+        });
+        Api {
+            registry,
+            config,
+            enrichment,
+            is_generated_token: configured.is_none(),
+            token: configured.unwrap_or_else(|| Uuid::new_v4().simple().to_string()),
+        }
+    }
+
+    /// Returns the token `POST` requests must send, if it was generated rather than
+    /// configured, so it can be shown to the user.
+    pub fn generated_token(&self) -> Option<&str> {
+        self.is_generated_token.then_some(self.token.as_str())
+    }
+
+    /// Returns `true` if browser pages from `origin` may call the API.
+    pub fn allows_origin(&self, origin: &str) -> bool {
+        self.config.server.allowed_origins.iter().any(|allowed| allowed == origin)
+    }
+
+    /// Routes a request to its handler, once [`Api::authorize`] lets it through.
+    pub fn handle(&self, request: &Request) -> Response {
+        let segments: Vec<&str> = request.segments.iter().map(String::as_str).collect();
+        if let Err(response) = self.authorize(request) {
+            return response;
+        }
+        let result = match (request.method.as_str(), segments.as_slice()) {
+            ("OPTIONS", _) => Ok(Response::ok(Value::Null)),
+            ("GET", ["projects"]) => self.list(request),
+            ("GET", ["projects", id]) => self.find(id).map(|entry| Response::ok(json!(entry))),
+            ("POST", ["projects", id, "rescan"]) => {
+                self.find(id).and_then(|entry| self.rescan(&entry.path)).map(|entry| Response::ok(json!(entry)))
+            }
+            ("POST", ["rescan"]) => self.rescan_all(),
+            (_, ["projects"] | ["projects", _] | ["projects", _, "rescan"] | ["rescan"]) => {
+                Err(Response::error(405, format!("{} is not allowed here", request.method)))
+            }
+            _ => Err(Response::error(404, "no such route")),
+        };
+        result.unwrap_or_else(|error| error)
+    }
+
+    /// Refuses requests without a `Host` header or for another host, from browser
+    /// origins that are not allowed, and `POST` requests without the token.
+    fn authorize(&self, request: &Request) -> Result<(), Response> {
+        let Some(host) = request.header("host") else {
+            return Err(Response::error(400, "a Host header is required"));
+        };
+        if !is_loopback_host(host) && !self.config.server.allowed_hosts.iter().any(|allowed| host_name(host) == allowed) {
+            return Err(Response::error(403, format!("host '{}' is not allowed", host)));
+        }
+        if let Some(origin) = request.header("origin") {
+            if !self.allows_origin(origin) {
+                return Err(Response::error(403, format!("origin '{}' is not allowed", origin)));
+            }
+        }
+        if request.method == "POST" {
+            let sent = request.header("authorization").and_then(|value| value.strip_prefix("Bearer "));
+            if sent != Some(self.token.as_str()) {
+                return Err(Response::error(401, "a valid bearer token is required"));
+            }
+        }
+        Ok(())
+    }
+
+    fn entries(&self) -> Result<Vec<RegistryEntry>, Response> {
+        self.registry
+            .entries()
+            .map_err(|e| Response::error(500, format!("could not read the registry: {}", e)))
+    }
+
+    fn list(&self, request: &Request) -> Result<Response, Response> {
+        let status = match request.query_values("status").next() {
+            Some(status) => Some(status.parse::<ProjectStatus>().map_err(|e| Response::error(400, e))?),
+            None => None,
+        };
+        let patterns: Vec<&str> = request.query_values("tag").collect();
+
+        let entries: Vec<RegistryEntry> = self
+            .entries()?
+            .into_iter()
+            .filter(|entry| status.is_none() || entry.info.status == status)
+            .filter(|entry| {
+                patterns
+                    .iter()
+                    .all(|pattern| entry.info.tags.iter().any(|tag| tags::matches(tag, pattern)))
+            })
+            .collect();
+        Ok(Response::ok(json!(entries)))
+    }
+
+    fn find(&self, id: &str) -> Result<RegistryEntry, Response> {
+        self.entries()?
+            .into_iter()
+            .find(|entry| !entry.info.id.is_empty() && entry.info.id == id)
+            .ok_or_else(|| Response::error(404, format!("no project with id '{}'", id)))
+    }
+
+    fn rescan(&self, path: &Path) -> Result<RegistryEntry, Response> {
+        let options = ScanOptions {
+            save: true,
+            ..ScanOptions::default()
+        };
+        let project = scan::scan(path, &options, self.config, self.enrichment)
+            .projects
+            .into_iter()
+            .next()
+            .ok_or_else(|| Response::error(404, format!("{} is no longer a project", path.display())))?;
+
+        match (project.info, project.error) {
+            (Some(info), None) => Ok(RegistryEntry { path: project.path, info }),
+            (_, error) => Err(Response::error(500, error.unwrap_or_else(|| "the scan failed".to_string()))),
+        }
+    }
+
+    fn rescan_all(&self) -> Result<Response, Response> {
+        let mut rescanned = 0;
+        let mut errors = Vec::new();
+        for entry in self.entries()? {
+            match self.rescan(&entry.path) {
+                Ok(_) => rescanned += 1,
+                Err(response) => errors.push(json!({ "path": entry.path, "error": response.body["error"] })),
+            }
+        }
+        Ok(Response::ok(json!({ "rescanned": rescanned, "errors": errors })))
+    }
+}
+
+/// Serves the API on a listener, such as one bound to [`DEFAULT_ADDRESS`], until the
+/// process is stopped. Requests are answered one at a time, and a client that does
+/// not send its request within [`CLIENT_TIMEOUT`] is dropped.
+pub fn serve(listener: TcpListener, api: &Api) -> io::Result<()> {
+    info!("Listening on http://{}", listener.local_addr()?);
+
+    for stream in listener.incoming() {
+        let result = stream.and_then(|stream| handle_connection(stream, api, CLIENT_TIMEOUT));
+        if let Err(e) = result {
+            warn!("Could not answer a request: {}", e);
+        }
+    }
+    Ok(())
+}
+
+fn handle_connection(stream: TcpStream, api: &Api, timeout: Duration) -> io::Result<()> {
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut head = (&mut reader).take(MAX_HEADER_BYTES);
+    let too_large = Response::error(431, "the request headers are too large");
+
+    let mut request_line = String::new();
+    head.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target), true) = (parts.next(), parts.next(), request_line.ends_with('\n')) else {
+        let response = if head.limit() == 0 { too_large } else { Response::error(400, "malformed request line") };
+        return write_response(stream, &response, None);
+    };
+    let mut request = Request::new(method, target);
+
+    // Read the headers, and skip any body, so the client is not cut off mid-send.
+    loop {
+        let mut header = String::new();
+        head.read_line(&mut header)?;
+        if !header.ends_with('\n') && head.limit() == 0 {
+            return write_response(stream, &too_large, None);
+        }
+        if header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            request.headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+        }
+    }
+    let content_length: usize = request.header("content-length").and_then(|value| value.parse().ok()).unwrap_or(0);
+    io::copy(&mut reader.take(content_length.min(MAX_BODY_BYTES) as u64), &mut io::sink())?;
+
+    let response = api.handle(&request);
+    info!("{} {} -> {}", method, target, response.status);
+    let origin = request.header("origin").filter(|origin| api.allows_origin(origin));
+    write_response(stream, &response, origin)
+}
+
+/// Returns the host name of a `Host` header, without its port.
+fn host_name(host: &str) -> &str {
+    match host.strip_prefix('[') {
+        Some(rest) => rest.split(']').next().unwrap_or(rest),
+        None => host.rsplit_once(':').map_or(host, |(name, _)| name),
+    }
+}
+
+/// Returns `true` if a `Host` header names this machine's loopback interface.
+fn is_loopback_host(host: &str) -> bool {
+    let name = host_name(host);
+    name.eq_ignore_ascii_case("localhost") || name.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+fn write_response(mut stream: TcpStream, response: &Response, origin: Option<&str>) -> io::Result<()> {
+    let body = serde_json::to_string_pretty(&response.body).map_err(io::Error::other)?;
+    let reason = match response.status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        431 => "Request Header Fields Too Large",
+        _ => "Internal Server Error",
+    };
+    // Browser dashboards served from an allowed origin may call the API too.
+    let cors = match origin {
+        Some(origin) => format!(
+            "Access-Control-Allow-Origin: {}\r\nVary: Origin\r\nAccess-Control-Allow-Methods: GET, POST, OPTIONS\r\n\
+             Access-Control-Allow-Headers: Authorization\r\n",
+            origin
+        ),
+        None => String::new(),
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n{}Connection: close\r\n\r\n{}",
+        response.status,
+        reason,
+        body.len(),
+        cors,
+        body
+    )?;
+    stream.flush()
+}
+
+/// Decodes `%XX` escapes and `+` (a space in query strings).
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+                match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                    Some(byte) => {
+                        decoded.push(byte);
+                        i += 2;
+                    }
+                    None => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enrichment::EnrichmentConfig;
+    use crate::project_info::ProjectInfo;
+    use std::time::Instant;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_request_parsing() {
+        let request = Request::new("get", "/projects/?tag=lang%3Arust&tag=software+development");
+        assert_eq!(request.method, "GET");
+        assert_eq!(request.segments, vec!["projects"]);
+        assert_eq!(
+            request.query,
+            vec![
+                ("tag".to_string(), "lang:rust".to_string()),
+                ("tag".to_string(), "software development".to_string()),
+            ]
+        );
+        assert_eq!(percent_decode("100%"), "100%");
+    }
+
+    #[test]
+    fn test_api_routes() {
+        let dir = tempdir().unwrap();
+        let registry = Registry::open(&dir.path().join("registry")).unwrap();
+        for (name, id, tags, status) in [
+            ("app", "app-id", vec!["lang:rust"], Some(ProjectStatus::Active)),
+            ("song", "song-id", vec!["daw:reaper"], None),
+        ] {
+            let project = dir.path().join(name);
+            std::fs::create_dir_all(&project).unwrap();
+            let info = ProjectInfo {
+                name: name.to_string(),
+                id: id.to_string(),
+                tags: tags.into_iter().map(String::from).collect(),
+                status,
+                ..Default::default()
+            };
+            registry.register(&project, &info).unwrap();
+        }
+
+        let config = Config::default();
+        let enrichment = Enrichment::new(EnrichmentConfig {
+            offline: true,
+            cache_dir: Some(dir.path().join("cache")),
+            ..EnrichmentConfig::default()
+        });
+        let api = Api::new(registry, &config, &enrichment);
+        let get = |target: &str| {
+            let mut request = Request::new("GET", target);
+            request.headers.push(("host".to_string(), "localhost:7878".to_string()));
+            api.handle(&request)
+        };
+
+        let all = get("/projects");
+        assert_eq!(all.status, 200);
+        assert_eq!(all.body.as_array().unwrap().len(), 2);
+
+        let rust = get("/projects?tag=rust");
+        assert_eq!(rust.body[0]["name"], "app");
+        assert_eq!(rust.body.as_array().unwrap().len(), 1);
+        assert_eq!(get("/projects?status=active").body.as_array().unwrap().len(), 1);
+        assert_eq!(get("/projects?status=done").status, 400);
+
+        let song = get("/projects/song-id");
+        assert_eq!(song.body["tags"][0], "daw:reaper");
+        assert!(song.body["path"].as_str().unwrap().ends_with("song"));
+        assert_eq!(get("/projects/missing").status, 404);
+        assert_eq!(get("/nothing").status, 404);
+        let mut delete = Request::new("DELETE", "/projects/song-id");
+        delete.headers.push(("host".to_string(), "localhost".to_string()));
+        assert_eq!(api.handle(&delete).status, 405);
+    }
+
+    #[test]
+    fn test_requests_are_authorized() {
+        let dir = tempdir().unwrap();
+        let registry = Registry::open(&dir.path().join("registry")).unwrap();
+        let mut config = Config::default();
+        config.server.allowed_origins = vec!["http://localhost:3000".to_string()];
+        config.server.allowed_hosts = vec!["studio.local".to_string()];
+        config.server.token_env = Some("TAGGER_TEST_SERVER_TOKEN".to_string());
+        std::env::set_var("TAGGER_TEST_SERVER_TOKEN", "secret");
+        let enrichment = Enrichment::new(EnrichmentConfig {
+            offline: true,
+            cache_dir: Some(dir.path().join("cache")),
+            ..EnrichmentConfig::default()
+        });
+        let api = Api::new(registry, &config, &enrichment);
+        assert_eq!(api.generated_token(), None);
+        let send = |method: &str, target: &str, headers: &[(&str, &str)]| {
+            let mut request = Request::new(method, target);
+            request.headers = headers.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect();
+            if request.header("host").is_none() {
+                request.headers.push(("host".to_string(), "localhost:7878".to_string()));
+            }
+            api.handle(&request).status
+        };
+
+        for host in ["localhost:7878", "127.0.0.1:7878", "[::1]:7878", "studio.local:7878"] {
+            assert_eq!(send("GET", "/projects", &[("host", host)]), 200);
+        }
+        // A rebound domain still sends its own name.
+        assert_eq!(send("GET", "/projects", &[("host", "attacker.example:7878")]), 403);
+        assert_eq!(api.handle(&Request::new("GET", "/projects")).status, 400);
+
+        assert_eq!(send("GET", "/projects", &[("origin", "http://localhost:3000")]), 200);
+        assert_eq!(send("OPTIONS", "/rescan", &[("origin", "http://localhost:3000")]), 200);
+        assert_eq!(send("GET", "/projects", &[("origin", "https://example.com")]), 403);
+
+        assert_eq!(send("POST", "/rescan", &[]), 401);
+        assert_eq!(send("POST", "/rescan", &[("authorization", "Bearer wrong")]), 401);
+        assert_eq!(send("POST", "/rescan", &[("authorization", "Bearer secret")]), 200);
+    }
+
+    #[test]
+    fn test_post_requests_need_a_generated_token() {
+        let dir = tempdir().unwrap();
+        let registry = Registry::open(&dir.path().join("registry")).unwrap();
+        let config = Config::default();
+        let enrichment = Enrichment::new(EnrichmentConfig {
+            offline: true,
+            cache_dir: Some(dir.path().join("cache")),
+            ..EnrichmentConfig::default()
+        });
+        let api = Api::new(registry, &config, &enrichment);
+        let token = api.generated_token().unwrap().to_string();
+        let rescan = |authorization: Option<String>| {
+            let mut request = Request::new("POST", "/rescan");
+            request.headers.push(("host".to_string(), "localhost".to_string()));
+            request.headers.extend(authorization.map(|value| ("authorization".to_string(), value)));
+            api.handle(&request).status
+        };
+
+        assert_eq!(rescan(None), 401);
+        assert_eq!(rescan(Some(format!("Bearer {}", token))), 200);
+    }
+
+    #[test]
+    fn test_connections_are_bounded() {
+        let dir = tempdir().unwrap();
+        let registry = Registry::open(&dir.path().join("registry")).unwrap();
+        let config = Config::default();
+        let enrichment = Enrichment::new(EnrichmentConfig {
+            offline: true,
+            cache_dir: Some(dir.path().join("cache")),
+            ..EnrichmentConfig::default()
+        });
+        let api = Api::new(registry, &config, &enrichment);
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+
+        // A client that connects and sends nothing is dropped.
+        let _idle = TcpStream::connect(address).unwrap();
+        let (stream, _) = listener.accept().unwrap();
+        let started = Instant::now();
+        assert!(handle_connection(stream, &api, Duration::from_millis(200)).is_err());
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        // Endless headers are refused once they pass the limit.
+        let mut client = TcpStream::connect(address).unwrap();
+        let mut request = b"GET /projects HTTP/1.1\r\n".to_vec();
+        request.resize(MAX_HEADER_BYTES as usize, b'x');
+        client.write_all(&request).unwrap();
+        client.shutdown(std::net::Shutdown::Write).unwrap();
+        let (stream, _) = listener.accept().unwrap();
+        handle_connection(stream, &api, Duration::from_secs(5)).unwrap();
+        let mut response = String::new();
+        client.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 431"), "{}", response);
+    }
+}
@@ -0,0 +1,244 @@
+use serde::Deserialize;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use log::{info, warn};
+
+use crate::bundle::{BundleStore, TEMPLATES_DIR};
+use crate::config::dirs;
+use crate::project_info::ProjectInfo;
+
+/// The optional manifest at the root of a template directory. It is not copied into
+/// new projects.
+pub const TEMPLATE_MANIFEST: &str = "template.toml";
+
+/// What a template pre-fills in a new project, read from its [`TEMPLATE_MANIFEST`]:
+///
+/// ```toml
+/// type = "music"
+/// tags = ["session"]
+/// notes = ["Bounce stems before sending to mixing"]
+/// directories = ["Stems", "Bounces", "Samples/Recorded"]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct TemplateManifest {
+    /// The project type, pinned in the project's `.tagger.toml`.
+    #[serde(rename = "type")]
+    pub project_type: Option<String>,
+    /// Tags added to the generated ones.
+    pub tags: Vec<String>,
+    /// The project's first notes.
+    pub notes: Vec<String>,
+    /// Empty directories to create, relative to the project. Files and folders in the
+    /// template directory are copied as well; this lists the ones git cannot keep.
+    pub directories: Vec<String>,
+}
+
+/// A scaffolding template for new projects: a directory whose contents are copied
+/// into the project, with an optional [`TEMPLATE_MANIFEST`].
+#[derive(Debug, Clone)]
+pub struct Template {
+    /// The template's name, which is its directory name.
+    pub name: String,
+    /// The template directory.
+    pub path: PathBuf,
+    /// The template's manifest, or the default one when it has none.
+    pub manifest: TemplateManifest,
+}
+
+impl Template {
+    /// Loads the template in the given directory.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let manifest_path = path.join(TEMPLATE_MANIFEST);
+        let manifest: TemplateManifest = match fs::read_to_string(&manifest_path) {
+            Ok(content) => toml::from_str(&content).map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("invalid template manifest {}: {}", manifest_path.display(), e),
+                )
+            })?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => TemplateManifest::default(),
+            Err(e) => return Err(e),
+        };
+
+        if let Some(directory) = manifest.directories.iter().find(|directory| !is_relative_inside(directory)) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("template directory '{}' must be a relative path inside the project", directory),
+            ));
+        }
+
+        Ok(Template {
+            name: path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default(),
+            path: path.to_path_buf(),
+            manifest,
+        })
+    }
+
+    /// Copies the template's files and creates its directories in the project
+    /// directory. Files that already exist there are left alone.
+    pub fn scaffold(&self, directory: &Path) -> io::Result<()> {
+        fs::create_dir_all(directory)?;
+        copy_new_files(&self.path, directory, true)?;
+        for subdirectory in &self.manifest.directories {
+            fs::create_dir_all(directory.join(subdirectory))?;
+        }
+        info!("Scaffolded {} from template '{}'", directory.display(), self.name);
+        Ok(())
+    }
+
+    /// Adds the template's tags and notes to the project's information.
+    pub fn apply(&self, info: &mut ProjectInfo) {
+        for tag in &self.manifest.tags {
+            let tag = tag.trim().to_lowercase();
+            if !tag.is_empty() && !info.tags.contains(&tag) {
+                info.tags.push(tag);
+            }
+        }
+        info.notes.extend(self.manifest.notes.iter().cloned());
+    }
+}
+
+/// Returns the directory of the user's own templates, `templates` in the
+/// configuration directory.
+pub fn default_location() -> io::Result<PathBuf> {
+    Ok(dirs::config_dir()?.join("templates"))
+}
+
+/// Returns every template directory, by name: the user's own templates first, then
+/// those of installed bundles. An earlier template hides a later one of the same name.
+fn template_dirs() -> Vec<(String, PathBuf)> {
+    let mut dirs = Vec::new();
+    if let Ok(entries) = default_location().and_then(fs::read_dir) {
+        let mut own: Vec<(String, PathBuf)> = entries
+            .flatten()
+            .filter(|entry| entry.path().is_dir())
+            .map(|entry| (entry.file_name().to_string_lossy().to_string(), entry.path()))
+            .collect();
+        own.sort();
+        dirs.extend(own);
+    }
+
+    if BundleStore::default_location().is_ok_and(|path| path.exists()) {
+        match BundleStore::open_default().and_then(|store| store.bundles()) {
+            Ok(bundles) => {
+                for bundle in bundles {
+                    for name in bundle.templates() {
+                        let path = bundle.path.join(TEMPLATES_DIR).join(&name);
+                        dirs.push((name, path));
+                    }
+                }
+            }
+            Err(e) => warn!("Unable to read the templates of installed bundles: {}", e),
+        }
+    }
+    dirs
+}
+
+/// Returns the names of every available template, sorted and without duplicates.
+pub fn available() -> Vec<String> {
+    let mut names: Vec<String> = template_dirs().into_iter().map(|(name, _)| name).collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Finds and loads the template with the given name.
+///
+/// # Errors
+///
+/// Returns a `NotFound` error naming the available templates when there is no such
+/// template.
+pub fn find(name: &str) -> io::Result<Template> {
+    match template_dirs().into_iter().find(|(template, _)| template == name) {
+        Some((_, path)) => Template::load(&path),
+        None => {
+            let names = available();
+            let hint = if names.is_empty() {
+                format!("add one to {}", default_location()?.display())
+            } else {
+                format!("available: {}", names.join(", "))
+            };
+            Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no template named '{}' ({})", name, hint),
+            ))
+        }
+    }
+}
+
+/// Returns `true` if the path is relative and does not climb out of its base.
+fn is_relative_inside(path: &str) -> bool {
+    Path::new(path)
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+}
+
+/// Copies a directory tree, keeping files that already exist at the destination.
+fn copy_new_files(from: &Path, to: &Path, is_root: bool) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)?.flatten() {
+        if is_root && entry.file_name() == TEMPLATE_MANIFEST {
+            continue;
+        }
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_new_files(&entry.path(), &target, false)?;
+        } else if !target.exists() {
+            fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_scaffold_and_apply_template() {
+        let dir = tempdir().unwrap();
+        let template_dir = dir.path().join("music-session");
+        fs::create_dir_all(template_dir.join("Docs")).unwrap();
+        fs::write(template_dir.join("Docs/checklist.md"), "- [ ] Bounce stems\n").unwrap();
+        fs::write(template_dir.join("README.md"), "# Session\n").unwrap();
+        fs::write(
+            template_dir.join(TEMPLATE_MANIFEST),
+            "type = \"music\"\ntags = [\"Session\", \"audio\"]\nnotes = [\"Mix by Friday\"]\n\
+             directories = [\"Stems\", \"Bounces/Final\"]\n",
+        )
+        .unwrap();
+
+        let template = Template::load(&template_dir).unwrap();
+        assert_eq!(template.name, "music-session");
+        assert_eq!(template.manifest.project_type.as_deref(), Some("music"));
+
+        let project = dir.path().join("beat42");
+        fs::create_dir_all(&project).unwrap();
+        fs::write(project.join("README.md"), "# Beat 42\n").unwrap();
+        template.scaffold(&project).unwrap();
+
+        assert!(project.join("Stems").is_dir());
+        assert!(project.join("Bounces/Final").is_dir());
+        assert!(project.join("Docs/checklist.md").is_file());
+        assert!(!project.join(TEMPLATE_MANIFEST).exists());
+        assert_eq!(fs::read_to_string(project.join("README.md")).unwrap(), "# Beat 42\n");
+
+        let mut info = ProjectInfo {
+            tags: vec!["audio".to_string()],
+            ..Default::default()
+        };
+        template.apply(&mut info);
+        assert_eq!(info.tags, vec!["audio", "session"]);
+        assert_eq!(info.notes, vec!["Mix by Friday"]);
+    }
+
+    #[test]
+    fn test_template_directories_stay_inside_project() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join(TEMPLATE_MANIFEST), "directories = [\"../escape\"]\n").unwrap();
+        assert_eq!(Template::load(dir.path()).unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+}
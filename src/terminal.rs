@@ -0,0 +1,187 @@
+//! Colors and formatting for output meant for people reading a terminal.
+
+use std::env;
+use std::fmt;
+use std::io::{self, IsTerminal};
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use chrono::{DateTime, Local};
+
+use crate::messages::{tr, Message};
+
+/// When output is colored.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Color standard output when it is a terminal and `NO_COLOR` is not set.
+    #[default]
+    Auto,
+    /// Always color, even when `NO_COLOR` is set or output is redirected.
+    Always,
+    /// Never color.
+    Never,
+}
+
+impl ColorChoice {
+    /// Every choice.
+    pub const ALL: [ColorChoice; 3] = [ColorChoice::Auto, ColorChoice::Always, ColorChoice::Never];
+
+    /// Returns the choice's name on the command line.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ColorChoice::Auto => "auto",
+            ColorChoice::Always => "always",
+            ColorChoice::Never => "never",
+        }
+    }
+
+    /// Decides whether to color, following <https://no-color.org>: a non-empty
+    /// `NO_COLOR` turns off automatic coloring.
+    fn enabled(self) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                env::var_os("NO_COLOR").is_none_or(|value| value.is_empty()) && io::stdout().is_terminal()
+            }
+        }
+    }
+}
+
+impl fmt::Display for ColorChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ColorChoice {
+    type Err = String;
+
+    /// Parses a choice name, ignoring case.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim().to_lowercase();
+        Self::ALL.into_iter().find(|choice| choice.as_str() == value).ok_or_else(|| {
+            let names: Vec<&str> = Self::ALL.iter().map(|choice| choice.as_str()).collect();
+            format!("unknown color choice '{}' (expected one of: {})", value, names.join(", "))
+        })
+    }
+}
+
+static COLOR: OnceLock<bool> = OnceLock::new();
+
+/// Sets whether output is colored for the rest of the process.
+///
+/// Only the first call has an effect.
+pub fn set_color(choice: ColorChoice) {
+    let _ = COLOR.set(choice.enabled());
+}
+
+/// Returns `true` if output is colored, as decided by [`set_color`] or else
+/// automatically.
+pub fn color_enabled() -> bool {
+    *COLOR.get_or_init(|| ColorChoice::Auto.enabled())
+}
+
+/// How a piece of text is shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    /// A field name.
+    Label,
+    /// The project's name.
+    Title,
+    /// A tag.
+    Tag,
+    /// Secondary details, such as relative dates.
+    Dim,
+    /// A problem worth a look.
+    Warning,
+}
+
+impl Style {
+    fn ansi(self) -> &'static str {
+        match self {
+            Style::Label => "1;36",
+            Style::Title => "1",
+            Style::Tag => "30;46",
+            Style::Dim => "2",
+            Style::Warning => "33",
+        }
+    }
+}
+
+/// Returns the text in the given style, or unchanged when output is not colored.
+pub fn paint(text: &str, style: Style) -> String {
+    if color_enabled() {
+        format!("\x1b[{}m{}\x1b[0m", style.ansi(), text)
+    } else {
+        text.to_string()
+    }
+}
+
+/// Shows tags as chips: highlighted when colored, bracketed otherwise.
+pub fn tag_chips(tags: &[String]) -> String {
+    if tags.is_empty() {
+        return tr(Message::None, &[]);
+    }
+    let chips: Vec<String> = if color_enabled() {
+        tags.iter().map(|tag| paint(&format!(" {} ", tag), Style::Tag)).collect()
+    } else {
+        tags.iter().map(|tag| format!("[{}]", tag)).collect()
+    };
+    chips.join(" ")
+}
+
+/// Describes how long before `now` a date was, such as "3 days ago".
+pub fn relative_time(date: DateTime<Local>, now: DateTime<Local>) -> String {
+    const MINUTE: i64 = 60;
+    const HOUR: i64 = 60 * MINUTE;
+    const DAY: i64 = 24 * HOUR;
+    const MONTH: i64 = 30 * DAY;
+    const YEAR: i64 = 365 * DAY;
+
+    let seconds = (now - date).num_seconds();
+    if seconds < MINUTE {
+        return tr(Message::JustNow, &[]);
+    }
+    let (count, one, many) = match seconds {
+        s if s < HOUR => (s / MINUTE, Message::Minute, Message::Minutes),
+        s if s < DAY => (s / HOUR, Message::Hour, Message::Hours),
+        s if s < MONTH => (s / DAY, Message::Day, Message::Days),
+        s if s < YEAR => (s / MONTH, Message::Month, Message::Months),
+        s => (s / YEAR, Message::Year, Message::Years),
+    };
+    let amount = tr(if count == 1 { one } else { many }, &[&count]);
+    tr(Message::Ago, &[&amount])
+}
+
+/// Lays out `(label, value)` rows with the labels padded to the same width.
+pub fn aligned(rows: &[(String, String)]) -> String {
+    let width = rows.iter().map(|(label, _)| label.chars().count()).max().unwrap_or_default();
+    let mut out = String::new();
+    for (label, value) in rows {
+        let padding = " ".repeat(width - label.chars().count());
+        out.push_str(&format!("{}{} {}\n", paint(&format!("{}:", label), Style::Label), padding, value));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_relative_time() {
+        let now = Local::now();
+        assert_eq!(relative_time(now - Duration::seconds(5), now), "just now");
+        assert_eq!(relative_time(now - Duration::minutes(1), now), "1 minute ago");
+        assert_eq!(relative_time(now - Duration::days(3), now), "3 days ago");
+        assert_eq!(relative_time(now - Duration::days(800), now), "2 years ago");
+    }
+
+    #[test]
+    fn test_parse_color_choice() {
+        assert_eq!("Always".parse(), Ok(ColorChoice::Always));
+        assert!("sometimes".parse::<ColorChoice>().is_err());
+    }
+}
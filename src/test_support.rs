@@ -0,0 +1,146 @@
+//! Builders for realistic project fixtures.
+//!
+//! Available with the `test-support` feature. The fixtures back tagger's own
+//! integration tests and let detector authors exercise their detectors against the
+//! same kinds of directories tagger is tested with.
+
+use std::fs;
+use std::path::Path;
+
+use git2::{Repository, Signature};
+use tempfile::TempDir;
+
+/// A project directory in a temporary location, removed when dropped.
+pub struct Fixture {
+    dir: TempDir,
+}
+
+impl Fixture {
+    /// Creates an empty fixture directory.
+    pub fn new() -> Self {
+        Fixture {
+            dir: tempfile::tempdir().expect("failed to create a fixture directory"),
+        }
+    }
+
+    /// Returns the fixture's root directory.
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// Writes a file, creating its parent directories.
+    pub fn with_file(self, relative: &str, contents: impl AsRef<[u8]>) -> Self {
+        let path = self.path().join(relative);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).expect("failed to create fixture directories");
+        }
+        fs::write(&path, contents).expect("failed to write fixture file");
+        self
+    }
+
+    /// Creates a directory, including any missing parents.
+    pub fn with_dir(self, relative: &str) -> Self {
+        fs::create_dir_all(self.path().join(relative)).expect("failed to create fixture directory");
+        self
+    }
+
+    /// Turns the fixture into a git repository with every file committed and an
+    /// `origin` remote pointing at `remote_url`.
+    pub fn with_git(self, remote_url: &str) -> Self {
+        let repo = Repository::init(self.path()).expect("failed to init fixture repository");
+        repo.remote("origin", remote_url).expect("failed to add fixture remote");
+
+        let mut index = repo.index().expect("failed to open fixture index");
+        index
+            .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+            .expect("failed to stage fixture files");
+        index.write().expect("failed to write fixture index");
+        let tree = repo
+            .find_tree(index.write_tree().expect("failed to write fixture tree"))
+            .expect("failed to find fixture tree");
+
+        let signature = Signature::now("Fixture", "fixture@example.com").expect("invalid signature");
+        repo.commit(Some("HEAD"), &signature, &signature, "Initial commit", &tree, &[])
+            .expect("failed to commit fixture");
+        drop(tree);
+
+        self
+    }
+}
+
+impl Default for Fixture {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A Rust binary crate in a git repository with a GitHub remote.
+pub fn rust_repo() -> Fixture {
+    Fixture::new()
+        .with_file(
+            "Cargo.toml",
+            "[package]\nname = \"fixture-app\"\nversion = \"0.1.0\"\nedition = \"2021\"\nrust-version = \"1.70\"\nlicense = \"MIT\"\n",
+        )
+        .with_file("src/main.rs", "fn main() {\n    println!(\"Hello, world!\");\n}\n")
+        .with_file("README.md", "# fixture-app\n\nA tiny command-line app used in tests.\n")
+        .with_git("https://github.com/example/fixture-app.git")
+}
+
+/// A Reaper session with stems whose names carry the tempo and key.
+pub fn reaper_session() -> Fixture {
+    Fixture::new()
+        .with_file(
+            "Night Drive.rpp",
+            "<REAPER_PROJECT 0.1 \"7.0\" 1700000000\n  TEMPO 124 4 4\n>\n",
+        )
+        .with_file("stems/kick_124bpm_Fmin.wav", wav_header(48_000))
+        .with_file("stems/bass_124bpm_Fmin.wav", wav_header(48_000))
+        .with_file("renders/night_drive_master.mp3", "")
+}
+
+/// A Unity game project with a scene, scripts, and project settings.
+pub fn unity_project() -> Fixture {
+    Fixture::new()
+        .with_file("Assets/Scenes/Main.unity", "%YAML 1.1\n")
+        .with_file(
+            "Assets/Scripts/Player.cs",
+            "using UnityEngine;\n\npublic class Player : MonoBehaviour {}\n",
+        )
+        .with_file("Packages/manifest.json", "{ \"dependencies\": {} }\n")
+        .with_file("ProjectSettings/ProjectVersion.txt", "m_EditorVersion: 2022.3.10f1\n")
+        .with_dir("Library")
+}
+
+/// A Cargo workspace with two member crates and a web front end.
+pub fn monorepo() -> Fixture {
+    Fixture::new()
+        .with_file("Cargo.toml", "[workspace]\nmembers = [\"crates/*\"]\n")
+        .with_file("crates/core/Cargo.toml", "[package]\nname = \"core\"\nversion = \"0.1.0\"\n")
+        .with_file("crates/core/src/lib.rs", "")
+        .with_file("crates/cli/Cargo.toml", "[package]\nname = \"cli\"\nversion = \"0.1.0\"\n")
+        .with_file("crates/cli/src/main.rs", "fn main() {}\n")
+        .with_file("web/package.json", "{ \"name\": \"web\" }\n")
+        .with_file("web/index.js", "")
+}
+
+/// Returns a minimal 16-bit stereo PCM WAV header for the given sample rate.
+pub fn wav_header(sample_rate: u32) -> Vec<u8> {
+    let channels: u16 = 2;
+    let bits_per_sample: u16 = 16;
+    let block_align = channels * bits_per_sample / 8;
+
+    let mut header = Vec::with_capacity(44);
+    header.extend_from_slice(b"RIFF");
+    header.extend_from_slice(&36u32.to_le_bytes());
+    header.extend_from_slice(b"WAVEfmt ");
+    header.extend_from_slice(&16u32.to_le_bytes());
+    header.extend_from_slice(&1u16.to_le_bytes());
+    header.extend_from_slice(&channels.to_le_bytes());
+    header.extend_from_slice(&sample_rate.to_le_bytes());
+    header.extend_from_slice(&(sample_rate * u32::from(block_align)).to_le_bytes());
+    header.extend_from_slice(&block_align.to_le_bytes());
+    header.extend_from_slice(&bits_per_sample.to_le_bytes());
+    header.extend_from_slice(b"data");
+    header.extend_from_slice(&0u32.to_le_bytes());
+    header
+}
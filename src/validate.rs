@@ -0,0 +1,315 @@
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local};
+use serde::de::{self, Visitor};
+use serde::Deserialize;
+
+use crate::config::Config;
+use crate::project_info::ProjectInfo;
+use crate::registry::RegistryEntry;
+
+/// A problem with a saved `project_info.toml`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Problem {
+    /// The file cannot be read or does not match the schema.
+    Invalid(String),
+    /// A top-level key tagger does not know, e.g. a misspelled `aliass`.
+    UnknownField(String),
+    /// A required field is blank.
+    EmptyField(&'static str),
+    /// The project has no tags.
+    NoTags,
+    /// A `related` link to a project that cannot be found.
+    DanglingLink { name: String, id: String },
+    /// Another registered project has the same alias.
+    DuplicateAlias { alias: String, other: PathBuf },
+    /// A date later than the time of the check.
+    FutureDate { field: &'static str, date: DateTime<Local> },
+}
+
+impl Problem {
+    /// Returns `true` if `tagger validate --fix` can repair the problem: unknown
+    /// fields are dropped, missing tags regenerated, dangling links removed, and
+    /// future dates reset to the time of the fix.
+    pub fn is_fixable(&self) -> bool {
+        matches!(
+            self,
+            Problem::UnknownField(_) | Problem::NoTags | Problem::DanglingLink { .. } | Problem::FutureDate { .. }
+        )
+    }
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Problem::Invalid(e) => write!(f, "invalid file: {}", e),
+            Problem::UnknownField(key) => write!(f, "unknown field '{}'", key),
+            Problem::EmptyField(field) => write!(f, "'{}' is empty", field),
+            Problem::NoTags => f.write_str("no tags"),
+            Problem::DanglingLink { name, id } => write!(f, "related project '{}' ({}) not found", name, id),
+            Problem::DuplicateAlias { alias, other } => {
+                write!(f, "alias '{}' is also used by {}", alias, other.display())
+            }
+            Problem::FutureDate { field, date } => write!(f, "{} is in the future ({})", field, date.to_rfc3339()),
+        }
+    }
+}
+
+/// The result of validating one project.
+#[derive(Debug, Clone)]
+pub struct Validation {
+    /// The project's directory.
+    pub directory: PathBuf,
+    /// The project's information file.
+    pub file: PathBuf,
+    /// The information, if the file could be parsed.
+    pub info: Option<ProjectInfo>,
+    /// Every problem found, in the order of the checks.
+    pub problems: Vec<Problem>,
+}
+
+impl Validation {
+    /// Returns the number of problems `--fix` can repair.
+    pub fn fixable(&self) -> usize {
+        self.problems.iter().filter(|problem| problem.is_fixable()).count()
+    }
+}
+
+/// Checks saved project information against the schema and the rest of the registry.
+pub struct Validator<'a> {
+    entries: &'a [RegistryEntry],
+    config: &'a Config,
+    now: DateTime<Local>,
+}
+
+impl<'a> Validator<'a> {
+    /// Creates a validator that compares projects with the registry's entries.
+    pub fn new(entries: &'a [RegistryEntry], config: &'a Config) -> Self {
+        Validator {
+            entries,
+            config,
+            now: Local::now(),
+        }
+    }
+
+    /// Validates the information saved for a project directory.
+    pub fn validate(&self, directory: &Path) -> Validation {
+        let file = self.config.output.file_for(directory);
+        let mut validation = Validation {
+            directory: directory.to_path_buf(),
+            file: file.clone(),
+            info: None,
+            problems: Vec::new(),
+        };
+
+        let content = match fs::read_to_string(&file) {
+            Ok(content) => content,
+            Err(e) => {
+                validation.problems.push(Problem::Invalid(format!("{}: {}", file.display(), e)));
+                return validation;
+            }
+        };
+        let info = match toml::from_str::<ProjectInfo>(&content) {
+            Ok(info) => info,
+            Err(e) => {
+                validation.problems.push(Problem::Invalid(e.message().to_string()));
+                return validation;
+            }
+        };
+
+        // The file parsed, so it is a table.
+        if let Ok(toml::Value::Table(table)) = toml::from_str::<toml::Value>(&content) {
+            let known = struct_fields::<ProjectInfo>();
+            validation.problems.extend(
+                table
+                    .keys()
+                    .filter(|key| !known.contains(&key.as_str()))
+                    .map(|key| Problem::UnknownField(key.clone())),
+            );
+        }
+
+        if info.name.trim().is_empty() {
+            validation.problems.push(Problem::EmptyField("name"));
+        }
+        if info.project_type.trim().is_empty() {
+            validation.problems.push(Problem::EmptyField("project_type"));
+        }
+        if info.tags.is_empty() {
+            validation.problems.push(Problem::NoTags);
+        }
+        for link in &info.related {
+            if !self.link_resolves(&link.id, &link.path) {
+                validation.problems.push(Problem::DanglingLink {
+                    name: link.name.clone(),
+                    id: link.id.clone(),
+                });
+            }
+        }
+        if !info.alias.is_empty() {
+            let path = fs::canonicalize(directory).unwrap_or_else(|_| directory.to_path_buf());
+            validation.problems.extend(
+                self.entries
+                    .iter()
+                    .filter(|entry| entry.path != path && entry.info.alias.eq_ignore_ascii_case(&info.alias))
+                    .map(|entry| Problem::DuplicateAlias {
+                        alias: info.alias.clone(),
+                        other: entry.path.clone(),
+                    }),
+            );
+        }
+        let dates = [
+            ("date_created", Some(info.date_created)),
+            ("date_modified", Some(info.date_modified)),
+            ("date_added", info.date_added),
+            ("last_opened", info.last_opened),
+        ];
+        for (field, date) in dates {
+            if let Some(date) = date.filter(|date| *date > self.now) {
+                validation.problems.push(Problem::FutureDate { field, date });
+            }
+        }
+
+        validation.info = Some(info);
+        validation
+    }
+
+    /// Returns `true` if a linked project is registered, or still has its information
+    /// at the recorded path.
+    fn link_resolves(&self, id: &str, path: &Path) -> bool {
+        self.entries.iter().any(|entry| entry.info.id == id)
+            || ProjectInfo::load_from_file(&self.config.output.file_for(path)).is_ok_and(|info| info.id == id)
+    }
+
+    /// Repairs the fixable problems of a validation.
+    ///
+    /// # Returns
+    ///
+    /// The repaired information, to be saved by the caller, or `None` if there is
+    /// nothing to repair.
+    pub fn fix(&self, validation: &Validation) -> Result<Option<ProjectInfo>, Box<dyn std::error::Error>> {
+        let Some(mut info) = validation.info.clone() else {
+            return Ok(None);
+        };
+        if validation.fixable() == 0 {
+            return Ok(None);
+        }
+
+        for problem in &validation.problems {
+            match problem {
+                Problem::NoTags => {
+                    info.tags = ProjectInfo::generate_project_info(&validation.directory, self.config)?.tags;
+                }
+                Problem::DanglingLink { id, .. } => info.related.retain(|link| &link.id != id),
+                Problem::FutureDate { field, .. } => match *field {
+                    "date_created" => info.date_created = self.now,
+                    "date_modified" => info.date_modified = self.now,
+                    "date_added" => info.date_added = Some(self.now),
+                    _ => info.last_opened = Some(self.now),
+                },
+                // Unknown fields are dropped when the file is written again.
+                _ => {}
+            }
+        }
+        Ok(Some(info))
+    }
+}
+
+/// Returns the top-level fields a type reads, as listed by its derived
+/// `Deserialize` implementation.
+fn struct_fields<'de, T: Deserialize<'de>>() -> &'static [&'static str] {
+    let mut fields: &'static [&'static str] = &[];
+    let _ = T::deserialize(FieldCollector(&mut fields));
+    fields
+}
+
+/// A deserializer that only records the field names a struct asks for.
+struct FieldCollector<'a>(&'a mut &'static [&'static str]);
+
+impl<'de> de::Deserializer<'de> for FieldCollector<'_> {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom("not a struct"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        *self.0 = fields;
+        Err(de::Error::custom("fields collected"))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf option unit
+        unit_struct newtype_struct seq tuple tuple_struct map enum identifier ignored_any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project_info::related::RelatedProject;
+    use chrono::Duration;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_validate_reports_and_fixes_problems() {
+        let dir = tempdir().unwrap();
+        let other = tempdir().unwrap();
+        let info = ProjectInfo {
+            name: "beat42".to_string(),
+            alias: "b42".to_string(),
+            project_type: "music".to_string(),
+            date_created: Local::now() + Duration::days(30),
+            related: vec![RelatedProject {
+                id: "gone".to_string(),
+                name: "old-album".to_string(),
+                path: other.path().join("missing"),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let content = toml::to_string(&info).unwrap();
+        fs::write(dir.path().join("project_info.toml"), format!("aliass = \"typo\"\n{}", content)).unwrap();
+        fs::write(dir.path().join("song.rpp"), "<REAPER_PROJECT").unwrap();
+
+        let entries = vec![RegistryEntry {
+            path: other.path().to_path_buf(),
+            info: ProjectInfo {
+                alias: "B42".to_string(),
+                ..Default::default()
+            },
+        }];
+        let config = Config::default();
+        let validator = Validator::new(&entries, &config);
+        let validation = validator.validate(dir.path());
+
+        assert_eq!(validation.problems.len(), 5, "{:?}", validation.problems);
+        assert_eq!(validation.problems[0], Problem::UnknownField("aliass".to_string()));
+        assert_eq!(validation.problems[1], Problem::NoTags);
+        assert!(matches!(validation.problems[2], Problem::DanglingLink { .. }));
+        assert!(matches!(validation.problems[3], Problem::DuplicateAlias { .. }));
+        assert!(matches!(validation.problems[4], Problem::FutureDate { field: "date_created", .. }));
+        assert_eq!(validation.fixable(), 4);
+
+        let fixed = validator.fix(&validation).unwrap().unwrap();
+        assert!(!fixed.tags.is_empty());
+        assert!(fixed.related.is_empty());
+        assert!(fixed.date_created <= Local::now());
+    }
+
+    #[test]
+    fn test_invalid_file_is_reported() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("project_info.toml"), "name = 3\n").unwrap();
+        let config = Config::default();
+        let validation = Validator::new(&[], &config).validate(dir.path());
+        assert!(matches!(validation.problems[..], [Problem::Invalid(_)]));
+        assert!(validation.info.is_none());
+    }
+}
@@ -0,0 +1,187 @@
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+use log::{info, warn};
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+
+use crate::config::{Config, RefreshPolicy};
+use crate::enrichment::Enrichment;
+use crate::messages::{tr, Message};
+use crate::project_info::ignore::IgnoreRules;
+use crate::project_info::{ProjectInfo, SKIPPED_DIRECTORIES};
+use crate::scan::save_project;
+
+/// Watches a project and rewrites its `project_info.toml` whenever its files change.
+///
+/// Changes are debounced, and the refresh policy of the project's type decides how
+/// often a refresh may happen: `never` disables refreshing, an interval such as
+/// `weekly` skips changes until the interval has passed since the last refresh, and
+/// the other policies refresh after every change. The alias, notes, and creation date
+/// already recorded in `project_info.toml` are kept.
+///
+/// Runs until the watcher fails.
+pub fn watch(directory: &Path, config: &Config, enrichment: &Enrichment) -> Result<(), Box<dyn std::error::Error>> {
+    // Events carry absolute paths with symbolic links resolved.
+    let directory = &fs::canonicalize(directory)?;
+
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender)?;
+    watcher.watch(directory, RecursiveMode::Recursive)?;
+    let debounce = Duration::from_secs(config.watch.debounce_secs);
+    // The file watch mode rewrites; its own writes do not count as changes.
+    let output = config.output.file_for(directory);
+    let ignore_rules = IgnoreRules::load(directory);
+
+    let mut project = refresh(directory, config, enrichment)?;
+    let mut last_refresh = Instant::now();
+    println!("{}", tr(Message::WatchStarted, &[&directory.display()]));
+
+    loop {
+        // Wait for a change to the project's own files.
+        let event = receiver.recv()?;
+        match event {
+            Ok(event) if is_relevant(&event, directory, &output, &ignore_rules) => {}
+            Ok(_) => continue,
+            Err(e) => {
+                warn!("Watch error: {}", e);
+                continue;
+            }
+        }
+
+        // Let bursts of changes settle first.
+        loop {
+            match receiver.recv_timeout(debounce) {
+                Ok(_) => continue,
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return Ok(()),
+            }
+        }
+
+        let due = match config.refresh_policy(&project.project_type) {
+            RefreshPolicy::Never => false,
+            RefreshPolicy::Every(interval) => last_refresh.elapsed() >= interval,
+            RefreshPolicy::OnChange | RefreshPolicy::Always => true,
+        };
+        if !due {
+            info!("Not refreshing {} yet, as its refresh policy says.", directory.display());
+            continue;
+        }
+
+        match refresh(directory, config, enrichment) {
+            Ok(refreshed) => {
+                project = refreshed;
+                last_refresh = Instant::now();
+            }
+            Err(e) => warn!("Unable to refresh {}: {}", directory.display(), e),
+        }
+    }
+}
+
+/// Regenerates the project information and saves it, keeping what the user recorded.
+fn refresh(directory: &Path, config: &Config, enrichment: &Enrichment) -> Result<ProjectInfo, Box<dyn std::error::Error>> {
+    let mut project = ProjectInfo::generate_project_info(directory, config)?;
+    enrichment.enrich(directory, &mut project);
+
+    let output = config.output.file_for(directory);
+    match ProjectInfo::load_from_file(&output) {
+        Ok(recorded) => project.keep_user_fields(recorded),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => warn!("Unable to read the existing {}: {}", output.display(), e),
+    }
+
+    save_project(directory, &project, config)?;
+    Ok(project)
+}
+
+/// Returns `true` if the event changes the project's own files.
+///
+/// Reads, the output file (`project_info.toml` by default) itself, and anything in
+/// hidden or build directories (e.g. `.git`, `target`) or matched by the project's
+/// top-level ignore files are ignored.
+fn is_relevant(event: &Event, directory: &Path, output: &Path, ignore_rules: &IgnoreRules) -> bool {
+    if matches!(event.kind, EventKind::Access(_)) {
+        return false;
+    }
+
+    event.paths.iter().any(|path| {
+        if path == output {
+            return false;
+        }
+        let Ok(relative) = path.strip_prefix(directory) else {
+            return false;
+        };
+        if relative.as_os_str().is_empty() {
+            return false;
+        }
+
+        let mut components = relative.components().peekable();
+        let mut path_so_far = PathBuf::new();
+        while let Some(Component::Normal(name)) = components.next() {
+            path_so_far.push(name);
+            let name = name.to_string_lossy();
+            let is_dir = components.peek().is_some();
+            if is_dir && (name.starts_with('.') || SKIPPED_DIRECTORIES.contains(&name.as_ref())) {
+                return false;
+            }
+            if ignore_rules.is_ignored(&path_so_far, is_dir) {
+                return false;
+            }
+        }
+        true
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeZone};
+    use notify::event::{AccessKind, ModifyKind};
+    use std::path::PathBuf;
+
+    fn event(kind: EventKind, path: &str) -> Event {
+        Event::new(kind).add_path(PathBuf::from("/projects/song").join(path))
+    }
+
+    #[test]
+    fn test_is_relevant() {
+        let directory = Path::new("/projects/song");
+        let output = directory.join("project_info.toml");
+        let modify = EventKind::Modify(ModifyKind::Any);
+        let mut rules = IgnoreRules::default();
+        rules.add_rules("Bounces/\n*.tmp\n", Path::new(""));
+        let is_relevant = |event: &Event| is_relevant(event, directory, &output, &rules);
+
+        assert!(is_relevant(&event(modify, "stems/kick.wav")));
+        assert!(is_relevant(&event(modify, ".tagger.toml")));
+        assert!(!is_relevant(&event(modify, "project_info.toml")));
+        assert!(!is_relevant(&event(modify, ".git/index")));
+        assert!(!is_relevant(&event(modify, "target/debug/app")));
+        assert!(!is_relevant(&event(modify, "Bounces/mix.wav")));
+        assert!(!is_relevant(&event(modify, "stems/render.tmp")));
+        assert!(!is_relevant(&event(EventKind::Access(AccessKind::Any), "song.rpp")));
+    }
+
+    #[test]
+    fn test_keep_user_fields() {
+        let mut project = ProjectInfo {
+            name: "song".to_string(),
+            tags: vec!["audio".to_string()],
+            ..Default::default()
+        };
+        let recorded = ProjectInfo {
+            alias: "demo".to_string(),
+            notes: vec!["Needs a new bridge.".to_string()],
+            date_created: Local.with_ymd_and_hms(2023, 5, 1, 12, 0, 0).unwrap(),
+            ..Default::default()
+        };
+
+        project.keep_user_fields(recorded.clone());
+        assert_eq!(project.alias, "demo");
+        assert_eq!(project.notes, recorded.notes);
+        assert_eq!(project.date_created, recorded.date_created);
+        assert_eq!(project.tags, vec!["audio"]);
+    }
+}
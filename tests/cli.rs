@@ -0,0 +1,1156 @@
+use std::fs;
+use std::path::Path;
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use tagger::test_support::{self, Fixture};
+
+/// Returns a `tagger` command whose home directory (and so its configuration,
+/// registry, and caches) is the given directory, with network requests disabled
+/// and English output.
+fn tagger(home: &Path) -> Command {
+    let mut command = Command::cargo_bin("tagger").unwrap();
+    command
+        .arg("--offline")
+        .env("HOME", home)
+        .env_remove("TAGGER_CONFIG")
+        .env_remove("TAGGER_REGISTRY")
+        .env("XDG_CONFIG_HOME", home.join(".config"))
+        .env("XDG_DATA_HOME", home.join(".local/share"))
+        .env("XDG_CACHE_HOME", home.join(".cache"))
+        .env_remove("RUST_LOG")
+        .env("TAGGER_LANG", "en");
+    command
+}
+
+/// A Rust crate in an `app` directory, so its directory name is predictable.
+fn rust_app() -> Fixture {
+    Fixture::new()
+        .with_file(
+            "app/Cargo.toml",
+            "[package]\nname = \"golden-app\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+        )
+        .with_file("app/src/main.rs", "fn main() {}\n")
+}
+
+/// Reads a saved `project_info.toml` without its timestamps and identifier, which
+/// change per run.
+fn read_without_dates(path: &Path) -> String {
+    fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .filter(|line| {
+            !line.starts_with("date_created")
+                && !line.starts_with("date_modified")
+                && !line.starts_with("date =")
+                && !line.starts_with("id =")
+        })
+        .map(|line| format!("{}\n", line))
+        .collect()
+}
+
+#[test]
+fn test_interactive_save_matches_golden_file() {
+    let home = Fixture::new();
+    let fixture = rust_app();
+    let app = fixture.path().join("app");
+
+    tagger(home.path())
+        .arg(&app)
+        .write_stdin("demo\nfirst note\ny\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"Project Type: +programming").unwrap())
+        .stdout(predicate::str::contains("✅ Saved to"));
+
+    assert_eq!(
+        read_without_dates(&app.join("project_info.toml")),
+        include_str!("golden/interactive_rust.toml")
+    );
+
+    // Saving also registers the project.
+    let registry = home.path().join(".local/share/tagger/registry");
+    assert_eq!(fs::read_dir(registry).unwrap().count(), 1);
+}
+
+#[test]
+fn test_interactive_color_output() {
+    let home = Fixture::new();
+    let fixture = rust_app();
+    let app = fixture.path().join("app");
+
+    // Output to a pipe is plain, with tags in brackets and relative dates.
+    tagger(home.path())
+        .arg(&app)
+        .write_stdin("\n\nn\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[lang:rust]"))
+        .stdout(predicate::str::contains("(just now)"))
+        .stdout(predicate::str::contains("\x1b[").not());
+
+    tagger(home.path())
+        .args(["--color", "always"])
+        .env("NO_COLOR", "1")
+        .arg(&app)
+        .write_stdin("\n\nn\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\x1b[30;46m lang:rust \x1b[0m"));
+}
+
+#[test]
+fn test_interactive_decline_does_not_save() {
+    let home = Fixture::new();
+    let fixture = rust_app();
+    let app = fixture.path().join("app");
+
+    tagger(home.path())
+        .arg(&app)
+        .write_stdin("\n\nmaybe\nn\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Invalid input. Please enter 'y' or 'n'."))
+        .stdout(predicate::str::contains("❌ Project information not saved."));
+
+    assert!(!app.join("project_info.toml").exists());
+}
+
+#[test]
+fn test_interactive_spanish_output() {
+    let home = Fixture::new();
+    let fixture = rust_app();
+    let app = fixture.path().join("app");
+
+    tagger(home.path())
+        .env("TAGGER_LANG", "es_ES.UTF-8")
+        .arg(&app)
+        .write_stdin("\n\nsí\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"Tipo de proyecto: +programming").unwrap())
+        .stdout(predicate::str::contains("✅ Guardado en"));
+
+    assert!(app.join("project_info.toml").exists());
+}
+
+#[test]
+fn test_interactive_closed_stdin_does_not_save() {
+    let home = Fixture::new();
+    let fixture = rust_app();
+    let app = fixture.path().join("app");
+
+    tagger(home.path())
+        .arg(&app)
+        .write_stdin("")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("❌ Project information not saved."));
+
+    assert!(!app.join("project_info.toml").exists());
+}
+
+#[test]
+fn test_interactive_type_correction_is_recorded() {
+    let home = Fixture::new();
+    let fixture = Fixture::new().with_file("image.png", "").with_file("document.pdf", "");
+
+    tagger(home.path())
+        .arg(fixture.path())
+        .write_stdin("1\n\n\nn\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("The project type 'unknown' is a guess."))
+        .stdout(predicate::str::is_match(r"Project Type: +programming").unwrap());
+
+    let overrides = fs::read_to_string(fixture.path().join(".tagger.toml")).unwrap();
+    assert_eq!(overrides.trim(), "type = \"programming\"");
+}
+
+#[test]
+fn test_copied_project_prompts_to_relink() {
+    let home = Fixture::new();
+    let fixture = rust_app();
+    let app = fixture.path().join("app");
+    tagger(home.path()).arg(&app).write_stdin("demo\n\ny\n").assert().success();
+
+    let copy = fixture.path().join("app copy");
+    copy_dir(&app, &copy);
+
+    tagger(home.path())
+        .arg(&copy)
+        .write_stdin("r\n\n\ny\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("is a copy of the one in"))
+        .stdout(predicate::str::is_match(r"Alias: +demo").unwrap());
+
+    // Relinking moves the registry entry to the copy.
+    let registry = home.path().join(".local/share/tagger/registry");
+    assert_eq!(fs::read_dir(registry).unwrap().count(), 1);
+}
+
+#[test]
+fn test_verbosity_flags() {
+    let home = Fixture::new();
+    let fixture = rust_app();
+    let app = fixture.path().join("app");
+
+    // Detector details are only shown with -vv.
+    tagger(home.path())
+        .args(["scan", "-vv", "--ndjson"])
+        .arg(&app)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Detected Cargo.toml"));
+
+    tagger(home.path())
+        .args(["scan", "--ndjson"])
+        .arg(&app)
+        .assert()
+        .success()
+        .stderr("");
+
+    tagger(home.path()).args(["scan", "-q", "-v"]).arg(&app).assert().failure();
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+#[test]
+fn test_config_path_ignores_relative_directories() {
+    let home = Fixture::new();
+
+    // A relative variable would put files wherever tagger happens to run.
+    tagger(home.path())
+        .args(["config", "path", "cache"])
+        .env("XDG_CACHE_HOME", "cache")
+        .assert()
+        .success()
+        .stdout(format!("{}\n", home.path().join(".cache/tagger").display()));
+    tagger(home.path())
+        .args(["config", "path"])
+        .env("HOME", "home")
+        .env_remove("XDG_CONFIG_HOME")
+        .env_remove("XDG_DATA_HOME")
+        .env_remove("XDG_CACHE_HOME")
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn test_config_path_and_edit() {
+    let home = Fixture::new();
+
+    tagger(home.path())
+        .args(["config", "path"])
+        .assert()
+        .success()
+        .stdout(format!("{}\n", home.path().join(".config/tagger/config.toml").display()));
+    tagger(home.path())
+        .args(["config", "path", "cache"])
+        .assert()
+        .success()
+        .stdout(format!("{}\n", home.path().join(".cache/tagger").display()));
+
+    // The editor gets a starter file, and a broken result is reported.
+    tagger(home.path()).args(["config", "edit"]).env("VISUAL", "true").assert().success();
+    let config = home.path().join(".config/tagger/config.toml");
+    assert!(fs::read_to_string(&config).unwrap().contains("# [tags]"));
+
+    fs::write(&config, "[tags\n").unwrap();
+    tagger(home.path())
+        .args(["config", "edit"])
+        .env("VISUAL", "true")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid configuration"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_scan_with_detector_plugin() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let home = Fixture::new();
+    let plugins = home.path().join(".config/tagger/plugins");
+    fs::create_dir_all(&plugins).unwrap();
+    let plugin = plugins.join("fpga");
+    fs::write(
+        &plugin,
+        "#!/bin/sh\nls \"$1\"/*.xpr >/dev/null 2>&1 || exit 0\necho '{\"type\": \"fpga\", \"tags\": [\"vendor:xilinx\"], \"fields\": {\"part\": \"xc7a35t\"}}'\n",
+    )
+    .unwrap();
+    fs::set_permissions(&plugin, fs::Permissions::from_mode(0o755)).unwrap();
+    let fixture = Fixture::new().with_file("blinky.xpr", "<Project/>");
+
+    let output = tagger(home.path()).args(["scan", "--ndjson"]).arg(fixture.path()).output().unwrap();
+    assert!(output.status.success());
+    let scan: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(scan["info"]["project_type"], "fpga");
+    assert_eq!(scan["info"]["tags"], serde_json::json!(["format:xpr", "vendor:xilinx"]));
+    assert_eq!(scan["info"]["fields"]["fpga"]["part"], "xc7a35t");
+}
+
+#[test]
+fn test_suggest_tags_needs_an_endpoint() {
+    let home = Fixture::new();
+    let fixture = rust_app();
+    let app = fixture.path().join("app");
+
+    tagger(home.path())
+        .args(["scan", "--suggest-tags"])
+        .arg(&app)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("[suggest]"));
+
+    // Offline, with nothing cached, the scan goes ahead without suggestions.
+    let config = home.path().join(".config/tagger/config.toml");
+    fs::create_dir_all(config.parent().unwrap()).unwrap();
+    fs::write(&config, "[suggest]\nendpoint = \"http://127.0.0.1:9/v1\"\n").unwrap();
+    let output = tagger(home.path()).args(["scan", "--ndjson", "--suggest-tags"]).arg(&app).output().unwrap();
+    assert!(output.status.success());
+    let scan: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(scan["info"].get("suggested_tags").is_none());
+}
+
+#[test]
+fn test_missing_directory_fails() {
+    let home = Fixture::new();
+
+    tagger(home.path())
+        .arg(home.path().join("does-not-exist"))
+        .assert()
+        .failure()
+        .code(1);
+}
+
+#[test]
+fn test_missing_argument_is_a_usage_error() {
+    let home = Fixture::new();
+
+    tagger(home.path())
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains("Usage"));
+}
+
+#[test]
+fn test_scan_saves_projects_and_writes_report() {
+    let home = Fixture::new();
+    let root = Fixture::new();
+    let rust = test_support::rust_repo();
+    let reaper = test_support::reaper_session();
+    for (name, fixture) in [("code", &rust), ("beat", &reaper)] {
+        copy_dir(fixture.path(), &root.path().join(name));
+    }
+    let report = root.path().join("report.json");
+
+    tagger(home.path())
+        .args(["scan", "--recursive", "--yes", "--report"])
+        .arg(&report)
+        .arg(root.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[music]"))
+        .stdout(predicate::str::contains("[programming]"))
+        .stdout(predicate::str::contains("✅ Scan report written to"));
+
+    assert!(root.path().join("code/project_info.toml").exists());
+    assert!(root.path().join("beat/project_info.toml").exists());
+
+    let report: serde_json::Value = serde_json::from_str(&fs::read_to_string(report).unwrap()).unwrap();
+    let projects = report["projects"].as_array().unwrap();
+    assert_eq!(projects.len(), 2);
+    assert!(projects.iter().all(|project| project["saved"] == true));
+}
+
+#[test]
+fn test_recursive_scan_and_list_as_ndjson() {
+    let home = Fixture::new();
+    let root = Fixture::new();
+    copy_dir(test_support::rust_repo().path(), &root.path().join("code"));
+    copy_dir(test_support::reaper_session().path(), &root.path().join("beat"));
+
+    let output = tagger(home.path())
+        .args(["scan", "--recursive", "--yes", "--ndjson"])
+        .arg(root.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Saved to").not())
+        .get_output()
+        .stdout
+        .clone();
+    let mut types: Vec<String> = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap()["info"]["project_type"].to_string())
+        .collect();
+    types.sort();
+    assert_eq!(types, vec!["\"music\"", "\"programming\""]);
+
+    let output = tagger(home.path()).args(["list", "--ndjson"]).assert().success().get_output().stdout.clone();
+    let entries: Vec<serde_json::Value> = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(entries.len(), 2);
+    assert!(entries.iter().all(|entry| entry["path"].is_string() && entry["tags"].is_array()));
+}
+
+#[test]
+fn test_scan_output_writes_to_a_shared_folder() {
+    let home = Fixture::new();
+    let root = rust_app();
+    let metadata = root.path().join("metadata/");
+
+    tagger(home.path())
+        .args(["scan", "--yes", "--output"])
+        .arg(&metadata)
+        .arg(root.path().join("app"))
+        .assert()
+        .success();
+
+    let files: Vec<String> =
+        fs::read_dir(&metadata).unwrap().map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned()).collect();
+    assert_eq!(files.len(), 1);
+    assert!(files[0].starts_with("app-") && files[0].ends_with(".toml"));
+    assert!(!root.path().join("app/project_info.toml").exists());
+}
+
+#[test]
+fn test_scan_exclude_glob() {
+    let home = Fixture::new();
+    let root = rust_app();
+
+    tagger(home.path())
+        .args(["scan", "--exclude", "src/**", "--exclude", "Cargo.toml"])
+        .arg(root.path().join("app"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("lang:rust").not());
+}
+
+#[test]
+fn test_scan_archive_without_extracting() {
+    let home = Fixture::new();
+    let root = Fixture::new();
+    let archive = root.path().join("old-app.zip");
+    let mut zip = zip::ZipWriter::new(fs::File::create(&archive).unwrap());
+    for (name, contents) in [("app/Cargo.toml", "[package]\nname = \"old-app\"\n"), ("app/src/main.rs", "fn main() {}\n")] {
+        zip.start_file(name, zip::write::SimpleFileOptions::default()).unwrap();
+        std::io::Write::write_all(&mut zip, contents.as_bytes()).unwrap();
+    }
+    zip.finish().unwrap();
+
+    tagger(home.path())
+        .args(["scan", "--recursive", "--archives", "--yes"])
+        .arg(root.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("old-app.zip [programming]"));
+
+    let info = fs::read_to_string(root.path().join("old-app.zip.toml")).unwrap();
+    assert!(info.contains("name = \"old-app\""));
+    assert!(info.contains("directory_name = \"app\""));
+    assert!(!root.path().join("app").exists());
+}
+
+#[test]
+fn test_scan_from_a_subdirectory_tags_the_project_root() {
+    let home = Fixture::new();
+    let root = rust_app();
+    let src = root.path().join("app/src");
+
+    tagger(home.path())
+        .args(["scan", "--yes"])
+        .arg(&src)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Using the project root"));
+    assert!(root.path().join("app/project_info.toml").exists());
+    assert!(!src.join("project_info.toml").exists());
+
+    tagger(home.path()).args(["scan", "--yes", "--no-ascend"]).arg(&src).assert().success();
+    assert!(src.join("project_info.toml").exists());
+}
+
+#[test]
+fn test_scan_paths_from_stdin_as_ndjson() {
+    let home = Fixture::new();
+    let root = rust_app();
+    let app = root.path().join("app");
+    let missing = root.path().join("missing");
+
+    let output = tagger(home.path())
+        .args(["scan", "-", "--yes"])
+        .write_stdin(format!("{}\n\n{}\n", app.display(), missing.display()))
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let lines: Vec<serde_json::Value> = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["info"]["name"], "golden-app");
+    assert_eq!(lines[0]["saved"], true);
+    assert!(lines[1]["error"].is_string());
+    assert!(app.join("project_info.toml").exists());
+}
+
+#[test]
+fn test_rescan_keeps_id_and_slug() {
+    let home = Fixture::new();
+    let root = rust_app();
+    let app = root.path().join("app");
+    let read_id = || {
+        let info: toml::Value = toml::from_str(&fs::read_to_string(app.join("project_info.toml")).unwrap()).unwrap();
+        (info["id"].as_str().unwrap().to_string(), info["slug"].as_str().unwrap().to_string())
+    };
+
+    tagger(home.path()).args(["scan", "--yes", "--no-cache"]).arg(&app).assert().success();
+    let (id, slug) = read_id();
+    assert_eq!(slug, "golden-app");
+
+    fs::write(app.join("Cargo.toml"), "[package]\nname = \"renamed\"\nversion = \"0.2.0\"\n").unwrap();
+    tagger(home.path()).args(["scan", "--yes", "--no-cache"]).arg(&app).assert().success();
+    assert_eq!(read_id(), (id, slug));
+}
+
+#[test]
+fn test_set_status_and_list_by_status_and_tag() {
+    let home = Fixture::new();
+    let root = rust_app();
+    let app = root.path().join("app");
+
+    tagger(home.path()).args(["scan", "--yes"]).arg(&app).assert().success();
+    tagger(home.path()).args(["set", "status", "archived"]).arg(&app).assert().success();
+
+    // Rescanning keeps the status.
+    tagger(home.path()).args(["scan", "--yes", "--no-cache"]).arg(&app).assert().success();
+    assert!(fs::read_to_string(app.join("project_info.toml")).unwrap().contains("status = \"archived\""));
+
+    tagger(home.path())
+        .args(["list", "--status", "archived"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("golden-app [programming] (archived)"));
+    tagger(home.path())
+        .args(["list", "--status", "active"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No projects found."));
+    tagger(home.path())
+        .args(["list", "--tag", "lang:", "--tag", "cli"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("golden-app"));
+    tagger(home.path())
+        .args(["list", "--tag", "daw:"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No projects found."));
+    tagger(home.path()).args(["set", "status", "done"]).arg(&app).assert().failure();
+}
+
+#[test]
+fn test_validate_and_fix() {
+    let home = Fixture::new();
+    let root = rust_app();
+    let app = root.path().join("app");
+    let info_file = app.join("project_info.toml");
+
+    tagger(home.path()).args(["scan", "--yes"]).arg(&app).assert().success();
+    tagger(home.path())
+        .arg("validate")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No problems found in 1 projects"));
+
+    let content = fs::read_to_string(&info_file).unwrap();
+    fs::write(&info_file, format!("aliass = \"typo\"\n{}", content)).unwrap();
+    tagger(home.path())
+        .arg("validate")
+        .arg(&app)
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("unknown field 'aliass'"))
+        .stdout(predicate::str::contains("(1 fixable with --fix)"));
+
+    tagger(home.path()).args(["validate", "--fix"]).arg(&app).assert().success();
+    assert!(!fs::read_to_string(&info_file).unwrap().contains("aliass"));
+}
+
+#[test]
+fn test_dedupe_flags_copied_projects() {
+    let home = Fixture::new();
+    let root = rust_app();
+    let app = root.path().join("app");
+    let copy = root.path().join("app_final_v2");
+    copy_dir(&app, &copy);
+
+    tagger(home.path()).args(["scan", "--yes"]).arg(&app).assert().success();
+    tagger(home.path())
+        .arg("dedupe")
+        .arg(root.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No duplicate projects found"));
+
+    tagger(home.path()).args(["scan", "--yes"]).arg(&copy).assert().success();
+    tagger(home.path())
+        .arg("dedupe")
+        .arg(root.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("identical Cargo.toml"))
+        .stdout(predicate::str::contains("app_final_v2 [golden-app"));
+}
+
+#[test]
+fn test_similar_ranks_registered_projects() {
+    let home = Fixture::new();
+    let root = rust_app().with_file("notes/draft.md", "# Draft\n\nSome prose.\n");
+    let app = root.path().join("app");
+    let copy = root.path().join("app2");
+    copy_dir(&app, &copy);
+
+    tagger(home.path()).args(["scan", "--yes"]).arg(&copy).assert().success();
+    tagger(home.path()).args(["scan", "--yes"]).arg(root.path().join("notes")).assert().success();
+    tagger(home.path())
+        .arg("similar")
+        .arg(&app)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Projects similar to golden-app:"))
+        .stdout(predicate::str::contains("100%  golden-app [programming]"))
+        .stdout(predicate::str::contains("lang:rust"))
+        .stdout(predicate::str::contains("notes").not());
+}
+
+#[test]
+fn test_stats_summarizes_the_registry() {
+    let home = Fixture::new();
+    let root = rust_app();
+    let app = root.path().join("app");
+    let copy = root.path().join("app2");
+    copy_dir(&app, &copy);
+
+    tagger(home.path()).args(["scan", "--yes"]).arg(&app).assert().success();
+    tagger(home.path()).args(["scan", "--yes"]).arg(&copy).assert().success();
+    tagger(home.path())
+        .arg("stats")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Projects: 2"))
+        .stdout(predicate::str::is_match(r"lang:rust +2").unwrap())
+        .stdout(predicate::str::is_match(r"programming +2").unwrap());
+
+    let output = tagger(home.path()).args(["stats", "--json"]).output().unwrap();
+    assert!(output.status.success());
+    let stats: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(stats["projects"], 2);
+    assert_eq!(stats["types"][0]["project_type"], "programming");
+    assert_eq!(stats["orphaned_tags"], serde_json::json!([]));
+}
+
+#[test]
+fn test_timeline_of_registered_projects() {
+    let home = Fixture::new();
+    let root = rust_app();
+    let app = root.path().join("app");
+
+    tagger(home.path()).arg("timeline").assert().success().stdout("No projects found.\n");
+
+    tagger(home.path()).args(["scan", "--yes"]).arg(&app).assert().success();
+    tagger(home.path())
+        .args(["timeline", "--by-type", "--period", "year"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("programming:\ngolden-app  █████"));
+
+    let output = tagger(home.path()).args(["timeline", "--json"]).output().unwrap();
+    assert!(output.status.success());
+    let timeline: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(timeline["period"], "quarter");
+    assert_eq!(timeline["projects"][0]["name"], "golden-app");
+}
+
+#[test]
+fn test_health_lists_projects_needing_attention() {
+    let home = Fixture::new();
+    let root = rust_app();
+    let app = root.path().join("app");
+
+    tagger(home.path()).arg("health").assert().success().stdout(predicate::str::contains("looks healthy"));
+
+    tagger(home.path()).args(["scan", "--yes"]).arg(&app).assert().success();
+    tagger(home.path())
+        .arg("health")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 projects need attention"))
+        .stdout(predicate::str::contains("1. golden-app [programming]"))
+        .stdout(predicate::str::contains("- no backup recorded"))
+        .stdout(predicate::str::contains("- no license"));
+
+    fs::remove_dir_all(&app).unwrap();
+    let output = tagger(home.path()).args(["health", "--json"]).output().unwrap();
+    assert!(output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report[0]["issues"], serde_json::json!([{"issue": "missing"}]));
+}
+
+#[test]
+fn test_list_repair_finds_moved_projects() {
+    let home = Fixture::new();
+    let root = rust_app();
+    let app = root.path().join("app");
+    let drive = Fixture::new();
+
+    tagger(home.path()).args(["scan", "--yes"]).arg(&app).assert().success();
+    let moved = drive.path().join("code/app");
+    fs::create_dir(drive.path().join("code")).unwrap();
+    copy_dir(&app, &moved);
+    fs::remove_dir_all(&app).unwrap();
+    // Another directory named `app` does not match the saved fingerprint.
+    let decoy = drive.path().join("other/app");
+    fs::create_dir_all(&decoy).unwrap();
+    fs::write(decoy.join("notes.txt"), "unrelated").unwrap();
+
+    tagger(home.path())
+        .args(["list", "--repair"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("was not found"));
+    tagger(home.path())
+        .args(["list", "--repair", "--search"])
+        .arg(drive.path().join("other"))
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("was not found"));
+
+    let moved = fs::canonicalize(&moved).unwrap();
+    tagger(home.path())
+        .args(["list", "--repair", "--search"])
+        .arg(drive.path())
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("golden-app moved from"))
+        .stderr(predicate::str::contains("(matched by id)"))
+        .stdout(predicate::str::contains(format!("golden-app [programming] {}", moved.display())));
+
+    tagger(home.path()).args(["list", "--repair"]).assert().success().stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn test_list_refresh_rescans_changed_projects() {
+    let home = Fixture::new();
+    let root = rust_app();
+    let app = root.path().join("app");
+    tagger(home.path()).args(["scan", "--yes"]).arg(&app).assert().success();
+
+    tagger(home.path())
+        .args(["list", "--refresh"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Refreshed 1 projects, 0 rescanned"))
+        .stdout(predicate::str::contains("golden-app [programming]"));
+
+    fs::write(app.join("Dockerfile"), "FROM rust:1\n").unwrap();
+    tagger(home.path())
+        .args(["list", "--refresh"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Refreshed 1 projects, 1 rescanned"));
+    assert!(fs::read_to_string(app.join("project_info.toml")).unwrap().contains("tool:docker"));
+
+    // The list stays machine-readable.
+    let output = tagger(home.path()).args(["list", "--refresh", "--no-cache", "--ndjson"]).output().unwrap();
+    assert!(output.status.success());
+    let entry: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(entry["name"], "golden-app");
+}
+
+#[test]
+fn test_sync_rescans_configured_roots() {
+    let home = Fixture::new();
+    let code = rust_app();
+    let sessions = Fixture::new()
+        .with_file("jam/take 1.wav", "")
+        .with_file("jam/.tagger.toml", "[tags]\nadd = [\"live\"]\n");
+    let config = home.path().join(".config/tagger/config.toml");
+    fs::create_dir_all(config.parent().unwrap()).unwrap();
+
+    tagger(home.path()).arg("sync").assert().success().stdout(predicate::str::contains("No roots to sync"));
+
+    fs::write(
+        &config,
+        format!(
+            "[[roots]]\npath = {:?}\n\n[[roots]]\npath = {:?}\ntype = \"podcast\"\nschedule = \"weekly\"\n\n[[roots]]\npath = \"/nonexistent/drive\"\n",
+            code.path(),
+            sessions.path()
+        ),
+    )
+    .unwrap();
+    tagger(home.path())
+        .arg("sync")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!("✅ {}: 1 projects, 1 rescanned", code.path().display())))
+        .stdout(predicate::str::contains(format!("✅ {}: 1 projects, 1 rescanned", sessions.path().display())))
+        .stdout(predicate::str::contains("/nonexistent/drive is not available"));
+    let jam = fs::read_to_string(sessions.path().join("jam/project_info.toml")).unwrap();
+    assert!(jam.contains("project_type = \"podcast\""), "{}", jam);
+
+    tagger(home.path())
+        .arg("sync")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!("✅ {}: 1 projects, 0 rescanned", code.path().display())))
+        .stdout(predicate::str::contains(format!("⏭️  {}: not due", sessions.path().display())));
+    tagger(home.path())
+        .args(["list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("golden-app [programming]"))
+        .stdout(predicate::str::contains("jam [podcast]"));
+}
+
+#[test]
+fn test_import_seeds_and_keeps_metadata() {
+    let home = Fixture::new();
+    let root = rust_app();
+    let app = root.path().join("app");
+    tagger(home.path()).args(["scan", "--yes"]).arg(&app).assert().success();
+    let sources = Fixture::new()
+        .with_file(
+            "repo.json",
+            r#"{"name": "golden-app", "full_name": "me/golden-app", "description": "The golden app", "topics": ["demo"]}"#,
+        )
+        .with_file(
+            "tokei.json",
+            r#"{"Rust": {"code": 900}, "Python": {"code": 100}, "Total": {"code": 1000}}"#,
+        )
+        .with_file("inventory.csv", "name,status,notes\ngolden-app,paused,waiting on review\nunknown,,\n");
+
+    tagger(home.path())
+        .arg("import")
+        .arg(sources.path().join("repo.json"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("📥 golden-app: +demo, description"));
+    tagger(home.path())
+        .arg("import")
+        .arg(sources.path().join("tokei.json"))
+        .arg("--project")
+        .arg(&app)
+        .arg("--dry-run")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("📥 golden-app: +lang:python"))
+        .stdout(predicate::str::contains("Nothing was saved"));
+    tagger(home.path())
+        .arg("import")
+        .arg(sources.path().join("inventory.csv"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("📥 golden-app: status paused, 1 notes"))
+        .stdout(predicate::str::contains("unknown: no registered project matches"));
+
+    // A rescan keeps what was imported.
+    tagger(home.path()).args(["scan", "--yes"]).arg(&app).assert().success();
+    let info = fs::read_to_string(app.join("project_info.toml")).unwrap();
+    assert!(info.contains("description = \"The golden app\""), "{}", info);
+    assert!(info.contains("\"demo\""), "{}", info);
+    assert!(!info.contains("lang:python"), "{}", info);
+    assert!(info.contains("status = \"paused\""), "{}", info);
+    assert!(info.contains("waiting on review"), "{}", info);
+    tagger(home.path())
+        .arg("import")
+        .arg(sources.path().join("repo.json"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("golden-app: nothing new"));
+}
+
+#[test]
+fn test_sync_push_and_pull_through_git() {
+    let remote = Fixture::new();
+    let repository = remote.path().join("registry.git");
+    let init = std::process::Command::new("git").arg("init").arg("--quiet").arg("--bare").arg(&repository).status();
+    assert!(init.unwrap().success());
+    let studio = Fixture::new();
+    let laptop = Fixture::new();
+    for home in [&studio, &laptop] {
+        let config = home.path().join(".config/tagger/config.toml");
+        fs::create_dir_all(config.parent().unwrap()).unwrap();
+        fs::write(&config, format!("[remote]\nurl = {:?}\n", repository.display().to_string())).unwrap();
+    }
+    let root = rust_app();
+    tagger(studio.path()).args(["scan", "--yes"]).arg(root.path().join("app")).assert().success();
+
+    tagger(studio.path())
+        .args(["sync", "push", "--projects"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Pushed 2 files"));
+    tagger(studio.path())
+        .args(["sync", "push", "--projects"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Pushed 0 files").and(predicate::str::contains("(2 unchanged)")));
+
+    tagger(laptop.path())
+        .args(["sync", "pull"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Pulled 1 files"));
+    tagger(laptop.path())
+        .arg("list")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("golden-app [programming]"));
+}
+
+#[test]
+fn test_list_unmounted_projects() {
+    let home = Fixture::new();
+    let root = rust_app();
+    let app = root.path().join("app");
+    tagger(home.path()).args(["scan", "--yes"]).arg(&app).assert().success();
+
+    // A session registered from a USB drive that is not plugged in.
+    let registry = home.path().join(".local/share/tagger/registry");
+    let app_entry = fs::read_dir(&registry).unwrap().next().unwrap().unwrap().path();
+    let app_path = fs::canonicalize(&app).unwrap();
+    let session = fs::read_to_string(app_entry)
+        .unwrap()
+        .replace(&format!("{:?}", app_path.display().to_string()), "\"/nonexistent/Music Archive/session\"")
+        .replace("name = \"golden-app\"", "name = \"session\"")
+        .replace("project_type = \"programming\"", "project_type = \"music\"");
+    fs::write(
+        registry.join("usb-session.toml"),
+        session + "\n[volume]\nmount_point = \"/nonexistent/Music Archive\"\nuuid = \"64A1-2B7C\"\nlabel = \"Music Archive\"\n",
+    )
+    .unwrap();
+
+    tagger(home.path())
+        .arg("list")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("golden-app [programming]"))
+        .stdout(predicate::str::contains(
+            "session [music] /nonexistent/Music Archive/session (offline: Music Archive is not mounted)",
+        ));
+    tagger(home.path())
+        .args(["list", "--unmounted"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("golden-app").not())
+        .stdout(predicate::str::contains("session [music]"));
+
+    // Offline projects are neither missing nor repaired.
+    tagger(home.path()).arg("health").assert().success().stdout(predicate::str::contains("session").not());
+    tagger(home.path()).args(["list", "--repair"]).assert().success().stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn test_verify_detects_changed_files() {
+    let home = Fixture::new();
+    let root = rust_app();
+    let app = root.path().join("app");
+
+    tagger(home.path()).args(["scan", "--yes"]).arg(&app).assert().success();
+    tagger(home.path())
+        .arg("verify")
+        .arg(&app)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("golden-app is unchanged since"));
+
+    fs::write(app.join("src/lib.rs"), "pub fn added() {}\n").unwrap();
+    tagger(home.path())
+        .arg("verify")
+        .arg(&app)
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("2 files → 3"));
+}
+
+#[test]
+fn test_archive_records_checksum() {
+    let home = Fixture::new();
+    let root = rust_app();
+    let app = root.path().join("app");
+
+    tagger(home.path()).args(["scan", "--yes"]).arg(&app).assert().success();
+    tagger(home.path())
+        .args(["archive", "--format", "zip"])
+        .arg(&app)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("📦 Archived"));
+
+    assert!(root.path().join("app.zip").is_file());
+    let info = fs::read_to_string(app.join("project_info.toml")).unwrap();
+    assert!(info.contains("[[archives]]"), "{}", info);
+    assert!(info.contains("format = \"zip\""));
+
+    tagger(home.path())
+        .args(["archive", "--format", "zip"])
+        .arg(&app)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("already exists"));
+}
+
+#[test]
+fn test_link_projects_both_ways() {
+    let home = Fixture::new();
+    let root = rust_app().with_file("pack/kick.wav", "RIFF");
+    let (app, pack) = (root.path().join("app"), root.path().join("pack"));
+
+    tagger(home.path()).args(["scan", "--yes"]).arg(&app).assert().success();
+    tagger(home.path())
+        .arg("link")
+        .args([&app, &pack])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("tag the project first"));
+
+    tagger(home.path()).args(["scan", "--yes"]).arg(&pack).assert().success();
+    tagger(home.path())
+        .arg("link")
+        .args([&app, &pack])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("🔗 Linked golden-app and pack"));
+
+    let app_info = fs::read_to_string(app.join("project_info.toml")).unwrap();
+    let pack_info = fs::read_to_string(pack.join("project_info.toml")).unwrap();
+    assert!(app_info.contains("[[related]]") && app_info.contains("name = \"pack\""));
+    assert!(pack_info.contains("[[related]]") && pack_info.contains("name = \"golden-app\""));
+}
+
+/// Copies a directory tree, including hidden entries such as `.git`.
+fn copy_dir(from: &Path, to: &Path) {
+    fs::create_dir_all(to).unwrap();
+    for entry in fs::read_dir(from).unwrap().flatten() {
+        let target = to.join(entry.file_name());
+        if entry.file_type().unwrap().is_dir() {
+            copy_dir(&entry.path(), &target);
+        } else {
+            fs::copy(entry.path(), target).unwrap();
+        }
+    }
+}
+
+#[test]
+fn test_export_csv_to_stdout_and_tsv_to_file() {
+    let home = Fixture::new();
+    let root = rust_app();
+    let app = root.path().join("app");
+
+    tagger(home.path()).args(["scan", "--yes"]).arg(&app).assert().success();
+    tagger(home.path())
+        .args(["export", "--csv"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("name,alias,type,status,tags,"))
+        .stdout(predicate::str::contains("golden-app,,programming,,cli; lang:rust; software development,"));
+
+    let tsv = home.path().join("projects.tsv");
+    tagger(home.path())
+        .args(["export", "--tsv"])
+        .arg(&tsv)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Exported 1 projects"));
+    assert!(fs::read_to_string(&tsv).unwrap().contains("golden-app\t\tprogramming\t"));
+
+    tagger(home.path()).arg("export").assert().failure();
+}
+
+#[test]
+fn test_path_resolves_registered_project() {
+    let home = Fixture::new();
+    let root = rust_app();
+    let app = root.path().join("app");
+
+    tagger(home.path()).args(["scan", "--yes"]).arg(&app).assert().success();
+    tagger(home.path())
+        .args(["path", "GOLDEN-APP"])
+        .assert()
+        .success()
+        .stdout(format!("{}\n", fs::canonicalize(&app).unwrap().display()));
+    tagger(home.path())
+        .args(["cd", "missing"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No registered project is called 'missing'"));
+}
+
+#[test]
+fn test_init_from_template() {
+    let home = Fixture::new();
+    let template = home.path().join(".config/tagger/templates/music-session");
+    fs::create_dir_all(template.join("Bounces")).unwrap();
+    fs::write(template.join("Bounces/.keep"), "").unwrap();
+    fs::write(
+        template.join("template.toml"),
+        "type = \"music\"\ntags = [\"session\"]\nnotes = [\"Send stems to mixing\"]\ndirectories = [\"Stems\"]\n",
+    )
+    .unwrap();
+
+    let project = home.path().join("beat42");
+    tagger(home.path())
+        .arg("init")
+        .arg(&project)
+        .args(["--template", "music-session"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Created beat42 (music)"));
+
+    assert!(project.join("Stems").is_dir());
+    assert!(project.join("Bounces/.keep").is_file());
+    let info = fs::read_to_string(project.join("project_info.toml")).unwrap();
+    assert!(info.contains("\"session\""));
+    assert!(info.contains("Send stems to mixing"));
+
+    // A project that is already tagged is left alone, as is an unknown template.
+    tagger(home.path()).arg("init").arg(&project).assert().failure();
+    tagger(home.path())
+        .arg("init")
+        .arg(home.path().join("other"))
+        .args(["--template", "film"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("available: music-session"));
+}
+
+#[test]
+fn test_report_writes_html_without_saving() {
+    let home = Fixture::new();
+    let root = rust_app();
+    let html = home.path().join("report.html");
+
+    tagger(home.path())
+        .arg("report")
+        .arg(root.path())
+        .arg("--html")
+        .arg(&html)
+        .assert()
+        .success();
+
+    let report = fs::read_to_string(&html).unwrap();
+    assert!(report.contains("<p class=\"summary\">1 projects,"));
+    assert!(report.contains(">golden-app</td><td>programming</td>"));
+    assert!(!root.path().join("app").join("project_info.toml").exists());
+}
@@ -0,0 +1,65 @@
+use tagger::config::Config;
+use tagger::project_info::ProjectInfo;
+use tagger::test_support;
+
+fn generate(fixture: &test_support::Fixture) -> ProjectInfo {
+    ProjectInfo::generate_project_info(fixture.path(), &Config::default()).unwrap()
+}
+
+#[test]
+fn test_rust_repo_fixture() {
+    let fixture = test_support::rust_repo();
+    let info = generate(&fixture);
+
+    assert_eq!(info.name, "fixture-app");
+    assert_eq!(
+        info.description.as_deref(),
+        Some("fixture-app — A tiny command-line app used in tests.")
+    );
+    assert_eq!(info.project_type, "programming");
+    assert!(info.tags.contains(&"lang:rust".to_string()));
+    assert!(info.tags.contains(&"license:mit".to_string()));
+    assert_eq!(info.license.as_deref(), Some("MIT"));
+    assert_eq!(info.version.as_deref(), Some("0.1.0"));
+    assert_eq!(
+        info.git_url.as_deref(),
+        Some("https://github.com/example/fixture-app.git")
+    );
+
+    let git = info.git.unwrap();
+    assert_eq!(git.commit_count, 1);
+    assert_eq!(git.contributors[0].name, "Fixture");
+    assert!(!info.git_status.unwrap().dirty);
+}
+
+#[test]
+fn test_reaper_session_fixture() {
+    let fixture = test_support::reaper_session();
+    let info = generate(&fixture);
+
+    assert_eq!(info.project_type, "music");
+    for tag in ["daw:reaper", "format:rpp", "format:wav", "format:mp3"] {
+        assert!(info.tags.contains(&tag.to_string()), "missing tag {}", tag);
+    }
+    assert_eq!(info.fields["music"]["bpm"].as_integer(), Some(124));
+    assert_eq!(info.fields["music"]["key"].as_str(), Some("F minor"));
+    assert!(info.warnings.is_empty());
+}
+
+#[test]
+fn test_unity_project_fixture() {
+    let fixture = test_support::unity_project();
+    let info = generate(&fixture);
+
+    assert_eq!(info.project_type, "programming");
+    assert!(info.tags.contains(&"lang:c#".to_string()));
+}
+
+#[test]
+fn test_monorepo_fixture() {
+    let fixture = test_support::monorepo();
+    let info = generate(&fixture);
+
+    assert_eq!(info.project_type, "programming");
+    assert_eq!(info.subprojects, vec!["crates/cli", "crates/core"]);
+}